@@ -7,6 +7,7 @@ use criterion::{criterion_group, criterion_main, Criterion};
 use indoc::indoc;
 use qsc::{interpret::Interpreter, PackageType, TargetCapabilityFlags};
 use qsc_data_structures::language_features::LanguageFeatures;
+use qsc_eval::backend::{Backend, GateOp, SparseSim};
 use qsc_eval::output::GenericReceiver;
 use qsc_frontend::compile::SourceMap;
 
@@ -189,6 +190,52 @@ pub fn large_nested_iteration(c: &mut Criterion) {
     });
 }
 
+/// Rotation chains on every qubit of a 12-qubit state in uniform superposition,
+/// so each gate application has to touch every basis state.
+fn rotation_chains() -> (usize, Vec<GateOp>) {
+    let num_qubits = 12;
+    let mut ops: Vec<_> = (0..num_qubits).map(GateOp::H).collect();
+    for q in 0..num_qubits {
+        for i in 0..8 {
+            let theta = 0.1 * f64::from(i);
+            ops.extend([
+                GateOp::Rx(theta, q),
+                GateOp::Ry(theta, q),
+                GateOp::Rz(theta, q),
+            ]);
+        }
+    }
+    (num_qubits, ops)
+}
+
+pub fn sequential_gates(c: &mut Criterion) {
+    c.bench_function("Sequential gate application", |b| {
+        let (num_qubits, ops) = rotation_chains();
+        b.iter(|| {
+            let mut sim = SparseSim::new();
+            for _ in 0..num_qubits {
+                sim.qubit_allocate();
+            }
+            for op in &ops {
+                sim.apply_gate(*op);
+            }
+        });
+    });
+}
+
+pub fn batched_gates(c: &mut Criterion) {
+    c.bench_function("Batched gate application", |b| {
+        let (num_qubits, ops) = rotation_chains();
+        b.iter(|| {
+            let mut sim = SparseSim::new();
+            for _ in 0..num_qubits {
+                sim.qubit_allocate();
+            }
+            sim.apply_batch(&ops);
+        });
+    });
+}
+
 criterion_group!(
     benches,
     teleport,
@@ -198,5 +245,7 @@ criterion_group!(
     array_update,
     array_literal,
     large_nested_iteration,
+    sequential_gates,
+    batched_gates,
 );
 criterion_main!(benches);
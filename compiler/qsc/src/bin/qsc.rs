@@ -7,7 +7,10 @@ use clap::{crate_version, ArgGroup, Parser, ValueEnum};
 use log::info;
 use miette::{Context, IntoDiagnostic, Report};
 use qsc::hir::PackageId;
-use qsc::{compile::compile, PassContext};
+use qsc::{
+    compile::{compile, has_errors},
+    PassContext,
+};
 use qsc_codegen::qir::fir_to_qir;
 use qsc_data_structures::{language_features::LanguageFeatures, target::TargetCapabilityFlags};
 use qsc_frontend::{
@@ -161,7 +164,7 @@ fn main() -> miette::Result<ExitCode> {
                     eprintln!("QIR generation is not supported for unrestricted profile");
                     return Ok(ExitCode::FAILURE);
                 }
-                if errors.is_empty() {
+                if !has_errors(&errors) {
                     if let Err(reports) = emit_qir(out_dir, &store, package_id, capabilities) {
                         for report in reports {
                             eprintln!("{report:?}");
@@ -173,14 +176,15 @@ fn main() -> miette::Result<ExitCode> {
         }
     }
 
-    if errors.is_empty() {
-        Ok(ExitCode::SUCCESS)
-    } else {
-        for error in errors {
-            eprintln!("{:?}", Report::new(error));
-        }
+    let failed = has_errors(&errors);
+    for error in errors {
+        eprintln!("{:?}", Report::new(error));
+    }
 
+    if failed {
         Ok(ExitCode::FAILURE)
+    } else {
+        Ok(ExitCode::SUCCESS)
     }
 }
 
@@ -241,7 +245,13 @@ fn emit_qir(
     }
     let compute_properties = results.expect("should have compute properties");
 
-    match fir_to_qir(&fir_store, capabilities, Some(compute_properties), &entry) {
+    match fir_to_qir(
+        &fir_store,
+        capabilities,
+        Some(compute_properties),
+        &entry,
+        true,
+    ) {
         Ok(qir) => {
             let path = out_dir.join("qir.ll");
             info!(
@@ -4,46 +4,48 @@
 #[cfg(test)]
 mod tests;
 
+use num_bigint::BigUint;
+use num_complex::Complex;
 use qsc_codegen::qir::fir_to_qir;
 use qsc_data_structures::{language_features::LanguageFeatures, target::TargetCapabilityFlags};
+use qsc_eval::{
+    backend::{Backend, Chain as BackendChain, SparseSim},
+    debug::Frame,
+    output::GenericReceiver,
+    val::Value,
+    Env, EvalOptions,
+};
+use qsc_fir::fir;
 use qsc_frontend::{
     compile::{PackageStore, SourceMap},
     error::WithSource,
 };
+use qsc_hir::hir::PackageId;
 use qsc_partial_eval::ProgramEntry;
 use qsc_passes::{PackageType, PassContext};
+use qsc_rca::{
+    ComputeKind, ComputePropertiesLookup, PackageStoreComputeProperties, RuntimeFeatureFlags,
+};
 
-use crate::{compile, interpret::Error};
+use crate::{
+    compile,
+    interpret::{eval_error, Error},
+};
+use std::rc::Rc;
 
+/// Generates QIR for the entry point of the given sources. When `emit_runtime_decls` is false,
+/// the declarations of the external runtime and intrinsic functions are left out of the module.
 pub fn get_qir(
     sources: SourceMap,
     language_features: LanguageFeatures,
     capabilities: TargetCapabilityFlags,
+    emit_runtime_decls: bool,
 ) -> Result<String, Vec<Error>> {
     if capabilities == TargetCapabilityFlags::all() {
         return Err(vec![Error::UnsupportedRuntimeCapabilities]);
     }
-    let core = compile::core();
-    let mut package_store = PackageStore::new(core);
-    let std = compile::std(&package_store, capabilities);
-    let std = package_store.insert(std);
-
-    let (unit, errors) = crate::compile::compile(
-        &package_store,
-        &[std],
-        sources,
-        PackageType::Exe,
-        capabilities,
-        language_features,
-    );
-
-    // Ensure it compiles before trying to add it to the store.
-    if !errors.is_empty() {
-        return Err(errors.iter().map(|e| Error::Compile(e.clone())).collect());
-    }
-
-    let package_id = package_store.insert(unit);
-    let (fir_store, fir_package_id) = qsc_passes::lower_hir_to_fir(&package_store, package_id);
+    let (package_store, package_id, fir_store, fir_package_id) =
+        compile_to_fir(sources, language_features, capabilities)?;
     let package = fir_store.get(fir_package_id);
     let entry = ProgramEntry {
         exec_graph: package.entry_exec_graph.clone(),
@@ -56,20 +58,22 @@ pub fn get_qir(
             .into(),
     };
 
-    let compute_properties =
-        PassContext::run_fir_passes_on_fir(&fir_store, fir_package_id, capabilities).map_err(
-            |errors| {
-                let source_package = package_store
-                    .get(package_id)
-                    .expect("package should be in store");
-                errors
-                    .iter()
-                    .map(|e| Error::Pass(WithSource::from_map(&source_package.sources, e.clone())))
-                    .collect::<Vec<_>>()
-            },
-        )?;
-
-    fir_to_qir(&fir_store, capabilities, Some(compute_properties), &entry).map_err(|e| {
+    let compute_properties = run_fir_passes(
+        &package_store,
+        package_id,
+        &fir_store,
+        fir_package_id,
+        capabilities,
+    )?;
+
+    fir_to_qir(
+        &fir_store,
+        capabilities,
+        Some(compute_properties),
+        &entry,
+        emit_runtime_decls,
+    )
+    .map_err(|e| {
         let source_package_id = match e.span() {
             Some(span) => span.package,
             None => package_id,
@@ -83,3 +87,235 @@ pub fn get_qir(
         ))]
     })
 }
+
+/// Counts of the resources used by a program, as computed by [`estimate_resources`].
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct ResourceEstimate {
+    /// The largest number of qubits allocated at the same time.
+    pub qubits: usize,
+    /// The number of `T` and adjoint `T` gates. Each `CCNOT` gate counts as 7 `T` gates.
+    pub t_gates: usize,
+    /// The number of `CNOT` gates. Each `CCNOT` gate counts as 6 `CNOT` gates.
+    pub cnots: usize,
+    /// The number of single-qubit measurements, including measurements that reset the qubit.
+    pub measurements: usize,
+    /// Whether the program has a loop whose condition depends on measurement results. The counts of
+    /// such a program are only those of one possible execution.
+    pub partial: bool,
+}
+
+/// Counts the qubits, `T` gates, `CNOT` gates and measurements used by the entry point of a program,
+/// without generating QIR.
+///
+/// Classical computation, including loops with constant bounds, is evaluated to find the gates that
+/// are applied, but no quantum state is simulated and every measurement is taken to return `Zero`.
+/// A loop whose condition depends on measurement results may run for a different number of
+/// iterations in every execution, so programs that contain one are marked as
+/// [`ResourceEstimate::partial`] and counted along a single simulated execution instead.
+///
+/// # Errors
+///
+/// Returns the compilation errors if the program does not compile, or the runtime error if
+/// evaluating the program fails.
+pub fn estimate_resources(
+    sources: SourceMap,
+    language_features: LanguageFeatures,
+    capabilities: TargetCapabilityFlags,
+) -> Result<ResourceEstimate, Vec<Error>> {
+    let (package_store, package_id, fir_store, fir_package_id) =
+        compile_to_fir(sources, language_features, capabilities)?;
+    let package = fir_store.get(fir_package_id);
+    let entry = package
+        .entry
+        .expect("package must have an entry expression");
+
+    // The analysis runs with every capability so that dynamic loops are reported rather than rejected.
+    let compute_properties = run_fir_passes(
+        &package_store,
+        package_id,
+        &fir_store,
+        fir_package_id,
+        TargetCapabilityFlags::all(),
+    )?;
+    let partial = has_dynamic_loop(&compute_properties, fir_package_id, entry);
+
+    let exec_graph = package.entry_exec_graph.clone();
+    let counts = if partial {
+        let mut sim = BackendChain::new(SparseSim::new(), ResourceCounter::default());
+        eval_entry(&fir_store, fir_package_id, exec_graph, &mut sim).map(|()| sim.chained.counts)
+    } else {
+        let mut sim = ResourceCounter::default();
+        eval_entry(&fir_store, fir_package_id, exec_graph, &mut sim).map(|()| sim.counts)
+    }
+    .map_err(|(error, call_stack)| eval_error(&package_store, &fir_store, call_stack, error))?;
+
+    Ok(ResourceEstimate { partial, ..counts })
+}
+
+fn eval_entry(
+    fir_store: &fir::PackageStore,
+    package: fir::PackageId,
+    exec_graph: Rc<[fir::ExecGraphNode]>,
+    sim: &mut impl Backend<ResultType = bool>,
+) -> Result<(), (qsc_eval::Error, Vec<Frame>)> {
+    let mut sink = std::io::sink();
+    let mut out = GenericReceiver::new(&mut sink);
+    qsc_eval::eval(
+        package,
+        &EvalOptions::default(),
+        exec_graph,
+        fir_store,
+        &mut Env::default(),
+        sim,
+        &mut out,
+    )
+    .map(|_| ())
+}
+
+fn has_dynamic_loop(
+    compute_properties: &PackageStoreComputeProperties,
+    package: fir::PackageId,
+    entry: fir::ExprId,
+) -> bool {
+    match compute_properties
+        .get_expr((package, entry).into())
+        .inherent
+    {
+        ComputeKind::Quantum(properties) => properties
+            .runtime_features
+            .contains(RuntimeFeatureFlags::LoopWithDynamicCondition),
+        ComputeKind::Classical => false,
+    }
+}
+
+/// A backend that counts resources instead of simulating them.
+#[derive(Default)]
+struct ResourceCounter {
+    counts: ResourceEstimate,
+    allocated: usize,
+    free: Vec<usize>,
+    next_id: usize,
+}
+
+impl Backend for ResourceCounter {
+    type ResultType = bool;
+
+    fn ccx(&mut self, _ctl0: usize, _ctl1: usize, _q: usize) {
+        // Counted as the standard decomposition of a Toffoli gate into Clifford+T gates.
+        self.counts.t_gates += 7;
+        self.counts.cnots += 6;
+    }
+    fn cx(&mut self, _ctl: usize, _q: usize) {
+        self.counts.cnots += 1;
+    }
+    fn cy(&mut self, _ctl: usize, _q: usize) {}
+    fn cz(&mut self, _ctl: usize, _q: usize) {}
+    fn h(&mut self, _q: usize) {}
+    fn m(&mut self, _q: usize) -> Self::ResultType {
+        self.counts.measurements += 1;
+        false
+    }
+    fn mresetz(&mut self, _q: usize) -> Self::ResultType {
+        self.counts.measurements += 1;
+        false
+    }
+    fn reset(&mut self, _q: usize) {}
+    fn rx(&mut self, _theta: f64, _q: usize) {}
+    fn rxx(&mut self, _theta: f64, _q0: usize, _q1: usize) {}
+    fn ry(&mut self, _theta: f64, _q: usize) {}
+    fn ryy(&mut self, _theta: f64, _q0: usize, _q1: usize) {}
+    fn rz(&mut self, _theta: f64, _q: usize) {}
+    fn rzz(&mut self, _theta: f64, _q0: usize, _q1: usize) {}
+    fn sadj(&mut self, _q: usize) {}
+    fn s(&mut self, _q: usize) {}
+    fn swap(&mut self, _q0: usize, _q1: usize) {}
+    fn tadj(&mut self, _q: usize) {
+        self.counts.t_gates += 1;
+    }
+    fn t(&mut self, _q: usize) {
+        self.counts.t_gates += 1;
+    }
+    fn x(&mut self, _q: usize) {}
+    fn y(&mut self, _q: usize) {}
+    fn z(&mut self, _q: usize) {}
+
+    fn qubit_allocate(&mut self) -> usize {
+        self.allocated += 1;
+        self.counts.qubits = self.counts.qubits.max(self.allocated);
+        self.free.pop().unwrap_or_else(|| {
+            self.next_id += 1;
+            self.next_id - 1
+        })
+    }
+
+    fn qubit_release(&mut self, q: usize) {
+        self.allocated -= 1;
+        self.free.push(q);
+    }
+
+    fn capture_quantum_state(&mut self) -> (Vec<(BigUint, Complex<f64>)>, usize) {
+        (Vec::new(), self.allocated)
+    }
+
+    fn qubit_is_zero(&mut self, _q: usize) -> bool {
+        true
+    }
+
+    fn custom_intrinsic(&mut self, name: &str, _arg: Value) -> Option<Result<Value, String>> {
+        match name {
+            "GlobalPhase" => Some(Ok(Value::unit())),
+            _ => None,
+        }
+    }
+}
+
+/// Compiles the sources into an executable package along with the standard library, and lowers
+/// the result to FIR.
+fn compile_to_fir(
+    sources: SourceMap,
+    language_features: LanguageFeatures,
+    capabilities: TargetCapabilityFlags,
+) -> Result<(PackageStore, PackageId, fir::PackageStore, fir::PackageId), Vec<Error>> {
+    let core = compile::core();
+    let mut package_store = PackageStore::new(core);
+    let std = compile::std(&package_store, capabilities);
+    let std = package_store.insert(std);
+
+    let (mut unit, errors) = crate::compile::compile(
+        &package_store,
+        &[std],
+        sources,
+        PackageType::Exe,
+        capabilities,
+        language_features,
+    );
+
+    // Ensure it compiles before trying to add it to the store.
+    if compile::has_errors(&errors) {
+        return Err(errors.iter().map(|e| Error::Compile(e.clone())).collect());
+    }
+
+    qsc_passes::inline_constant_functions(&mut unit.package);
+
+    let package_id = package_store.insert(unit);
+    let (fir_store, fir_package_id) = qsc_passes::lower_hir_to_fir(&package_store, package_id);
+    Ok((package_store, package_id, fir_store, fir_package_id))
+}
+
+fn run_fir_passes(
+    package_store: &PackageStore,
+    package_id: PackageId,
+    fir_store: &fir::PackageStore,
+    fir_package_id: fir::PackageId,
+    capabilities: TargetCapabilityFlags,
+) -> Result<PackageStoreComputeProperties, Vec<Error>> {
+    PassContext::run_fir_passes_on_fir(fir_store, fir_package_id, capabilities).map_err(|errors| {
+        let source_package = package_store
+            .get(package_id)
+            .expect("package should be in store");
+        errors
+            .iter()
+            .map(|e| Error::Pass(WithSource::from_map(&source_package.sources, e.clone())))
+            .collect::<Vec<_>>()
+    })
+}
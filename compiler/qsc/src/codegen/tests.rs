@@ -55,7 +55,7 @@ fn code_with_errors_returns_errors() {
             ],
         )
     "#]]
-    .assert_debug_eq(&get_qir(sources, language_features, capabilities));
+    .assert_debug_eq(&get_qir(sources, language_features, capabilities, true));
 }
 
 mod base_profile {
@@ -86,8 +86,8 @@ mod base_profile {
         let language_features = LanguageFeatures::default();
         let capabilities = TargetCapabilityFlags::empty();
 
-        let qir =
-            get_qir(sources, language_features, capabilities).expect("Failed to generate QIR");
+        let qir = get_qir(sources, language_features, capabilities, true)
+            .expect("Failed to generate QIR");
         expect![[r#"
             %Result = type opaque
             %Qubit = type opaque
@@ -136,8 +136,8 @@ mod base_profile {
         let language_features = LanguageFeatures::default();
         let capabilities = TargetCapabilityFlags::empty();
 
-        let qir =
-            get_qir(sources, language_features, capabilities).expect("Failed to generate QIR");
+        let qir = get_qir(sources, language_features, capabilities, true)
+            .expect("Failed to generate QIR");
         expect![[r#"
             %Result = type opaque
             %Qubit = type opaque
@@ -189,8 +189,8 @@ mod base_profile {
         let language_features = LanguageFeatures::default();
         let capabilities = TargetCapabilityFlags::empty();
 
-        let qir =
-            get_qir(sources, language_features, capabilities).expect("Failed to generate QIR");
+        let qir = get_qir(sources, language_features, capabilities, true)
+            .expect("Failed to generate QIR");
         expect![[r#"
             %Result = type opaque
             %Qubit = type opaque
@@ -228,6 +228,78 @@ mod base_profile {
             !3 = !{i32 1, !"dynamic_result_management", i1 false}
         "#]].assert_eq(&qir);
     }
+
+    fn bell_qir(emit_runtime_decls: bool) -> String {
+        let source = "namespace Test {
+            @EntryPoint()
+            operation Main() : Result[] {
+                use (q0, q1) = (Qubit(), Qubit());
+                H(q0);
+                CNOT(q0, q1);
+                [MResetZ(q0), MResetZ(q1)]
+            }
+        }";
+        let sources = SourceMap::new([("test.qs".into(), source.into())], None);
+        get_qir(
+            sources,
+            LanguageFeatures::default(),
+            TargetCapabilityFlags::empty(),
+            emit_runtime_decls,
+        )
+        .expect("Failed to generate QIR")
+    }
+
+    #[test]
+    fn runtime_decls_are_emitted_when_requested() {
+        let qir = bell_qir(true);
+        let decls = qir
+            .lines()
+            .filter(|line| line.starts_with("declare "))
+            .collect::<Vec<_>>();
+        expect![[r#"
+            [
+                "declare void @__quantum__qis__h__body(%Qubit*)",
+                "declare void @__quantum__qis__cx__body(%Qubit*, %Qubit*)",
+                "declare void @__quantum__rt__array_record_output(i64, i8*)",
+                "declare void @__quantum__rt__result_record_output(%Result*, i8*)",
+                "declare void @__quantum__qis__m__body(%Qubit*, %Result*) #1",
+            ]
+        "#]]
+        .assert_debug_eq(&decls);
+    }
+
+    #[test]
+    fn runtime_decls_are_omitted_when_not_requested() {
+        let qir = bell_qir(false);
+        expect![[r#"
+            %Result = type opaque
+            %Qubit = type opaque
+
+            define void @ENTRYPOINT__main() #0 {
+            block_0:
+              call void @__quantum__qis__h__body(%Qubit* inttoptr (i64 0 to %Qubit*))
+              call void @__quantum__qis__cx__body(%Qubit* inttoptr (i64 0 to %Qubit*), %Qubit* inttoptr (i64 1 to %Qubit*))
+              call void @__quantum__qis__m__body(%Qubit* inttoptr (i64 0 to %Qubit*), %Result* inttoptr (i64 0 to %Result*))
+              call void @__quantum__qis__m__body(%Qubit* inttoptr (i64 1 to %Qubit*), %Result* inttoptr (i64 1 to %Result*))
+              call void @__quantum__rt__array_record_output(i64 2, i8* null)
+              call void @__quantum__rt__result_record_output(%Result* inttoptr (i64 0 to %Result*), i8* null)
+              call void @__quantum__rt__result_record_output(%Result* inttoptr (i64 1 to %Result*), i8* null)
+              ret void
+            }
+
+            attributes #0 = { "entry_point" "output_labeling_schema" "qir_profiles"="base_profile" "required_num_qubits"="2" "required_num_results"="2" }
+            attributes #1 = { "irreversible" }
+
+            ; module flags
+
+            !llvm.module.flags = !{!0, !1, !2, !3}
+
+            !0 = !{i32 1, !"qir_major_version", i32 1}
+            !1 = !{i32 7, !"qir_minor_version", i32 0}
+            !2 = !{i32 1, !"dynamic_qubit_management", i1 false}
+            !3 = !{i32 1, !"dynamic_result_management", i1 false}
+        "#]].assert_eq(&qir);
+    }
 }
 
 mod adaptive_profile {
@@ -258,8 +330,8 @@ mod adaptive_profile {
         let language_features = LanguageFeatures::default();
         let capabilities = TargetCapabilityFlags::Adaptive;
 
-        let qir =
-            get_qir(sources, language_features, capabilities).expect("Failed to generate QIR");
+        let qir = get_qir(sources, language_features, capabilities, true)
+            .expect("Failed to generate QIR");
         expect![[r#"
             %Result = type opaque
             %Qubit = type opaque
@@ -315,8 +387,8 @@ mod adaptive_profile {
         let language_features = LanguageFeatures::default();
         let capabilities = TargetCapabilityFlags::Adaptive;
 
-        let qir =
-            get_qir(sources, language_features, capabilities).expect("Failed to generate QIR");
+        let qir = get_qir(sources, language_features, capabilities, true)
+            .expect("Failed to generate QIR");
         expect![[r#"
             %Result = type opaque
             %Qubit = type opaque
@@ -375,8 +447,8 @@ mod adaptive_profile {
         let language_features = LanguageFeatures::default();
         let capabilities = TargetCapabilityFlags::Adaptive;
 
-        let qir =
-            get_qir(sources, language_features, capabilities).expect("Failed to generate QIR");
+        let qir = get_qir(sources, language_features, capabilities, true)
+            .expect("Failed to generate QIR");
         expect![[r#"
             %Result = type opaque
             %Qubit = type opaque
@@ -453,8 +525,8 @@ mod adaptive_ri_profile {
             | TargetCapabilityFlags::QubitReset
             | TargetCapabilityFlags::IntegerComputations;
 
-        let qir =
-            get_qir(sources, language_features, capabilities).expect("Failed to generate QIR");
+        let qir = get_qir(sources, language_features, capabilities, true)
+            .expect("Failed to generate QIR");
         expect![[r#"
             %Result = type opaque
             %Qubit = type opaque
@@ -512,8 +584,8 @@ mod adaptive_ri_profile {
             | TargetCapabilityFlags::QubitReset
             | TargetCapabilityFlags::IntegerComputations;
 
-        let qir =
-            get_qir(sources, language_features, capabilities).expect("Failed to generate QIR");
+        let qir = get_qir(sources, language_features, capabilities, true)
+            .expect("Failed to generate QIR");
         expect![[r#"
             %Result = type opaque
             %Qubit = type opaque
@@ -574,8 +646,8 @@ mod adaptive_ri_profile {
             | TargetCapabilityFlags::QubitReset
             | TargetCapabilityFlags::IntegerComputations;
 
-        let qir =
-            get_qir(sources, language_features, capabilities).expect("Failed to generate QIR");
+        let qir = get_qir(sources, language_features, capabilities, true)
+            .expect("Failed to generate QIR");
         expect![[r#"
             %Result = type opaque
             %Qubit = type opaque
@@ -637,8 +709,8 @@ mod adaptive_ri_profile {
             | TargetCapabilityFlags::QubitReset
             | TargetCapabilityFlags::IntegerComputations;
 
-        let qir =
-            get_qir(sources, language_features, capabilities).expect("Failed to generate QIR");
+        let qir = get_qir(sources, language_features, capabilities, true)
+            .expect("Failed to generate QIR");
         expect![[r#"
             %Result = type opaque
             %Qubit = type opaque
@@ -689,3 +761,109 @@ mod adaptive_ri_profile {
         "#]].assert_eq(&qir);
     }
 }
+
+mod resource_estimates {
+    use expect_test::expect;
+    use qsc_data_structures::{language_features::LanguageFeatures, target::TargetCapabilityFlags};
+    use qsc_frontend::compile::SourceMap;
+
+    use crate::codegen::estimate_resources;
+
+    #[test]
+    fn fixed_size_circuit_has_exact_counts() {
+        let source = "namespace Test {
+            @EntryPoint()
+            operation Main() : Result[] {
+                use qs = Qubit[3];
+                for i in 0..1 {
+                    CNOT(qs[i], qs[i + 1]);
+                }
+                T(qs[0]);
+                Adjoint T(qs[1]);
+                within {
+                    H(qs[2]);
+                } apply {
+                    T(qs[2]);
+                }
+                use aux = Qubit();
+                CNOT(qs[0], aux);
+                Reset(aux);
+                MResetEachZ(qs)
+            }
+        }";
+        let sources = SourceMap::new([("test.qs".into(), source.into())], None);
+        expect![[r"
+            Ok(
+                ResourceEstimate {
+                    qubits: 4,
+                    t_gates: 3,
+                    cnots: 3,
+                    measurements: 3,
+                    partial: false,
+                },
+            )
+        "]]
+        .assert_debug_eq(&estimate_resources(
+            sources,
+            LanguageFeatures::default(),
+            TargetCapabilityFlags::all(),
+        ));
+    }
+
+    #[test]
+    fn ccnot_counts_as_its_decomposition() {
+        let source = "namespace Test {
+            @EntryPoint()
+            operation Main() : Result {
+                use (c0, c1, target) = (Qubit(), Qubit(), Qubit());
+                CCNOT(c0, c1, target);
+                Reset(c0);
+                Reset(c1);
+                MResetZ(target)
+            }
+        }";
+        let sources = SourceMap::new([("test.qs".into(), source.into())], None);
+        expect![[r"
+            Ok(
+                ResourceEstimate {
+                    qubits: 3,
+                    t_gates: 7,
+                    cnots: 6,
+                    measurements: 1,
+                    partial: false,
+                },
+            )
+        "]]
+        .assert_debug_eq(&estimate_resources(
+            sources,
+            LanguageFeatures::default(),
+            TargetCapabilityFlags::all(),
+        ));
+    }
+
+    #[test]
+    fn data_dependent_loop_is_partial() {
+        let source = "namespace Test {
+            @EntryPoint()
+            operation Main() : Int {
+                use q = Qubit();
+                mutable attempts = 0;
+                repeat {
+                    H(q);
+                    set attempts += 1;
+                } until MResetZ(q) == Zero;
+                attempts
+            }
+        }";
+        let sources = SourceMap::new([("test.qs".into(), source.into())], None);
+        let estimate = estimate_resources(
+            sources,
+            LanguageFeatures::default(),
+            TargetCapabilityFlags::all(),
+        )
+        .expect("estimating resources should succeed");
+        assert!(estimate.partial);
+        assert_eq!(estimate.qubits, 1);
+        assert!(estimate.measurements >= 1);
+    }
+}
@@ -1,16 +1,35 @@
 // Copyright (c) Microsoft Corporation.
 // Licensed under the MIT License.
 
-use miette::{Diagnostic, Report};
-use qsc_data_structures::{language_features::LanguageFeatures, target::TargetCapabilityFlags};
+#[cfg(test)]
+mod tests;
+
+use miette::{Diagnostic, Report, Severity};
+use qsc_data_structures::{
+    language_features::LanguageFeatures,
+    line_column::{Encoding, Range},
+    span::Span,
+    target::TargetCapabilityFlags,
+};
 use qsc_frontend::{
     compile::{CompileUnit, PackageStore, SourceMap},
     error::WithSource,
 };
 use qsc_hir::hir::PackageId;
-use qsc_passes::{run_core_passes, run_default_passes, PackageType};
+use qsc_passes::{
+    check_unused_private_items, run_check_passes, run_core_passes, run_default_passes, PackageType,
+};
+use serde_json::{json, Value};
+use std::{fmt::Write, iter};
 use thiserror::Error;
 
+pub use qsc_frontend::compile::{
+    call_graph, compile_timed, compile_with_strict_prelude, extract_string_literals,
+    functor_support, holes, intrinsic_callers, references_to, semantic_tokens, shadowing_events,
+    symbol_table, type_at_span, used_language_features, ExportedItem, Functors, PhaseTimings,
+    SemanticToken, SemanticTokenKind, ShadowEvent, SymbolEntry, SymbolKind, UsedSyntax,
+};
+
 pub type Error = WithSource<ErrorKind>;
 
 #[derive(Clone, Debug, Diagnostic, Error)]
@@ -50,12 +69,15 @@ pub fn compile_ast(
         ast_package,
         sources,
         capabilities,
+        &[],
         vec![],
     );
     process_compile_unit(store, package_type, unit)
 }
 
-/// Compiles a package from its source representation.
+/// Compiles a package from its source representation. Along with the frontend and pass
+/// diagnostics, items that cannot be used outside of the package and are never used within it are
+/// reported as warnings.
 #[must_use]
 pub fn compile(
     store: &PackageStore,
@@ -75,6 +97,45 @@ pub fn compile(
     process_compile_unit(store, package_type, unit)
 }
 
+/// Checks a package from its source representation, returning the same diagnostics as
+/// [`compile`] would. Passes that only prepare the package for evaluation or code generation
+/// are skipped, so the package is not returned.
+#[must_use]
+pub fn check_only(
+    store: &PackageStore,
+    dependencies: &[PackageId],
+    sources: SourceMap,
+    package_type: PackageType,
+    capabilities: TargetCapabilityFlags,
+    language_features: LanguageFeatures,
+) -> Vec<Error> {
+    let mut unit = qsc_frontend::compile::compile(
+        store,
+        dependencies,
+        sources,
+        capabilities,
+        language_features,
+    );
+    let mut errors = Vec::new();
+    for error in unit.errors.drain(..) {
+        errors.push(WithSource::from_map(&unit.sources, error.into()));
+    }
+
+    if !has_errors(&errors) {
+        for error in run_check_passes(store.core(), &mut unit, package_type) {
+            errors.push(WithSource::from_map(&unit.sources, error.into()));
+        }
+    }
+
+    if !has_errors(&errors) {
+        for error in check_unused_private_items(&unit) {
+            errors.push(WithSource::from_map(&unit.sources, error.into()));
+        }
+    }
+
+    errors
+}
+
 #[must_use]
 #[allow(clippy::module_name_repetitions)]
 fn process_compile_unit(
@@ -87,15 +148,134 @@ fn process_compile_unit(
         errors.push(WithSource::from_map(&unit.sources, error.into()));
     }
 
-    if errors.is_empty() {
+    if !has_errors(&errors) {
         for error in run_default_passes(store.core(), &mut unit, package_type) {
             errors.push(WithSource::from_map(&unit.sources, error.into()));
         }
     }
 
+    if !has_errors(&errors) {
+        for error in check_unused_private_items(&unit) {
+            errors.push(WithSource::from_map(&unit.sources, error.into()));
+        }
+    }
+
     (unit, errors)
 }
 
+/// Sorts diagnostics of a compilation, such as the frontend and pass diagnostics returned together
+/// by [`compile`].
+///
+/// Offsets in the source map increase from one source to the next, so sorting by the start of
+/// the primary label orders the diagnostics by source and then by span start. Errors come before
+/// warnings at the same position, and diagnostics that still tie keep the order of their stages.
+#[must_use]
+pub fn sorted_diagnostics(mut errors: Vec<Error>) -> Vec<Error> {
+    errors.sort_by_key(|error| {
+        let offset = error
+            .labels()
+            .and_then(|mut labels| labels.next())
+            .map_or(usize::MAX, |label| label.offset());
+        let severity = match error.severity() {
+            None | Some(Severity::Error) => 0,
+            Some(Severity::Warning) => 1,
+            Some(Severity::Advice) => 2,
+        };
+        (offset, severity)
+    });
+    errors
+}
+
+/// Returns `true` if any of the diagnostics is an error, as opposed to a warning that
+/// does not prevent compilation from continuing.
+#[must_use]
+pub fn has_errors(errors: &[Error]) -> bool {
+    errors
+        .iter()
+        .any(|error| error.severity() != Some(Severity::Warning))
+}
+
+/// Serializes diagnostics to a JSON array for consumption by tools such as CI systems. Each
+/// diagnostic becomes an object with the fields `file`, `range`, `severity`, `code` and `message`.
+///
+/// The file and range are those of the first label of the diagnostic, with zero-based lines and
+/// columns expressed in the given encoding. They are `null` for diagnostics without a label in
+/// `source_map`, and `code` is `null` for diagnostics without a code.
+#[must_use]
+pub fn diagnostics_to_json(
+    diagnostics: &[impl Diagnostic],
+    source_map: &SourceMap,
+    encoding: Encoding,
+) -> String {
+    let diagnostics = diagnostics
+        .iter()
+        .map(|diagnostic| {
+            let location = diagnostic
+                .labels()
+                .and_then(|mut labels| labels.next())
+                .and_then(|label| {
+                    let lo = u32::try_from(label.offset()).expect("offset should fit into u32");
+                    let len = u32::try_from(label.len()).expect("length should fit into u32");
+                    let source = source_map.find_by_offset(lo)?;
+                    let span = Span {
+                        lo: lo - source.offset,
+                        hi: lo - source.offset + len,
+                    };
+                    let range = Range::from_span(encoding, &source.contents, &span);
+                    Some((source.name.to_string(), range))
+                });
+            let (file, range) = match location {
+                Some((file, range)) => (
+                    Value::from(file),
+                    json!({
+                        "start": { "line": range.start.line, "column": range.start.column },
+                        "end": { "line": range.end.line, "column": range.end.column },
+                    }),
+                ),
+                None => (Value::Null, Value::Null),
+            };
+            let severity = match diagnostic.severity().unwrap_or(Severity::Error) {
+                Severity::Error => "error",
+                Severity::Warning => "warning",
+                Severity::Advice => "info",
+            };
+            // e.g. "type error: expected Int, found Double"
+            let mut message = diagnostic.to_string();
+            for source in iter::successors(diagnostic.source(), |e| e.source()) {
+                write!(message, ": {source}").expect("message should be writable");
+            }
+            json!({
+                "file": file,
+                "range": range,
+                "severity": severity,
+                "code": diagnostic.code().map(|code| code.to_string()),
+                "message": message,
+            })
+        })
+        .collect();
+    Value::Array(diagnostics).to_string()
+}
+
+/// Statically determines the smallest set of target capabilities needed to run a compiled unit,
+/// such as [`TargetCapabilityFlags::Adaptive`] for a program whose control flow depends on
+/// measurement results. The result is empty for a program that can run on a base profile target.
+///
+/// The unit should be compiled against `store` with all capabilities enabled, so that no part of
+/// the program is left out of the analysis.
+#[must_use]
+pub fn minimum_capabilities(store: &PackageStore, unit: &CompileUnit) -> TargetCapabilityFlags {
+    let (mut fir_store, _) = qsc_passes::lower_hir_to_fir(store, PackageId::CORE);
+    // Package IDs are assigned in order, so this is the ID the unit would get in the store.
+    let package_id = qsc_lowerer::map_hir_package_to_fir(store.iter().count().into());
+    let package = qsc_lowerer::Lowerer::new().lower_package(&unit.package);
+    fir_store.insert(package_id, package);
+    let compute_properties = qsc_rca::Analyzer::init(&fir_store).analyze_all();
+    qsc_passes::required_capabilities(
+        fir_store.get(package_id),
+        compute_properties.get(package_id),
+    )
+}
+
 /// Compiles the core library.
 ///
 /// # Panics
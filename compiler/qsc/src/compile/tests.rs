@@ -0,0 +1,305 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+use super::{
+    check_only, compile, diagnostics_to_json, minimum_capabilities, sorted_diagnostics, std, Error,
+};
+use expect_test::expect;
+use miette::Diagnostic;
+use qsc_data_structures::{
+    language_features::LanguageFeatures, line_column::Encoding, target::TargetCapabilityFlags,
+};
+use qsc_frontend::compile::{PackageStore, SourceMap};
+use qsc_passes::PackageType;
+
+fn check_matches_compile(source: &str, package_type: PackageType) -> Vec<Error> {
+    let mut store = PackageStore::new(super::core());
+    let std_id = store.insert(std(&store, TargetCapabilityFlags::all()));
+    let sources = || SourceMap::new([("test".into(), source.into())], None);
+
+    let (_, compile_errors) = compile(
+        &store,
+        &[std_id],
+        sources(),
+        package_type,
+        TargetCapabilityFlags::all(),
+        LanguageFeatures::default(),
+    );
+    let check_errors = check_only(
+        &store,
+        &[std_id],
+        sources(),
+        package_type,
+        TargetCapabilityFlags::all(),
+        LanguageFeatures::default(),
+    );
+
+    assert_eq!(format!("{check_errors:?}"), format!("{compile_errors:?}"));
+    check_errors
+}
+
+#[test]
+fn check_only_reports_same_type_errors_as_compile() {
+    let errors = check_matches_compile(
+        "namespace Test {
+            @EntryPoint()
+            operation Main() : Int {
+                let x : Int = 1.0;
+                x + true
+            }
+        }",
+        PackageType::Exe,
+    );
+    assert_eq!(errors.len(), 2);
+}
+
+#[test]
+fn check_only_reports_same_pass_errors_as_compile() {
+    let errors = check_matches_compile(
+        "namespace Test {
+            function Foo() : Unit {
+                use q = Qubit();
+            }
+        }",
+        PackageType::Exe,
+    );
+    assert_eq!(errors.len(), 2, "{errors:?}");
+}
+
+#[test]
+fn check_only_reports_no_errors_for_valid_program() {
+    let errors = check_matches_compile(
+        "namespace Test {
+            @EntryPoint()
+            operation Main() : Result {
+                use q = Qubit();
+                H(q);
+                MResetZ(q)
+            }
+        }",
+        PackageType::Exe,
+    );
+    assert!(errors.is_empty());
+}
+
+#[test]
+fn diagnostics_to_json_reports_location_severity_and_code() {
+    let mut store = PackageStore::new(super::core());
+    let std_id = store.insert(std(&store, TargetCapabilityFlags::all()));
+    let source = "namespace Test {
+    @Config(Base)
+    function Dropped() : Unit {}
+    export Dropped;
+
+    function Main() : Unit {
+        let x : Int = 1.0;
+    }
+}";
+    let (unit, errors) = compile(
+        &store,
+        &[std_id],
+        SourceMap::new([("test.qs".into(), source.into())], None),
+        PackageType::Lib,
+        TargetCapabilityFlags::all(),
+        LanguageFeatures::default(),
+    );
+
+    let json = diagnostics_to_json(&errors, &unit.sources, Encoding::Utf16);
+    let json: serde_json::Value = serde_json::from_str(&json).expect("output should be valid JSON");
+    let json = serde_json::to_string_pretty(&json).expect("JSON should be serializable");
+    expect![[r#"
+        [
+          {
+            "code": "Qsc.Resolve.ExportOfDroppedItem",
+            "file": "test.qs",
+            "message": "name error: exported item `Dropped` is not available for the current compilation configuration",
+            "range": {
+              "end": {
+                "column": 18,
+                "line": 3
+              },
+              "start": {
+                "column": 11,
+                "line": 3
+              }
+            },
+            "severity": "warning"
+          },
+          {
+            "code": "Qsc.TypeCk.TyMismatch",
+            "file": "test.qs",
+            "message": "type error: expected Int, found Double",
+            "range": {
+              "end": {
+                "column": 25,
+                "line": 6
+              },
+              "start": {
+                "column": 22,
+                "line": 6
+              }
+            },
+            "severity": "error"
+          }
+        ]"#]].assert_eq(&json);
+}
+
+#[test]
+fn compile_reports_unused_private_items() {
+    let errors = check_matches_compile(
+        "namespace Test {
+            internal function Helper() : Unit {}
+            function Main() : Unit {}
+        }",
+        PackageType::Lib,
+    );
+    expect![[r#"
+        [
+            "Qsc.UnusedItems.UnusedPrivateItem",
+        ]
+    "#]]
+    .assert_debug_eq(&diagnostic_codes(&errors));
+}
+
+fn diagnostic_codes(errors: &[Error]) -> Vec<String> {
+    errors
+        .iter()
+        .map(|error| {
+            error
+                .code()
+                .expect("diagnostic should have a code")
+                .to_string()
+        })
+        .collect()
+}
+
+#[test]
+fn sorted_diagnostics_orders_frontend_stages_by_source_and_position() {
+    let store = PackageStore::new(super::core());
+    let sources = SourceMap::new(
+        [
+            (
+                "a.qs".into(),
+                "namespace A {
+                    function F() : Unit { Missing(); }
+                    function G() : Unit { let x = ; }
+                }"
+                .into(),
+            ),
+            (
+                "b.qs".into(),
+                "namespace B {
+                    function H() : Unit { let y = ; }
+                    function I() : Unit { AlsoMissing(); }
+                }"
+                .into(),
+            ),
+        ],
+        None,
+    );
+    let (_, errors) = compile(
+        &store,
+        &[],
+        sources,
+        PackageType::Lib,
+        TargetCapabilityFlags::all(),
+        LanguageFeatures::default(),
+    );
+
+    expect![[r#"
+        [
+            "Qsc.Resolve.NotFound",
+            "Qsc.TypeCk.AmbiguousTy",
+            "Qsc.Parse.Rule",
+            "Qsc.Parse.Rule",
+            "Qsc.Resolve.NotFound",
+            "Qsc.TypeCk.AmbiguousTy",
+        ]
+    "#]]
+    .assert_debug_eq(&diagnostic_codes(&sorted_diagnostics(errors)));
+}
+
+#[test]
+fn sorted_diagnostics_includes_pass_diagnostics() {
+    let mut store = PackageStore::new(super::core());
+    let std_id = store.insert(std(&store, TargetCapabilityFlags::all()));
+    let source = "namespace Test {
+    function Foo() : Unit {
+        use q = Qubit();
+    }
+
+    @Config(Base)
+    function Dropped() : Unit {}
+    export Dropped;
+}";
+    let (_, errors) = compile(
+        &store,
+        &[std_id],
+        SourceMap::new([("test.qs".into(), source.into())], None),
+        PackageType::Lib,
+        TargetCapabilityFlags::all(),
+        LanguageFeatures::default(),
+    );
+
+    let codes = diagnostic_codes(&sorted_diagnostics(errors));
+    let position = |code: &str| {
+        codes
+            .iter()
+            .position(|c| c == code)
+            .unwrap_or_else(|| panic!("{code} should be reported, got {codes:?}"))
+    };
+    assert!(
+        position("Qsc.CallableLimits.QubitAlloc") < position("Qsc.Resolve.ExportOfDroppedItem"),
+        "the pass error comes first in the source, got {codes:?}"
+    );
+}
+
+fn compile_minimum_capabilities(source: &str) -> TargetCapabilityFlags {
+    let mut store = PackageStore::new(super::core());
+    let std_id = store.insert(std(&store, TargetCapabilityFlags::all()));
+    let sources = SourceMap::new([("test".into(), source.into())], None);
+    let (unit, errors) = compile(
+        &store,
+        &[std_id],
+        sources,
+        PackageType::Exe,
+        TargetCapabilityFlags::all(),
+        LanguageFeatures::default(),
+    );
+    assert!(errors.is_empty(), "{errors:?}");
+    minimum_capabilities(&store, &unit)
+}
+
+#[test]
+fn minimum_capabilities_of_base_program_are_empty() {
+    let capabilities = compile_minimum_capabilities(
+        "namespace Test {
+            @EntryPoint()
+            operation Main() : (Result, Result) {
+                use (q1, q2) = (Qubit(), Qubit());
+                H(q1);
+                CNOT(q1, q2);
+                (M(q1), M(q2))
+            }
+        }",
+    );
+    assert_eq!(capabilities, TargetCapabilityFlags::empty());
+}
+
+#[test]
+fn minimum_capabilities_of_conditional_gate_on_measurement_include_adaptive() {
+    let capabilities = compile_minimum_capabilities(
+        "namespace Test {
+            @EntryPoint()
+            operation Main() : Result {
+                use (q1, q2) = (Qubit(), Qubit());
+                H(q1);
+                if M(q1) == One {
+                    X(q2);
+                }
+                M(q2)
+            }
+        }",
+    );
+    assert_eq!(capabilities, TargetCapabilityFlags::Adaptive);
+}
@@ -60,7 +60,7 @@ impl Compiler {
             capabilities,
             language_features,
         );
-        if !errors.is_empty() {
+        if compile::has_errors(&errors) {
             return Err(errors);
         }
 
@@ -162,8 +162,9 @@ impl Compiler {
         let mut increment =
             self.frontend
                 .compile_fragments(unit, source_name, source_contents, |e| {
-                    errors = errors || !e.is_empty();
-                    accumulate_errors(into_errors(e))
+                    let e = into_errors(e);
+                    errors = errors || compile::has_errors(&e);
+                    accumulate_errors(e)
                 })?;
 
         // Even if we don't fail fast, skip passes if there were compilation errors.
@@ -207,8 +208,9 @@ impl Compiler {
             source_contents,
             package,
             |e| {
-                errors = errors || !e.is_empty();
-                accumulate_errors(into_errors(e))
+                let e = into_errors(e);
+                errors = errors || compile::has_errors(&e);
+                accumulate_errors(e)
             },
         )?;
 
@@ -316,7 +318,7 @@ where
 }
 
 fn fail_on_error(errors: Errors) -> Result<(), Errors> {
-    if !errors.is_empty() {
+    if compile::has_errors(&errors) {
         return Err(errors);
     }
     Ok(())
@@ -12,7 +12,7 @@ mod debugger_tests;
 #[cfg(test)]
 mod circuit_tests;
 
-use std::rc::Rc;
+use std::{cell::RefCell, collections::VecDeque, rc::Rc};
 
 pub use qsc_eval::{
     debug::Frame,
@@ -21,7 +21,7 @@ pub use qsc_eval::{
     val::Range as ValueRange,
     val::Result,
     val::Value,
-    StepAction, StepResult,
+    Event, LogEvent, StepAction, StepResult,
 };
 use qsc_lowerer::{map_fir_package_to_hir, map_hir_package_to_fir};
 use qsc_partial_eval::ProgramEntry;
@@ -35,10 +35,10 @@ use crate::{
 use debug::format_call_stack;
 use miette::Diagnostic;
 use num_bigint::BigUint;
-use num_complex::Complex;
+use num_complex::{Complex, Complex64};
 use qsc_circuit::{
     operations::entry_expr_for_qubit_operation, Builder as CircuitBuilder, Circuit,
-    Config as CircuitConfig,
+    Config as CircuitConfig, Tracer as CircuitTracer,
 };
 use qsc_codegen::qir::fir_to_qir;
 use qsc_data_structures::{
@@ -49,9 +49,12 @@ use qsc_data_structures::{
     target::TargetCapabilityFlags,
 };
 use qsc_eval::{
-    backend::{Backend, Chain as BackendChain, SparseSim},
+    backend::{
+        Backend, Chain as BackendChain, DryRun, GateOp, ResultStream, SparseSim, StabilizerSim,
+        TraceWriter,
+    },
     output::Receiver,
-    val, Env, State, VariableInfo,
+    val, Env, EvalOptions, State, VariableInfo,
 };
 use qsc_fir::fir::{self, ExecGraphNode, Global, PackageStoreLookup};
 use qsc_fir::{
@@ -64,7 +67,9 @@ use qsc_frontend::{
     incremental::Increment,
 };
 use qsc_passes::{PackageType, PassContext};
-use rustc_hash::FxHashSet;
+use rand::{rngs::StdRng, RngCore, SeedableRng};
+use rustc_hash::{FxHashMap, FxHashSet};
+use serde_json::json;
 use thiserror::Error;
 
 impl Error {
@@ -101,9 +106,22 @@ pub enum Error {
     #[diagnostic(code("Qsc.Interpret.NotAnOperation"))]
     #[diagnostic(help("provide the name of a callable or a lambda expression"))]
     NotAnOperation,
+    #[error("`{0}` does not name a callable")]
+    #[diagnostic(code("Qsc.Interpret.CallableNotFound"))]
+    #[diagnostic(help("provide the namespace-qualified name of a callable, such as `Main.Foo`"))]
+    CallableNotFound(String),
+    #[error("arguments do not match the callable's input type `{0}`")]
+    #[diagnostic(code("Qsc.Interpret.ArgumentMismatch"))]
+    ArgumentMismatch(String),
     #[error("partial evaluation error")]
     #[diagnostic(transparent)]
     PartialEvaluation(#[from] WithSource<qsc_partial_eval::Error>),
+    #[error("no earlier statement to step back to")]
+    #[diagnostic(code("Qsc.Interpret.NoStepHistory"))]
+    #[diagnostic(help(
+        "at most {STEP_BACK_LIMIT} steps can be undone, and none before the first step"
+    ))]
+    NoStepHistory,
 }
 
 /// A Q# interpreter.
@@ -120,6 +138,9 @@ pub struct Interpreter {
     fir_store: fir::PackageStore,
     /// FIR lowerer
     lowerer: qsc_lowerer::Lowerer,
+    /// Whether constant sub-expressions of the source package and of fragments are folded before
+    /// they are lowered, see [`Interpreter::new_with_fold_constants`].
+    fold_constants: bool,
     /// The ID of the current package.
     /// This ID is valid both for the FIR store and the `PackageStore`.
     package: PackageId,
@@ -132,15 +153,35 @@ pub struct Interpreter {
     /// The quantum seed, if any. This is cached here so that it can be used in calls to
     /// `run_internal` which use a passed instance of the simulator instead of the one above.
     quantum_seed: Option<u64>,
-    /// The classical seed, if any. This needs to be passed to the evaluator for use in intrinsic
-    /// calls that produce classical random numbers.
-    classical_seed: Option<u64>,
+    /// The options passed to the evaluator, such as the classical seed used in intrinsic calls
+    /// that produce classical random numbers and the limits on evaluation.
+    eval_options: EvalOptions,
+    /// Whether generated circuits nest the gates of each operation call under a named group.
+    group_circuit_operations: bool,
+    /// Whether [`Interpreter::run`] simulates Clifford operations on a stabilizer tableau.
+    clifford_simulation: bool,
     /// The evaluator environment.
     env: Env,
+    /// The statements in the source package with a breakpoint set through
+    /// [`Interpreter::set_breakpoint`], each with its optional compiled condition.
+    breakpoints: FxHashMap<StmtId, Option<Rc<BreakpointCondition>>>,
+    /// The order in which measurement results of a register map to bit significance.
+    result_endianness: Endianness,
 }
 
 pub type InterpretResult = std::result::Result<Value, Vec<Error>>;
 
+/// The order in which the results of measuring a register, as a `Result[]`, map to bit
+/// significance when converted to an integer or displayed as a bit string.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Endianness {
+    /// The first result is the least significant bit, as in `ResultArrayAsInt`.
+    #[default]
+    Little,
+    /// The first result is the most significant bit.
+    Big,
+}
+
 impl Interpreter {
     /// Creates a new incremental compiler, compiling the passed in sources.
     /// # Errors
@@ -153,6 +194,7 @@ impl Interpreter {
         language_features: LanguageFeatures,
     ) -> std::result::Result<Self, Vec<Error>> {
         Self::new_internal(
+            false,
             false,
             std,
             sources,
@@ -173,6 +215,32 @@ impl Interpreter {
         language_features: LanguageFeatures,
     ) -> std::result::Result<Self, Vec<Error>> {
         Self::new_internal(
+            true,
+            false,
+            std,
+            sources,
+            package_type,
+            capabilities,
+            language_features,
+        )
+    }
+
+    /// Creates a new incremental compiler that folds constant sub-expressions ahead of time,
+    /// compiling the passed in sources. Constant arithmetic, such as the bounds of loops over
+    /// constant ranges, is then computed once rather than on every evaluation. Folding never
+    /// changes the results of evaluation: an expression that would overflow or fail at runtime is
+    /// left in place, so that it behaves the same with or without checked arithmetic.
+    /// # Errors
+    /// If compiling the sources fails, compiler errors are returned.
+    pub fn new_with_fold_constants(
+        std: bool,
+        sources: SourceMap,
+        package_type: PackageType,
+        capabilities: TargetCapabilityFlags,
+        language_features: LanguageFeatures,
+    ) -> std::result::Result<Self, Vec<Error>> {
+        Self::new_internal(
+            false,
             true,
             std,
             sources,
@@ -184,6 +252,7 @@ impl Interpreter {
 
     fn new_internal(
         dbg: bool,
+        fold_constants: bool,
         std: bool,
         sources: SourceMap,
         package_type: PackageType,
@@ -193,17 +262,18 @@ impl Interpreter {
         let compiler = Compiler::new(std, sources, package_type, capabilities, language_features)
             .map_err(into_errors)?;
 
+        let source_package_id = compiler.source_package_id();
         let mut fir_store = fir::PackageStore::new();
         for (id, unit) in compiler.package_store() {
-            fir_store.insert(
-                map_hir_package_to_fir(id),
-                qsc_lowerer::Lowerer::new()
-                    .with_debug(dbg)
-                    .lower_package(&unit.package),
-            );
+            let mut lowerer = qsc_lowerer::Lowerer::new().with_debug(dbg);
+            let package = if fold_constants && id == source_package_id {
+                lowerer.lower_package(&folded(&unit.package))
+            } else {
+                lowerer.lower_package(&unit.package)
+            };
+            fir_store.insert(map_hir_package_to_fir(id), package);
         }
 
-        let source_package_id = compiler.source_package_id();
         let package_id = compiler.package_id();
 
         let package = map_hir_package_to_fir(package_id);
@@ -232,12 +302,17 @@ impl Interpreter {
             capabilities,
             fir_store,
             lowerer: qsc_lowerer::Lowerer::new().with_debug(dbg),
+            fold_constants,
             env: Env::default(),
-            sim: sim_circuit_backend(),
+            sim: sim_circuit_backend(false),
             quantum_seed: None,
-            classical_seed: None,
+            eval_options: EvalOptions::default(),
+            group_circuit_operations: false,
+            clifford_simulation: false,
             package,
             source_package: map_hir_package_to_fir(source_package_id),
+            breakpoints: FxHashMap::default(),
+            result_endianness: Endianness::default(),
         })
     }
 
@@ -268,12 +343,17 @@ impl Interpreter {
             capabilities,
             fir_store,
             lowerer: qsc_lowerer::Lowerer::new(),
+            fold_constants: false,
             env: Env::default(),
-            sim: sim_circuit_backend(),
+            sim: sim_circuit_backend(false),
             quantum_seed: None,
-            classical_seed: None,
+            eval_options: EvalOptions::default(),
+            group_circuit_operations: false,
+            clifford_simulation: false,
             package: map_hir_package_to_fir(package_id),
             source_package: map_hir_package_to_fir(source_package_id),
+            breakpoints: FxHashMap::default(),
+            result_endianness: Endianness::default(),
         })
     }
 
@@ -283,8 +363,306 @@ impl Interpreter {
     }
 
     pub fn set_classical_seed(&mut self, seed: Option<u64>) {
-        self.classical_seed = seed;
+        self.eval_options.classical_seed = seed;
+        self.eval_options.classical_rng = None;
+    }
+
+    /// Sets the generator used for classical randomness, such as in `DrawRandomInt` and
+    /// `DrawRandomDouble`, independently of the randomness used to sample measurements.
+    /// The generator replaces any classical seed and is shared by subsequent evaluations,
+    /// which continue its sequence. Calling [`Interpreter::set_classical_seed`] removes it.
+    pub fn set_classical_rng(&mut self, rng: Box<dyn RngCore>) {
+        self.eval_options.classical_rng = Some(Rc::new(RefCell::new(rng)));
+    }
+
+    /// Sets the maximum number of nested calls allowed during evaluation.
+    /// Exceeding it produces a runtime error pointing at the offending call, rather than
+    /// recursing until memory runs out. Defaults to [`qsc_eval::DEFAULT_MAX_CALL_DEPTH`].
+    pub fn set_max_call_depth(&mut self, depth: usize) {
+        self.eval_options.max_call_depth = Some(depth);
+    }
+
+    /// Sets the maximum number of iterations of any single loop allowed during evaluation.
+    /// A loop that runs longer produces a runtime error pointing at the loop, which, unlike a
+    /// timeout, is reproducible from run to run.
+    pub fn set_max_loop_iterations(&mut self, limit: u64) {
+        self.eval_options.max_loop_iterations = Some(limit);
+    }
+
+    /// Sets whether `Int` addition, multiplication and exponentiation that overflow 64 bits
+    /// produce a runtime error pointing at the operation, rather than silently wrapping around.
+    /// `BigInt` arithmetic is unaffected.
+    pub fn set_checked_arithmetic(&mut self, checked: bool) {
+        self.eval_options.checked_arithmetic = checked;
+    }
+
+    /// Sets the number of significant digits used when interpolating `Double` values into
+    /// strings, such as in `Message($"{x}")`. Interpolated values are written in full by default.
+    /// # Panics
+    /// Panics if `digits` is 0.
+    pub fn set_double_precision(&mut self, digits: usize) {
+        assert!(
+            digits > 0,
+            "at least one significant digit should be written"
+        );
+        self.eval_options.double_precision = Some(digits);
+    }
+
+    /// Sets the maximum number of qubit measurements allowed during a single evaluation, such as
+    /// one run of the entry expression. A run that measures more often produces a runtime error
+    /// pointing at the measurement that exceeds the budget, which helps catch measurements in
+    /// loops that run more often than intended.
+    pub fn set_max_measurements(&mut self, limit: u64) {
+        self.eval_options.max_measurements = Some(limit);
+    }
+
+    /// Sets whether evaluation runs in verbose mode, in which every variable updated by a `set`
+    /// statement is recorded as an [`Event::Assign`] with its old and new values. The recorded
+    /// events are returned by [`Interpreter::take_events`].
+    pub fn set_verbose(&mut self, verbose: bool) {
+        self.eval_options.events = verbose.then(Rc::default);
+    }
+
+    /// Returns the events recorded in verbose mode since the last call, in the order they
+    /// happened.
+    pub fn take_events(&mut self) -> Vec<Event> {
+        self.eval_options
+            .events
+            .as_ref()
+            .map(|events| events.take())
+            .unwrap_or_default()
+    }
+
+    /// Sets whether [`Interpreter::run`] simulates the program on a stabilizer tableau for as long
+    /// as it only applies Clifford gates and measurements, switching to sparse state vector
+    /// simulation at the first non-Clifford operation. This makes large Clifford circuits fast,
+    /// but a stabilizer tableau does not track the global phase, so states output by
+    /// `DumpMachine` may differ from sparse simulation by a global phase. Measurement outcomes are
+    /// sampled differently too, so a run with a given quantum seed does not reproduce the results
+    /// of sparse simulation with the same seed. It is off by default.
+    pub fn set_clifford_simulation(&mut self, enabled: bool) {
+        self.clifford_simulation = enabled;
+    }
+
+    /// Sets the order in which the results of a register measurement map to bit significance in
+    /// [`Interpreter::results_as_int`] and [`Interpreter::results_as_bit_string`].
+    pub fn set_result_endianness(&mut self, endianness: Endianness) {
+        self.result_endianness = endianness;
+    }
+
+    /// Converts the results of a register measurement to the integer they encode, using the
+    /// endianness set with [`Interpreter::set_result_endianness`].
+    /// Returns `None` if the value is not an array of results.
+    #[must_use]
+    pub fn results_as_int(&self, value: &Value) -> Option<BigUint> {
+        let bits = self.result_bits(value)?;
+        Some(
+            bits.iter()
+                .fold(BigUint::default(), |int, &bit| (int << 1u8) + u8::from(bit)),
+        )
+    }
+
+    /// Formats the results of a register measurement as a bit string with the most significant bit
+    /// first, e.g. `"110"`, using the endianness set with [`Interpreter::set_result_endianness`].
+    /// Returns `None` if the value is not an array of results.
+    #[must_use]
+    pub fn results_as_bit_string(&self, value: &Value) -> Option<String> {
+        let bits = self.result_bits(value)?;
+        Some(
+            bits.iter()
+                .map(|&bit| if bit { '1' } else { '0' })
+                .collect(),
+        )
+    }
+
+    /// Returns the results of a register measurement as bits, most significant first.
+    fn result_bits(&self, value: &Value) -> Option<Vec<bool>> {
+        let Value::Array(results) = value else {
+            return None;
+        };
+        let mut bits = results
+            .iter()
+            .map(|result| match result {
+                Value::Result(val::Result::Val(bit)) => Some(*bit),
+                _ => None,
+            })
+            .collect::<Option<Vec<_>>>()?;
+        if self.result_endianness == Endianness::Little {
+            bits.reverse();
+        }
+        Some(bits)
+    }
+
+    /// Sets whether circuits generated by [`Interpreter::circuit`] group the gates
+    /// applied by each operation call under the name of the callable.
+    pub fn set_circuit_grouping(&mut self, enabled: bool) {
+        self.group_circuit_operations = enabled;
+    }
+
+    /// Sets a breakpoint on every statement of the source package that starts within `span`.
+    /// When `condition` is given, it is a Q# boolean expression that is evaluated in the paused
+    /// scope each time one of those statements is reached, and execution only pauses when it
+    /// evaluates to `true`. The condition can refer to the local variables in scope at the
+    /// statement and call functions, but not operations.
+    /// Setting a breakpoint on a statement again replaces its condition.
+    /// Breakpoints take effect when stepping with [`Debugger::eval_step`].
+    /// # Errors
+    /// Returns a vector of errors if the condition fails to compile, in which case no
+    /// breakpoint is set.
+    pub fn set_breakpoint(
+        &mut self,
+        span: Span,
+        condition: Option<String>,
+    ) -> std::result::Result<(), Vec<Error>> {
+        let package = self.fir_store.get(self.source_package);
+        let stmts = package
+            .stmts
+            .iter()
+            .filter(|(_, stmt)| {
+                stmt.span != Span::default() && span.lo <= stmt.span.lo && stmt.span.lo < span.hi
+            })
+            .map(|(id, _)| id)
+            .collect::<Vec<_>>();
+        let mut conditions = Vec::new();
+        if let Some(condition) = condition {
+            // Statements with the same locals in scope share one compiled condition.
+            let mut compiled: FxHashMap<Vec<fir::LocalVarId>, Rc<BreakpointCondition>> =
+                FxHashMap::default();
+            for &stmt in &stmts {
+                let locals = debug::locals_in_scope(self.fir_store.get(self.source_package), stmt);
+                let ids = locals.iter().map(|local| local.id).collect::<Vec<_>>();
+                let compiled = match compiled.get(&ids) {
+                    Some(compiled) => compiled.clone(),
+                    None => {
+                        let new = Rc::new(self.compile_breakpoint_condition(&locals, &condition)?);
+                        compiled.insert(ids, new.clone());
+                        new
+                    }
+                };
+                conditions.push(Some(compiled));
+            }
+        } else {
+            conditions.resize(stmts.len(), None);
+        }
+        self.breakpoints.extend(stmts.into_iter().zip(conditions));
+        Ok(())
+    }
+
+    /// Removes all breakpoints set through [`Interpreter::set_breakpoint`].
+    pub fn clear_breakpoints(&mut self) {
+        self.breakpoints.clear();
+    }
+
+    /// Compiles a breakpoint condition into a function that takes the given locals as
+    /// parameters. Locals whose type cannot be written in Q#, like those of a type parameter,
+    /// are left out, so the condition cannot refer to them.
+    fn compile_breakpoint_condition(
+        &mut self,
+        locals: &[debug::Local],
+        condition: &str,
+    ) -> std::result::Result<BreakpointCondition, Vec<Error>> {
+        let (params, locals): (Vec<_>, Vec<_>) = locals
+            .iter()
+            .filter(|local| !local.name.starts_with('@'))
+            .filter_map(|local| {
+                debug::ty_source(&self.fir_store, self.source_package, &local.ty)
+                    .map(|ty| (format!("{} : {ty}", local.name), local.id))
+            })
+            .unzip();
+        let label = self.next_line_label();
+        let name = format!("__{label}_breakpoint_condition");
+        let increment = self
+            .compiler
+            .compile_fragments_fail_fast(
+                &label,
+                &format!(
+                    "function {name}({}) : Bool {{ {condition} }}",
+                    params.join(", ")
+                ),
+            )
+            .map_err(into_errors)?;
+        self.lower(&increment)?;
+        self.compiler.update(increment);
+        let item = self
+            .fir_store
+            .get(self.package)
+            .items
+            .iter()
+            .find_map(|(id, item)| match &item.kind {
+                fir::ItemKind::Callable(decl) if *decl.name.name == name => Some(id),
+                _ => None,
+            })
+            .expect("compiled condition should be a callable in the interpreter package");
+        Ok(BreakpointCondition {
+            callable: Value::Global((self.package, item).into(), FunctorApp::default()),
+            locals,
+        })
+    }
+
+    /// Evaluates a breakpoint condition with the current values of the locals of the paused
+    /// frame. The condition runs on a backend that applies no gates, so that it cannot change
+    /// the state of the simulator, and in an environment of its own, since leaving the frame of
+    /// the condition would otherwise leave the paused frame.
+    fn eval_breakpoint_condition(
+        &mut self,
+        receiver: &mut impl Receiver,
+        condition: &BreakpointCondition,
+    ) -> std::result::Result<bool, Vec<Error>> {
+        let mut args = condition
+            .locals
+            .iter()
+            .map(|&id| {
+                self.env
+                    .get(id)
+                    .expect("local in scope at the breakpoint should be bound")
+                    .value
+                    .clone()
+            })
+            .collect::<Vec<_>>();
+        let args = match args.len() {
+            0 => Value::unit(),
+            1 => args.remove(0),
+            _ => Value::Tuple(args.into()),
+        };
+        let value = qsc_eval::invoke(
+            self.package,
+            &EvalOptions {
+                classical_rng: None,
+                events: None,
+                log: None,
+                ..self.eval_options.clone()
+            },
+            &self.fir_store,
+            &mut Env::default(),
+            &mut DryRun::default(),
+            receiver,
+            condition.callable.clone(),
+            args,
+        )
+        .map_err(|(error, call_stack)| {
+            eval_error(
+                self.compiler.package_store(),
+                &self.fir_store,
+                call_stack,
+                error,
+            )
+        })?;
+        Ok(value == Value::Bool(true))
     }
+
+    /// Returns the current value of the in-scope variable with the given name, if any.
+    /// When several variables share the name, the one bound most recently in the innermost
+    /// scope is returned, matching what the name refers to in source at this point.
+    #[must_use]
+    pub fn read_variable(&self, name: &str) -> Option<Value> {
+        self.env
+            .get_variables_in_top_frame()
+            .into_iter()
+            .rev()
+            .find(|v| &*v.name == name)
+            .map(|v| v.value)
+    }
+
     /// Executes the entry expression until the end of execution.
     /// # Errors
     /// Returns a vector of errors if evaluating the entry point fails.
@@ -295,7 +673,7 @@ impl Interpreter {
         let graph = self.get_entry_exec_graph()?;
         eval(
             self.source_package,
-            self.classical_seed,
+            &self.eval_options,
             graph,
             self.compiler.package_store(),
             &self.fir_store,
@@ -305,6 +683,101 @@ impl Interpreter {
         )
     }
 
+    /// Executes the entry expression until the end of execution, recording every gate,
+    /// measurement, qubit allocation and release, message and assignment in an [`EventLog`].
+    /// Messages are recorded instead of being output.
+    /// # Errors
+    /// Returns a vector of errors if evaluating the entry point fails.
+    pub fn eval_entry_recording(&mut self) -> std::result::Result<(Value, EventLog), Vec<Error>> {
+        let graph = self.get_entry_exec_graph()?;
+        let log = Rc::new(RefCell::new(Vec::new()));
+        let mut sink = std::io::sink();
+        let mut out = GenericReceiver::new(&mut sink);
+        let value = eval(
+            self.source_package,
+            &EvalOptions {
+                log: Some(log.clone()),
+                ..self.eval_options.clone()
+            },
+            graph,
+            self.compiler.package_store(),
+            &self.fir_store,
+            &mut Env::default(),
+            &mut self.sim,
+            &mut out,
+        )?;
+        Ok((value, EventLog(log.take())))
+    }
+
+    /// Executes the entry expression until the end of execution, writing a line to `writer`
+    /// for each gate, reset and measurement as it is applied, e.g. `H q0`, `CNOT q0 q1` or
+    /// `M q1 -> One`.
+    /// # Errors
+    /// Returns a vector of errors if evaluating the entry point fails.
+    pub fn eval_entry_with_trace_writer(
+        &mut self,
+        writer: &mut dyn std::io::Write,
+        receiver: &mut impl Receiver,
+    ) -> std::result::Result<Value, Vec<Error>> {
+        let graph = self.get_entry_exec_graph()?;
+        eval(
+            self.source_package,
+            &self.eval_options,
+            graph,
+            self.compiler.package_store(),
+            &self.fir_store,
+            &mut Env::default(),
+            &mut TraceWriter::new(&mut self.sim, writer),
+            receiver,
+        )
+    }
+
+    /// Executes the entry expression until the end of execution, taking the outcome of each
+    /// measurement in order from `results` instead of sampling it, e.g. to replay the measurements
+    /// of a run on hardware through the classical logic of the program.
+    /// # Errors
+    /// Returns a vector of errors if evaluating the entry point fails, including when the program
+    /// measures more times than there are results.
+    pub fn eval_entry_with_result_stream(
+        &mut self,
+        results: &[Result],
+        receiver: &mut impl Receiver,
+    ) -> std::result::Result<Value, Vec<Error>> {
+        let graph = self.get_entry_exec_graph()?;
+        eval(
+            self.source_package,
+            &self.eval_options,
+            graph,
+            self.compiler.package_store(),
+            &self.fir_store,
+            &mut Env::default(),
+            &mut ResultStream::new(&mut self.sim, results),
+            receiver,
+        )
+    }
+
+    /// Executes the classical logic of the entry expression without simulating its quantum state,
+    /// with every measurement returning `Zero` and output discarded. This quickly surfaces runtime
+    /// errors, such as an out-of-range index or a failed `Fact`, along that path.
+    /// # Errors
+    /// Returns a vector of errors if evaluating the entry point fails.
+    pub fn dry_run(&mut self) -> std::result::Result<(), Vec<Error>> {
+        let graph = self.get_entry_exec_graph()?;
+        let mut sink = std::io::sink();
+        let mut out = GenericReceiver::new(&mut sink);
+        eval(
+            self.source_package,
+            &self.eval_options,
+            graph,
+            self.compiler.package_store(),
+            &self.fir_store,
+            &mut Env::default(),
+            &mut DryRun::default(),
+            &mut out,
+        )
+        .map(|_| ())
+    }
+
     /// Executes the entry expression until the end of execution, using the given simulator backend
     /// and a new instance of the environment.
     pub fn eval_entry_with_sim(
@@ -318,7 +791,7 @@ impl Interpreter {
         }
         eval(
             self.source_package,
-            self.classical_seed,
+            &self.eval_options,
             graph,
             self.compiler.package_store(),
             &self.fir_store,
@@ -394,7 +867,7 @@ impl Interpreter {
 
         eval(
             self.package,
-            self.classical_seed,
+            &self.eval_options,
             graph.into(),
             self.compiler.package_store(),
             &self.fir_store,
@@ -406,12 +879,96 @@ impl Interpreter {
 
     /// Runs the given entry expression on a new instance of the environment and simulator,
     /// but using the current compilation.
+    ///
+    /// With [`Interpreter::set_clifford_simulation`] enabled, the simulation uses a stabilizer
+    /// tableau for as long as the program only applies Clifford gates and measurements.
     pub fn run(
         &mut self,
         receiver: &mut impl Receiver,
         expr: &str,
     ) -> std::result::Result<InterpretResult, Vec<Error>> {
-        self.run_with_sim(&mut SparseSim::new(), receiver, expr)
+        if self.clifford_simulation {
+            self.run_with_sim(&mut StabilizerSim::new(), receiver, expr)
+        } else {
+            self.run_with_sim(&mut SparseSim::new(), receiver, expr)
+        }
+    }
+
+    /// Calls the callable named by `path`, such as `Test.Main`, with the given arguments on a new
+    /// instance of the environment and simulator, so that each call starts from a fresh state.
+    /// An empty `args` passes `()`, a single value is passed as is, and several values are passed
+    /// as a tuple. Output from the callable is discarded.
+    /// `path` is only looked up as a namespace-qualified name and is never evaluated as Q#.
+    /// # Errors
+    /// Returns a vector of errors if `path` does not name a callable, if `args` do not match the
+    /// callable's input type, or if evaluating the call fails.
+    pub fn eval_callable_by_name(
+        &mut self,
+        path: &str,
+        args: &[Value],
+    ) -> std::result::Result<Value, Vec<Error>> {
+        let (id, input) = self
+            .find_callable(path)
+            .ok_or_else(|| vec![Error::CallableNotFound(path.to_string())])?;
+        let args = match args {
+            [] => Value::unit(),
+            [arg] => arg.clone(),
+            args => Value::Tuple(args.into()),
+        };
+        if !value_matches_ty(&args, &input) {
+            return Err(vec![Error::ArgumentMismatch(input.to_string())]);
+        }
+
+        let mut sink = std::io::sink();
+        let mut out = GenericReceiver::new(&mut sink);
+
+        let mut sim = SparseSim::new();
+        if self.quantum_seed.is_some() {
+            sim.set_seed(self.quantum_seed);
+        }
+        qsc_eval::invoke(
+            self.package,
+            &self.eval_options,
+            &self.fir_store,
+            &mut Env::default(),
+            &mut sim,
+            &mut out,
+            Value::Global(id, FunctorApp::default()),
+            args,
+        )
+        .map_err(|(error, call_stack)| {
+            eval_error(
+                self.compiler.package_store(),
+                &self.fir_store,
+                call_stack,
+                error,
+            )
+        })
+    }
+
+    /// Sets the state of the next qubit allocated by a `borrow` statement in the interpreter's
+    /// simulator, given as the amplitudes of |0⟩ and |1⟩, instead of |0⟩. This allows testing that
+    /// operations restore borrowed qubits: when the qubit is returned, evaluation fails with a
+    /// runtime error unless it is back in that state. Qubits borrowed by top-level statements are
+    /// never returned, so they are not checked.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `amplitudes` does not have exactly two entries or if both are zero.
+    #[allow(clippy::needless_pass_by_value)]
+    pub fn set_borrow_state(&mut self, amplitudes: Vec<Complex64>) {
+        let [alpha, beta] = amplitudes[..] else {
+            panic!("borrow state should have two amplitudes");
+        };
+        self.sim.main.set_borrow_state(alpha, beta);
+    }
+
+    /// Returns the ids of the qubits that are still allocated in the simulator, such as after
+    /// [`Interpreter::eval_entry`] completes. A program that releases every qubit it allocates
+    /// leaves this empty.
+    #[must_use]
+    pub fn leaked_qubits(&self) -> Vec<usize> {
+        self.sim.main.allocated_qubits()
     }
 
     /// Gets the current quantum state of the simulator.
@@ -419,6 +976,19 @@ impl Interpreter {
         self.sim.capture_quantum_state()
     }
 
+    /// Returns the purity `Tr(ρ²)` of the full simulator state, such as after [`Interpreter::eval_entry`],
+    /// where `ρ = |ψ⟩⟨ψ|` is the density matrix of the state of all allocated qubits.
+    /// The default simulator tracks a pure state vector, so this is `1.0` up to numerical error,
+    /// and a value that drifts away from it indicates that the state lost its normalization.
+    pub fn final_state_purity(&mut self) -> f64 {
+        let (state, _) = self.sim.capture_quantum_state();
+        let norm: f64 = state
+            .iter()
+            .map(|(_, amplitude)| amplitude.norm_sqr())
+            .sum();
+        norm * norm
+    }
+
     /// Get the current circuit representation of the program.
     pub fn get_circuit(&self) -> Circuit {
         self.sim.chained.snapshot()
@@ -457,6 +1027,7 @@ impl Interpreter {
             self.capabilities,
             Some(compute_properties),
             &entry,
+            true,
         )
         .map_err(|e| {
             let hir_package_id = match e.span() {
@@ -484,9 +1055,11 @@ impl Interpreter {
     /// e.g. `Sample.Main` , `qs => H(qs[0])`
     ///
     /// If `simulate` is specified, the program is simulated and the resulting
-    /// circuit is returned (a.k.a. trace mode). Otherwise, the circuit is generated without
-    /// simulation. In this case circuit generation may fail if the program contains dynamic
-    /// behavior (quantum operations that are dependent on measurement results).
+    /// circuit is returned (a.k.a. trace mode), with the gates applied in branches taken because
+    /// of a measurement outcome drawn as classically controlled by that measurement. Otherwise,
+    /// the circuit is generated without simulation. In this case circuit generation may fail if
+    /// the program contains dynamic behavior (quantum operations that are dependent on
+    /// measurement results).
     pub fn circuit(
         &mut self,
         entry: CircuitEntryPoint,
@@ -504,14 +1077,15 @@ impl Interpreter {
         };
 
         let circuit = if simulate {
-            let mut sim = sim_circuit_backend();
+            let mut sim = CircuitTracer::new(sim_circuit_config(self.group_circuit_operations));
 
             self.run_with_sim_no_output(entry_expr, &mut sim)?;
 
-            sim.chained.finish()
+            sim.finish()
         } else {
             let mut sim = CircuitBuilder::new(CircuitConfig {
                 base_profile: self.capabilities.is_empty(),
+                group_operations: self.group_circuit_operations,
             });
 
             self.run_with_sim_no_output(entry_expr, &mut sim)?;
@@ -538,7 +1112,7 @@ impl Interpreter {
 
         Ok(eval(
             self.package,
-            self.classical_seed,
+            &self.eval_options,
             graph.into(),
             self.compiler.package_store(),
             &self.fir_store,
@@ -570,7 +1144,7 @@ impl Interpreter {
 
         eval(
             package_id,
-            self.classical_seed,
+            &self.eval_options,
             graph,
             self.compiler.package_store(),
             &self.fir_store,
@@ -616,23 +1190,28 @@ impl Interpreter {
         unit_addition: &qsc_frontend::incremental::Increment,
     ) -> core::result::Result<(Vec<ExecGraphNode>, Option<PackageStoreComputeProperties>), Vec<Error>>
     {
+        let folded_hir;
+        let hir = if self.fold_constants {
+            folded_hir = folded(&unit_addition.hir);
+            &folded_hir
+        } else {
+            &unit_addition.hir
+        };
         if self.capabilities != TargetCapabilityFlags::all() {
-            return self.run_fir_passes(unit_addition);
+            return self.run_fir_passes(hir);
         }
         let fir_package = self.fir_store.get_mut(self.package);
-        self.lowerer
-            .lower_and_update_package(fir_package, &unit_addition.hir);
+        self.lowerer.lower_and_update_package(fir_package, hir);
         Ok((self.lowerer.take_exec_graph(), None))
     }
 
     fn run_fir_passes(
         &mut self,
-        unit: &qsc_frontend::incremental::Increment,
+        hir: &qsc_hir::hir::Package,
     ) -> std::result::Result<(Vec<ExecGraphNode>, Option<PackageStoreComputeProperties>), Vec<Error>>
     {
         let fir_package = self.fir_store.get_mut(self.package);
-        self.lowerer
-            .lower_and_update_package(fir_package, &unit.hir);
+        self.lowerer.lower_and_update_package(fir_package, hir);
 
         let cap_results =
             PassContext::run_fir_passes_on_fir(&self.fir_store, self.package, self.capabilities);
@@ -669,6 +1248,35 @@ impl Interpreter {
     /// and return the Item ID and function application for the callable.
     /// Examples: "Microsoft.Quantum.Diagnostics.DumpMachine", "(qs: Qubit[]) => H(qs[0])",
     /// "Controlled SWAP"
+    /// Finds the callable with the namespace-qualified name `path` in any package, returning its
+    /// id and input type.
+    fn find_callable(&self, path: &str) -> Option<(fir::StoreItemId, qsc_fir::ty::Ty)> {
+        let (namespace, name) = path.rsplit_once('.')?;
+        self.fir_store.iter().find_map(|(package_id, package)| {
+            package.items.values().find_map(|item| {
+                let fir::ItemKind::Callable(decl) = &item.kind else {
+                    return None;
+                };
+                let parent = package.items.get(item.parent?)?;
+                match &parent.kind {
+                    fir::ItemKind::Namespace(ns, _)
+                        if &*ns.name == namespace && &*decl.name.name == name =>
+                    {
+                        let input = package.pats.get(decl.input)?.ty.clone();
+                        Some((
+                            fir::StoreItemId {
+                                package: package_id,
+                                item: item.id,
+                            },
+                            input,
+                        ))
+                    }
+                    _ => None,
+                }
+            })
+        })
+    }
+
     fn eval_to_operation(
         &mut self,
         operation_expr: &str,
@@ -696,22 +1304,60 @@ impl Interpreter {
     }
 }
 
-fn sim_circuit_backend() -> BackendChain<SparseSim, CircuitBuilder> {
+/// Checks that `value` has the shape of `ty`. Type parameters and user-defined types accept any
+/// value.
+fn value_matches_ty(value: &Value, ty: &qsc_fir::ty::Ty) -> bool {
+    use qsc_fir::ty::{Prim, Ty};
+    match (value, ty) {
+        (_, Ty::Param(_) | Ty::Udt(_) | Ty::Infer(_) | Ty::Err)
+        | (Value::Closure(_) | Value::Global(..), Ty::Arrow(_))
+        | (Value::BigInt(_), Ty::Prim(Prim::BigInt))
+        | (Value::Bool(_), Ty::Prim(Prim::Bool))
+        | (Value::Double(_), Ty::Prim(Prim::Double))
+        | (Value::Int(_), Ty::Prim(Prim::Int))
+        | (Value::Pauli(_), Ty::Prim(Prim::Pauli))
+        | (Value::Qubit(_), Ty::Prim(Prim::Qubit))
+        | (
+            Value::Range(_),
+            Ty::Prim(Prim::Range | Prim::RangeTo | Prim::RangeFrom | Prim::RangeFull),
+        )
+        | (Value::Result(_), Ty::Prim(Prim::Result))
+        | (Value::String(_), Ty::Prim(Prim::String)) => true,
+        (Value::Array(values), Ty::Array(item)) => {
+            values.iter().all(|value| value_matches_ty(value, item))
+        }
+        (Value::Tuple(values), Ty::Tuple(items)) => {
+            values.len() == items.len()
+                && values
+                    .iter()
+                    .zip(items)
+                    .all(|(value, item)| value_matches_ty(value, item))
+        }
+        _ => false,
+    }
+}
+
+fn sim_circuit_backend(group_operations: bool) -> BackendChain<SparseSim, CircuitBuilder> {
     BackendChain::new(
         SparseSim::new(),
-        CircuitBuilder::new(CircuitConfig {
-            // When using in conjunction with the simulator,
-            // the circuit builder should *not* perform base profile
-            // decompositions, in order to match the simulator's behavior.
-            //
-            // Note that conditional compilation (e.g. @Config(Base) attributes)
-            // will still respect the selected profile. This also
-            // matches the behavior of the simulator.
-            base_profile: false,
-        }),
+        CircuitBuilder::new(sim_circuit_config(group_operations)),
     )
 }
 
+fn sim_circuit_config(group_operations: bool) -> CircuitConfig {
+    CircuitConfig {
+        // When using in conjunction with the simulator,
+        // the circuit builder should *not* perform base profile
+        // decompositions, in order to match the simulator's behavior.
+        //
+        // Note that conditional compilation (e.g. @Config(Base) attributes)
+        // will still respect the selected profile. This also
+        // matches the behavior of the simulator.
+        base_profile: false,
+        group_operations,
+    }
+}
+
 /// Describes the entry point for circuit generation.
 pub enum CircuitEntryPoint {
     /// An operation. This must be a callable name or a lambda
@@ -724,6 +1370,9 @@ pub enum CircuitEntryPoint {
     EntryPoint,
 }
 
+/// The number of statement boundaries that [`Debugger::step_back`] can return to.
+pub const STEP_BACK_LIMIT: usize = 100;
+
 /// A debugger that enables step-by-step evaluation of code
 /// and inspecting state in the interpreter.
 pub struct Debugger {
@@ -733,6 +1382,21 @@ pub struct Debugger {
     position_encoding: Encoding,
     /// The current state of the evaluator.
     state: State,
+    /// The generator for classical randomness used by the evaluator, kept here so that
+    /// snapshots can save and restore its position in the sequence.
+    rng: Rc<RefCell<StdRng>>,
+    /// The statement boundaries that can be returned to, oldest first and at most
+    /// [`STEP_BACK_LIMIT`] of them. Each is a snapshot taken before a step.
+    snapshots: VecDeque<Snapshot>,
+}
+
+/// The classical and quantum state of the debugger at a statement boundary, see
+/// [`Debugger::step_back`].
+struct Snapshot {
+    state: State,
+    env: Env,
+    sim: BackendChain<SparseSim, CircuitBuilder>,
+    rng: StdRng,
 }
 
 impl Debugger {
@@ -752,39 +1416,119 @@ impl Debugger {
         let source_package_id = interpreter.source_package;
         let unit = interpreter.fir_store.get(source_package_id);
         let entry_exec_graph = unit.entry_exec_graph.clone();
+        let rng = Rc::new(RefCell::new(StdRng::from_entropy()));
+        let mut state = State::new(source_package_id, entry_exec_graph, None);
+        state.set_rng(rng.clone());
         Ok(Self {
             interpreter,
             position_encoding,
-            state: State::new(source_package_id, entry_exec_graph, None),
+            state,
+            rng,
+            snapshots: VecDeque::new(),
         })
     }
 
     /// Resumes execution with specified `StepAction`.
+    /// Execution pauses at the given `breakpoints` as well as at those set with
+    /// [`Debugger::set_breakpoint`] whose condition, if any, holds.
     /// # Errors
-    /// Returns a vector of errors if evaluating the entry point fails.
+    /// Returns a vector of errors if evaluating the entry point fails,
+    /// or if a breakpoint condition fails to compile or evaluate.
     pub fn eval_step(
         &mut self,
         receiver: &mut impl Receiver,
         breakpoints: &[StmtId],
         step: StepAction,
     ) -> std::result::Result<StepResult, Vec<Error>> {
-        self.state
-            .eval(
-                &self.interpreter.fir_store,
-                &mut self.interpreter.env,
-                &mut self.interpreter.sim,
-                receiver,
-                breakpoints,
-                step,
-            )
-            .map_err(|(error, call_stack)| {
-                eval_error(
-                    self.interpreter.compiler.package_store(),
+        if self.snapshots.len() == STEP_BACK_LIMIT {
+            self.snapshots.pop_front();
+        }
+        self.snapshots.push_back(Snapshot {
+            state: self.state.clone(),
+            env: self.interpreter.env.clone(),
+            sim: self.interpreter.sim.clone(),
+            rng: self.rng.borrow().clone(),
+        });
+        self.resume(receiver, breakpoints, step)
+    }
+
+    /// Reverts to the statement boundary before the most recent step, restoring both the
+    /// classical and the quantum state from the snapshot taken before that step. Output
+    /// produced by the step is not taken back.
+    /// # Errors
+    /// Returns [`Error::NoStepHistory`] if no step has been taken, or if the last
+    /// [`STEP_BACK_LIMIT`] steps have already been undone.
+    pub fn step_back(&mut self) -> std::result::Result<(), Vec<Error>> {
+        let snapshot = self
+            .snapshots
+            .pop_back()
+            .ok_or_else(|| vec![Error::NoStepHistory])?;
+        self.state = snapshot.state;
+        self.interpreter.env = snapshot.env;
+        self.interpreter.sim = snapshot.sim;
+        *self.rng.borrow_mut() = snapshot.rng;
+        Ok(())
+    }
+
+    fn resume(
+        &mut self,
+        receiver: &mut impl Receiver,
+        breakpoints: &[StmtId],
+        step: StepAction,
+    ) -> std::result::Result<StepResult, Vec<Error>> {
+        let mut all_breakpoints = breakpoints.to_vec();
+        all_breakpoints.extend(self.interpreter.breakpoints.keys().copied());
+        loop {
+            let result = self
+                .state
+                .eval(
                     &self.interpreter.fir_store,
-                    call_stack,
-                    error,
+                    &mut self.interpreter.env,
+                    &mut self.interpreter.sim,
+                    receiver,
+                    &all_breakpoints,
+                    step,
                 )
-            })
+                .map_err(|(error, call_stack)| {
+                    eval_error(
+                        self.interpreter.compiler.package_store(),
+                        &self.interpreter.fir_store,
+                        call_stack,
+                        error,
+                    )
+                })?;
+            if let StepResult::BreakpointHit(stmt) = result {
+                if !breakpoints.contains(&stmt) {
+                    if let Some(Some(condition)) = self.interpreter.breakpoints.get(&stmt).cloned()
+                    {
+                        if !self
+                            .interpreter
+                            .eval_breakpoint_condition(receiver, &condition)?
+                        {
+                            continue;
+                        }
+                    }
+                }
+            }
+            return Ok(result);
+        }
+    }
+
+    /// Sets a breakpoint, optionally conditional, on the statements starting within `span`.
+    /// See [`Interpreter::set_breakpoint`].
+    /// # Errors
+    /// Returns a vector of errors if the condition fails to compile.
+    pub fn set_breakpoint(
+        &mut self,
+        span: Span,
+        condition: Option<String>,
+    ) -> std::result::Result<(), Vec<Error>> {
+        self.interpreter.set_breakpoint(span, condition)
+    }
+
+    /// Removes all breakpoints set through [`Debugger::set_breakpoint`].
+    pub fn clear_breakpoints(&mut self) {
+        self.interpreter.clear_breakpoints();
     }
 
     #[must_use]
@@ -864,6 +1608,13 @@ impl Debugger {
             .collect()
     }
 
+    /// Returns the current value of the in-scope variable with the given name at the
+    /// point where execution is paused. See [`Interpreter::read_variable`].
+    #[must_use]
+    pub fn read_variable(&self, name: &str) -> Option<Value> {
+        self.interpreter.read_variable(name)
+    }
+
     fn source_package(&self) -> &CompileUnit {
         self.interpreter
             .compiler
@@ -873,11 +1624,18 @@ impl Debugger {
     }
 }
 
+/// Returns a copy of the package with its constant sub-expressions folded.
+fn folded(package: &qsc_hir::hir::Package) -> qsc_hir::hir::Package {
+    let mut package = package.clone();
+    qsc_passes::fold_constants(&mut package);
+    package
+}
+
 /// Wrapper function for `qsc_eval::eval` that handles error conversion.
 #[allow(clippy::too_many_arguments)]
 fn eval(
     package: PackageId,
-    classical_seed: Option<u64>,
+    options: &EvalOptions,
     exec_graph: Rc<[ExecGraphNode]>,
     package_store: &PackageStore,
     fir_store: &fir::PackageStore,
@@ -885,16 +1643,113 @@ fn eval(
     sim: &mut impl Backend<ResultType = impl Into<val::Result>>,
     receiver: &mut impl Receiver,
 ) -> InterpretResult {
-    qsc_eval::eval(
-        package,
-        classical_seed,
-        exec_graph,
-        fir_store,
-        env,
-        sim,
-        receiver,
-    )
-    .map_err(|(error, call_stack)| eval_error(package_store, fir_store, call_stack, error))
+    qsc_eval::eval(package, options, exec_graph, fir_store, env, sim, receiver)
+        .map_err(|(error, call_stack)| eval_error(package_store, fir_store, call_stack, error))
+}
+
+/// A breakpoint condition compiled into a function of the locals in scope at its statement.
+struct BreakpointCondition {
+    /// The function that evaluates the condition.
+    callable: Value,
+    /// The locals passed to the function, in the order of its parameters.
+    locals: Vec<fir::LocalVarId>,
+}
+
+/// The events of an evaluation in the order they happened, see
+/// [`Interpreter::eval_entry_recording`].
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct EventLog(Vec<LogEvent>);
+
+impl EventLog {
+    #[must_use]
+    pub fn events(&self) -> &[LogEvent] {
+        &self.0
+    }
+
+    /// Serializes the log to a JSON array for viewers that replay it. Each event becomes an object
+    /// whose `kind` is one of `assign`, `gate`, `measure`, `measurePauli`, `reset`, `allocate`,
+    /// `release` or `message`, with the remaining fields depending on the kind. Values are given
+    /// as Q# literals.
+    #[must_use]
+    pub fn to_json(&self) -> String {
+        let events = self
+            .0
+            .iter()
+            .map(|event| match event {
+                LogEvent::Assign(Event::Assign {
+                    name,
+                    old,
+                    new,
+                    span,
+                }) => json!({
+                    "kind": "assign",
+                    "name": name.as_ref(),
+                    "old": old.to_string(),
+                    "new": new.to_string(),
+                    "span": {
+                        "package": usize::from(span.package),
+                        "lo": span.span.lo,
+                        "hi": span.span.hi,
+                    },
+                }),
+                LogEvent::Gate { gate } => {
+                    let (name, params) = match *gate {
+                        GateOp::Ccx(..) => ("CCX", vec![]),
+                        GateOp::Cx(..) => ("CX", vec![]),
+                        GateOp::Cy(..) => ("CY", vec![]),
+                        GateOp::Cz(..) => ("CZ", vec![]),
+                        GateOp::H(_) => ("H", vec![]),
+                        GateOp::Rx(theta, _) => ("Rx", vec![theta]),
+                        GateOp::Rxx(theta, ..) => ("Rxx", vec![theta]),
+                        GateOp::Ry(theta, _) => ("Ry", vec![theta]),
+                        GateOp::Ryy(theta, ..) => ("Ryy", vec![theta]),
+                        GateOp::Rz(theta, _) => ("Rz", vec![theta]),
+                        GateOp::Rzz(theta, ..) => ("Rzz", vec![theta]),
+                        GateOp::Sadj(_) => ("Adjoint S", vec![]),
+                        GateOp::S(_) => ("S", vec![]),
+                        GateOp::Swap(..) => ("SWAP", vec![]),
+                        GateOp::Tadj(_) => ("Adjoint T", vec![]),
+                        GateOp::T(_) => ("T", vec![]),
+                        GateOp::X(_) => ("X", vec![]),
+                        GateOp::Y(_) => ("Y", vec![]),
+                        GateOp::Z(_) => ("Z", vec![]),
+                    };
+                    json!({
+                        "kind": "gate",
+                        "gate": name,
+                        "params": params,
+                        "qubits": gate.qubits(),
+                    })
+                }
+                LogEvent::Measure {
+                    qubit,
+                    result,
+                    reset,
+                } => json!({
+                    "kind": "measure",
+                    "qubit": qubit,
+                    "result": Value::Result(*result).to_string(),
+                    "reset": reset,
+                }),
+                LogEvent::MeasurePauli { paulis, result } => json!({
+                    "kind": "measurePauli",
+                    "bases": paulis
+                        .iter()
+                        .map(|&(basis, _)| Value::Pauli(basis).to_string())
+                        .collect::<Vec<_>>(),
+                    "qubits": paulis.iter().map(|&(_, q)| q).collect::<Vec<_>>(),
+                    "result": Value::Result(*result).to_string(),
+                }),
+                LogEvent::Reset { qubit } => json!({ "kind": "reset", "qubit": qubit }),
+                LogEvent::Allocate { qubit } => json!({ "kind": "allocate", "qubit": qubit }),
+                LogEvent::Release { qubit } => json!({ "kind": "release", "qubit": qubit }),
+                LogEvent::Message { message } => {
+                    json!({ "kind": "message", "message": message.as_ref() })
+                }
+            })
+            .collect();
+        serde_json::Value::Array(events).to_string()
+    }
 }
 
 /// Represents a stack frame for debugging.
@@ -1000,7 +1855,7 @@ impl<'a> Visitor<'a> for BreakpointCollector<'a> {
     }
 }
 
-fn eval_error(
+pub(crate) fn eval_error(
     package_store: &PackageStore,
     fir_store: &fir::PackageStore,
     call_stack: Vec<Frame>,
@@ -11,6 +11,7 @@ use qsc_data_structures::language_features::LanguageFeatures;
 use qsc_eval::output::GenericReceiver;
 use qsc_frontend::compile::SourceMap;
 use qsc_passes::PackageType;
+use std::fmt::Write;
 
 fn interpreter(code: &str, profile: Profile) -> Interpreter {
     let sources = SourceMap::new([("test.qs".into(), code.into())], None);
@@ -284,16 +285,17 @@ fn unrestricted_profile_result_comparison() {
     let mut r = GenericReceiver::new(&mut out);
 
     // Result comparisons are okay when tracing
-    // circuit with the simulator.
+    // circuit with the simulator, and the gate
+    // in the branch is controlled by both results.
     let circ = interpreter
         .circuit(CircuitEntryPoint::EntryPoint, true)
         .expect("circuit generation should succeed");
 
     expect![[r"
-        q_0    ── H ──── M ──── X ─── |0〉 ─
-                         ╘═════════════════
-        q_1    ── H ──── M ─── |0〉 ────────
-                         ╘═════════════════
+        q_0    ── H ──── M ──── X ─── |0〉 ─
+                         ╘═════ ● ═════════
+        q_1    ── H ──── M ─────┼──── |0〉 ─
+                         ╘═════ ● ═════════
     "]]
     .assert_eq(&circ.to_string());
 
@@ -315,6 +317,45 @@ fn unrestricted_profile_result_comparison() {
     .assert_eq(&circuit.to_string());
 }
 
+#[test]
+fn branch_on_measurement_result_is_classically_controlled() {
+    let mut interpreter = interpreter(
+        r"
+            namespace Test {
+                @EntryPoint()
+                operation Main() : Unit {
+                    use (q0, q1) = (Qubit(), Qubit());
+                    X(q0);
+                    let r = M(q0);
+                    if r == One {
+                        X(q1);
+                    }
+                    Z(q1);
+                    ResetAll([q0, q1]);
+                }
+            }
+        ",
+        Profile::Unrestricted,
+    );
+
+    let circ = interpreter
+        .circuit(CircuitEntryPoint::EntryPoint, true)
+        .expect("circuit generation should succeed");
+
+    expect![[r"
+        q_0    ── X ──── M ─── |0〉 ───────────────
+                         ╘═════ ● ════════════════
+        q_1    ──────────────── X ──── Z ─── |0〉 ─
+    "]]
+    .assert_eq(&circ.to_string());
+
+    let conditional = &circ.operations[2];
+    assert_eq!(conditional.classical_controls().count(), 1);
+    assert_eq!(circ.operations[3].classical_controls().count(), 0);
+    expect![[r#"{"gate":"X","isControlled":true,"controls":[{"qId":0,"type":1,"cId":0}],"targets":[{"qId":1,"type":0}]}"#]]
+    .assert_eq(&serde_json::to_string(conditional).expect("serialization should succeed"));
+}
+
 #[test]
 fn custom_intrinsic() {
     let mut interpreter = interpreter(
@@ -764,6 +805,89 @@ fn operation_with_non_qubit_args() {
     .assert_debug_eq(&circ_err);
 }
 
+fn grouping_interpreter() -> Interpreter {
+    let mut interpreter = interpreter(
+        r"
+        namespace Test {
+            @EntryPoint()
+            operation Main() : Result[] {
+                use qs = Qubit[2];
+                PrepareBell(qs[0], qs[1]);
+                X(qs[1]);
+                MResetEachZ(qs)
+            }
+
+            operation PrepareBell(q1 : Qubit, q2 : Qubit) : Unit {
+                H(q1);
+                CNOT(q1, q2);
+            }
+        }
+        ",
+        Profile::Unrestricted,
+    );
+    interpreter.set_circuit_grouping(true);
+    interpreter
+}
+
+/// Renders the operation tree of a circuit, one gate per line, indented by nesting depth.
+fn operation_tree(operations: &[crate::circuit::Operation], depth: usize, out: &mut String) {
+    for op in operations {
+        let _ = writeln!(out, "{:indent$}{}", "", op.gate, indent = depth * 2);
+        operation_tree(&op.children, depth + 1, out);
+    }
+}
+
+#[test]
+fn grouped_operation_calls() {
+    let mut interpreter = grouping_interpreter();
+
+    let circ = interpreter
+        .circuit(CircuitEntryPoint::EntryPoint, false)
+        .expect("circuit generation should succeed");
+
+    let mut tree = String::new();
+    operation_tree(&circ.operations, 0, &mut tree);
+    expect![[r"
+        Main
+          PrepareBell
+            H
+            X
+          X
+          MResetEachZ
+            MResetZ
+              Measure
+              |0〉
+            MResetZ
+              Measure
+              |0〉
+    "]]
+    .assert_eq(&tree);
+}
+
+#[test]
+fn flatten_grouped_circuit() {
+    let mut interpreter = grouping_interpreter();
+    let grouped = interpreter
+        .circuit(CircuitEntryPoint::EntryPoint, false)
+        .expect("circuit generation should succeed");
+
+    interpreter.set_circuit_grouping(false);
+    let ungrouped = interpreter
+        .circuit(CircuitEntryPoint::EntryPoint, false)
+        .expect("circuit generation should succeed");
+
+    let flattened = grouped.flatten();
+    assert!(flattened.operations.iter().all(|op| op.children.is_empty()));
+    assert_eq!(flattened, ungrouped);
+    expect![[r"
+        q_0    ── H ──── ● ──── M ─── |0〉 ────────
+                         │      ╘═════════════════
+        q_1    ───────── X ──── X ──── M ─── |0〉 ─
+                                       ╘══════════
+    "]]
+    .assert_eq(&flattened.to_string());
+}
+
 /// Tests that invoke circuit generation throught the debugger.
 mod debugger_stepping {
     use super::Debugger;
@@ -5,7 +5,12 @@
 mod tests;
 
 use qsc_eval::debug::Frame;
-use qsc_fir::fir::{Global, PackageStoreLookup, StoreItemId};
+use qsc_fir::fir::{
+    self, Block, BlockId, CallableDecl, Expr, ExprId, Global, LocalVarId, PackageId, PackageLookup,
+    PackageStoreLookup, Pat, PatId, PatKind, SpecDecl, Stmt, StmtId, StmtKind, StoreItemId,
+};
+use qsc_fir::ty::{Arrow, FunctorSet, FunctorSetValue, Prim, Ty};
+use qsc_fir::visit::{self, Visitor};
 use qsc_frontend::compile::PackageStore;
 use qsc_hir::hir;
 use qsc_hir::hir::{Item, ItemKind};
@@ -89,3 +94,175 @@ fn get_ns_name(item: &Item) -> Option<Rc<str>> {
     };
     Some(ns.name())
 }
+
+/// A local variable in scope at a statement.
+#[derive(Clone)]
+pub(crate) struct Local {
+    pub(crate) id: LocalVarId,
+    pub(crate) name: Rc<str>,
+    pub(crate) ty: Ty,
+}
+
+/// Returns the local variables in scope at the given statement of `package`, in the order they
+/// are bound. A variable shadowed by a later one of the same name is left out.
+#[must_use]
+pub(crate) fn locals_in_scope(package: &fir::Package, stmt: StmtId) -> Vec<Local> {
+    let mut finder = LocalsInScope {
+        package,
+        target: stmt,
+        scopes: Vec::new(),
+        found: None,
+    };
+    finder.visit_package(package);
+    let mut locals: Vec<Local> = Vec::new();
+    for local in finder.found.unwrap_or_default() {
+        locals.retain(|l| l.name != local.name);
+        locals.push(local);
+    }
+    locals
+}
+
+struct LocalsInScope<'a> {
+    package: &'a fir::Package,
+    target: StmtId,
+    scopes: Vec<Vec<Local>>,
+    found: Option<Vec<Local>>,
+}
+
+impl LocalsInScope<'_> {
+    fn bind(&mut self, pat: PatId) {
+        let pat = self.get_pat(pat);
+        match &pat.kind {
+            PatKind::Bind(ident) => {
+                if let Some(scope) = self.scopes.last_mut() {
+                    scope.push(Local {
+                        id: ident.id,
+                        name: ident.name.clone(),
+                        ty: pat.ty.clone(),
+                    });
+                }
+            }
+            PatKind::Discard => {}
+            PatKind::Tuple(items) => {
+                for &item in items {
+                    self.bind(item);
+                }
+            }
+        }
+    }
+}
+
+impl<'a> Visitor<'a> for LocalsInScope<'a> {
+    fn visit_callable_decl(&mut self, decl: &'a CallableDecl) {
+        self.scopes.push(Vec::new());
+        self.bind(decl.input);
+        visit::walk_callable_decl(self, decl);
+        self.scopes.pop();
+    }
+
+    fn visit_spec_decl(&mut self, decl: &'a SpecDecl) {
+        self.scopes.push(Vec::new());
+        if let Some(input) = decl.input {
+            self.bind(input);
+        }
+        visit::walk_spec_decl(self, decl);
+        self.scopes.pop();
+    }
+
+    fn visit_block(&mut self, block: BlockId) {
+        self.scopes.push(Vec::new());
+        visit::walk_block(self, block);
+        self.scopes.pop();
+    }
+
+    fn visit_stmt(&mut self, stmt: StmtId) {
+        if stmt == self.target {
+            self.found = Some(self.scopes.iter().flatten().cloned().collect());
+        }
+        visit::walk_stmt(self, stmt);
+        // The variables of a binding are only in scope from the next statement on.
+        if let StmtKind::Local(_, pat, _) = &self.get_stmt(stmt).kind {
+            self.bind(*pat);
+        }
+    }
+
+    fn get_block(&self, id: BlockId) -> &'a Block {
+        self.package.get_block(id)
+    }
+
+    fn get_expr(&self, id: ExprId) -> &'a Expr {
+        self.package.get_expr(id)
+    }
+
+    fn get_pat(&self, id: PatId) -> &'a Pat {
+        self.package.get_pat(id)
+    }
+
+    fn get_stmt(&self, id: StmtId) -> &'a Stmt {
+        self.package.get_stmt(id)
+    }
+}
+
+/// Writes `ty`, used in `package`, as Q# source. Returns `None` for types that cannot be named
+/// in source, such as type parameters.
+#[must_use]
+pub(crate) fn ty_source(store: &fir::PackageStore, package: PackageId, ty: &Ty) -> Option<String> {
+    match ty {
+        Ty::Array(item) => Some(format!("{}[]", ty_source(store, package, item)?)),
+        Ty::Arrow(arrow) => arrow_source(store, package, arrow),
+        Ty::Prim(prim) => match prim {
+            Prim::BigInt => Some("BigInt".into()),
+            Prim::Bool => Some("Bool".into()),
+            Prim::Double => Some("Double".into()),
+            Prim::Int => Some("Int".into()),
+            Prim::Pauli => Some("Pauli".into()),
+            Prim::Qubit => Some("Qubit".into()),
+            Prim::Range => Some("Range".into()),
+            Prim::Result => Some("Result".into()),
+            Prim::String => Some("String".into()),
+            Prim::RangeTo | Prim::RangeFrom | Prim::RangeFull => None,
+        },
+        Ty::Tuple(items) => {
+            let items = items
+                .iter()
+                .map(|item| ty_source(store, package, item))
+                .collect::<Option<Vec<_>>>()?;
+            Some(match items.as_slice() {
+                [] => "Unit".into(),
+                [item] => format!("({item},)"),
+                _ => format!("({})", items.join(", ")),
+            })
+        }
+        Ty::Udt(fir::Res::Item(id)) => {
+            let unit = store.get(id.package.unwrap_or(package));
+            let item = unit.items.get(id.item)?;
+            let fir::ItemKind::Ty(name, _) = &item.kind else {
+                return None;
+            };
+            match item.parent.and_then(|parent| unit.items.get(parent)) {
+                Some(fir::Item {
+                    kind: fir::ItemKind::Namespace(ns, _),
+                    ..
+                }) => Some(format!("{}.{}", ns.name, name.name)),
+                _ => Some(name.name.to_string()),
+            }
+        }
+        Ty::Udt(_) | Ty::Infer(_) | Ty::Param(_) | Ty::Err => None,
+    }
+}
+
+fn arrow_source(store: &fir::PackageStore, package: PackageId, arrow: &Arrow) -> Option<String> {
+    let input = ty_source(store, package, &arrow.input)?;
+    let output = ty_source(store, package, &arrow.output)?;
+    let functors = match arrow.functors {
+        FunctorSet::Value(FunctorSetValue::Empty) => "",
+        FunctorSet::Value(FunctorSetValue::Adj) => " is Adj",
+        FunctorSet::Value(FunctorSetValue::Ctl) => " is Ctl",
+        FunctorSet::Value(FunctorSetValue::CtlAdj) => " is Adj + Ctl",
+        FunctorSet::Param(_) | FunctorSet::Infer(_) => return None,
+    };
+    Some(match arrow.kind {
+        fir::CallableKind::Function => format!("({input} -> {output})"),
+        fir::CallableKind::Operation => format!("({input} => {output}{functors})"),
+    })
+}
@@ -220,4 +220,335 @@ mod given_debugger {
             Ok(())
         }
     }
+
+    #[cfg(test)]
+    mod conditional_breakpoint {
+        use super::*;
+        use crate::interpret::Value;
+        use qsc_data_structures::{span::Span, target::TargetCapabilityFlags};
+
+        static LOOP_SOURCE: &str = r"
+        namespace Test {
+            @EntryPoint()
+            operation Main() : Int {
+                mutable total = 0;
+                for i in 0..4 {
+                    set total += i;
+                }
+                total
+            }
+        }";
+
+        fn loop_body_span() -> Span {
+            let lo = LOOP_SOURCE
+                .find("set total")
+                .expect("source should contain the loop body");
+            let hi = lo + "set total += i;".len();
+            Span {
+                lo: lo.try_into().expect("offset should fit in u32"),
+                hi: hi.try_into().expect("offset should fit in u32"),
+            }
+        }
+
+        fn debugger() -> Result<Debugger, Vec<crate::interpret::Error>> {
+            let sources = SourceMap::new([("test".into(), LOOP_SOURCE.into())], None);
+            Debugger::new(
+                sources,
+                TargetCapabilityFlags::all(),
+                Encoding::Utf8,
+                LanguageFeatures::default(),
+            )
+        }
+
+        #[test]
+        fn fires_only_on_iteration_where_condition_holds(
+        ) -> Result<(), Vec<crate::interpret::Error>> {
+            let mut debugger = debugger()?;
+            debugger.set_breakpoint(loop_body_span(), Some("i == 3".into()))?;
+            let (result, _) = step(&mut debugger, &[], StepAction::Continue);
+            match result? {
+                StepResult::BreakpointHit(_) => (),
+                v => panic!("Expected BP, got {v:?}"),
+            }
+            assert_eq!(debugger.read_variable("i"), Some(Value::Int(3)));
+            assert_eq!(debugger.read_variable("total"), Some(Value::Int(3)));
+            let (result, _) = step(&mut debugger, &[], StepAction::Continue);
+            match result? {
+                StepResult::Return(value) => assert_eq!(value, Value::Int(10)),
+                v => panic!("Expected Return, got {v:?}"),
+            }
+            Ok(())
+        }
+
+        #[test]
+        fn without_condition_fires_on_every_iteration() -> Result<(), Vec<crate::interpret::Error>>
+        {
+            let mut debugger = debugger()?;
+            debugger.set_breakpoint(loop_body_span(), None)?;
+            for expected in 0..5 {
+                let (result, _) = step(&mut debugger, &[], StepAction::Continue);
+                match result? {
+                    StepResult::BreakpointHit(_) => (),
+                    v => panic!("Expected BP, got {v:?}"),
+                }
+                assert_eq!(debugger.read_variable("i"), Some(Value::Int(expected)));
+            }
+            let (result, _) = step(&mut debugger, &[], StepAction::Continue);
+            match result? {
+                StepResult::Return(value) => assert_eq!(value, Value::Int(10)),
+                v => panic!("Expected Return, got {v:?}"),
+            }
+            Ok(())
+        }
+
+        static LOCALS_SOURCE: &str = r"
+        namespace Test {
+            newtype Pair = (First : Int, Second : Int);
+            @EntryPoint()
+            operation Main() : Int {
+                use q = Qubit();
+                let double = x -> 2 * x;
+                mutable total = 0;
+                for i in 0..4 {
+                    let pair = Pair(i, double(i));
+                    H(q);
+                    set total += pair::Second;
+                }
+                Reset(q);
+                total
+            }
+        }";
+
+        fn locals_debugger() -> Result<(Debugger, Span), Vec<crate::interpret::Error>> {
+            let sources = SourceMap::new([("test".into(), LOCALS_SOURCE.into())], None);
+            let debugger = Debugger::new(
+                sources,
+                TargetCapabilityFlags::all(),
+                Encoding::Utf8,
+                LanguageFeatures::default(),
+            )?;
+            let lo = LOCALS_SOURCE
+                .find("H(q)")
+                .expect("source should contain the loop body");
+            let span = Span {
+                lo: lo.try_into().expect("offset should fit in u32"),
+                hi: (lo + 1).try_into().expect("offset should fit in u32"),
+            };
+            Ok((debugger, span))
+        }
+
+        #[test]
+        fn condition_can_use_qubit_udt_and_callable_locals(
+        ) -> Result<(), Vec<crate::interpret::Error>> {
+            let (mut debugger, span) = locals_debugger()?;
+            debugger.set_breakpoint(
+                span,
+                Some(
+                    "pair::First == 2 and double(pair::First) == pair::Second and Length([q]) == 1"
+                        .into(),
+                ),
+            )?;
+            let (result, _) = step(&mut debugger, &[], StepAction::Continue);
+            match result? {
+                StepResult::BreakpointHit(_) => (),
+                v => panic!("Expected BP, got {v:?}"),
+            }
+            assert_eq!(debugger.read_variable("i"), Some(Value::Int(2)));
+            Ok(())
+        }
+
+        #[test]
+        fn condition_is_compiled_once() -> Result<(), Vec<crate::interpret::Error>> {
+            let (mut debugger, span) = locals_debugger()?;
+            debugger.set_breakpoint(span, Some("i == 10".into()))?;
+            let package = debugger.interpreter.package;
+            let items = debugger
+                .interpreter
+                .fir_store
+                .get(package)
+                .items
+                .iter()
+                .count();
+            let (result, _) = step(&mut debugger, &[], StepAction::Continue);
+            match result? {
+                StepResult::Return(value) => assert_eq!(value, Value::Int(20)),
+                v => panic!("Expected Return, got {v:?}"),
+            }
+            assert_eq!(
+                debugger
+                    .interpreter
+                    .fir_store
+                    .get(package)
+                    .items
+                    .iter()
+                    .count(),
+                items
+            );
+            Ok(())
+        }
+
+        #[test]
+        fn condition_does_not_change_quantum_state() -> Result<(), Vec<crate::interpret::Error>> {
+            let (mut debugger, span) = locals_debugger()?;
+            debugger.set_breakpoint(span, Some("i == 1".into()))?;
+            let (result, _) = step(&mut debugger, &[], StepAction::Continue);
+            match result? {
+                StepResult::BreakpointHit(_) => (),
+                v => panic!("Expected BP, got {v:?}"),
+            }
+            // The gate on the paused statement has been applied once, on the first iteration.
+            let (state, qubit_count) = debugger.capture_quantum_state();
+            assert_eq!(qubit_count, 1);
+            assert_eq!(state.len(), 2);
+            Ok(())
+        }
+
+        #[test]
+        fn condition_that_fails_to_compile_is_an_error() -> Result<(), Vec<crate::interpret::Error>>
+        {
+            let (mut debugger, span) = locals_debugger()?;
+            let errors = debugger
+                .set_breakpoint(span, Some("i == true".into()))
+                .expect_err("condition should fail to compile");
+            assert!(!errors.is_empty());
+            let (result, _) = step(&mut debugger, &[], StepAction::Continue);
+            match result? {
+                StepResult::Return(value) => assert_eq!(value, Value::Int(20)),
+                v => panic!("Expected Return, got {v:?}"),
+            }
+            Ok(())
+        }
+    }
+
+    #[cfg(test)]
+    mod step_back {
+        use qsc_data_structures::{span::Span, target::TargetCapabilityFlags};
+
+        use super::*;
+
+        fn paused_at(debugger: &Debugger) -> Vec<(String, u32)> {
+            debugger
+                .get_stack_frames()
+                .into_iter()
+                .map(|frame| (frame.name, frame.location.range.start.line))
+                .collect()
+        }
+
+        #[test]
+        fn returns_to_earlier_statements() -> Result<(), Vec<crate::interpret::Error>> {
+            let sources = SourceMap::new([("test".into(), STEPPING_SOURCE.into())], None);
+            let mut debugger = Debugger::new(
+                sources,
+                TargetCapabilityFlags::all(),
+                Encoding::Utf8,
+                LanguageFeatures::default(),
+            )?;
+            expect_in(&mut debugger);
+            let first = paused_at(&debugger);
+            expect_in(&mut debugger);
+            expect_in(&mut debugger);
+            assert_eq!(
+                paused_at(&debugger),
+                [("A".to_string(), 4), ("B".to_string(), 10)]
+            );
+
+            debugger.step_back()?;
+            debugger.step_back()?;
+            assert_eq!(paused_at(&debugger), first);
+            assert_eq!(first, [("A".to_string(), 4)]);
+
+            expect_in(&mut debugger);
+            assert_eq!(
+                paused_at(&debugger),
+                [("A".to_string(), 4), ("B".to_string(), 9)]
+            );
+            Ok(())
+        }
+
+        #[test]
+        fn past_the_first_step_fails() -> Result<(), Vec<crate::interpret::Error>> {
+            let sources = SourceMap::new([("test".into(), STEPPING_SOURCE.into())], None);
+            let mut debugger = Debugger::new(
+                sources,
+                TargetCapabilityFlags::all(),
+                Encoding::Utf8,
+                LanguageFeatures::default(),
+            )?;
+            expect_in(&mut debugger);
+            debugger.step_back()?;
+            let errors = debugger.step_back().expect_err("step back should fail");
+            assert!(matches!(
+                errors.as_slice(),
+                [crate::interpret::Error::NoStepHistory]
+            ));
+            Ok(())
+        }
+
+        #[test]
+        fn ignores_breakpoints_set_after_the_step() -> Result<(), Vec<crate::interpret::Error>> {
+            let sources = SourceMap::new([("test".into(), STEPPING_SOURCE.into())], None);
+            let mut debugger = Debugger::new(
+                sources,
+                TargetCapabilityFlags::all(),
+                Encoding::Utf8,
+                LanguageFeatures::default(),
+            )?;
+            expect_in(&mut debugger);
+            expect_in(&mut debugger);
+            let second = paused_at(&debugger);
+            expect_in(&mut debugger);
+            debugger.set_breakpoint(
+                Span {
+                    lo: 0,
+                    hi: STEPPING_SOURCE
+                        .len()
+                        .try_into()
+                        .expect("source length should fit in u32"),
+                },
+                None,
+            )?;
+
+            debugger.step_back()?;
+            assert_eq!(paused_at(&debugger), second);
+            Ok(())
+        }
+
+        static MEASUREMENT_SOURCE: &str = r#"
+        namespace Test {
+            @EntryPoint()
+            operation Main() : Result {
+                use q = Qubit();
+                H(q);
+                let r = M(q);
+                Reset(q);
+                r
+            }
+        }"#;
+
+        #[test]
+        fn restores_quantum_state_from_before_measurement(
+        ) -> Result<(), Vec<crate::interpret::Error>> {
+            let sources = SourceMap::new([("test".into(), MEASUREMENT_SOURCE.into())], None);
+            let mut debugger = Debugger::new(
+                sources,
+                TargetCapabilityFlags::all(),
+                Encoding::Utf8,
+                LanguageFeatures::default(),
+            )?;
+            let mut states = vec![debugger.capture_quantum_state()];
+            for _ in 0..4 {
+                expect_next(&mut debugger);
+                states.push(debugger.capture_quantum_state());
+            }
+            assert!(states.iter().any(|(state, _)| state.len() == 2));
+            assert_eq!(states.last().map(|(state, _)| state.len()), Some(1));
+
+            states.pop();
+            while let Some(expected) = states.pop() {
+                debugger.step_back()?;
+                assert_eq!(debugger.capture_quantum_state(), expected);
+            }
+            Ok(())
+        }
+    }
 }
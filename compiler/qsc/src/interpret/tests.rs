@@ -4,15 +4,56 @@
 #![allow(clippy::needless_raw_string_hashes)]
 
 mod given_interpreter {
-    use crate::interpret::{Error, InterpretResult, Interpreter};
+    use crate::interpret::{Endianness, Error, Event, InterpretResult, Interpreter};
     use expect_test::Expect;
     use miette::Diagnostic;
+    use num_bigint::{BigInt, BigUint};
+    use num_complex::Complex64;
     use qsc_data_structures::{language_features::LanguageFeatures, target::TargetCapabilityFlags};
-    use qsc_eval::{output::CursorReceiver, val::Value};
+    use qsc_eval::{
+        output::{self, CursorReceiver, Receiver},
+        val::{self, Value},
+    };
     use qsc_frontend::compile::SourceMap;
     use qsc_passes::PackageType;
+    use rand::{rngs::StdRng, SeedableRng};
     use std::{fmt::Write, io::Cursor, iter, str::from_utf8};
 
+    type State = Vec<(BigUint, Complex64)>;
+
+    /// Records the register states reported during an evaluation, keeping any other output separately.
+    #[derive(Default)]
+    struct RegisterStateReceiver {
+        register_states: Vec<(Vec<usize>, State)>,
+        other_output: Vec<String>,
+    }
+
+    impl Receiver for RegisterStateReceiver {
+        fn state(
+            &mut self,
+            state: Vec<(BigUint, Complex64)>,
+            qubit_count: usize,
+        ) -> Result<(), output::Error> {
+            self.other_output
+                .push(format!("state of {qubit_count} qubits: {state:?}"));
+            Ok(())
+        }
+
+        fn register_state(
+            &mut self,
+            qubits: &[usize],
+            state: Vec<(BigUint, Complex64)>,
+        ) -> Result<(), output::Error> {
+            self.register_states.push((qubits.to_vec(), state));
+            Ok(())
+        }
+
+        fn message(&mut self, msg: &str) -> Result<(), output::Error> {
+            self.other_output.push(msg.to_string());
+            Ok(())
+        }
+    }
+
     fn line(interpreter: &mut Interpreter, line: &str) -> (InterpretResult, String) {
         let mut cursor = Cursor::new(Vec::<u8>::new());
         let mut receiver = CursorReceiver::new(&mut cursor);
@@ -22,6 +63,15 @@ mod given_interpreter {
         )
     }
 
+    fn line_register_states(
+        interpreter: &mut Interpreter,
+        line: &str,
+    ) -> (InterpretResult, RegisterStateReceiver) {
+        let mut receiver = RegisterStateReceiver::default();
+        let result = interpreter.eval_fragments(&mut receiver, line);
+        (result, receiver)
+    }
+
     fn run(
         interpreter: &mut Interpreter,
         expr: &str,
@@ -282,6 +332,77 @@ mod given_interpreter {
             is_unit_with_output(&result, &output, "STATE:\n|0⟩: 1+0i");
         }
 
+        #[test]
+        fn dump_register_of_separable_qubits_reports_register_state() {
+            let mut interpreter = get_interpreter();
+            let (result, receiver) = line_register_states(
+                &mut interpreter,
+                indoc! {"
+                    open Microsoft.Quantum.Diagnostics;
+                    use qs = Qubit[4];
+                    H(qs[0]);
+                    X(qs[1]);
+                    H(qs[2]);
+                    CNOT(qs[2], qs[3]);
+                    DumpRegister([qs[0], qs[1]]);
+                    ResetAll(qs);
+                "},
+            );
+            assert!(result.is_ok(), "expected success, got {result:?}");
+            assert!(receiver.other_output.is_empty());
+            expect![[r#"
+                [
+                    (
+                        [
+                            0,
+                            1,
+                        ],
+                        [
+                            (
+                                1,
+                                Complex {
+                                    re: 0.7071067811865475,
+                                    im: 0.0,
+                                },
+                            ),
+                            (
+                                3,
+                                Complex {
+                                    re: 0.7071067811865475,
+                                    im: 0.0,
+                                },
+                            ),
+                        ],
+                    ),
+                ]
+            "#]]
+            .assert_debug_eq(&receiver.register_states);
+        }
+
+        #[test]
+        fn dump_register_of_entangled_qubits_is_error() {
+            let mut interpreter = get_interpreter();
+            let (result, receiver) = line_register_states(
+                &mut interpreter,
+                indoc! {"
+                    open Microsoft.Quantum.Diagnostics;
+                    use qs = Qubit[3];
+                    H(qs[0]);
+                    CNOT(qs[0], qs[1]);
+                    DumpRegister([qs[0], qs[2]]);
+                "},
+            );
+            assert!(receiver.register_states.is_empty());
+            assert!(receiver.other_output.is_empty());
+            is_error(
+                &result.expect_err("dumping entangled qubits should fail"),
+                &expect![[r#"
+                    runtime error: qubits are not separable
+                       [line_0] [[qs[0], qs[2]]]
+                "#]],
+            );
+        }
+
         #[test]
         fn declare_namespace_call() {
             let mut interpreter = get_interpreter();
@@ -446,6 +567,46 @@ mod given_interpreter {
             );
         }
 
+        #[test]
+        fn classical_rng_produces_deterministic_sequence() {
+            let draw_sequence = || {
+                let mut interpreter = get_interpreter();
+                interpreter.set_classical_rng(Box::new(StdRng::seed_from_u64(42)));
+                (0..3)
+                    .map(|_| {
+                        let (result, output) = line(
+                            &mut interpreter,
+                            "Microsoft.Quantum.Random.DrawRandomInt(0, 1000000)",
+                        );
+                        assert!(output.is_empty(), "unexpected output: {output}");
+                        result
+                            .expect("drawing a random int should succeed")
+                            .unwrap_int()
+                    })
+                    .collect::<Vec<_>>()
+            };
+            let first = draw_sequence();
+            assert_eq!(first, draw_sequence());
+            assert!(
+                first.windows(2).any(|pair| pair[0] != pair[1]),
+                "the sequence should continue across evaluations: {first:?}"
+            );
+        }
+
+        #[test]
+        fn final_state_purity_is_one_for_allocated_ghz_state() {
+            let mut interpreter = get_interpreter();
+            let (result, output) = line(
+                &mut interpreter,
+                "use qs = Qubit[3]; H(qs[0]); CNOT(qs[0], qs[1]); CNOT(qs[1], qs[2]);",
+            );
+            is_only_value(&result, &output, &Value::unit());
+            let (state, qubit_count) = interpreter.get_quantum_state();
+            assert_eq!((state.len(), qubit_count), (2, 3));
+            let purity = interpreter.final_state_purity();
+            assert!((purity - 1.0).abs() < 1e-9, "unexpected purity {purity}");
+        }
+
         #[test]
         fn compiler_error_across_lines() {
             let mut interpreter = get_interpreter();
@@ -1239,6 +1400,108 @@ mod given_interpreter {
             }
         }
 
+        #[test]
+        fn run_large_clifford_circuit() {
+            let mut interpreter = get_interpreter();
+            interpreter.set_clifford_simulation(true);
+            interpreter.set_quantum_seed(Some(42));
+            let (result, output) = line(
+                &mut interpreter,
+                indoc! {"
+                    operation Foo() : (Int, Bool) {
+                        use qs = Qubit[200];
+                        Microsoft.Quantum.Canon.ApplyToEach(H, qs[...99]);
+                        mutable ones = 0;
+                        for q in qs[...99] {
+                            if MResetZ(q) == One {
+                                set ones += 1;
+                            }
+                        }
+                        H(qs[100]);
+                        for i in 100..198 {
+                            CNOT(qs[i], qs[i + 1]);
+                        }
+                        let ghz = Microsoft.Quantum.Measurement.MResetEachZ(qs[100...]);
+                        (ones, ghz == Repeated(ghz[0], 100))
+                    }
+                "},
+            );
+            is_only_value(&result, &output, &Value::unit());
+            let (result, output) = run(&mut interpreter, "Foo()");
+            assert_eq!(output, "");
+            let Value::Tuple(values) = result
+                .expect("compilation should succeed")
+                .expect("run should succeed")
+            else {
+                panic!("expected a tuple");
+            };
+            let [Value::Int(ones), Value::Bool(ghz_agrees)] = &*values else {
+                panic!("expected an integer and a boolean");
+            };
+            assert!((30..70).contains(ones), "unexpected number of ones: {ones}");
+            assert!(ghz_agrees, "GHZ measurements should all agree");
+        }
+
+        #[test]
+        fn run_dumps_global_phase_without_clifford_simulation() {
+            let mut interpreter = get_interpreter();
+            let (result, output) = line(
+                &mut interpreter,
+                "operation Foo() : Unit { use q = Qubit(); X(q); S(q); Microsoft.Quantum.Diagnostics.DumpMachine(); Reset(q); }",
+            );
+            is_only_value(&result, &output, &Value::unit());
+            let (result, output) = run(&mut interpreter, "Foo()");
+            is_only_value(
+                &result.expect("compilation should succeed"),
+                "",
+                &Value::unit(),
+            );
+            expect![[r#"
+                STATE:
+                |1⟩: 0+1i"#]]
+            .assert_eq(&output);
+        }
+
+        #[test]
+        fn run_dumps_state_up_to_global_phase_with_clifford_simulation() {
+            let mut interpreter = get_interpreter();
+            interpreter.set_clifford_simulation(true);
+            let (result, output) = line(
+                &mut interpreter,
+                "operation Foo() : Unit { use q = Qubit(); X(q); S(q); Microsoft.Quantum.Diagnostics.DumpMachine(); Reset(q); }",
+            );
+            is_only_value(&result, &output, &Value::unit());
+            let (result, output) = run(&mut interpreter, "Foo()");
+            is_only_value(
+                &result.expect("compilation should succeed"),
+                "",
+                &Value::unit(),
+            );
+            expect![[r#"
+                STATE:
+                |1⟩: 1+0i"#]]
+            .assert_eq(&output);
+        }
+
+        #[test]
+        fn run_falls_back_from_clifford_simulation() {
+            let mut interpreter = get_interpreter();
+            interpreter.set_clifford_simulation(true);
+            let (result, output) = line(
+                &mut interpreter,
+                "operation Foo() : Result { use q = Qubit(); H(q); T(q); T(q); T(q); T(q); H(q); MResetZ(q) }",
+            );
+            is_only_value(&result, &output, &Value::unit());
+            for _ in 0..4 {
+                let (result, output) = run(&mut interpreter, "Foo()");
+                is_only_value(
+                    &result.expect("compilation should succeed"),
+                    &output,
+                    &Value::RESULT_ONE,
+                );
+            }
+        }
+
         #[test]
         fn run_parse_error() {
             let mut interpreter = get_interpreter();
@@ -1290,95 +1553,376 @@ mod given_interpreter {
         }
 
         #[test]
-        fn run_output_merged() {
+        fn failed_fact_is_assertion_error() {
             let mut interpreter = get_interpreter();
             let (result, output) = line(
                 &mut interpreter,
-                r#"operation Foo() : Unit { Message("hello!") }"#,
+                r#"Microsoft.Quantum.Diagnostics.Fact(false, "bad")"#,
+            );
+            is_only_error(
+                &result,
+                &output,
+                &expect![[r#"
+                    runtime error: assertion failed: bad
+                      failed assertion [line_0] [Microsoft.Quantum.Diagnostics.Fact(false, "bad")]
+                "#]],
+            );
+        }
+
+        #[test]
+        fn deep_recursion_is_limited_by_default() {
+            let mut interpreter = get_interpreter();
+            let (result, output) = line(
+                &mut interpreter,
+                "function Count(n : Int) : Int { if n == 0 { 0 } else { 1 + Count(n - 1) } }",
             );
             is_only_value(&result, &output, &Value::unit());
-            for _ in 0..4 {
-                let (result, output) = run(&mut interpreter, "Foo()");
-                is_unit_with_output(
-                    &result.expect("compilation should succeed"),
-                    &output,
-                    "hello!",
-                );
-            }
+            let (result, output) = line(&mut interpreter, "Count(20000)");
+            is_only_error(
+                &result,
+                &output,
+                &expect![[r#"
+                runtime error: maximum call depth of 10000 exceeded
+                  this call exceeds the maximum call depth [line_0] [Count(n - 1)]
+            "#]],
+            );
         }
 
         #[test]
-        fn base_prof_non_result_return() {
-            let mut interpreter = Interpreter::new(
-                true,
-                SourceMap::default(),
-                PackageType::Lib,
-                TargetCapabilityFlags::empty(),
-                LanguageFeatures::default(),
-            )
-            .expect("interpreter should be created");
-            let (result, output) = line(&mut interpreter, "123");
-            is_only_value(&result, &output, &Value::Int(123));
+        fn recursion_within_call_depth_limit_succeeds() {
+            let mut interpreter = get_interpreter();
+            interpreter.set_max_call_depth(100);
+            let (result, output) = line(
+                &mut interpreter,
+                "function Count(n : Int) : Int { if n == 0 { 0 } else { 1 + Count(n - 1) } }",
+            );
+            is_only_value(&result, &output, &Value::unit());
+            let (result, output) = line(&mut interpreter, "Count(50)");
+            is_only_value(&result, &output, &Value::Int(50));
         }
-    }
 
-    fn get_interpreter() -> Interpreter {
-        Interpreter::new(
-            true,
-            SourceMap::default(),
-            PackageType::Lib,
-            TargetCapabilityFlags::all(),
-            LanguageFeatures::default(),
-        )
-        .expect("interpreter should be created")
-    }
+        #[test]
+        fn recursion_exceeding_call_depth_limit_is_error() {
+            let mut interpreter = get_interpreter();
+            interpreter.set_max_call_depth(100);
+            let (result, output) = line(
+                &mut interpreter,
+                "function Count(n : Int) : Int { if n == 0 { 0 } else { 1 + Count(n - 1) } }",
+            );
+            is_only_value(&result, &output, &Value::unit());
+            let (result, output) = line(&mut interpreter, "Count(200)");
+            is_only_error(
+                &result,
+                &output,
+                &expect![[r#"
+                runtime error: maximum call depth of 100 exceeded
+                  this call exceeds the maximum call depth [line_0] [Count(n - 1)]
+            "#]],
+            );
+        }
 
-    fn get_interpreter_with_capbilities(capabilities: TargetCapabilityFlags) -> Interpreter {
-        Interpreter::new(
-            true,
-            SourceMap::default(),
-            PackageType::Lib,
-            capabilities,
-            LanguageFeatures::default(),
-        )
-        .expect("interpreter should be created")
-    }
+        #[test]
+        fn loop_within_iteration_limit_succeeds() {
+            let mut interpreter = get_interpreter();
+            interpreter.set_max_loop_iterations(10);
+            let (result, output) = line(
+                &mut interpreter,
+                "{ mutable total = 0; for _ in 1..10 { for i in 1..10 { set total += i; } } total }",
+            );
+            is_only_value(&result, &output, &Value::Int(550));
+        }
 
-    fn is_only_value(result: &InterpretResult, output: &str, value: &Value) {
-        assert_eq!("", output);
+        #[test]
+        fn loop_exceeding_iteration_limit_is_error() {
+            let mut interpreter = get_interpreter();
+            interpreter.set_max_loop_iterations(10);
+            let (result, output) = line(
+                &mut interpreter,
+                "{ mutable i = 0; while true { set i += 1; } }",
+            );
+            is_only_error(
+                &result,
+                &output,
+                &expect![[r#"
+                    runtime error: maximum number of loop iterations of 10 exceeded
+                      this loop exceeds the maximum number of iterations [line_0] [true]
+                "#]],
+            );
+        }
 
-        match result {
-            Ok(v) => assert_eq!(value, v),
-            Err(e) => panic!("Expected {value:?}, got {e:?}"),
+        #[test]
+        fn for_loop_exceeding_iteration_limit_is_error() {
+            let mut interpreter = get_interpreter();
+            interpreter.set_max_loop_iterations(10);
+            let (result, output) = line(
+                &mut interpreter,
+                "function Sum(n : Int) : Int { mutable total = 0; for i in 1..n { set total += i; } total }",
+            );
+            is_only_value(&result, &output, &Value::unit());
+            let (result, output) = line(&mut interpreter, "Sum(11)");
+            is_only_error(
+                &result,
+                &output,
+                &expect![[r#"
+                runtime error: maximum number of loop iterations of 10 exceeded
+                  this loop exceeds the maximum number of iterations [line_0] [1..n]
+            "#]],
+            );
         }
-    }
 
-    fn is_unit_with_output_eval_entry(
-        result: &Result<Value, Vec<crate::interpret::Error>>,
-        output: &str,
-        expected_output: &str,
-    ) {
-        assert_eq!(expected_output, output);
+        #[test]
+        fn measurements_within_budget_succeed() {
+            let mut interpreter = get_interpreter();
+            interpreter.set_max_measurements(3);
+            // The budget applies to each run separately, so repeating the run stays within it.
+            for _ in 0..2 {
+                let (result, output) = line(
+                    &mut interpreter,
+                    "{ use q = Qubit(); for _ in 1..2 { MResetZ(q); } MResetZ(q) == Zero }",
+                );
+                is_only_value(&result, &output, &Value::Bool(true));
+            }
+        }
 
-        match result {
-            Ok(value) => assert_eq!(Value::unit(), *value),
-            Err(e) => panic!("Expected unit value, got {e:?}"),
+        #[test]
+        fn measurements_exceeding_budget_is_error() {
+            let mut interpreter = get_interpreter();
+            interpreter.set_max_measurements(3);
+            let (result, output) = line(
+                &mut interpreter,
+                "{ use q = Qubit(); for _ in 1..4 { MResetZ(q); } }",
+            );
+            is_only_error(
+                &result,
+                &output,
+                &expect![[r#"
+                    runtime error: maximum number of measurements of 3 exceeded
+                      this measurement exceeds the maximum number of measurements [line_0] [MResetZ(q)]
+                "#]],
+            );
         }
-    }
 
-    fn is_unit_with_output(result: &InterpretResult, output: &str, expected_output: &str) {
-        assert_eq!(expected_output, output);
+        #[test]
+        fn overflowing_multiplication_is_error_with_checked_arithmetic() {
+            let mut interpreter = get_interpreter();
+            interpreter.set_checked_arithmetic(true);
+            let (result, output) = line(&mut interpreter, "let x = 4611686018427387904; x * 2");
+            is_only_error(
+                &result,
+                &output,
+                &expect![[r#"
+                runtime error: integer overflow
+                  this operation overflows [line_0] [x * 2]
+            "#]],
+            );
+        }
 
-        match result {
-            Ok(value) => assert_eq!(Value::unit(), *value),
-            Err(e) => panic!("Expected unit value, got {e:?}"),
+        #[test]
+        fn overflowing_multiplication_wraps_without_checked_arithmetic() {
+            let mut interpreter = get_interpreter();
+            let (result, output) = line(&mut interpreter, "let x = 4611686018427387904; x * 2");
+            is_only_value(&result, &output, &Value::Int(i64::MIN));
         }
-    }
 
-    fn is_only_error<E>(result: &Result<Value, Vec<E>>, output: &str, expected_errors: &Expect)
-    where
-        E: Diagnostic,
-    {
+        #[test]
+        fn overflowing_constant_is_error_with_checked_arithmetic_and_folding() {
+            let mut interpreter = Interpreter::new_with_fold_constants(
+                true,
+                SourceMap::default(),
+                PackageType::Lib,
+                TargetCapabilityFlags::all(),
+                LanguageFeatures::default(),
+            )
+            .expect("interpreter should be created");
+            interpreter.set_checked_arithmetic(true);
+            let (result, output) = line(&mut interpreter, "4611686018427387904 * 2");
+            is_only_error(
+                &result,
+                &output,
+                &expect![[r#"
+                    runtime error: integer overflow
+                      this operation overflows [line_0] [4611686018427387904 * 2]
+                "#]],
+            );
+            let (result, output) = line(&mut interpreter, "2 ^ 63");
+            is_only_error(
+                &result,
+                &output,
+                &expect![[r#"
+                    runtime error: integer overflow
+                      this operation overflows [line_1] [2 ^ 63]
+                "#]],
+            );
+        }
+
+        #[test]
+        fn non_overflowing_arithmetic_succeeds_with_checked_arithmetic() {
+            let mut interpreter = get_interpreter();
+            interpreter.set_checked_arithmetic(true);
+            let (result, output) = line(
+                &mut interpreter,
+                "let x = 3037000499; (x * x + 1, 2^62, 4611686018427387904L * 4L)",
+            );
+            is_only_value(
+                &result,
+                &output,
+                &Value::Tuple(
+                    vec![
+                        Value::Int(9_223_372_030_926_249_002),
+                        Value::Int(4_611_686_018_427_387_904),
+                        Value::BigInt(BigInt::from(4_611_686_018_427_387_904_i64) * 4),
+                    ]
+                    .into(),
+                ),
+            );
+        }
+
+        #[test]
+        fn interpolated_doubles_use_configured_precision() {
+            let code =
+                r#"let x = 3.14159265; Message($"{x} {[x * 100.0, -0.0001]} {(2.0, 12345.6)}")"#;
+            let mut interpreter = get_interpreter();
+            interpreter.set_double_precision(3);
+            let (result, output) = line(&mut interpreter, code);
+            is_unit_with_output(&result, &output, "3.14 [314, -0.000100] (2.00, 12300)");
+
+            let mut interpreter = get_interpreter();
+            interpreter.set_double_precision(6);
+            let (result, output) = line(&mut interpreter, code);
+            is_unit_with_output(
+                &result,
+                &output,
+                "3.14159 [314.159, -0.000100000] (2.00000, 12345.6)",
+            );
+        }
+
+        #[test]
+        fn interpolated_doubles_are_written_in_full_by_default() {
+            let mut interpreter = get_interpreter();
+            let (result, output) = line(&mut interpreter, r#"Message($"{3.14159265}")"#);
+            is_unit_with_output(&result, &output, "3.14159265");
+        }
+
+        #[test]
+        fn read_variable_returns_latest_value_of_mutable_array() {
+            let mut interpreter = get_interpreter();
+            let (result, output) = line(&mut interpreter, "mutable results = [Zero, Zero];");
+            is_only_value(&result, &output, &Value::unit());
+            let (result, output) = line(&mut interpreter, "set results += [One];");
+            is_only_value(&result, &output, &Value::unit());
+            let (result, output) = line(&mut interpreter, "set results w/= 0 <- One;");
+            is_only_value(&result, &output, &Value::unit());
+
+            let value = interpreter
+                .read_variable("results")
+                .expect("variable should be in scope");
+            expect!["[One, Zero, One]"].assert_eq(&value.to_string());
+        }
+
+        #[test]
+        fn read_variable_returns_innermost_shadowing_binding() {
+            let mut interpreter = get_interpreter();
+            let (result, output) = line(&mut interpreter, "let x = 1; let x = 2;");
+            is_only_value(&result, &output, &Value::unit());
+            assert_eq!(interpreter.read_variable("x"), Some(Value::Int(2)));
+        }
+
+        #[test]
+        fn read_variable_not_in_scope_is_none() {
+            let interpreter = get_interpreter();
+            assert_eq!(interpreter.read_variable("missing"), None);
+        }
+
+        #[test]
+        fn run_output_merged() {
+            let mut interpreter = get_interpreter();
+            let (result, output) = line(
+                &mut interpreter,
+                r#"operation Foo() : Unit { Message("hello!") }"#,
+            );
+            is_only_value(&result, &output, &Value::unit());
+            for _ in 0..4 {
+                let (result, output) = run(&mut interpreter, "Foo()");
+                is_unit_with_output(
+                    &result.expect("compilation should succeed"),
+                    &output,
+                    "hello!",
+                );
+            }
+        }
+
+        #[test]
+        fn base_prof_non_result_return() {
+            let mut interpreter = Interpreter::new(
+                true,
+                SourceMap::default(),
+                PackageType::Lib,
+                TargetCapabilityFlags::empty(),
+                LanguageFeatures::default(),
+            )
+            .expect("interpreter should be created");
+            let (result, output) = line(&mut interpreter, "123");
+            is_only_value(&result, &output, &Value::Int(123));
+        }
+    }
+
+    fn get_interpreter() -> Interpreter {
+        Interpreter::new(
+            true,
+            SourceMap::default(),
+            PackageType::Lib,
+            TargetCapabilityFlags::all(),
+            LanguageFeatures::default(),
+        )
+        .expect("interpreter should be created")
+    }
+
+    fn get_interpreter_with_capbilities(capabilities: TargetCapabilityFlags) -> Interpreter {
+        Interpreter::new(
+            true,
+            SourceMap::default(),
+            PackageType::Lib,
+            capabilities,
+            LanguageFeatures::default(),
+        )
+        .expect("interpreter should be created")
+    }
+
+    fn is_only_value(result: &InterpretResult, output: &str, value: &Value) {
+        assert_eq!("", output);
+
+        match result {
+            Ok(v) => assert_eq!(value, v),
+            Err(e) => panic!("Expected {value:?}, got {e:?}"),
+        }
+    }
+
+    fn is_unit_with_output_eval_entry(
+        result: &Result<Value, Vec<crate::interpret::Error>>,
+        output: &str,
+        expected_output: &str,
+    ) {
+        assert_eq!(expected_output, output);
+
+        match result {
+            Ok(value) => assert_eq!(Value::unit(), *value),
+            Err(e) => panic!("Expected unit value, got {e:?}"),
+        }
+    }
+
+    fn is_unit_with_output(result: &InterpretResult, output: &str, expected_output: &str) {
+        assert_eq!(expected_output, output);
+
+        match result {
+            Ok(value) => assert_eq!(Value::unit(), *value),
+            Err(e) => panic!("Expected unit value, got {e:?}"),
+        }
+    }
+
+    fn is_only_error<E>(result: &Result<Value, Vec<E>>, output: &str, expected_errors: &Expect)
+    where
+        E: Diagnostic,
+    {
         assert_eq!("", output);
 
         match result {
@@ -1458,6 +2002,854 @@ mod given_interpreter {
             is_unit_with_output_eval_entry(&result, &output, "hello there...");
         }
 
+        #[test]
+        fn entry_trace_writer_records_gates_and_measurements() {
+            let source = indoc! { r#"
+            namespace Test {
+                @EntryPoint()
+                operation Main() : (Result, Result) {
+                    use (q0, q1) = (Qubit(), Qubit());
+                    H(q0);
+                    CNOT(q0, q1);
+                    let results = (M(q0), M(q1));
+                    Reset(q0);
+                    Reset(q1);
+                    results
+                }
+            }"#};
+
+            let sources = SourceMap::new([("test".into(), source.into())], None);
+            let mut interpreter = Interpreter::new(
+                true,
+                sources,
+                PackageType::Exe,
+                TargetCapabilityFlags::all(),
+                LanguageFeatures::default(),
+            )
+            .expect("interpreter should be created");
+
+            let mut trace = Vec::new();
+            let mut cursor = Cursor::new(Vec::<u8>::new());
+            let mut receiver = CursorReceiver::new(&mut cursor);
+            let result = interpreter
+                .eval_entry_with_trace_writer(&mut trace, &mut receiver)
+                .expect("entry should evaluate");
+            let Value::Tuple(results) = result else {
+                panic!("expected a tuple of results, got {result}");
+            };
+            assert_eq!(results[0], results[1], "Bell pair results should agree");
+            assert_eq!(
+                String::from_utf8(trace).expect("trace should be UTF-8"),
+                format!(
+                    "H q0\nCNOT q0 q1\nM q0 -> {}\nM q1 -> {}\nReset q0\nReset q1\n",
+                    results[0], results[1]
+                )
+            );
+        }
+
+        #[test]
+        fn entry_trace_writer_records_joint_pauli_measurement() {
+            let source = indoc! { r#"
+            namespace Test {
+                @EntryPoint()
+                operation Main() : Result {
+                    use (q0, q1) = (Qubit(), Qubit());
+                    H(q0);
+                    let result = Measure([PauliX, PauliZ], [q0, q1]);
+                    Reset(q0);
+                    result
+                }
+            }"#};
+
+            let sources = SourceMap::new([("test".into(), source.into())], None);
+            let mut interpreter = Interpreter::new(
+                true,
+                sources,
+                PackageType::Exe,
+                TargetCapabilityFlags::all(),
+                LanguageFeatures::default(),
+            )
+            .expect("interpreter should be created");
+
+            let mut trace = Vec::new();
+            let mut cursor = Cursor::new(Vec::<u8>::new());
+            let mut receiver = CursorReceiver::new(&mut cursor);
+            let result = interpreter
+                .eval_entry_with_trace_writer(&mut trace, &mut receiver)
+                .expect("entry should evaluate");
+            assert_eq!(result, Value::RESULT_ZERO);
+            assert_eq!(
+                String::from_utf8(trace).expect("trace should be UTF-8"),
+                "H q0\nMeasure PauliX q0 PauliZ q1 -> Zero\nReset q0\n"
+            );
+        }
+
+        #[test]
+        fn entry_replays_result_stream_through_classical_logic() {
+            let source = indoc! { r#"
+            namespace Test {
+                @EntryPoint()
+                operation Main() : (Result, Result) {
+                    use (q0, q1) = (Qubit(), Qubit());
+                    H(q0);
+                    let first = MResetZ(q0);
+                    if first == One {
+                        X(q1);
+                    }
+                    (first, MResetZ(q1))
+                }
+            }"#};
+
+            let sources = SourceMap::new([("test".into(), source.into())], None);
+            let mut interpreter = Interpreter::new(
+                true,
+                sources,
+                PackageType::Exe,
+                TargetCapabilityFlags::all(),
+                LanguageFeatures::default(),
+            )
+            .expect("interpreter should be created");
+
+            let mut cursor = Cursor::new(Vec::<u8>::new());
+            let mut receiver = CursorReceiver::new(&mut cursor);
+            let result = interpreter
+                .eval_entry_with_result_stream(
+                    &[val::Result::Val(true), val::Result::Val(false)],
+                    &mut receiver,
+                )
+                .expect("entry should evaluate");
+            assert_eq!(
+                result,
+                Value::Tuple(vec![Value::RESULT_ONE, Value::RESULT_ZERO].into()),
+                "measurements should take their outcomes from the stream"
+            );
+
+            let errors = interpreter
+                .eval_entry_with_result_stream(&[val::Result::Val(true)], &mut receiver)
+                .expect_err("running out of results should fail");
+            assert_eq!(
+                errors[0].code().map(|code| code.to_string()).as_deref(),
+                Some("Qsc.Eval.ResultsExhausted")
+            );
+        }
+
+        #[test]
+        fn dry_run_catches_index_out_of_range_without_simulation() {
+            // Simulating a superposition over this many qubits is not feasible, so the error
+            // can only be reached quickly if the quantum state is never simulated.
+            let source = indoc! { r#"
+            namespace Test {
+                @EntryPoint()
+                operation Main() : Int {
+                    use qs = Qubit[64];
+                    ApplyToEach(H, qs);
+                    let results = MResetEachZ(qs);
+                    let values = [1, 2, 3];
+                    if results[0] == Zero {
+                        values[3]
+                    } else {
+                        values[0]
+                    }
+                }
+            }"#};
+
+            let sources = SourceMap::new([("test".into(), source.into())], None);
+            let mut interpreter = Interpreter::new(
+                true,
+                sources,
+                PackageType::Exe,
+                TargetCapabilityFlags::all(),
+                LanguageFeatures::default(),
+            )
+            .expect("interpreter should be created");
+
+            let errors = interpreter
+                .dry_run()
+                .expect_err("dry run should fail on the out-of-range index");
+            assert_eq!(
+                errors[0].code().map(|code| code.to_string()).as_deref(),
+                Some("Qsc.Eval.IndexOutOfRange")
+            );
+        }
+
+        #[test]
+        fn callables_evaluated_by_name_run_independently() {
+            let source = indoc! { r#"
+            namespace Test {
+                operation FlipAll(n : Int) : Result[] {
+                    use qs = Qubit[n];
+                    ApplyToEach(X, qs);
+                    MResetEachZ(qs)
+                }
+
+                operation MeasureFresh() : Result {
+                    use q = Qubit();
+                    M(q)
+                }
+
+                function Add(a : Int, b : Int) : Int {
+                    a + b
+                }
+            }"#};
+
+            let sources = SourceMap::new([("test".into(), source.into())], None);
+            let mut interpreter = Interpreter::new(
+                true,
+                sources,
+                PackageType::Lib,
+                TargetCapabilityFlags::all(),
+                LanguageFeatures::default(),
+            )
+            .expect("interpreter should be created");
+
+            let flipped = interpreter
+                .eval_callable_by_name("Test.FlipAll", &[Value::Int(2)])
+                .expect("call should succeed");
+            assert_eq!(
+                flipped,
+                Value::Array(vec![Value::RESULT_ONE, Value::RESULT_ONE].into())
+            );
+
+            let fresh = interpreter
+                .eval_callable_by_name("Test.MeasureFresh", &[])
+                .expect("call should succeed");
+            assert_eq!(fresh, Value::RESULT_ZERO);
+
+            let sum = interpreter
+                .eval_callable_by_name("Test.Add", &[Value::Int(2), Value::Int(3)])
+                .expect("call should succeed");
+            assert_eq!(sum, Value::Int(5));
+
+            let errors = interpreter
+                .eval_callable_by_name("42", &[])
+                .expect_err("a non-callable should fail");
+            assert_eq!(
+                errors[0].code().map(|code| code.to_string()).as_deref(),
+                Some("Qsc.Interpret.CallableNotFound")
+            );
+
+            let errors = interpreter
+                .eval_callable_by_name("Test.Add(1, 2)", &[])
+                .expect_err("an expression should not be evaluated");
+            assert_eq!(
+                errors[0].code().map(|code| code.to_string()).as_deref(),
+                Some("Qsc.Interpret.CallableNotFound")
+            );
+        }
+
+        #[test]
+        fn callable_by_name_with_wrong_arguments_fails() {
+            let source = indoc! { r#"
+            namespace Test {
+                function Add(a : Int, b : Int) : Int {
+                    a + b
+                }
+            }"#};
+
+            let sources = SourceMap::new([("test".into(), source.into())], None);
+            let mut interpreter = Interpreter::new(
+                true,
+                sources,
+                PackageType::Lib,
+                TargetCapabilityFlags::all(),
+                LanguageFeatures::default(),
+            )
+            .expect("interpreter should be created");
+
+            for args in [
+                &[Value::Int(2)][..],
+                &[Value::Int(2), Value::Int(3), Value::Int(4)],
+                &[Value::Int(2), Value::Bool(true)],
+            ] {
+                let errors = interpreter
+                    .eval_callable_by_name("Test.Add", args)
+                    .expect_err("mismatched arguments should fail");
+                assert_eq!(
+                    errors[0].code().map(|code| code.to_string()).as_deref(),
+                    Some("Qsc.Interpret.ArgumentMismatch")
+                );
+            }
+        }
+
+        #[test]
+        fn borrowed_qubit_starts_in_injected_state_and_must_be_restored() {
+            let source = indoc! { r#"
+            namespace Test {
+                operation Restores() : Result {
+                    borrow q = Qubit();
+                    X(q);
+                    let r = M(q);
+                    X(q);
+                    r
+                }
+
+                operation Clobbers() : Unit {
+                    borrow q = Qubit();
+                    X(q);
+                }
+            }"#};
+
+            let sources = SourceMap::new([("test".into(), source.into())], None);
+            let mut interpreter = Interpreter::new(
+                true,
+                sources,
+                PackageType::Lib,
+                TargetCapabilityFlags::all(),
+                LanguageFeatures::default(),
+            )
+            .expect("interpreter should be created");
+
+            let mut cursor = Cursor::new(Vec::<u8>::new());
+            let mut receiver = CursorReceiver::new(&mut cursor);
+            let one = vec![Complex64::new(0.0, 0.0), Complex64::new(1.0, 0.0)];
+            interpreter.set_borrow_state(one.clone());
+            let result = interpreter
+                .eval_fragments(&mut receiver, "Test.Restores()")
+                .expect("restoring the borrowed qubit should succeed");
+            assert_eq!(
+                result,
+                Value::RESULT_ZERO,
+                "X should flip the borrowed |1⟩ to |0⟩"
+            );
+
+            interpreter.set_borrow_state(one);
+            let errors = interpreter
+                .eval_fragments(&mut receiver, "Test.Clobbers()")
+                .expect_err("leaving the borrowed qubit flipped should fail");
+            assert_eq!(
+                errors[0].code().map(|code| code.to_string()).as_deref(),
+                Some("Qsc.Eval.BorrowedQubitNotRestored")
+            );
+        }
+
+        #[test]
+        fn top_level_borrowed_qubit_starts_in_injected_state() {
+            let mut interpreter = Interpreter::new(
+                true,
+                SourceMap::default(),
+                PackageType::Lib,
+                TargetCapabilityFlags::all(),
+                LanguageFeatures::default(),
+            )
+            .expect("interpreter should be created");
+            interpreter.set_borrow_state(vec![Complex64::new(0.0, 0.0), Complex64::new(1.0, 0.0)]);
+            let (result, output) = line(&mut interpreter, "borrow q = Qubit();");
+            is_only_value(&result, &output, &Value::unit());
+            let (result, output) = line(&mut interpreter, "M(q)");
+            is_only_value(&result, &output, &Value::RESULT_ONE);
+        }
+
+        #[test]
+        fn final_state_purity_is_one_after_noiseless_ghz_run() {
+            let source = indoc! { r#"
+            namespace Test {
+                @EntryPoint()
+                operation Main() : Result[] {
+                    use qs = Qubit[5];
+                    H(qs[0]);
+                    for i in 1..4 {
+                        CNOT(qs[i - 1], qs[i]);
+                    }
+                    MResetEachZ(qs)
+                }
+            }"#};
+
+            let sources = SourceMap::new([("test".into(), source.into())], None);
+            let mut interpreter = Interpreter::new(
+                true,
+                sources,
+                PackageType::Exe,
+                TargetCapabilityFlags::all(),
+                LanguageFeatures::default(),
+            )
+            .expect("interpreter should be created");
+
+            let (result, _) = entry(&mut interpreter);
+            result.expect("entry should run");
+            let purity = interpreter.final_state_purity();
+            assert!((purity - 1.0).abs() < 1e-9, "unexpected purity {purity}");
+        }
+
+        #[test]
+        fn leaked_qubits_reports_qubits_never_released() {
+            let source = indoc! { r#"
+            namespace Test {
+                @EntryPoint()
+                operation Main() : Unit {
+                    use q = Qubit();
+                    let leaked = QIR.Runtime.__quantum__rt__qubit_allocate();
+                    X(leaked);
+                }
+            }"#};
+
+            let sources = SourceMap::new([("test".into(), source.into())], None);
+            let mut interpreter = Interpreter::new(
+                true,
+                sources,
+                PackageType::Exe,
+                TargetCapabilityFlags::all(),
+                LanguageFeatures::default(),
+            )
+            .expect("interpreter should be created");
+
+            let (result, _) = entry(&mut interpreter);
+            result.expect("entry should run");
+            assert_eq!(interpreter.leaked_qubits(), vec![1]);
+        }
+
+        #[test]
+        fn leaked_qubits_is_empty_when_all_qubits_are_released() {
+            let source = indoc! { r#"
+            namespace Test {
+                @EntryPoint()
+                operation Main() : Result[] {
+                    use qs = Qubit[3];
+                    use aux = Qubit();
+                    ApplyToEach(H, qs);
+                    MResetEachZ(qs)
+                }
+            }"#};
+
+            let sources = SourceMap::new([("test".into(), source.into())], None);
+            let mut interpreter = Interpreter::new(
+                true,
+                sources,
+                PackageType::Exe,
+                TargetCapabilityFlags::all(),
+                LanguageFeatures::default(),
+            )
+            .expect("interpreter should be created");
+
+            let (result, _) = entry(&mut interpreter);
+            result.expect("entry should run");
+            assert!(interpreter.leaked_qubits().is_empty());
+        }
+
+        #[test]
+        fn verbose_mode_records_assignments_in_loop() {
+            let source = indoc! { r#"
+            namespace Test {
+                @EntryPoint()
+                operation Main() : Int {
+                    mutable count = 0;
+                    for _ in 1..3 {
+                        set count += 1;
+                    }
+                    let unchanged = count;
+                    set count = unchanged * 10;
+                    count
+                }
+            }"#};
+
+            let sources = SourceMap::new([("test".into(), source.into())], None);
+            let mut interpreter = Interpreter::new(
+                true,
+                sources,
+                PackageType::Exe,
+                TargetCapabilityFlags::all(),
+                LanguageFeatures::default(),
+            )
+            .expect("interpreter should be created");
+
+            interpreter.set_verbose(true);
+            let (result, output) = entry(&mut interpreter);
+            is_only_value(&result, &output, &Value::Int(30));
+            let events = interpreter
+                .take_events()
+                .into_iter()
+                .map(|event| {
+                    let Event::Assign {
+                        name,
+                        old,
+                        new,
+                        span,
+                    } = event;
+                    let text = &source[span.span.lo as usize..span.span.hi as usize];
+                    format!("{name}: {old} -> {new} at `{text}`")
+                })
+                .collect::<Vec<_>>();
+            expect![[r#"
+                [
+                    "count: 0 -> 1 at `set count += 1`",
+                    "count: 1 -> 2 at `set count += 1`",
+                    "count: 2 -> 3 at `set count += 1`",
+                    "count: 3 -> 30 at `set count = unchanged * 10`",
+                ]
+            "#]]
+            .assert_debug_eq(&events);
+            assert!(
+                interpreter.take_events().is_empty(),
+                "events should only be returned once"
+            );
+        }
+
+        #[test]
+        fn assignments_are_not_recorded_by_default() {
+            let source = indoc! { r#"
+            namespace Test {
+                @EntryPoint()
+                operation Main() : Int {
+                    mutable count = 0;
+                    set count += 1;
+                    count
+                }
+            }"#};
+
+            let sources = SourceMap::new([("test".into(), source.into())], None);
+            let mut interpreter = Interpreter::new(
+                true,
+                sources,
+                PackageType::Exe,
+                TargetCapabilityFlags::all(),
+                LanguageFeatures::default(),
+            )
+            .expect("interpreter should be created");
+
+            let (result, output) = entry(&mut interpreter);
+            is_only_value(&result, &output, &Value::Int(1));
+            assert!(interpreter.take_events().is_empty());
+        }
+
+        #[test]
+        fn eval_entry_recording_returns_event_log() {
+            let source = indoc! { r#"
+            namespace Test {
+                @EntryPoint()
+                operation Main() : Result {
+                    mutable count = 0;
+                    use q = Qubit();
+                    X(q);
+                    Rz(0.5, q);
+                    set count += 1;
+                    let r = MResetZ(q);
+                    Message("done");
+                    r
+                }
+            }"#};
+
+            let sources = SourceMap::new([("test".into(), source.into())], None);
+            let mut interpreter = Interpreter::new(
+                true,
+                sources,
+                PackageType::Exe,
+                TargetCapabilityFlags::all(),
+                LanguageFeatures::default(),
+            )
+            .expect("interpreter should be created");
+
+            let (value, log) = interpreter
+                .eval_entry_recording()
+                .expect("evaluation should succeed");
+            expect!["One"].assert_eq(&value.to_string());
+            expect![[r#"
+                [
+                    Allocate {
+                        qubit: 0,
+                    },
+                    Gate {
+                        gate: X(
+                            0,
+                        ),
+                    },
+                    Gate {
+                        gate: Rz(
+                            0.5,
+                            0,
+                        ),
+                    },
+                    Assign(
+                        Assign {
+                            name: "count",
+                            old: Int(
+                                0,
+                            ),
+                            new: Int(
+                                1,
+                            ),
+                            span: PackageSpan {
+                                package: PackageId(
+                                    2,
+                                ),
+                                span: Span {
+                                    lo: 161,
+                                    hi: 175,
+                                },
+                            },
+                        },
+                    ),
+                    Measure {
+                        qubit: 0,
+                        result: Val(
+                            true,
+                        ),
+                        reset: true,
+                    },
+                    Message {
+                        message: "done",
+                    },
+                    Release {
+                        qubit: 0,
+                    },
+                ]
+            "#]]
+            .assert_debug_eq(&log.events());
+            expect![[r#"[{"kind":"allocate","qubit":0},{"gate":"X","kind":"gate","params":[],"qubits":[0]},{"gate":"Rz","kind":"gate","params":[0.5],"qubits":[0]},{"kind":"assign","name":"count","new":"1","old":"0","span":{"hi":175,"lo":161,"package":2}},{"kind":"measure","qubit":0,"reset":true,"result":"One"},{"kind":"message","message":"done"},{"kind":"release","qubit":0}]"#]].assert_eq(&log.to_json());
+            serde_json::from_str::<serde_json::Value>(&log.to_json())
+                .expect("event log should be valid JSON");
+        }
+
+        #[test]
+        fn folding_constants_does_not_change_results() {
+            let source = indoc! { r#"
+            namespace Test {
+                import Microsoft.Quantum.Convert.IntAsDouble;
+
+                function Scale(x : Int) : Int {
+                    let factor = 3 * 7 - 1;
+                    x * factor + 2 ^ 10
+                }
+
+                @EntryPoint()
+                operation Main() : (Int, Double, Bool) {
+                    let n = Scale(4) % 97;
+                    let angle = 2.0 * 3.14159 / 8.0 + 1.0e-3;
+                    mutable sum = 0;
+                    mutable total = 0.0;
+                    for i in 0..n * 2 + 1 {
+                        set sum += i * (n - 5) / 3 + Scale(2) - (1 <<< 4);
+                        set total += angle * IntAsDouble(i) - 0.5 ^ 2.0;
+                    }
+                    Message($"{n}, {sum}");
+                    (sum, total, n > 40 and not (angle < 0.5))
+                }
+            }"#};
+
+            let run = |fold_constants| {
+                let sources = SourceMap::new([("test".into(), source.into())], None);
+                let new = if fold_constants {
+                    Interpreter::new_with_fold_constants
+                } else {
+                    Interpreter::new
+                };
+                let mut interpreter = new(
+                    true,
+                    sources,
+                    PackageType::Exe,
+                    TargetCapabilityFlags::all(),
+                    LanguageFeatures::default(),
+                )
+                .expect("interpreter should be created");
+                let (result, output) = entry(&mut interpreter);
+                let (fragment, _) = line(
+                    &mut interpreter,
+                    "{ let k = 4 * 5; Test.Scale(k - 18) + k ^ 2 }",
+                );
+                (
+                    result.expect("entry should run"),
+                    output,
+                    fragment.expect("fragment should run"),
+                )
+            };
+
+            let (folded, folded_output, folded_fragment) = run(true);
+            let (unfolded, unfolded_output, unfolded_fragment) = run(false);
+            assert_eq!(folded, unfolded);
+            assert_eq!(folded_output, unfolded_output);
+            assert_eq!(folded_fragment, unfolded_fragment);
+            expect!["(110023, 2222.2328750000006, false)"].assert_eq(&folded.to_string());
+            expect!["1464"].assert_eq(&folded_fragment.to_string());
+        }
+
+        #[test]
+        fn folding_constants_keeps_overflow_error_of_checked_arithmetic() {
+            let source = indoc! { r#"
+            namespace Test {
+                @EntryPoint()
+                function Main() : Int {
+                    let max = 9223372036854775807;
+                    max + 1
+                }
+            }"#};
+
+            let sources = SourceMap::new([("test".into(), source.into())], None);
+            let mut interpreter = Interpreter::new_with_fold_constants(
+                true,
+                sources,
+                PackageType::Exe,
+                TargetCapabilityFlags::all(),
+                LanguageFeatures::default(),
+            )
+            .expect("interpreter should be created");
+            interpreter.set_checked_arithmetic(true);
+            let (result, output) = entry(&mut interpreter);
+            is_only_error(
+                &result,
+                &output,
+                &expect![[r#"
+                    runtime error: integer overflow
+                      this operation overflows [test] [max + 1]
+                "#]],
+            );
+        }
+
+        #[test]
+        fn result_endianness_of_palindromic_register() {
+            let source = indoc! { r#"
+            namespace Test {
+                @EntryPoint()
+                operation Main() : Result[] {
+                    use qs = Qubit[3];
+                    X(qs[0]);
+                    X(qs[2]);
+                    MResetEachZ(qs)
+                }
+            }"#};
+
+            let sources = SourceMap::new([("test".into(), source.into())], None);
+            let mut interpreter = Interpreter::new(
+                true,
+                sources,
+                PackageType::Exe,
+                TargetCapabilityFlags::all(),
+                LanguageFeatures::default(),
+            )
+            .expect("interpreter should be created");
+
+            let (result, _) = entry(&mut interpreter);
+            let results = result.expect("entry should run");
+            assert_eq!(interpreter.results_as_int(&results), Some(5u8.into()));
+            interpreter.set_result_endianness(Endianness::Big);
+            assert_eq!(interpreter.results_as_int(&results), Some(5u8.into()));
+        }
+
+        #[test]
+        fn result_endianness_determines_bit_significance() {
+            let source = indoc! { r#"
+            namespace Test {
+                @EntryPoint()
+                operation Main() : Result[] {
+                    use qs = Qubit[3];
+                    X(qs[0]);
+                    X(qs[1]);
+                    MResetEachZ(qs)
+                }
+            }"#};
+
+            let sources = SourceMap::new([("test".into(), source.into())], None);
+            let mut interpreter = Interpreter::new(
+                true,
+                sources,
+                PackageType::Exe,
+                TargetCapabilityFlags::all(),
+                LanguageFeatures::default(),
+            )
+            .expect("interpreter should be created");
+
+            let (result, _) = entry(&mut interpreter);
+            let results = result.expect("entry should run");
+            assert_eq!(interpreter.results_as_int(&results), Some(3u8.into()));
+            assert_eq!(
+                interpreter.results_as_bit_string(&results).as_deref(),
+                Some("011")
+            );
+            interpreter.set_result_endianness(Endianness::Big);
+            assert_eq!(interpreter.results_as_int(&results), Some(6u8.into()));
+            assert_eq!(
+                interpreter.results_as_bit_string(&results).as_deref(),
+                Some("110")
+            );
+            assert_eq!(interpreter.results_as_int(&Value::Int(3)), None);
+        }
+
+        #[test]
+        fn export_of_dropped_item_does_not_prevent_compilation() {
+            let source = indoc! { r#"
+            namespace Test {
+                @Config(Base)
+                function Dropped() : Unit {}
+                export Dropped;
+
+                @EntryPoint()
+                operation Main() : Unit {
+                    Message("hello there...")
+                }
+            }"#};
+
+            let sources = SourceMap::new([("test".into(), source.into())], None);
+            let mut interpreter = Interpreter::new(
+                true,
+                sources,
+                PackageType::Exe,
+                TargetCapabilityFlags::all(),
+                LanguageFeatures::default(),
+            )
+            .expect("interpreter should be created");
+
+            let (result, output) = entry(&mut interpreter);
+            is_unit_with_output_eval_entry(&result, &output, "hello there...");
+        }
+
+        #[test]
+        fn type_shadowing_built_in_type_is_an_error() {
+            let source = indoc! { r#"
+            namespace Test {
+                newtype Result = Int;
+
+                @EntryPoint()
+                operation Main() : Int {
+                    let r = Result(3);
+                    r!
+                }
+            }"#};
+
+            let sources = SourceMap::new([("test".into(), source.into())], None);
+            let result = Interpreter::new(
+                true,
+                sources,
+                PackageType::Exe,
+                TargetCapabilityFlags::all(),
+                LanguageFeatures::default(),
+            );
+
+            match result {
+                Ok(_) => panic!("Expected error, got interpreter."),
+                Err(errors) => is_error(
+                    &errors,
+                    &expect![[r#"
+                        name error: declaration of `Result` shadows the built-in or prelude item `Result`
+                           [test] [Result]
+                    "#]],
+                ),
+            }
+        }
+
+        #[test]
+        fn export_of_dropped_item_does_not_prevent_fragment_evaluation() {
+            let mut interpreter = Interpreter::new(
+                true,
+                SourceMap::default(),
+                PackageType::Lib,
+                TargetCapabilityFlags::all(),
+                LanguageFeatures::default(),
+            )
+            .expect("interpreter should be created");
+
+            let (result, output) = line(
+                &mut interpreter,
+                indoc! { r#"
+                namespace Test {
+                    @Config(Base)
+                    function Dropped() : Unit {}
+                    export Dropped;
+
+                    function Kept() : Int { 42 }
+                }"#},
+            );
+            is_only_value(&result, &output, &Value::unit());
+            let (result, output) = line(&mut interpreter, "Test.Kept()");
+            is_only_value(&result, &output, &Value::Int(42));
+        }
+
         #[test]
         fn errors_returned_if_sources_do_not_match_profile() {
             let source = indoc! { r#"
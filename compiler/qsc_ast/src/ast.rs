@@ -5,13 +5,18 @@
 
 #![warn(missing_docs)]
 
+use crate::{
+    mut_visit::{self, MutVisitor},
+    visit::{self, Visitable, Visitor},
+};
 use indenter::{indented, Format, Indented};
 use num_bigint::BigInt;
 use qsc_data_structures::span::{Span, WithSpan};
 use std::{
     cmp::Ordering,
     fmt::{self, Display, Formatter, Write},
-    hash::{Hash, Hasher},
+    hash::{DefaultHasher, Hash, Hasher},
+    mem::discriminant,
     rc::Rc,
 };
 
@@ -177,6 +182,14 @@ impl Namespace {
             _ => &[],
         })
     }
+
+    /// Returns an iterator over the items in the namespace that are imported.
+    pub fn imports(&self) -> impl Iterator<Item = &ImportOrExportItem> {
+        self.items.iter().flat_map(|i| match i.kind.as_ref() {
+            ItemKind::ImportOrExport(decl) if decl.is_import() => &decl.items[..],
+            _ => &[],
+        })
+    }
 }
 
 impl Display for Namespace {
@@ -326,6 +339,54 @@ pub struct Attr {
     pub arg: Box<Expr>,
 }
 
+impl Attr {
+    /// Returns the argument of the attribute if it is a single string literal, as in
+    /// `@Deprecated("Use Bar instead")`.
+    #[must_use]
+    pub fn string_arg(&self) -> Option<&str> {
+        match single_attr_arg(&self.arg)?.kind.as_ref() {
+            ExprKind::Lit(lit) => match lit.as_ref() {
+                Lit::String(value) => Some(value),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+
+    /// Returns the argument of the attribute if it is a single integer literal, which
+    /// may be negated.
+    #[must_use]
+    pub fn int_arg(&self) -> Option<i64> {
+        match single_attr_arg(&self.arg)?.kind.as_ref() {
+            ExprKind::Lit(lit) => match lit.as_ref() {
+                Lit::Int(value) => Some(*value),
+                _ => None,
+            },
+            ExprKind::UnOp(UnOp::Neg, operand) => match operand.kind.as_ref() {
+                ExprKind::Lit(lit) => match lit.as_ref() {
+                    Lit::Int(value) => value.checked_neg(),
+                    _ => None,
+                },
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+}
+
+/// Unwraps the parentheses and single-element tuples around an attribute argument.
+/// Returns `None` for a unit argument or one with multiple elements.
+fn single_attr_arg(arg: &Expr) -> Option<&Expr> {
+    match arg.kind.as_ref() {
+        ExprKind::Paren(inner) => single_attr_arg(inner),
+        ExprKind::Tuple(items) => match items.as_ref() {
+            [item] => single_attr_arg(item),
+            _ => None,
+        },
+        _ => Some(arg),
+    }
+}
+
 impl Display for Attr {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
         let mut indent = set_indentation(indented(f), 0);
@@ -1609,6 +1670,13 @@ impl Idents {
         Rc::from(buf)
     }
 
+    /// The dot-separated path of the idents in this [`Idents`], folded to lowercase.
+    /// Two paths with the same normalized name differ at most in case, e.g. `Foo.Bar` and `foo.bar`.
+    #[must_use]
+    pub fn normalized_name(&self) -> String {
+        self.name().to_lowercase()
+    }
+
     /// Appends another ident to this [`Idents`].
     /// Returns a new [`Idents`] with the appended ident.
     #[must_use = "this method returns a new value and does not mutate the original value"]
@@ -1880,6 +1948,9 @@ pub struct ImportOrExportDecl {
     pub items: Box<[ImportOrExportItem]>,
     /// Whether this is an export declaration or not. If `false`, then this is an `Import`.
     is_export: bool,
+    /// Whether this is an `import type` declaration, which only brings the types of the items
+    /// into scope and not their constructors.
+    is_type_only: bool,
 }
 
 impl Display for ImportOrExportDecl {
@@ -1890,7 +1961,16 @@ impl Display for ImportOrExportDecl {
             .map(std::string::ToString::to_string)
             .collect::<Vec<_>>()
             .join(", ");
-        write!(f, "ImportOrExportDecl {}: [{items_str}]", self.span)
+        let type_only = if self.is_type_only {
+            " (type only)"
+        } else {
+            ""
+        };
+        write!(
+            f,
+            "ImportOrExportDecl {}{type_only}: [{items_str}]",
+            self.span
+        )
     }
 }
 
@@ -1902,6 +1982,16 @@ impl ImportOrExportDecl {
             span,
             items,
             is_export,
+            is_type_only: false,
+        }
+    }
+
+    /// Marks this import declaration as type-only, see [`ImportOrExportDecl::is_type_only`].
+    #[must_use]
+    pub fn type_only(self) -> Self {
+        Self {
+            is_type_only: true,
+            ..self
         }
     }
 
@@ -1917,6 +2007,13 @@ impl ImportOrExportDecl {
         !self.is_export
     }
 
+    /// Returns true if this is an `import type` declaration, whose items are only bound as types
+    /// and not as terms, so that the constructors of imported UDTs are not brought into scope.
+    #[must_use]
+    pub fn is_type_only(&self) -> bool {
+        self.is_type_only
+    }
+
     /// Returns an iterator over the items being exported from this namespace.
     pub fn items(&self) -> impl Iterator<Item = &ImportOrExportItem> {
         self.items.iter()
@@ -1984,3 +2081,223 @@ impl ImportOrExportItem {
         }
     }
 }
+
+/// Clears the doc comments of all namespaces and items in the package, including items declared
+/// in callable bodies and import or export declarations, leaving the rest of the package unchanged.
+pub fn strip_docs(package: &mut Package) {
+    DocStripper.visit_package(package);
+}
+
+struct DocStripper;
+
+impl MutVisitor for DocStripper {
+    fn visit_namespace(&mut self, namespace: &mut Namespace) {
+        namespace.doc = "".into();
+        mut_visit::walk_namespace(self, namespace);
+    }
+
+    fn visit_item(&mut self, item: &mut Item) {
+        item.doc = "".into();
+        mut_visit::walk_item(self, item);
+    }
+}
+
+/// Hashes the shape and literal content of an AST node, ignoring node IDs, spans and doc comments,
+/// so that identical code hashes the same wherever it appears.
+#[must_use]
+pub fn structural_hash(node: &impl Visitable) -> u64 {
+    let mut hasher = StructuralHasher(DefaultHasher::new());
+    node.visit_with(&mut hasher);
+    hasher.0.finish()
+}
+
+/// Hashes each visited node as a tag identifying the kind of node, followed by its own content,
+/// its children, and an end marker, so that different trees cannot produce the same sequence.
+struct StructuralHasher(DefaultHasher);
+
+impl StructuralHasher {
+    fn node(&mut self, tag: &str, content: impl Hash, walk: impl FnOnce(&mut Self)) {
+        tag.hash(&mut self.0);
+        content.hash(&mut self.0);
+        walk(self);
+        0xFFu8.hash(&mut self.0);
+    }
+}
+
+fn hash_lit(lit: &Lit, state: &mut impl Hasher) {
+    discriminant(lit).hash(state);
+    match lit {
+        Lit::BigInt(value) => value.hash(state),
+        Lit::Bool(value) => value.hash(state),
+        Lit::Double(value) => value.to_bits().hash(state),
+        Lit::Int(value) => value.hash(state),
+        Lit::Pauli(value) => value.hash(state),
+        Lit::Result(value) => value.hash(state),
+        Lit::String(value) => value.hash(state),
+    }
+}
+
+impl<'a> Visitor<'a> for StructuralHasher {
+    fn visit_package(&mut self, package: &'a Package) {
+        self.node("package", (), |vis| visit::walk_package(vis, package));
+    }
+
+    fn visit_namespace(&mut self, namespace: &'a Namespace) {
+        self.node("namespace", (), |vis| visit::walk_namespace(vis, namespace));
+    }
+
+    fn visit_item(&mut self, item: &'a Item) {
+        let content = match &*item.kind {
+            ItemKind::ImportOrExport(decl) => Some((
+                decl.is_export(),
+                decl.items
+                    .iter()
+                    .map(|item| item.is_glob)
+                    .collect::<Vec<_>>(),
+            )),
+            _ => None,
+        };
+        self.node("item", (discriminant(&*item.kind), content), |vis| {
+            visit::walk_item(vis, item);
+        });
+    }
+
+    fn visit_attr(&mut self, attr: &'a Attr) {
+        self.node("attr", (), |vis| visit::walk_attr(vis, attr));
+    }
+
+    fn visit_visibility(&mut self, visibility: &'a Visibility) {
+        self.node("visibility", visibility.kind, |_| {});
+    }
+
+    fn visit_ty_def(&mut self, def: &'a TyDef) {
+        self.node("ty_def", discriminant(&*def.kind), |vis| {
+            visit::walk_ty_def(vis, def);
+        });
+    }
+
+    fn visit_callable_decl(&mut self, decl: &'a CallableDecl) {
+        let content = (decl.kind, discriminant(&*decl.body));
+        self.node("callable_decl", content, |vis| {
+            visit::walk_callable_decl(vis, decl);
+        });
+    }
+
+    fn visit_struct_decl(&mut self, decl: &'a StructDecl) {
+        self.node("struct_decl", (), |vis| visit::walk_struct_decl(vis, decl));
+    }
+
+    fn visit_field_def(&mut self, def: &'a FieldDef) {
+        self.node("field_def", (), |vis| visit::walk_field_def(vis, def));
+    }
+
+    fn visit_spec_decl(&mut self, decl: &'a SpecDecl) {
+        let generator = match &decl.body {
+            SpecBody::Gen(generator) => Some(*generator),
+            SpecBody::Impl(..) => None,
+        };
+        self.node("spec_decl", (decl.spec, generator), |vis| {
+            visit::walk_spec_decl(vis, decl);
+        });
+    }
+
+    fn visit_functor_expr(&mut self, expr: &'a FunctorExpr) {
+        let content = match &*expr.kind {
+            FunctorExprKind::BinOp(op, ..) => (Some(*op), None),
+            FunctorExprKind::Lit(functor) => (None, Some(*functor)),
+            FunctorExprKind::Paren(_) => (None, None),
+        };
+        self.node(
+            "functor_expr",
+            (discriminant(&*expr.kind), content),
+            |vis| {
+                visit::walk_functor_expr(vis, expr);
+            },
+        );
+    }
+
+    fn visit_ty(&mut self, ty: &'a Ty) {
+        let kind = match &*ty.kind {
+            TyKind::Arrow(kind, ..) => Some(*kind),
+            _ => None,
+        };
+        self.node("ty", (discriminant(&*ty.kind), kind), |vis| {
+            visit::walk_ty(vis, ty);
+        });
+    }
+
+    fn visit_block(&mut self, block: &'a Block) {
+        self.node("block", (), |vis| visit::walk_block(vis, block));
+    }
+
+    fn visit_stmt(&mut self, stmt: &'a Stmt) {
+        let content = match &*stmt.kind {
+            StmtKind::Local(mutability, ..) => (Some(*mutability), None),
+            StmtKind::Qubit(source, ..) => (None, Some(*source)),
+            _ => (None, None),
+        };
+        self.node("stmt", (discriminant(&*stmt.kind), content), |vis| {
+            visit::walk_stmt(vis, stmt);
+        });
+    }
+
+    fn visit_expr(&mut self, expr: &'a Expr) {
+        let mut content = DefaultHasher::new();
+        match &*expr.kind {
+            ExprKind::AssignOp(op, ..) | ExprKind::BinOp(op, ..) => op.hash(&mut content),
+            ExprKind::Interpolate(components) => {
+                for component in components {
+                    match component {
+                        StringComponent::Expr(_) => None,
+                        StringComponent::Lit(lit) => Some(lit),
+                    }
+                    .hash(&mut content);
+                }
+            }
+            ExprKind::Lambda(kind, ..) => kind.hash(&mut content),
+            ExprKind::Lit(lit) => hash_lit(lit, &mut content),
+            // Only the present bounds of a range are visited, so record which ones they are.
+            ExprKind::Range(start, step, end) => {
+                (start.is_some(), step.is_some(), end.is_some()).hash(&mut content);
+            }
+            ExprKind::TernOp(op, ..) => op.hash(&mut content),
+            ExprKind::UnOp(op, _) => op.hash(&mut content),
+            _ => {}
+        }
+        self.node(
+            "expr",
+            (discriminant(&*expr.kind), content.finish()),
+            |vis| visit::walk_expr(vis, expr),
+        );
+    }
+
+    fn visit_field_assign(&mut self, assign: &'a FieldAssign) {
+        self.node("field_assign", (), |vis| {
+            visit::walk_field_assign(vis, assign);
+        });
+    }
+
+    fn visit_pat(&mut self, pat: &'a Pat) {
+        self.node("pat", discriminant(&*pat.kind), |vis| {
+            visit::walk_pat(vis, pat);
+        });
+    }
+
+    fn visit_qubit_init(&mut self, init: &'a QubitInit) {
+        self.node("qubit_init", discriminant(&*init.kind), |vis| {
+            visit::walk_qubit_init(vis, init);
+        });
+    }
+
+    fn visit_path(&mut self, path: &'a Path) {
+        self.node("path", (), |vis| visit::walk_path(vis, path));
+    }
+
+    fn visit_ident(&mut self, ident: &'a Ident) {
+        self.node("ident", &ident.name, |_| {});
+    }
+
+    fn visit_idents(&mut self, idents: &'a Idents) {
+        self.node("idents", (), |vis| visit::walk_idents(vis, idents));
+    }
+}
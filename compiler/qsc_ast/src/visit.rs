@@ -90,6 +90,40 @@ pub trait Visitor<'a>: Sized {
     }
 }
 
+/// An AST node that can be passed to the matching method of a [`Visitor`].
+pub trait Visitable {
+    fn visit_with<'a>(&'a self, vis: &mut impl Visitor<'a>);
+}
+
+macro_rules! impl_visitable {
+    ($($node:ty => $method:ident),* $(,)?) => {
+        $(impl Visitable for $node {
+            fn visit_with<'a>(&'a self, vis: &mut impl Visitor<'a>) {
+                vis.$method(self);
+            }
+        })*
+    };
+}
+
+impl_visitable! {
+    Package => visit_package,
+    Namespace => visit_namespace,
+    Item => visit_item,
+    Attr => visit_attr,
+    TyDef => visit_ty_def,
+    CallableDecl => visit_callable_decl,
+    StructDecl => visit_struct_decl,
+    SpecDecl => visit_spec_decl,
+    FunctorExpr => visit_functor_expr,
+    Ty => visit_ty,
+    Block => visit_block,
+    Stmt => visit_stmt,
+    Expr => visit_expr,
+    Pat => visit_pat,
+    QubitInit => visit_qubit_init,
+    Path => visit_path,
+}
+
 pub fn walk_package<'a>(vis: &mut impl Visitor<'a>, package: &'a Package) {
     package.nodes.iter().for_each(|n| match n {
         TopLevelNode::Namespace(ns) => vis.visit_namespace(ns),
@@ -9,14 +9,26 @@ use num_bigint::BigUint;
 use num_complex::Complex;
 use qsc_codegen::remapper::{HardwareId, Remapper};
 use qsc_data_structures::index_map::IndexMap;
-use qsc_eval::{backend::Backend, val::Value};
+use qsc_eval::{
+    backend::{Backend, GateOp, SparseSim},
+    val::Value,
+};
+use qsc_fir::fir::Pauli;
+use rustc_hash::FxHashMap;
 use std::{fmt::Write, mem::take, rc::Rc};
 
 /// Backend implementation that builds a circuit representation.
+#[derive(Clone)]
 pub struct Builder {
     circuit: Circuit,
     config: Config,
     remapper: Remapper,
+    /// Operation calls that have been entered but not yet exited, innermost last,
+    /// along with the operations recorded so far for each.
+    open_groups: Vec<(Rc<str>, Vec<Operation>)>,
+    /// Measurement result registers that the operations currently being recorded are
+    /// conditioned on, innermost last, or `None` for a result that has no register.
+    conditions: Vec<Option<Register>>,
 }
 
 impl Backend for Builder {
@@ -214,6 +226,32 @@ impl Backend for Builder {
             _ => Some(Ok(Value::unit())),
         }
     }
+
+    fn enter_operation(&mut self, name: &str) {
+        if self.config.group_operations {
+            self.open_groups.push((name.into(), Vec::new()));
+        }
+    }
+
+    fn exit_operation(&mut self) {
+        if let Some((name, operations)) = self.open_groups.pop() {
+            if let Some(operation) = group(&name, operations) {
+                self.push_gate(operation);
+            }
+        }
+    }
+
+    /// Marks the operations recorded until the matching `exit_conditional` as conditioned on the
+    /// given measurement result, so that they are drawn with a classical control line from the
+    /// result's wire.
+    fn enter_conditional(&mut self, result: usize) {
+        let register = self.result_register(result);
+        self.conditions.push(register);
+    }
+
+    fn exit_conditional(&mut self) {
+        self.conditions.pop();
+    }
 }
 
 impl Builder {
@@ -223,18 +261,22 @@ impl Builder {
             circuit: Circuit::default(),
             config,
             remapper: Remapper::default(),
+            open_groups: Vec::new(),
+            conditions: Vec::new(),
         }
     }
 
     #[must_use]
     pub fn snapshot(&self) -> Circuit {
-        let circuit = self.circuit.clone();
+        let mut circuit = self.circuit.clone();
+        close_groups(&mut circuit, self.open_groups.clone());
         self.finish_circuit(circuit)
     }
 
     #[must_use]
     pub fn finish(mut self) -> Circuit {
-        let circuit = take(&mut self.circuit);
+        let mut circuit = take(&mut self.circuit);
+        close_groups(&mut circuit, take(&mut self.open_groups));
         self.finish_circuit(circuit)
     }
 
@@ -242,8 +284,18 @@ impl Builder {
         self.remapper.map(qubit)
     }
 
-    fn push_gate(&mut self, gate: Operation) {
-        self.circuit.operations.push(gate);
+    fn push_gate(&mut self, mut gate: Operation) {
+        // The controls of a measurement are the measured qubits, so only other gates
+        // pick up the classical controls of the enclosing conditionals.
+        if !gate.is_measurement && self.conditions.iter().any(Option::is_some) {
+            gate.is_controlled = true;
+            gate.controls
+                .extend(self.conditions.iter().flatten().cloned());
+        }
+        match self.open_groups.last_mut() {
+            Some((_, operations)) => operations.push(gate),
+            None => self.circuit.operations.push(gate),
+        }
     }
 
     fn num_measurements_by_qubit(&self) -> IndexMap<usize, usize> {
@@ -268,6 +320,23 @@ impl Builder {
             .count()
     }
 
+    /// Returns the classical register that holds the given measurement result.
+    fn result_register(&self, result: usize) -> Option<Register> {
+        let mut measurements = self.remapper.measurements();
+        let (qubit, _) = measurements.find(|(_, id)| *id == result)?;
+        if self.config.base_profile {
+            // measurements are deferred, one per qubit
+            return Some(Register::classical(qubit.0, 0));
+        }
+        let res_id = self
+            .remapper
+            .measurements()
+            .take_while(|(_, id)| *id != result)
+            .filter(|(q, _)| q.0 == qubit.0)
+            .count();
+        Some(Register::classical(qubit.0, res_id))
+    }
+
     fn finish_circuit(&self, mut circuit: Circuit) -> Circuit {
         let by_qubit = self.num_measurements_by_qubit();
 
@@ -370,6 +439,242 @@ impl Builder {
     }
 }
 
+/// Backend that simulates a program while building its circuit, as in trace mode. Measurements
+/// return result ids whose outcomes come from the simulation, so that the branches the program
+/// takes because of a measurement outcome are drawn as classically controlled by it.
+pub struct Tracer {
+    sim: SparseSim,
+    builder: Builder,
+    /// The simulated outcome of each measurement, keyed by result id.
+    outcomes: FxHashMap<usize, bool>,
+}
+
+impl Tracer {
+    #[must_use]
+    pub fn new(config: Config) -> Self {
+        Tracer {
+            sim: SparseSim::new(),
+            builder: Builder::new(config),
+            outcomes: FxHashMap::default(),
+        }
+    }
+
+    #[must_use]
+    pub fn finish(self) -> Circuit {
+        self.builder.finish()
+    }
+}
+
+impl Backend for Tracer {
+    type ResultType = usize;
+
+    fn ccx(&mut self, ctl0: usize, ctl1: usize, q: usize) {
+        self.builder.ccx(ctl0, ctl1, q);
+        self.sim.ccx(ctl0, ctl1, q);
+    }
+
+    fn cx(&mut self, ctl: usize, q: usize) {
+        self.builder.cx(ctl, q);
+        self.sim.cx(ctl, q);
+    }
+
+    fn cy(&mut self, ctl: usize, q: usize) {
+        self.builder.cy(ctl, q);
+        self.sim.cy(ctl, q);
+    }
+
+    fn cz(&mut self, ctl: usize, q: usize) {
+        self.builder.cz(ctl, q);
+        self.sim.cz(ctl, q);
+    }
+
+    fn h(&mut self, q: usize) {
+        self.builder.h(q);
+        self.sim.h(q);
+    }
+
+    fn m(&mut self, q: usize) -> Self::ResultType {
+        let outcome = self.sim.m(q);
+        let id = self.builder.m(q);
+        self.outcomes.insert(id, outcome);
+        id
+    }
+
+    fn mresetz(&mut self, q: usize) -> Self::ResultType {
+        let outcome = self.sim.mresetz(q);
+        let id = self.builder.mresetz(q);
+        self.outcomes.insert(id, outcome);
+        id
+    }
+
+    fn measure_pauli(&mut self, paulis: &[(Pauli, usize)]) -> Self::ResultType {
+        let outcome = self.sim.measure_pauli(paulis);
+        let id = self.builder.measure_pauli(paulis);
+        self.outcomes.insert(id, outcome);
+        id
+    }
+
+    fn reset(&mut self, q: usize) {
+        self.builder.reset(q);
+        self.sim.reset(q);
+    }
+
+    fn rx(&mut self, theta: f64, q: usize) {
+        self.builder.rx(theta, q);
+        self.sim.rx(theta, q);
+    }
+
+    fn rxx(&mut self, theta: f64, q0: usize, q1: usize) {
+        self.builder.rxx(theta, q0, q1);
+        self.sim.rxx(theta, q0, q1);
+    }
+
+    fn ry(&mut self, theta: f64, q: usize) {
+        self.builder.ry(theta, q);
+        self.sim.ry(theta, q);
+    }
+
+    fn ryy(&mut self, theta: f64, q0: usize, q1: usize) {
+        self.builder.ryy(theta, q0, q1);
+        self.sim.ryy(theta, q0, q1);
+    }
+
+    fn rz(&mut self, theta: f64, q: usize) {
+        self.builder.rz(theta, q);
+        self.sim.rz(theta, q);
+    }
+
+    fn rzz(&mut self, theta: f64, q0: usize, q1: usize) {
+        self.builder.rzz(theta, q0, q1);
+        self.sim.rzz(theta, q0, q1);
+    }
+
+    fn sadj(&mut self, q: usize) {
+        self.builder.sadj(q);
+        self.sim.sadj(q);
+    }
+
+    fn s(&mut self, q: usize) {
+        self.builder.s(q);
+        self.sim.s(q);
+    }
+
+    fn swap(&mut self, q0: usize, q1: usize) {
+        self.builder.swap(q0, q1);
+        self.sim.swap(q0, q1);
+    }
+
+    fn tadj(&mut self, q: usize) {
+        self.builder.tadj(q);
+        self.sim.tadj(q);
+    }
+
+    fn t(&mut self, q: usize) {
+        self.builder.t(q);
+        self.sim.t(q);
+    }
+
+    fn x(&mut self, q: usize) {
+        self.builder.x(q);
+        self.sim.x(q);
+    }
+
+    fn y(&mut self, q: usize) {
+        self.builder.y(q);
+        self.sim.y(q);
+    }
+
+    fn z(&mut self, q: usize) {
+        self.builder.z(q);
+        self.sim.z(q);
+    }
+
+    fn qubit_allocate(&mut self) -> usize {
+        // As in `Chain`, the qubit id allocated by the simulator is used for both backends.
+        let _ = self.builder.qubit_allocate();
+        self.sim.qubit_allocate()
+    }
+
+    fn qubit_release(&mut self, q: usize) {
+        self.builder.qubit_release(q);
+        self.sim.qubit_release(q);
+    }
+
+    fn capture_quantum_state(&mut self) -> (Vec<(BigUint, Complex<f64>)>, usize) {
+        self.sim.capture_quantum_state()
+    }
+
+    fn qubit_is_zero(&mut self, q: usize) -> bool {
+        self.sim.qubit_is_zero(q)
+    }
+
+    fn qubit_borrow(&mut self) -> usize {
+        let _ = self.builder.qubit_borrow();
+        self.sim.qubit_borrow()
+    }
+
+    fn qubit_return(&mut self, q: usize) -> bool {
+        let _ = self.builder.qubit_return(q);
+        self.sim.qubit_return(q)
+    }
+
+    fn custom_intrinsic(&mut self, name: &str, arg: Value) -> Option<Result<Value, String>> {
+        let _ = self.builder.custom_intrinsic(name, arg.clone());
+        self.sim.custom_intrinsic(name, arg)
+    }
+
+    fn set_seed(&mut self, seed: Option<u64>) {
+        self.sim.set_seed(seed);
+    }
+
+    fn enter_operation(&mut self, name: &str) {
+        self.builder.enter_operation(name);
+    }
+
+    fn exit_operation(&mut self) {
+        self.builder.exit_operation();
+    }
+
+    fn result_outcome(&mut self, id: usize) -> Option<bool> {
+        self.outcomes.get(&id).copied()
+    }
+
+    fn enter_conditional(&mut self, result: usize) {
+        self.builder.enter_conditional(result);
+    }
+
+    fn exit_conditional(&mut self) {
+        self.builder.exit_conditional();
+    }
+
+    fn apply_batch(&mut self, ops: &[GateOp]) {
+        self.builder.apply_batch(ops);
+        self.sim.apply_batch(ops);
+    }
+}
+
+/// Closes any groups still open, e.g. when evaluation stopped partway through an
+/// operation call, so that their operations still appear in the circuit.
+fn close_groups(circuit: &mut Circuit, open_groups: Vec<(Rc<str>, Vec<Operation>)>) {
+    let mut inner: Option<Operation> = None;
+    for (name, mut operations) in open_groups.into_iter().rev() {
+        operations.extend(inner.take());
+        inner = group(&name, operations);
+    }
+    circuit.operations.extend(inner);
+}
+
+/// Groups the operations applied by a call. Calls that apply no operations are dropped, and
+/// calls that apply a single operation, such as library wrappers around intrinsic gates, are
+/// replaced by that operation since a group would add nothing to the diagram.
+fn group(name: &str, mut operations: Vec<Operation>) -> Option<Operation> {
+    match operations.len() {
+        0 => None,
+        1 => operations.pop(),
+        _ => Some(Operation::group(name, operations)),
+    }
+}
+
 #[allow(clippy::unicode_not_nfc)]
 static KET_ZERO: &str = "|0〉";
 
@@ -16,6 +16,226 @@ pub struct Circuit {
     pub qubits: Vec<Qubit>,
 }
 
+impl Circuit {
+    /// Returns a copy of the circuit with all operation groups replaced
+    /// by the operations they contain.
+    #[must_use]
+    pub fn flatten(&self) -> Circuit {
+        let mut operations = Vec::new();
+        flatten_into(&self.operations, &mut operations);
+        Circuit {
+            operations,
+            qubits: self.qubits.clone(),
+        }
+    }
+
+    /// Returns a copy of the circuit with pairs of identical self-inverse gates cancelled, such as
+    /// `X X`, `H H` or two `CNOT`s with the same control and target. A pair only cancels when no
+    /// operation between the two gates touches any of their qubits, and cancelling a pair can make
+    /// the gates around it adjacent in turn. The operations inside groups are simplified the same
+    /// way, and groups left without operations are removed.
+    #[must_use]
+    pub fn simplify(&self) -> Circuit {
+        Circuit {
+            operations: simplify_operations(&self.operations),
+            qubits: self.qubits.clone(),
+        }
+    }
+
+    /// Returns the circuit as a LaTeX `quantikz` environment, e.g. for publications.
+    /// Operation groups are replaced by the operations they contain. Measurement results are drawn
+    /// on classical wires that start at the measurement, and connections from a measurement result
+    /// to the operations it conditions are dashed.
+    #[must_use]
+    pub fn to_quantikz(&self) -> String {
+        // The label of each row, and whether it is a classical wire
+        let mut rows = vec![];
+        let mut register_to_row = FxHashMap::default();
+        for q in &self.qubits {
+            register_to_row.insert((q.id, None), rows.len());
+            rows.push((format!("\\lstick{{$q_{{{}}}$}}", q.id), false));
+            for i in 0..q.num_children {
+                register_to_row.insert((q.id, Some(i)), rows.len());
+                // Classical wires only start at the measurement that produces them
+                rows.push(("\\setwiretype{n}".to_string(), true));
+            }
+        }
+
+        let mut cells: Vec<ObjectsByColumn> = vec![FxHashMap::default(); rows.len()];
+        let mut next_column = vec![1; rows.len()];
+        let rows_of = |regs: &[Register]| {
+            regs.iter()
+                .filter_map(|reg| register_to_row.get(&(reg.q_id, reg.c_id)).copied())
+                .collect::<Vec<_>>()
+        };
+        for o in &self.flatten().operations {
+            let mut targets = rows_of(&o.targets);
+            targets.sort_unstable();
+            let controls = rows_of(&o.controls);
+            let (Some(begin), Some(end)) = (
+                targets.iter().chain(&controls).min().copied(),
+                targets.iter().chain(&controls).max().copied(),
+            ) else {
+                continue;
+            };
+            // Vertical wires cross every row in between, so the operation takes the first
+            // column that is free in all of them.
+            let column = next_column[begin..=end].iter().copied().max().unwrap_or(1);
+            next_column[begin..=end].fill(column + 1);
+            let mut set = |row: usize, cell: String| {
+                cells[row].insert(column, cell);
+            };
+
+            if o.is_measurement {
+                for &q in &controls {
+                    let mut cell = "\\meter{}".to_string();
+                    for &t in &targets {
+                        let _ = write!(cell, "\\wire[d][{}]{{c}}", t.abs_diff(q));
+                    }
+                    set(q, cell);
+                }
+                for &t in &targets {
+                    set(t, "\\setwiretype{c}".to_string());
+                }
+                continue;
+            }
+
+            let Some(&first) = targets.first() else {
+                // An operation without targets, such as a controlled global phase, is drawn as its
+                // controls joined by a vertical wire.
+                let mut controls = controls;
+                controls.sort_unstable();
+                for (i, &c) in controls.iter().enumerate() {
+                    match controls.get(i + 1) {
+                        Some(&next) => set(c, format!("\\ctrl{{{}}}", next - c)),
+                        None => set(c, "\\control{}".to_string()),
+                    }
+                }
+                continue;
+            };
+            for &c in &controls {
+                let distance = row_offset(c, first);
+                if rows[c].1 {
+                    set(
+                        c,
+                        format!("\\ctrl[vertical wire=c, wire style={{dashed}}]{{{distance}}}"),
+                    );
+                } else {
+                    set(c, format!("\\ctrl{{{distance}}}"));
+                }
+            }
+            let label = quantikz_gate_label(o);
+            let has_quantum_controls = controls.iter().any(|&c| !rows[c].1);
+            if o.gate == "X" && has_quantum_controls && targets.len() == 1 {
+                set(first, "\\targ{}".to_string());
+            } else if targets.windows(2).all(|pair| pair[1] == pair[0] + 1) && targets.len() > 1 {
+                set(first, format!("\\gate[{}]{{{label}}}", targets.len()));
+            } else {
+                for (i, &t) in targets.iter().enumerate() {
+                    let mut cell = format!("\\gate{{{label}}}");
+                    if let Some(&next) = targets.get(i + 1) {
+                        let _ = write!(cell, "\\wire[d][{}]{{q}}", next - t);
+                    }
+                    set(t, cell);
+                }
+            }
+        }
+
+        let end_column = next_column.iter().copied().max().unwrap_or(1);
+        let mut s = "\\begin{quantikz}\n".to_string();
+        for (row, (label, _)) in rows.iter().enumerate() {
+            s.push_str(label);
+            for column in 1..=end_column {
+                s.push_str(" &");
+                if let Some(cell) = cells[row].get(&column) {
+                    s.push(' ');
+                    s.push_str(cell);
+                }
+            }
+            if row + 1 < rows.len() {
+                s.push_str(" \\\\");
+            }
+            s.push('\n');
+        }
+        s.push_str("\\end{quantikz}\n");
+        s
+    }
+}
+
+/// The number of rows from `from` down to `to`, negative if `to` is above `from`.
+fn row_offset(from: usize, to: usize) -> String {
+    if to >= from {
+        (to - from).to_string()
+    } else {
+        format!("-{}", from - to)
+    }
+}
+
+/// "R_x^\dagger(0.5)", with the characters that are special to LaTeX escaped.
+fn quantikz_gate_label(o: &Operation) -> String {
+    let mut label = escape_latex(&o.gate);
+    if o.is_adjoint {
+        label.push_str("^\\dagger");
+    }
+    if let Some(args) = &o.display_args {
+        let _ = write!(label, "({})", escape_latex(args));
+    }
+    label
+}
+
+fn escape_latex(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        if matches!(c, '_' | '&' | '%' | '#' | '$' | '{' | '}') {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+fn flatten_into(operations: &[Operation], flattened: &mut Vec<Operation>) {
+    for operation in operations {
+        if operation.children.is_empty() {
+            flattened.push(operation.clone());
+        } else {
+            flatten_into(&operation.children, flattened);
+        }
+    }
+}
+
+fn simplify_operations(operations: &[Operation]) -> Vec<Operation> {
+    let q_ids = |operation: &Operation| {
+        operation
+            .controls
+            .iter()
+            .chain(&operation.targets)
+            .map(|reg| reg.q_id)
+            .collect::<Vec<_>>()
+    };
+    let mut simplified: Vec<Operation> = Vec::new();
+    for operation in operations {
+        let mut operation = operation.clone();
+        if !operation.children.is_empty() {
+            operation.children = simplify_operations(&operation.children);
+            if operation.children.is_empty() {
+                continue;
+            }
+        }
+        let touched = q_ids(&operation);
+        let previous = simplified
+            .iter()
+            .rposition(|prev| q_ids(prev).iter().any(|q_id| touched.contains(q_id)));
+        match previous {
+            Some(i) if operation.is_self_inverse() && simplified[i] == operation => {
+                simplified.remove(i);
+            }
+            _ => simplified.push(operation),
+        }
+    }
+    simplified
+}
+
 #[derive(Clone, Serialize, Debug, PartialEq)]
 pub struct Operation {
     #[allow(clippy::struct_field_names)]
@@ -39,6 +259,49 @@ pub struct Operation {
     pub children: Vec<Operation>,
 }
 
+impl Operation {
+    /// Creates a group named after a callable that nests the given operations.
+    /// The group targets every qubit touched by its children.
+    #[must_use]
+    pub fn group(name: &str, children: Vec<Operation>) -> Self {
+        let mut q_ids = children
+            .iter()
+            .flat_map(|child| child.controls.iter().chain(&child.targets))
+            .map(|reg| reg.q_id)
+            .collect::<Vec<_>>();
+        q_ids.sort_unstable();
+        q_ids.dedup();
+        Self {
+            gate: name.into(),
+            display_args: None,
+            is_controlled: false,
+            is_adjoint: false,
+            is_measurement: false,
+            controls: vec![],
+            targets: q_ids.into_iter().map(Register::quantum).collect(),
+            children,
+        }
+    }
+
+    /// Returns true if applying this operation twice in a row has no effect. Rotations, which
+    /// carry their angle as display arguments, and classically controlled gates are not included.
+    fn is_self_inverse(&self) -> bool {
+        matches!(self.gate.as_str(), "H" | "X" | "Y" | "Z" | "CX" | "SWAP")
+            && self.display_args.is_none()
+            && self.children.is_empty()
+            && !self.is_measurement
+            && self.classical_controls().next().is_none()
+    }
+
+    /// Returns the measurement result registers that this operation is conditioned on,
+    /// i.e. the classical wires that control whether it is applied.
+    pub fn classical_controls(&self) -> impl Iterator<Item = &Register> {
+        self.controls
+            .iter()
+            .filter(|reg| !self.is_measurement && reg.c_id.is_some())
+    }
+}
+
 const QUANTUM_REGISTER: usize = 0;
 const CLASSICAL_REGISTER: usize = 1;
 
@@ -81,6 +344,8 @@ pub struct Qubit {
 pub struct Config {
     /// Perform Base Profile decompositions
     pub base_profile: bool,
+    /// Nest the gates applied by each operation call under a group named after the callable
+    pub group_operations: bool,
 }
 
 type ObjectsByColumn = FxHashMap<usize, String>;
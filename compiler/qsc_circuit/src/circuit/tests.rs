@@ -267,3 +267,218 @@ fn two_targets() {
     "]]
     .assert_eq(&c.to_string());
 }
+
+#[test]
+fn quantikz_bell() {
+    let c = Circuit {
+        operations: vec![
+            Operation {
+                gate: "H".to_string(),
+                display_args: None,
+                is_controlled: false,
+                is_adjoint: false,
+                is_measurement: false,
+                controls: vec![],
+                targets: vec![Register::quantum(0)],
+                children: vec![],
+            },
+            Operation {
+                gate: "X".to_string(),
+                display_args: None,
+                is_controlled: true,
+                is_adjoint: false,
+                is_measurement: false,
+                controls: vec![Register::quantum(0)],
+                targets: vec![Register::quantum(1)],
+                children: vec![],
+            },
+            Operation {
+                gate: "Measure".to_string(),
+                display_args: None,
+                is_controlled: false,
+                is_adjoint: false,
+                is_measurement: true,
+                controls: vec![Register::quantum(0)],
+                targets: vec![Register::classical(0, 0)],
+                children: vec![],
+            },
+            Operation {
+                gate: "Measure".to_string(),
+                display_args: None,
+                is_controlled: false,
+                is_adjoint: false,
+                is_measurement: true,
+                controls: vec![Register::quantum(1)],
+                targets: vec![Register::classical(1, 0)],
+                children: vec![],
+            },
+        ],
+        qubits: vec![
+            Qubit {
+                id: 0,
+                num_children: 1,
+            },
+            Qubit {
+                id: 1,
+                num_children: 1,
+            },
+        ],
+    };
+
+    let quantikz = c.to_quantikz();
+    for command in [r"\gate{H}", r"\ctrl{2}", r"\targ{}", r"\meter{}"] {
+        assert!(quantikz.contains(command), "{command} not in {quantikz}");
+    }
+    expect![[r#"
+        \begin{quantikz}
+        \lstick{$q_{0}$} & \gate{H} & \ctrl{2} & \meter{}\wire[d][1]{c} & \\
+        \setwiretype{n} & & & \setwiretype{c} & \\
+        \lstick{$q_{1}$} & & \targ{} & \meter{}\wire[d][1]{c} & \\
+        \setwiretype{n} & & & \setwiretype{c} &
+        \end{quantikz}
+    "#]]
+    .assert_eq(&quantikz);
+}
+
+#[test]
+fn quantikz_control_classical() {
+    let c = Circuit {
+        operations: vec![
+            Operation {
+                gate: "Measure".to_string(),
+                display_args: None,
+                is_controlled: false,
+                is_adjoint: false,
+                is_measurement: true,
+                controls: vec![Register::quantum(0)],
+                targets: vec![Register::classical(0, 0)],
+                children: vec![],
+            },
+            Operation {
+                gate: "rx".to_string(),
+                display_args: Some("0.5".to_string()),
+                is_controlled: true,
+                is_adjoint: true,
+                is_measurement: false,
+                controls: vec![Register::classical(0, 0)],
+                targets: vec![Register::quantum(1)],
+                children: vec![],
+            },
+        ],
+        qubits: vec![
+            Qubit {
+                id: 0,
+                num_children: 1,
+            },
+            Qubit {
+                id: 1,
+                num_children: 0,
+            },
+        ],
+    };
+
+    expect![[r#"
+        \begin{quantikz}
+        \lstick{$q_{0}$} & \meter{}\wire[d][1]{c} & & \\
+        \setwiretype{n} & \setwiretype{c} & \ctrl[vertical wire=c, wire style={dashed}]{1} & \\
+        \lstick{$q_{1}$} & & \gate{rx^\dagger(0.5)} &
+        \end{quantikz}
+    "#]]
+    .assert_eq(&c.to_quantikz());
+}
+
+#[test]
+fn quantikz_controls_without_targets() {
+    let c = two_qubit_circuit(vec![simple_gate("GlobalPhase", &[1, 0], &[])]);
+
+    expect![[r#"
+        \begin{quantikz}
+        \lstick{$q_{0}$} & \ctrl{1} & \\
+        \lstick{$q_{1}$} & \control{} &
+        \end{quantikz}
+    "#]]
+    .assert_eq(&c.to_quantikz());
+}
+
+fn simple_gate(name: &str, controls: &[usize], targets: &[usize]) -> Operation {
+    Operation {
+        gate: name.to_string(),
+        display_args: None,
+        is_controlled: !controls.is_empty(),
+        is_adjoint: false,
+        is_measurement: false,
+        controls: controls.iter().copied().map(Register::quantum).collect(),
+        targets: targets.iter().copied().map(Register::quantum).collect(),
+        children: vec![],
+    }
+}
+
+fn two_qubit_circuit(operations: Vec<Operation>) -> Circuit {
+    Circuit {
+        operations,
+        qubits: vec![
+            Qubit {
+                id: 0,
+                num_children: 0,
+            },
+            Qubit {
+                id: 1,
+                num_children: 0,
+            },
+        ],
+    }
+}
+
+#[test]
+fn simplify_cancels_hadamard_pair() {
+    let c = two_qubit_circuit(vec![
+        simple_gate("H", &[], &[0]),
+        simple_gate("H", &[], &[0]),
+    ]);
+
+    expect![[r"
+        q_0
+        q_1
+    "]]
+    .assert_eq(&c.simplify().to_string());
+}
+
+#[test]
+fn simplify_keeps_different_gates() {
+    let c = two_qubit_circuit(vec![
+        simple_gate("H", &[], &[0]),
+        simple_gate("X", &[], &[0]),
+    ]);
+
+    assert_eq!(c.simplify(), c);
+}
+
+#[test]
+fn simplify_cancels_nested_pairs_and_cnots() {
+    let c = two_qubit_circuit(vec![
+        simple_gate("X", &[], &[0]),
+        simple_gate("X", &[0], &[1]),
+        simple_gate("H", &[], &[0]),
+        simple_gate("H", &[], &[0]),
+        simple_gate("X", &[0], &[1]),
+        simple_gate("X", &[], &[0]),
+        simple_gate("Z", &[], &[1]),
+    ]);
+
+    expect![[r#"
+        q_0    ───────
+        q_1    ── Z ──
+    "#]]
+    .assert_eq(&c.simplify().to_string());
+}
+
+#[test]
+fn simplify_keeps_pairs_separated_by_gates_on_their_qubits() {
+    let c = two_qubit_circuit(vec![
+        simple_gate("X", &[0], &[1]),
+        simple_gate("H", &[], &[1]),
+        simple_gate("X", &[0], &[1]),
+    ]);
+
+    assert_eq!(c.simplify(), c);
+}
@@ -27,14 +27,23 @@ fn lower_store(package_store: &qsc_frontend::compile::PackageStore) -> qsc_fir::
 }
 
 /// converts the given sources to QIR using the given language features.
+/// When `emit_runtime_decls` is false, the declarations of the external runtime and
+/// intrinsic functions are left out of the module, to be linked in separately.
 pub fn hir_to_qir(
     package_store: &qsc_frontend::compile::PackageStore,
     capabilities: TargetCapabilityFlags,
     compute_properties: Option<PackageStoreComputeProperties>,
     entry: &ProgramEntry,
+    emit_runtime_decls: bool,
 ) -> Result<String, qsc_partial_eval::Error> {
     let fir_store = lower_store(package_store);
-    fir_to_qir(&fir_store, capabilities, compute_properties, entry)
+    fir_to_qir(
+        &fir_store,
+        capabilities,
+        compute_properties,
+        entry,
+        emit_runtime_decls,
+    )
 }
 
 pub fn fir_to_qir(
@@ -42,10 +51,11 @@ pub fn fir_to_qir(
     capabilities: TargetCapabilityFlags,
     compute_properties: Option<PackageStoreComputeProperties>,
     entry: &ProgramEntry,
+    emit_runtime_decls: bool,
 ) -> Result<String, qsc_partial_eval::Error> {
     let mut program = get_rir_from_compilation(fir_store, compute_properties, entry, capabilities)?;
     check_and_transform(&mut program);
-    Ok(ToQir::<String>::to_qir(&program, &program))
+    Ok(program_to_qir(&program, emit_runtime_decls))
 }
 
 fn get_rir_from_compilation(
@@ -533,26 +543,33 @@ impl ToQir<String> for rir::Callable {
 
 impl ToQir<String> for rir::Program {
     fn to_qir(&self, _program: &rir::Program) -> String {
-        let callables = self
-            .callables
-            .iter()
-            .map(|(_, callable)| ToQir::<String>::to_qir(callable, self))
-            .collect::<Vec<_>>()
-            .join("\n\n");
-        let profile = if self.config.is_base() {
-            "base_profile"
-        } else {
-            "adaptive_profile"
-        };
-        let body = format!(
-            include_str!("./qir/template.ll"),
-            callables, profile, self.num_qubits, self.num_results
-        );
-        let flags = get_module_metadata(self);
-        body + "\n" + &flags
+        program_to_qir(self, true)
     }
 }
 
+/// Converts the program to QIR, leaving out the declarations of callables without a body
+/// unless `emit_runtime_decls` is set.
+fn program_to_qir(program: &rir::Program, emit_runtime_decls: bool) -> String {
+    let callables = program
+        .callables
+        .iter()
+        .filter(|(_, callable)| emit_runtime_decls || callable.body.is_some())
+        .map(|(_, callable)| ToQir::<String>::to_qir(callable, program))
+        .collect::<Vec<_>>()
+        .join("\n\n");
+    let profile = if program.config.is_base() {
+        "base_profile"
+    } else {
+        "adaptive_profile"
+    };
+    let body = format!(
+        include_str!("./qir/template.ll"),
+        callables, profile, program.num_qubits, program.num_results
+    );
+    let flags = get_module_metadata(program);
+    body + "\n" + &flags
+}
+
 /// Create the module metadata for the given program.
 /// creating the `llvm.module.flags` and its associated values.
 fn get_module_metadata(program: &rir::Program) -> String {
@@ -7,7 +7,8 @@ mod tests;
 use crate::span::Span;
 
 /// A line and column pair that describes a position in a string.
-#[derive(Clone, Copy, PartialEq, Debug, Eq, Hash)]
+/// Positions are ordered by line, then by column.
+#[derive(Clone, Copy, PartialEq, Debug, Eq, Hash, PartialOrd, Ord)]
 pub struct Position {
     /// Line offset.
     pub line: u32,
@@ -116,7 +117,7 @@ impl Range {
     /// the next char boundary will be returned.
     #[must_use]
     pub fn from_span(encoding: Encoding, contents: &str, span: &Span) -> Self {
-        let [start, end] = positions_from_utf8_byte_offsets(encoding, contents, [span.lo, span.hi]);
+        let (start, end) = span.to_lsp(contents, encoding);
         Self { start, end }
     }
 
@@ -124,6 +125,39 @@ impl Range {
     pub fn empty(&self) -> bool {
         self.start == self.end
     }
+
+    /// Returns true if `pos` lies within this range, including both its start and end.
+    /// Including the end means a cursor placed right after the last character is considered
+    /// to be inside the range, as editors expect.
+    #[must_use]
+    pub fn contains(&self, pos: Position) -> bool {
+        self.start <= pos && pos <= self.end
+    }
+}
+
+impl Span {
+    /// Converts a span expressed in utf-8 byte offsets into `contents` to the start and end
+    /// [`Position`]s of the equivalent LSP range. Use [`Encoding::Utf16`] for LSP clients,
+    /// which count columns in utf-16 code units.
+    ///
+    /// Offsets past the end of the string map to the end of the string, as in [`Range::from_span`].
+    #[must_use]
+    pub fn to_lsp(&self, contents: &str, encoding: Encoding) -> (Position, Position) {
+        let [start, end] = positions_from_utf8_byte_offsets(encoding, contents, [self.lo, self.hi]);
+        (start, end)
+    }
+
+    /// Creates a span of utf-8 byte offsets into `contents` from the start and end
+    /// [`Position`]s of an LSP range. This is the inverse of [`Span::to_lsp`].
+    ///
+    /// Positions past the end of the string map to the end of the string.
+    #[must_use]
+    pub fn from_lsp(contents: &str, encoding: Encoding, start: Position, end: Position) -> Self {
+        Self {
+            lo: start.to_utf8_byte_offset(encoding, contents),
+            hi: end.to_utf8_byte_offset(encoding, contents),
+        }
+    }
 }
 
 /// For a given string and array of utf-8 byte offsets, returns the [`Position`]s
@@ -339,6 +339,101 @@ fn range_out_of_bounds() {
     .assert_debug_eq(&range);
 }
 
+#[test]
+fn positions_are_ordered_by_line_then_column() {
+    let pos = |line, column| Position { line, column };
+    assert!(pos(0, 5) < pos(1, 0));
+    assert!(pos(1, 2) < pos(1, 3));
+    assert!(pos(2, 0) > pos(1, 10));
+    assert_eq!(pos(1, 1).cmp(&pos(1, 1)), std::cmp::Ordering::Equal);
+}
+
+#[test]
+fn range_contains_positions_inside() {
+    let range = multi_line_range();
+    assert!(range.contains(Position { line: 1, column: 5 }));
+    assert!(range.contains(Position { line: 1, column: 7 }));
+    assert!(range.contains(Position {
+        line: 2,
+        column: 10
+    }));
+    assert!(range.contains(Position { line: 3, column: 1 }));
+}
+
+#[test]
+fn range_contains_positions_at_boundaries() {
+    let range = multi_line_range();
+    assert!(range.contains(range.start));
+    assert!(range.contains(range.end));
+}
+
+#[test]
+fn range_does_not_contain_positions_outside() {
+    let range = multi_line_range();
+    assert!(!range.contains(Position { line: 0, column: 4 }));
+    assert!(!range.contains(Position { line: 1, column: 3 }));
+    assert!(!range.contains(Position { line: 3, column: 3 }));
+    assert!(!range.contains(Position { line: 4, column: 0 }));
+}
+
+#[test]
+fn span_to_lsp_with_utf_16_surrogate_pairs() {
+    // 𝑓 is 4 bytes in utf-8 and a surrogate pair in utf-16, Σ is 2 bytes and one code unit
+    let contents = "𝑓(𝑥)\n  Σ 𝑓";
+    let span = Span { lo: 5, hi: 9 };
+    assert_eq!(&contents[span], "𝑥");
+    let (start, end) = span.to_lsp(contents, Encoding::Utf16);
+    assert_eq!(start, Position { line: 0, column: 3 });
+    assert_eq!(end, Position { line: 0, column: 5 });
+    assert_eq!(Span::from_lsp(contents, Encoding::Utf16, start, end), span);
+
+    let span = Span { lo: 13, hi: 20 };
+    assert_eq!(&contents[span], "Σ 𝑓");
+    let (start, end) = span.to_lsp(contents, Encoding::Utf16);
+    assert_eq!(start, Position { line: 1, column: 2 });
+    assert_eq!(end, Position { line: 1, column: 6 });
+    assert_eq!(Span::from_lsp(contents, Encoding::Utf16, start, end), span);
+}
+
+#[test]
+fn span_to_lsp_round_trips_every_char_boundary() {
+    let contents = "𝑓(𝑥⃗) ≔ Σᵢ\n𝑥ᵢ 𝑟ᵢ";
+    let boundaries: Vec<u32> = contents
+        .char_indices()
+        .map(|(i, _)| u32::try_from(i).expect("offset should fit in u32"))
+        .chain(std::iter::once(
+            u32::try_from(contents.len()).expect("length should fit in u32"),
+        ))
+        .collect();
+    for encoding in [Encoding::Utf8, Encoding::Utf16] {
+        for (i, &lo) in boundaries.iter().enumerate() {
+            for &hi in &boundaries[i..] {
+                let span = Span { lo, hi };
+                let (start, end) = span.to_lsp(contents, encoding);
+                assert_eq!(
+                    Range::from_span(encoding, contents, &span),
+                    Range { start, end }
+                );
+                assert_eq!(Span::from_lsp(contents, encoding, start, end), span);
+            }
+        }
+    }
+}
+
+/// The range from line 1, column 4 to line 3, column 2.
+fn multi_line_range() -> Range {
+    let contents = "line\n    two\nthree and more\nfour\nfive";
+    let range = Range::from_span(Encoding::Utf8, contents, &Span { lo: 9, hi: 30 });
+    assert_eq!(
+        range,
+        Range {
+            start: Position { line: 1, column: 4 },
+            end: Position { line: 3, column: 2 },
+        }
+    );
+    range
+}
+
 #[allow(clippy::cast_possible_truncation)]
 fn check_all_offsets(contents: &str, expected: &expect_test::Expect) {
     let byte_offsets = 0..=contents.len();
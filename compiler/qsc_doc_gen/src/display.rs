@@ -1,6 +1,9 @@
 // Copyright (c) Microsoft Corporation.
 // Licensed under the MIT License.
 
+#[cfg(test)]
+mod tests;
+
 use qsc_ast::ast;
 use qsc_frontend::resolve;
 use qsc_hir::{
@@ -58,7 +61,28 @@ pub struct CodeDisplay<'a> {
 impl<'a> CodeDisplay<'a> {
     #[must_use]
     pub fn hir_callable_decl(&self, decl: &'a hir::CallableDecl) -> impl Display + '_ {
-        HirCallableDecl { decl }
+        HirCallableDecl {
+            decl,
+            expand_udts: None,
+        }
+    }
+
+    /// Like [`CodeDisplay::hir_callable_decl`], but parameters whose type is a struct-like UDT
+    /// are rendered with the field layout of the type, as in `p : Pair { First : Int, Second : Int }`.
+    /// `package_id` is the package the callable is declared in, which is used to resolve the UDTs.
+    #[must_use]
+    pub fn hir_callable_decl_expanded(
+        &self,
+        package_id: PackageId,
+        decl: &'a hir::CallableDecl,
+    ) -> impl Display + '_ {
+        HirCallableDecl {
+            decl,
+            expand_udts: Some(ExpandUdts {
+                lookup: self.compilation,
+                package_id,
+            }),
+        }
     }
 
     #[must_use]
@@ -105,12 +129,19 @@ impl<'a> CodeDisplay<'a> {
 
     #[must_use]
     pub fn hir_pat(&self, pat: &'a hir::Pat) -> impl Display + '_ {
-        HirPat { pat }
+        HirPat {
+            pat,
+            expand_udts: None,
+        }
     }
 
     #[must_use]
     pub fn get_param_offset(&self, decl: &hir::CallableDecl) -> u32 {
-        HirCallableDecl { decl }.get_param_offset()
+        HirCallableDecl {
+            decl,
+            expand_udts: None,
+        }
+        .get_param_offset()
     }
 
     // The rest of the display implementations are not made public b/c they're not used,
@@ -150,8 +181,33 @@ impl<'a> Display for NameTyId<'a> {
     }
 }
 
+/// The context needed to render UDT-typed parameters with their fields.
+#[derive(Clone, Copy)]
+struct ExpandUdts<'a> {
+    lookup: &'a dyn Lookup,
+    package_id: PackageId,
+}
+
+impl<'a> ExpandUdts<'a> {
+    /// Returns the UDT definition referred to by `ty` if it is a struct-like UDT.
+    fn struct_udt(&self, ty: &ty::Ty) -> Option<&'a ty::Udt> {
+        let ty::Ty::Udt(_, res) = ty else {
+            return None;
+        };
+        if !matches!(res, hir::Res::Item(_)) {
+            return None;
+        }
+        let (item, _) = self.lookup.resolve_item_res(self.package_id, res);
+        match &item.kind {
+            hir::ItemKind::Ty(_, udt) if udt.is_struct() => Some(udt),
+            _ => None,
+        }
+    }
+}
+
 struct HirCallableDecl<'a> {
     decl: &'a hir::CallableDecl,
+    expand_udts: Option<ExpandUdts<'a>>,
 }
 
 impl HirCallableDecl<'_> {
@@ -180,6 +236,7 @@ impl Display for HirCallableDecl<'_> {
         write!(f, "{type_params}")?;
         let input = HirPat {
             pat: &self.decl.input,
+            expand_udts: self.expand_udts,
         };
         if matches!(self.decl.input.kind, hir::PatKind::Tuple(_)) {
             write!(f, "{input}")?;
@@ -245,19 +302,44 @@ impl<'a> Display for AstCallableDecl<'a> {
 
 struct HirPat<'a> {
     pat: &'a hir::Pat,
+    expand_udts: Option<ExpandUdts<'a>>,
+}
+
+impl<'a> HirPat<'a> {
+    fn fmt_ty(&self, f: &mut Formatter<'_>) -> Result {
+        write!(f, "{}", self.pat.ty.display())?;
+        let udt = self
+            .expand_udts
+            .and_then(|expand_udts| expand_udts.struct_udt(&self.pat.ty));
+        if let Some(ty::UdtDefKind::Tuple(fields)) = udt.map(|udt| &udt.definition.kind) {
+            write!(f, " ")?;
+            fmt_brace_seq(f, fields, UdtDef::new)?;
+        }
+        Ok(())
+    }
 }
 
 impl<'a> Display for HirPat<'a> {
     fn fmt(&self, f: &mut Formatter<'_>) -> Result {
         match &self.pat.kind {
-            hir::PatKind::Bind(name) => write!(f, "{} : {}", name.name, self.pat.ty.display()),
-            hir::PatKind::Discard => write!(f, "_ : {}", self.pat.ty.display()),
+            hir::PatKind::Bind(name) => {
+                write!(f, "{} : ", name.name)?;
+                self.fmt_ty(f)
+            }
+            hir::PatKind::Discard => {
+                write!(f, "_ : ")?;
+                self.fmt_ty(f)
+            }
             hir::PatKind::Tuple(items) => {
+                let item = |pat| HirPat {
+                    pat,
+                    expand_udts: self.expand_udts,
+                };
                 let mut elements = items.iter();
                 if let Some(elem) = elements.next() {
-                    write!(f, "({}", HirPat { pat: elem })?;
+                    write!(f, "({}", item(elem))?;
                     for elem in elements {
-                        write!(f, ", {}", HirPat { pat: elem })?;
+                        write!(f, ", {}", item(elem))?;
                     }
                     write!(f, ")")
                 } else {
@@ -0,0 +1,77 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+#![allow(clippy::needless_raw_string_hashes)]
+
+use super::CodeDisplay;
+use crate::generate_docs::Compilation;
+use expect_test::{expect, Expect};
+use qsc_frontend::compile::SourceMap;
+use qsc_hir::hir::ItemKind;
+
+fn check_callable_decl(source: &str, name: &str, collapsed: &Expect, expanded: &Expect) {
+    let sources = SourceMap::new([("test".into(), source.into())], None);
+    let compilation = Compilation::new(Some(sources), None, None);
+    let (package_id, unit) = compilation
+        .package_store
+        .iter()
+        .last()
+        .expect("store should contain the user package");
+    let decl = unit
+        .package
+        .items
+        .values()
+        .find_map(|item| match &item.kind {
+            ItemKind::Callable(decl) if decl.name.name.as_ref() == name => Some(decl),
+            _ => None,
+        })
+        .expect("callable should exist");
+    let display = CodeDisplay {
+        compilation: &compilation,
+    };
+    collapsed.assert_eq(&display.hir_callable_decl(decl).to_string());
+    expanded.assert_eq(
+        &display
+            .hir_callable_decl_expanded(package_id, decl)
+            .to_string(),
+    );
+}
+
+#[test]
+fn struct_param_collapsed_and_expanded() {
+    check_callable_decl(
+        "namespace Test {
+            struct Pair { First : Int, Second : Int }
+            function Sum(p : Pair, scale : Int) : Int { scale * (p.First + p.Second) }
+        }",
+        "Sum",
+        &expect!["function Sum(p : Pair, scale : Int) : Int"],
+        &expect!["function Sum(p : Pair { First : Int, Second : Int }, scale : Int) : Int"],
+    );
+}
+
+#[test]
+fn single_struct_param_expanded() {
+    check_callable_decl(
+        "namespace Test {
+            struct Pair { First : Int, Second : Int }
+            function First(p : Pair) : Int { p.First }
+        }",
+        "First",
+        &expect!["function First(p : Pair) : Int"],
+        &expect!["function First(p : Pair { First : Int, Second : Int }) : Int"],
+    );
+}
+
+#[test]
+fn non_struct_udt_param_not_expanded() {
+    check_callable_decl(
+        "namespace Test {
+            newtype Wrapped = Int;
+            function Unwrap(w : Wrapped) : Int { w! }
+        }",
+        "Unwrap",
+        &expect!["function Unwrap(w : Wrapped) : Int"],
+        &expect!["function Unwrap(w : Wrapped) : Int"],
+    );
+}
@@ -22,9 +22,9 @@ type Files = Vec<(Arc<str>, Arc<str>, Arc<str>)>;
 
 /// Represents an immutable compilation state.
 #[derive(Debug)]
-struct Compilation {
+pub(crate) struct Compilation {
     /// Package store, containing the current package and all its dependencies.
-    package_store: PackageStore,
+    pub(crate) package_store: PackageStore,
 }
 
 impl Compilation {
@@ -1,12 +1,89 @@
 // Copyright (c) Microsoft Corporation.
 // Licensed under the MIT License.
 
+#[cfg(test)]
+mod tests;
+
+mod stabilizer;
+
+pub use stabilizer::StabilizerSim;
+
 use num_bigint::BigUint;
-use num_complex::Complex;
+use num_complex::{Complex, Complex64};
+use num_traits::ToPrimitive;
+use qsc_fir::fir::Pauli;
 use quantum_sparse_sim::QuantumSim;
 use rand::RngCore;
+use rustc_hash::{FxHashMap, FxHashSet};
+use std::{cell::RefCell, io::Write};
+
+use crate::{
+    output::{self, Receiver},
+    val::{self, Value},
+    LogEvent,
+};
+
+/// A single gate application, used to submit several gates to a backend at once
+/// through [`Backend::apply_batch`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum GateOp {
+    Ccx(usize, usize, usize),
+    Cx(usize, usize),
+    Cy(usize, usize),
+    Cz(usize, usize),
+    H(usize),
+    Rx(f64, usize),
+    Rxx(f64, usize, usize),
+    Ry(f64, usize),
+    Ryy(f64, usize, usize),
+    Rz(f64, usize),
+    Rzz(f64, usize, usize),
+    Sadj(usize),
+    S(usize),
+    Swap(usize, usize),
+    Tadj(usize),
+    T(usize),
+    X(usize),
+    Y(usize),
+    Z(usize),
+}
+
+impl GateOp {
+    /// The target qubit, if this is a single-qubit gate.
+    #[must_use]
+    pub fn single_qubit_target(&self) -> Option<usize> {
+        match *self {
+            GateOp::H(q)
+            | GateOp::Rx(_, q)
+            | GateOp::Ry(_, q)
+            | GateOp::Rz(_, q)
+            | GateOp::Sadj(q)
+            | GateOp::S(q)
+            | GateOp::Tadj(q)
+            | GateOp::T(q)
+            | GateOp::X(q)
+            | GateOp::Y(q)
+            | GateOp::Z(q) => Some(q),
+            _ => None,
+        }
+    }
 
-use crate::val::Value;
+    /// The qubits this gate acts on.
+    #[must_use]
+    pub fn qubits(&self) -> Vec<usize> {
+        match *self {
+            GateOp::Ccx(ctl0, ctl1, q) => vec![ctl0, ctl1, q],
+            GateOp::Cx(q0, q1)
+            | GateOp::Cy(q0, q1)
+            | GateOp::Cz(q0, q1)
+            | GateOp::Rxx(_, q0, q1)
+            | GateOp::Ryy(_, q0, q1)
+            | GateOp::Rzz(_, q0, q1)
+            | GateOp::Swap(q0, q1) => vec![q0, q1],
+            _ => self.single_qubit_target().into_iter().collect(),
+        }
+    }
+}
 
 /// The trait that must be implemented by a quantum backend, whose functions will be invoked when
 /// quantum intrinsics are called.
@@ -34,6 +111,52 @@ pub trait Backend {
     fn mresetz(&mut self, _q: usize) -> Self::ResultType {
         unimplemented!("mresetz operation");
     }
+
+    /// Measures the joint parity of the given distinct qubits, each in its own Pauli basis, as
+    /// `Measure(bases, qubits)` does. The result is `One` when the -1 eigenvalue of the Pauli
+    /// product is observed.
+    ///
+    /// By default this applies the same gates as `Measure` in the standard library: a single
+    /// qubit is mapped to the Z basis around `m`, while a joint measurement entangles the qubits
+    /// with an auxiliary qubit that is then measured with `mresetz`.
+    fn measure_pauli(&mut self, paulis: &[(Pauli, usize)]) -> Self::ResultType {
+        if let [(basis @ (Pauli::X | Pauli::Y | Pauli::Z), q)] = *paulis {
+            match basis {
+                Pauli::X => self.h(q),
+                Pauli::Y => {
+                    self.h(q);
+                    self.s(q);
+                    self.h(q);
+                }
+                Pauli::I | Pauli::Z => {}
+            }
+            let res = self.m(q);
+            match basis {
+                Pauli::X => self.h(q),
+                Pauli::Y => {
+                    self.h(q);
+                    self.sadj(q);
+                    self.h(q);
+                }
+                Pauli::I | Pauli::Z => {}
+            }
+            return res;
+        }
+        let aux = self.qubit_allocate();
+        self.h(aux);
+        for &(basis, q) in paulis {
+            match basis {
+                Pauli::X => self.cx(aux, q),
+                Pauli::Y => self.cy(aux, q),
+                Pauli::Z => self.cz(aux, q),
+                Pauli::I => {}
+            }
+        }
+        self.h(aux);
+        let res = self.mresetz(aux);
+        self.qubit_release(aux);
+        res
+    }
     fn reset(&mut self, _q: usize) {
         unimplemented!("reset gate");
     }
@@ -92,16 +215,101 @@ pub trait Backend {
         unimplemented!("qubit_is_zero operation");
     }
 
+    /// Allocates a qubit for a `borrow` statement. Borrowed qubits may be in any state, by default
+    /// they start in |0⟩ like freshly allocated qubits.
+    fn qubit_borrow(&mut self) -> usize {
+        self.qubit_allocate()
+    }
+
+    /// Releases a qubit allocated by [`Backend::qubit_borrow`]. Returns `false`, leaving the qubit
+    /// allocated, if the qubit is not back in the state it was in when it was borrowed.
+    fn qubit_return(&mut self, q: usize) -> bool {
+        let is_restored = self.qubit_is_zero(q);
+        if is_restored {
+            self.qubit_release(q);
+        }
+        is_restored
+    }
+
+    /// Returns `false` if the backend has no measurement results left to give, e.g. because it
+    /// replays a fixed sequence of results that has run out. Checked before each measurement.
+    fn can_measure(&mut self) -> bool {
+        true
+    }
+
     fn custom_intrinsic(&mut self, _name: &str, _arg: Value) -> Option<Result<Value, String>> {
         None
     }
 
     fn set_seed(&mut self, _seed: Option<u64>) {}
+
+    /// Called when evaluation enters the body of a non-intrinsic operation.
+    /// Every call is matched by a later call to [`Backend::exit_operation`].
+    fn enter_operation(&mut self, _name: &str) {}
+
+    /// Called when evaluation returns from the operation most recently entered.
+    fn exit_operation(&mut self) {}
+
+    /// Returns the outcome of the measurement that returned the given result id, for backends
+    /// whose measurements return ids but that also simulate the program. Results with a known
+    /// outcome can be compared, and the branches taken because of them are reported through
+    /// [`Backend::enter_conditional`].
+    fn result_outcome(&mut self, _id: usize) -> Option<bool> {
+        None
+    }
+
+    /// Called when evaluation enters a branch that it takes because of the outcome of the
+    /// measurement with the given result id. Every call is matched by a later call to
+    /// [`Backend::exit_conditional`], and branches nest.
+    fn enter_conditional(&mut self, _result: usize) {}
+
+    /// Called when evaluation leaves the branch most recently entered.
+    fn exit_conditional(&mut self) {}
+
+    /// Applies a sequence of gates in order. Backends can override this to take advantage
+    /// of seeing several gates at once, by default each gate is applied individually.
+    fn apply_batch(&mut self, ops: &[GateOp]) {
+        for op in ops {
+            self.apply_gate(*op);
+        }
+    }
+
+    /// Applies a single gate by dispatching to the corresponding gate method.
+    fn apply_gate(&mut self, op: GateOp) {
+        match op {
+            GateOp::Ccx(ctl0, ctl1, q) => self.ccx(ctl0, ctl1, q),
+            GateOp::Cx(ctl, q) => self.cx(ctl, q),
+            GateOp::Cy(ctl, q) => self.cy(ctl, q),
+            GateOp::Cz(ctl, q) => self.cz(ctl, q),
+            GateOp::H(q) => self.h(q),
+            GateOp::Rx(theta, q) => self.rx(theta, q),
+            GateOp::Rxx(theta, q0, q1) => self.rxx(theta, q0, q1),
+            GateOp::Ry(theta, q) => self.ry(theta, q),
+            GateOp::Ryy(theta, q0, q1) => self.ryy(theta, q0, q1),
+            GateOp::Rz(theta, q) => self.rz(theta, q),
+            GateOp::Rzz(theta, q0, q1) => self.rzz(theta, q0, q1),
+            GateOp::Sadj(q) => self.sadj(q),
+            GateOp::S(q) => self.s(q),
+            GateOp::Swap(q0, q1) => self.swap(q0, q1),
+            GateOp::Tadj(q) => self.tadj(q),
+            GateOp::T(q) => self.t(q),
+            GateOp::X(q) => self.x(q),
+            GateOp::Y(q) => self.y(q),
+            GateOp::Z(q) => self.z(q),
+        }
+    }
 }
 
 /// Default backend used when targeting sparse simulation.
+#[derive(Clone)]
 pub struct SparseSim {
     pub sim: QuantumSim,
+    /// The rotation angles preparing the state of the next borrowed qubit, if one was set.
+    next_borrow_state: Option<(f64, f64)>,
+    /// The borrowed qubits that were prepared in a given state, with the angles that prepared it.
+    prepared_borrows: Vec<(usize, f64, f64)>,
+    /// The qubits that have been allocated and not yet released.
+    allocated: FxHashSet<usize>,
 }
 
 impl Default for SparseSim {
@@ -115,7 +323,160 @@ impl SparseSim {
     pub fn new() -> Self {
         Self {
             sim: QuantumSim::new(None),
+            next_borrow_state: None,
+            prepared_borrows: Vec::new(),
+            allocated: FxHashSet::default(),
+        }
+    }
+
+    /// Returns the ids of the qubits that are currently allocated, in ascending order.
+    #[must_use]
+    pub fn allocated_qubits(&self) -> Vec<usize> {
+        let mut qubits = self.allocated.iter().copied().collect::<Vec<_>>();
+        qubits.sort_unstable();
+        qubits
+    }
+
+    /// Sets the state `alpha|0⟩ + beta|1⟩` that the next borrowed qubit starts in, instead of
+    /// |0⟩. The amplitudes are normalized and their global phase is ignored. When that qubit
+    /// is returned, it must be back in this state.
+    ///
+    /// # Panics
+    ///
+    /// Panics if both amplitudes are zero.
+    pub fn set_borrow_state(&mut self, alpha: Complex64, beta: Complex64) {
+        assert!(
+            alpha.norm() > 0.0 || beta.norm() > 0.0,
+            "borrow state should not have zero norm"
+        );
+        let theta = 2.0 * beta.norm().atan2(alpha.norm());
+        let phi = beta.arg() - alpha.arg();
+        self.next_borrow_state = Some((theta, phi));
+    }
+
+    /// Returns the full state vector with one amplitude per basis state, ordered by basis index
+    /// with the first allocated qubit as the most significant bit. Basis states absent from the
+    /// sparse state are filled with zero.
+    ///
+    /// The vector has `2^n` entries for `n` allocated qubits, 16 bytes each, so it takes 16 MiB
+    /// at 20 qubits and doubles with every additional qubit. Only use this for small registers.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the number of basis states does not fit in a `usize`.
+    #[must_use]
+    pub fn to_dense_vector(&mut self) -> Vec<Complex64> {
+        let (state, qubit_count) = self.capture_quantum_state();
+        let len = u32::try_from(qubit_count)
+            .ok()
+            .and_then(|count| 1_usize.checked_shl(count))
+            .expect("number of basis states should fit in usize");
+        let mut vector = vec![Complex64::default(); len];
+        for (index, amplitude) in state {
+            let index = index
+                .to_usize()
+                .expect("basis index should be within the state vector");
+            vector[index] = amplitude;
         }
+        vector
+    }
+
+    /// Applies the product of a run of single-qubit gates on `q` as one rotation,
+    /// using the decomposition `e^{iα} Rz(β) Ry(γ) Rz(δ)` of the combined unitary.
+    fn apply_fused(&mut self, ops: &[GateOp], q: usize) {
+        let unitary = ops.iter().fold(identity(), |acc, op| {
+            mat_mul(&single_qubit_matrix(op), &acc)
+        });
+        let (alpha, beta, gamma, delta) = zyz_decomposition(&unitary);
+        self.sim.rz(delta, q);
+        self.sim.ry(gamma, q);
+        self.sim.rz(beta, q);
+        if alpha.abs() > f64::EPSILON {
+            // Same approach as the `GlobalPhase` intrinsic: a rotation on a fresh qubit in |0⟩
+            // applies the phase to the entire state.
+            let aux = self.sim.allocate();
+            self.sim.mcrz(&[], -2.0 * alpha, aux);
+            self.sim.release(aux);
+        }
+    }
+}
+
+/// Runs of single-qubit gates shorter than this are applied gate by gate instead of fused.
+const FUSION_THRESHOLD: usize = 4;
+
+type Matrix2 = [[Complex<f64>; 2]; 2];
+
+fn identity() -> Matrix2 {
+    let (zero, one) = (Complex::new(0.0, 0.0), Complex::new(1.0, 0.0));
+    [[one, zero], [zero, one]]
+}
+
+fn mat_mul(a: &Matrix2, b: &Matrix2) -> Matrix2 {
+    let mut res = [[Complex::new(0.0, 0.0); 2]; 2];
+    for (i, row) in res.iter_mut().enumerate() {
+        for (j, entry) in row.iter_mut().enumerate() {
+            *entry = a[i][0] * b[0][j] + a[i][1] * b[1][j];
+        }
+    }
+    res
+}
+
+fn single_qubit_matrix(op: &GateOp) -> Matrix2 {
+    let zero = Complex::new(0.0, 0.0);
+    let one = Complex::new(1.0, 0.0);
+    let i = Complex::new(0.0, 1.0);
+    let phase = |theta: f64| [[one, zero], [zero, Complex::from_polar(1.0, theta)]];
+    match *op {
+        GateOp::H(_) => {
+            let h = Complex::new(std::f64::consts::FRAC_1_SQRT_2, 0.0);
+            [[h, h], [h, -h]]
+        }
+        GateOp::Rx(theta, _) => {
+            let (c, s) = (Complex::new((theta / 2.0).cos(), 0.0), (theta / 2.0).sin());
+            [[c, -i * s], [-i * s, c]]
+        }
+        GateOp::Ry(theta, _) => {
+            let (c, s) = (Complex::new((theta / 2.0).cos(), 0.0), (theta / 2.0).sin());
+            [[c, -s * one], [s * one, c]]
+        }
+        GateOp::Rz(theta, _) => [
+            [Complex::from_polar(1.0, -theta / 2.0), zero],
+            [zero, Complex::from_polar(1.0, theta / 2.0)],
+        ],
+        GateOp::Sadj(_) => phase(-std::f64::consts::FRAC_PI_2),
+        GateOp::S(_) => phase(std::f64::consts::FRAC_PI_2),
+        GateOp::Tadj(_) => phase(-std::f64::consts::FRAC_PI_4),
+        GateOp::T(_) => phase(std::f64::consts::FRAC_PI_4),
+        GateOp::X(_) => [[zero, one], [one, zero]],
+        GateOp::Y(_) => [[zero, -i], [i, zero]],
+        GateOp::Z(_) => phase(std::f64::consts::PI),
+        _ => panic!("gate {op:?} is not a single-qubit gate"),
+    }
+}
+
+/// Decomposes a single-qubit unitary into `(α, β, γ, δ)` such that
+/// `U = e^{iα} Rz(β) Ry(γ) Rz(δ)`.
+fn zyz_decomposition(u: &Matrix2) -> (f64, f64, f64, f64) {
+    let det = u[0][0] * u[1][1] - u[0][1] * u[1][0];
+    let alpha = det.arg() / 2.0;
+    let phase = Complex::from_polar(1.0, -alpha);
+    let (a, b) = (u[0][0] * phase, u[1][0] * phase);
+    let gamma = 2.0 * b.norm().atan2(a.norm());
+    // When either entry vanishes only β + δ or β - δ is determined, so the other is zero.
+    let half_sum = if a.norm() > 1e-12 { -a.arg() } else { 0.0 };
+    let half_diff = if b.norm() > 1e-12 { b.arg() } else { 0.0 };
+    (alpha, half_sum + half_diff, gamma, half_sum - half_diff)
+}
+
+/// Handles the resource estimation intrinsics, which have no effect on simulation.
+fn estimate_intrinsic(name: &str) -> Option<Result<Value, String>> {
+    match name {
+        "BeginEstimateCaching" => Some(Ok(Value::Bool(true))),
+        "EndEstimateCaching"
+        | "AccountForEstimatesInternal"
+        | "BeginRepeatEstimatesInternal"
+        | "EndRepeatEstimatesInternal" => Some(Ok(Value::unit())),
+        _ => None,
     }
 }
 
@@ -154,6 +515,44 @@ impl Backend for SparseSim {
         res
     }
 
+    /// Measures the parity directly on the state, so a joint measurement does not allocate an
+    /// auxiliary qubit. Identity factors do not take part in the measurement.
+    fn measure_pauli(&mut self, paulis: &[(Pauli, usize)]) -> Self::ResultType {
+        let map_to_z = |sim: &mut QuantumSim, pauli: Pauli, q: usize| match pauli {
+            Pauli::X => sim.h(q),
+            Pauli::Y => {
+                sim.sadj(q);
+                sim.h(q);
+            }
+            Pauli::I | Pauli::Z => {}
+        };
+        let map_from_z = |sim: &mut QuantumSim, pauli: Pauli, q: usize| match pauli {
+            Pauli::X => sim.h(q),
+            Pauli::Y => {
+                sim.h(q);
+                sim.s(q);
+            }
+            Pauli::I | Pauli::Z => {}
+        };
+
+        let qubits = paulis
+            .iter()
+            .filter(|(pauli, _)| *pauli != Pauli::I)
+            .map(|&(_, q)| q)
+            .collect::<Vec<_>>();
+        if qubits.is_empty() {
+            return false;
+        }
+        for &(pauli, q) in paulis {
+            map_to_z(&mut self.sim, pauli, q);
+        }
+        let res = self.sim.joint_measure(&qubits);
+        for &(pauli, q) in paulis {
+            map_from_z(&mut self.sim, pauli, q);
+        }
+        res
+    }
+
     fn reset(&mut self, q: usize) {
         self.mresetz(q);
     }
@@ -233,11 +632,14 @@ impl Backend for SparseSim {
     }
 
     fn qubit_allocate(&mut self) -> usize {
-        self.sim.allocate()
+        let q = self.sim.allocate();
+        self.allocated.insert(q);
+        q
     }
 
     fn qubit_release(&mut self, q: usize) {
         self.sim.release(q);
+        self.allocated.remove(&q);
     }
 
     fn capture_quantum_state(&mut self) -> (Vec<(BigUint, Complex<f64>)>, usize) {
@@ -264,6 +666,29 @@ impl Backend for SparseSim {
         self.sim.qubit_is_zero(q)
     }
 
+    fn qubit_borrow(&mut self) -> usize {
+        let q = self.qubit_allocate();
+        if let Some((theta, phi)) = self.next_borrow_state.take() {
+            self.ry(theta, q);
+            self.rz(phi, q);
+            self.prepared_borrows.push((q, theta, phi));
+        }
+        q
+    }
+
+    fn qubit_return(&mut self, q: usize) -> bool {
+        if let Some(index) = self.prepared_borrows.iter().position(|&(p, ..)| p == q) {
+            let (_, theta, phi) = self.prepared_borrows.swap_remove(index);
+            self.rz(-phi, q);
+            self.ry(-theta, q);
+        }
+        let is_restored = self.qubit_is_zero(q);
+        if is_restored {
+            self.qubit_release(q);
+        }
+        is_restored
+    }
+
     fn custom_intrinsic(&mut self, name: &str, arg: Value) -> Option<Result<Value, String>> {
         match name {
             "GlobalPhase" => {
@@ -286,12 +711,7 @@ impl Backend for SparseSim {
                 self.sim.release(q);
                 Some(Ok(Value::unit()))
             }
-            "BeginEstimateCaching" => Some(Ok(Value::Bool(true))),
-            "EndEstimateCaching"
-            | "AccountForEstimatesInternal"
-            | "BeginRepeatEstimatesInternal"
-            | "EndRepeatEstimatesInternal" => Some(Ok(Value::unit())),
-            _ => None,
+            _ => estimate_intrinsic(name),
         }
     }
 
@@ -301,6 +721,45 @@ impl Backend for SparseSim {
             None => self.sim.set_rng_seed(rand::thread_rng().next_u64()),
         }
     }
+
+    /// Single-qubit gates commute with gates on other qubits, so pending runs are tracked
+    /// per qubit and only flushed when a multi-qubit gate touches that qubit.
+    /// Runs of at least `FUSION_THRESHOLD` gates are fused into a single rotation.
+    /// The `Batched gate application` benchmark in `qsc` compares this with sequential application.
+    fn apply_batch(&mut self, ops: &[GateOp]) {
+        let mut pending: FxHashMap<usize, Vec<GateOp>> = FxHashMap::default();
+        let mut order = Vec::new();
+        let flush = |sim: &mut Self, run: Vec<GateOp>, q: usize| {
+            if run.len() >= FUSION_THRESHOLD {
+                sim.apply_fused(&run, q);
+            } else {
+                for op in run {
+                    sim.apply_gate(op);
+                }
+            }
+        };
+        for op in ops {
+            if let Some(q) = op.single_qubit_target() {
+                let run = pending.entry(q).or_default();
+                if run.is_empty() {
+                    order.push(q);
+                }
+                run.push(*op);
+            } else {
+                for q in op.qubits() {
+                    if let Some(run) = pending.remove(&q) {
+                        flush(self, run, q);
+                    }
+                }
+                self.apply_gate(*op);
+            }
+        }
+        for q in order {
+            if let Some(run) = pending.remove(&q) {
+                flush(self, run, q);
+            }
+        }
+    }
 }
 
 /// Simple struct that chains two backends together so that the chained
@@ -308,6 +767,7 @@ impl Backend for SparseSim {
 /// For any intrinsics that return a value,
 /// the value returned by the chained backend is ignored.
 /// The value returned by the main backend is returned.
+#[derive(Clone)]
 pub struct Chain<T1, T2> {
     pub main: T1,
     pub chained: T2,
@@ -368,6 +828,11 @@ where
         self.main.mresetz(q)
     }
 
+    fn measure_pauli(&mut self, paulis: &[(Pauli, usize)]) -> Self::ResultType {
+        let _ = self.chained.measure_pauli(paulis);
+        self.main.measure_pauli(paulis)
+    }
+
     fn reset(&mut self, q: usize) {
         self.chained.reset(q);
         self.main.reset(q);
@@ -470,6 +935,21 @@ where
         self.main.qubit_is_zero(q)
     }
 
+    fn qubit_borrow(&mut self) -> usize {
+        let _ = self.chained.qubit_borrow();
+        self.main.qubit_borrow()
+    }
+
+    fn qubit_return(&mut self, q: usize) -> bool {
+        let _ = self.chained.qubit_return(q);
+        self.main.qubit_return(q)
+    }
+
+    fn can_measure(&mut self) -> bool {
+        let _ = self.chained.can_measure();
+        self.main.can_measure()
+    }
+
     fn custom_intrinsic(&mut self, name: &str, arg: Value) -> Option<Result<Value, String>> {
         let _ = self.chained.custom_intrinsic(name, arg.clone());
         self.main.custom_intrinsic(name, arg)
@@ -479,4 +959,805 @@ where
         self.chained.set_seed(seed);
         self.main.set_seed(seed);
     }
+
+    fn enter_operation(&mut self, name: &str) {
+        self.chained.enter_operation(name);
+        self.main.enter_operation(name);
+    }
+
+    fn exit_operation(&mut self) {
+        self.chained.exit_operation();
+        self.main.exit_operation();
+    }
+
+    fn result_outcome(&mut self, id: usize) -> Option<bool> {
+        self.main.result_outcome(id)
+    }
+
+    fn enter_conditional(&mut self, result: usize) {
+        self.chained.enter_conditional(result);
+        self.main.enter_conditional(result);
+    }
+
+    fn exit_conditional(&mut self) {
+        self.chained.exit_conditional();
+        self.main.exit_conditional();
+    }
+
+    fn apply_batch(&mut self, ops: &[GateOp]) {
+        self.chained.apply_batch(ops);
+        self.main.apply_batch(ops);
+    }
+}
+
+/// Backend that forwards every call to another backend and writes a line describing each
+/// gate, reset and measurement as it is applied, such as `H q0`, `CNOT q0 q1` or
+/// `M q1 -> One`. Failures to write are ignored so that tracing never affects execution.
+pub struct TraceWriter<'a, B> {
+    backend: &'a mut B,
+    writer: &'a mut dyn Write,
+}
+
+impl<'a, B> TraceWriter<'a, B> {
+    pub fn new(backend: &'a mut B, writer: &'a mut dyn Write) -> Self {
+        Self { backend, writer }
+    }
+
+    fn trace(&mut self, line: std::fmt::Arguments) {
+        let _ = writeln!(self.writer, "{line}");
+    }
+
+    /// Writes the line describing a gate.
+    fn trace_gate(&mut self, gate: GateOp) {
+        match gate {
+            GateOp::Ccx(ctl0, ctl1, q) => self.trace(format_args!("CCNOT q{ctl0} q{ctl1} q{q}")),
+            GateOp::Cx(ctl, q) => self.trace(format_args!("CNOT q{ctl} q{q}")),
+            GateOp::Cy(ctl, q) => self.trace(format_args!("CY q{ctl} q{q}")),
+            GateOp::Cz(ctl, q) => self.trace(format_args!("CZ q{ctl} q{q}")),
+            GateOp::H(q) => self.trace(format_args!("H q{q}")),
+            GateOp::Rx(theta, q) => self.trace(format_args!("Rx({theta}) q{q}")),
+            GateOp::Rxx(theta, q0, q1) => self.trace(format_args!("Rxx({theta}) q{q0} q{q1}")),
+            GateOp::Ry(theta, q) => self.trace(format_args!("Ry({theta}) q{q}")),
+            GateOp::Ryy(theta, q0, q1) => self.trace(format_args!("Ryy({theta}) q{q0} q{q1}")),
+            GateOp::Rz(theta, q) => self.trace(format_args!("Rz({theta}) q{q}")),
+            GateOp::Rzz(theta, q0, q1) => self.trace(format_args!("Rzz({theta}) q{q0} q{q1}")),
+            GateOp::Sadj(q) => self.trace(format_args!("Adjoint S q{q}")),
+            GateOp::S(q) => self.trace(format_args!("S q{q}")),
+            GateOp::Swap(q0, q1) => self.trace(format_args!("SWAP q{q0} q{q1}")),
+            GateOp::Tadj(q) => self.trace(format_args!("Adjoint T q{q}")),
+            GateOp::T(q) => self.trace(format_args!("T q{q}")),
+            GateOp::X(q) => self.trace(format_args!("X q{q}")),
+            GateOp::Y(q) => self.trace(format_args!("Y q{q}")),
+            GateOp::Z(q) => self.trace(format_args!("Z q{q}")),
+        }
+    }
+}
+
+impl<B> Backend for TraceWriter<'_, B>
+where
+    B: Backend,
+    B::ResultType: Copy + Into<val::Result>,
+{
+    type ResultType = B::ResultType;
+
+    fn ccx(&mut self, ctl0: usize, ctl1: usize, q: usize) {
+        self.trace_gate(GateOp::Ccx(ctl0, ctl1, q));
+        self.backend.ccx(ctl0, ctl1, q);
+    }
+
+    fn cx(&mut self, ctl: usize, q: usize) {
+        self.trace_gate(GateOp::Cx(ctl, q));
+        self.backend.cx(ctl, q);
+    }
+
+    fn cy(&mut self, ctl: usize, q: usize) {
+        self.trace_gate(GateOp::Cy(ctl, q));
+        self.backend.cy(ctl, q);
+    }
+
+    fn cz(&mut self, ctl: usize, q: usize) {
+        self.trace_gate(GateOp::Cz(ctl, q));
+        self.backend.cz(ctl, q);
+    }
+
+    fn h(&mut self, q: usize) {
+        self.trace_gate(GateOp::H(q));
+        self.backend.h(q);
+    }
+
+    fn m(&mut self, q: usize) -> Self::ResultType {
+        let result = self.backend.m(q);
+        self.trace(format_args!("M q{q} -> {}", Value::Result(result.into())));
+        result
+    }
+
+    fn mresetz(&mut self, q: usize) -> Self::ResultType {
+        let result = self.backend.mresetz(q);
+        self.trace(format_args!(
+            "MResetZ q{q} -> {}",
+            Value::Result(result.into())
+        ));
+        result
+    }
+
+    fn measure_pauli(&mut self, paulis: &[(Pauli, usize)]) -> Self::ResultType {
+        let result = self.backend.measure_pauli(paulis);
+        let bases = paulis
+            .iter()
+            .map(|&(basis, q)| format!("{} q{q}", Value::Pauli(basis)))
+            .collect::<Vec<_>>()
+            .join(" ");
+        self.trace(format_args!(
+            "Measure {bases} -> {}",
+            Value::Result(result.into())
+        ));
+        result
+    }
+
+    fn reset(&mut self, q: usize) {
+        self.trace(format_args!("Reset q{q}"));
+        self.backend.reset(q);
+    }
+
+    fn rx(&mut self, theta: f64, q: usize) {
+        self.trace_gate(GateOp::Rx(theta, q));
+        self.backend.rx(theta, q);
+    }
+
+    fn rxx(&mut self, theta: f64, q0: usize, q1: usize) {
+        self.trace_gate(GateOp::Rxx(theta, q0, q1));
+        self.backend.rxx(theta, q0, q1);
+    }
+
+    fn ry(&mut self, theta: f64, q: usize) {
+        self.trace_gate(GateOp::Ry(theta, q));
+        self.backend.ry(theta, q);
+    }
+
+    fn ryy(&mut self, theta: f64, q0: usize, q1: usize) {
+        self.trace_gate(GateOp::Ryy(theta, q0, q1));
+        self.backend.ryy(theta, q0, q1);
+    }
+
+    fn rz(&mut self, theta: f64, q: usize) {
+        self.trace_gate(GateOp::Rz(theta, q));
+        self.backend.rz(theta, q);
+    }
+
+    fn rzz(&mut self, theta: f64, q0: usize, q1: usize) {
+        self.trace_gate(GateOp::Rzz(theta, q0, q1));
+        self.backend.rzz(theta, q0, q1);
+    }
+
+    fn sadj(&mut self, q: usize) {
+        self.trace_gate(GateOp::Sadj(q));
+        self.backend.sadj(q);
+    }
+
+    fn s(&mut self, q: usize) {
+        self.trace_gate(GateOp::S(q));
+        self.backend.s(q);
+    }
+
+    fn swap(&mut self, q0: usize, q1: usize) {
+        self.trace_gate(GateOp::Swap(q0, q1));
+        self.backend.swap(q0, q1);
+    }
+
+    fn tadj(&mut self, q: usize) {
+        self.trace_gate(GateOp::Tadj(q));
+        self.backend.tadj(q);
+    }
+
+    fn t(&mut self, q: usize) {
+        self.trace_gate(GateOp::T(q));
+        self.backend.t(q);
+    }
+
+    fn x(&mut self, q: usize) {
+        self.trace_gate(GateOp::X(q));
+        self.backend.x(q);
+    }
+
+    fn y(&mut self, q: usize) {
+        self.trace_gate(GateOp::Y(q));
+        self.backend.y(q);
+    }
+
+    fn z(&mut self, q: usize) {
+        self.trace_gate(GateOp::Z(q));
+        self.backend.z(q);
+    }
+
+    fn qubit_allocate(&mut self) -> usize {
+        self.backend.qubit_allocate()
+    }
+
+    fn qubit_release(&mut self, q: usize) {
+        self.backend.qubit_release(q);
+    }
+
+    fn capture_quantum_state(&mut self) -> (Vec<(BigUint, Complex<f64>)>, usize) {
+        self.backend.capture_quantum_state()
+    }
+
+    fn qubit_is_zero(&mut self, q: usize) -> bool {
+        self.backend.qubit_is_zero(q)
+    }
+
+    fn qubit_borrow(&mut self) -> usize {
+        self.backend.qubit_borrow()
+    }
+
+    fn qubit_return(&mut self, q: usize) -> bool {
+        self.backend.qubit_return(q)
+    }
+
+    fn can_measure(&mut self) -> bool {
+        self.backend.can_measure()
+    }
+
+    fn custom_intrinsic(&mut self, name: &str, arg: Value) -> Option<Result<Value, String>> {
+        self.backend.custom_intrinsic(name, arg)
+    }
+
+    fn set_seed(&mut self, seed: Option<u64>) {
+        self.backend.set_seed(seed);
+    }
+
+    fn enter_operation(&mut self, name: &str) {
+        self.backend.enter_operation(name);
+    }
+
+    fn exit_operation(&mut self) {
+        self.backend.exit_operation();
+    }
+
+    fn result_outcome(&mut self, id: usize) -> Option<bool> {
+        self.backend.result_outcome(id)
+    }
+
+    fn enter_conditional(&mut self, result: usize) {
+        self.backend.enter_conditional(result);
+    }
+
+    fn exit_conditional(&mut self) {
+        self.backend.exit_conditional();
+    }
+
+    fn apply_batch(&mut self, ops: &[GateOp]) {
+        for op in ops {
+            self.trace_gate(*op);
+        }
+        self.backend.apply_batch(ops);
+    }
+}
+
+/// Forwards every call to a backend or receiver, adding a [`LogEvent`] to a log for each gate,
+/// measurement, reset, qubit allocation and release, and message along the way.
+pub(crate) struct EventRecorder<'a, T> {
+    inner: &'a mut T,
+    events: &'a RefCell<Vec<LogEvent>>,
+}
+
+impl<'a, T> EventRecorder<'a, T> {
+    pub(crate) fn new(inner: &'a mut T, events: &'a RefCell<Vec<LogEvent>>) -> Self {
+        Self { inner, events }
+    }
+
+    fn record(&self, event: LogEvent) {
+        self.events.borrow_mut().push(event);
+    }
+
+    fn record_gate(&self, gate: GateOp) {
+        self.record(LogEvent::Gate { gate });
+    }
+}
+
+impl<B> Backend for EventRecorder<'_, B>
+where
+    B: Backend,
+    B::ResultType: Into<val::Result>,
+{
+    type ResultType = val::Result;
+
+    fn ccx(&mut self, ctl0: usize, ctl1: usize, q: usize) {
+        self.record_gate(GateOp::Ccx(ctl0, ctl1, q));
+        self.inner.ccx(ctl0, ctl1, q);
+    }
+
+    fn cx(&mut self, ctl: usize, q: usize) {
+        self.record_gate(GateOp::Cx(ctl, q));
+        self.inner.cx(ctl, q);
+    }
+
+    fn cy(&mut self, ctl: usize, q: usize) {
+        self.record_gate(GateOp::Cy(ctl, q));
+        self.inner.cy(ctl, q);
+    }
+
+    fn cz(&mut self, ctl: usize, q: usize) {
+        self.record_gate(GateOp::Cz(ctl, q));
+        self.inner.cz(ctl, q);
+    }
+
+    fn h(&mut self, q: usize) {
+        self.record_gate(GateOp::H(q));
+        self.inner.h(q);
+    }
+
+    fn m(&mut self, q: usize) -> Self::ResultType {
+        let result = self.inner.m(q).into();
+        self.record(LogEvent::Measure {
+            qubit: q,
+            result,
+            reset: false,
+        });
+        result
+    }
+
+    fn mresetz(&mut self, q: usize) -> Self::ResultType {
+        let result = self.inner.mresetz(q).into();
+        self.record(LogEvent::Measure {
+            qubit: q,
+            result,
+            reset: true,
+        });
+        result
+    }
+
+    fn measure_pauli(&mut self, paulis: &[(Pauli, usize)]) -> Self::ResultType {
+        let result = self.inner.measure_pauli(paulis).into();
+        self.record(LogEvent::MeasurePauli {
+            paulis: paulis.to_vec(),
+            result,
+        });
+        result
+    }
+
+    fn reset(&mut self, q: usize) {
+        self.record(LogEvent::Reset { qubit: q });
+        self.inner.reset(q);
+    }
+
+    fn rx(&mut self, theta: f64, q: usize) {
+        self.record_gate(GateOp::Rx(theta, q));
+        self.inner.rx(theta, q);
+    }
+
+    fn rxx(&mut self, theta: f64, q0: usize, q1: usize) {
+        self.record_gate(GateOp::Rxx(theta, q0, q1));
+        self.inner.rxx(theta, q0, q1);
+    }
+
+    fn ry(&mut self, theta: f64, q: usize) {
+        self.record_gate(GateOp::Ry(theta, q));
+        self.inner.ry(theta, q);
+    }
+
+    fn ryy(&mut self, theta: f64, q0: usize, q1: usize) {
+        self.record_gate(GateOp::Ryy(theta, q0, q1));
+        self.inner.ryy(theta, q0, q1);
+    }
+
+    fn rz(&mut self, theta: f64, q: usize) {
+        self.record_gate(GateOp::Rz(theta, q));
+        self.inner.rz(theta, q);
+    }
+
+    fn rzz(&mut self, theta: f64, q0: usize, q1: usize) {
+        self.record_gate(GateOp::Rzz(theta, q0, q1));
+        self.inner.rzz(theta, q0, q1);
+    }
+
+    fn sadj(&mut self, q: usize) {
+        self.record_gate(GateOp::Sadj(q));
+        self.inner.sadj(q);
+    }
+
+    fn s(&mut self, q: usize) {
+        self.record_gate(GateOp::S(q));
+        self.inner.s(q);
+    }
+
+    fn swap(&mut self, q0: usize, q1: usize) {
+        self.record_gate(GateOp::Swap(q0, q1));
+        self.inner.swap(q0, q1);
+    }
+
+    fn tadj(&mut self, q: usize) {
+        self.record_gate(GateOp::Tadj(q));
+        self.inner.tadj(q);
+    }
+
+    fn t(&mut self, q: usize) {
+        self.record_gate(GateOp::T(q));
+        self.inner.t(q);
+    }
+
+    fn x(&mut self, q: usize) {
+        self.record_gate(GateOp::X(q));
+        self.inner.x(q);
+    }
+
+    fn y(&mut self, q: usize) {
+        self.record_gate(GateOp::Y(q));
+        self.inner.y(q);
+    }
+
+    fn z(&mut self, q: usize) {
+        self.record_gate(GateOp::Z(q));
+        self.inner.z(q);
+    }
+
+    fn qubit_allocate(&mut self) -> usize {
+        let qubit = self.inner.qubit_allocate();
+        self.record(LogEvent::Allocate { qubit });
+        qubit
+    }
+
+    fn qubit_release(&mut self, q: usize) {
+        self.record(LogEvent::Release { qubit: q });
+        self.inner.qubit_release(q);
+    }
+
+    fn capture_quantum_state(&mut self) -> (Vec<(BigUint, Complex<f64>)>, usize) {
+        self.inner.capture_quantum_state()
+    }
+
+    fn qubit_is_zero(&mut self, q: usize) -> bool {
+        self.inner.qubit_is_zero(q)
+    }
+
+    fn qubit_borrow(&mut self) -> usize {
+        let qubit = self.inner.qubit_borrow();
+        self.record(LogEvent::Allocate { qubit });
+        qubit
+    }
+
+    fn qubit_return(&mut self, q: usize) -> bool {
+        let is_restored = self.inner.qubit_return(q);
+        if is_restored {
+            self.record(LogEvent::Release { qubit: q });
+        }
+        is_restored
+    }
+
+    fn can_measure(&mut self) -> bool {
+        self.inner.can_measure()
+    }
+
+    fn custom_intrinsic(&mut self, name: &str, arg: Value) -> Option<Result<Value, String>> {
+        self.inner.custom_intrinsic(name, arg)
+    }
+
+    fn set_seed(&mut self, seed: Option<u64>) {
+        self.inner.set_seed(seed);
+    }
+
+    fn enter_operation(&mut self, name: &str) {
+        self.inner.enter_operation(name);
+    }
+
+    fn exit_operation(&mut self) {
+        self.inner.exit_operation();
+    }
+
+    fn result_outcome(&mut self, id: usize) -> Option<bool> {
+        self.inner.result_outcome(id)
+    }
+
+    fn enter_conditional(&mut self, result: usize) {
+        self.inner.enter_conditional(result);
+    }
+
+    fn exit_conditional(&mut self) {
+        self.inner.exit_conditional();
+    }
+
+    fn apply_batch(&mut self, ops: &[GateOp]) {
+        for op in ops {
+            self.record_gate(*op);
+        }
+        self.inner.apply_batch(ops);
+    }
+}
+
+impl<R> Receiver for EventRecorder<'_, R>
+where
+    R: Receiver,
+{
+    fn state(
+        &mut self,
+        state: Vec<(BigUint, Complex64)>,
+        qubit_count: usize,
+    ) -> Result<(), output::Error> {
+        self.inner.state(state, qubit_count)
+    }
+
+    fn register_state(
+        &mut self,
+        qubits: &[usize],
+        state: Vec<(BigUint, Complex64)>,
+    ) -> Result<(), output::Error> {
+        self.inner.register_state(qubits, state)
+    }
+
+    fn message(&mut self, msg: &str) -> Result<(), output::Error> {
+        self.record(LogEvent::Message {
+            message: msg.into(),
+        });
+        self.inner.message(msg)
+    }
+}
+
+/// Backend that forwards every call to another backend, but takes the outcome of each measurement
+/// in order from a fixed sequence of results instead, e.g. to replay the measurements of a run on
+/// hardware through the classical logic of a program. The other backend still performs each
+/// measurement, so its state may not match the replayed outcomes.
+pub struct ResultStream<'a, B> {
+    backend: &'a mut B,
+    results: std::slice::Iter<'a, val::Result>,
+}
+
+impl<'a, B> ResultStream<'a, B> {
+    pub fn new(backend: &'a mut B, results: &'a [val::Result]) -> Self {
+        Self {
+            backend,
+            results: results.iter(),
+        }
+    }
+
+    fn next_result(&mut self) -> val::Result {
+        *self
+            .results
+            .next()
+            .expect("results should not be exhausted when measuring")
+    }
+}
+
+impl<B> Backend for ResultStream<'_, B>
+where
+    B: Backend,
+{
+    type ResultType = val::Result;
+
+    fn ccx(&mut self, ctl0: usize, ctl1: usize, q: usize) {
+        self.backend.ccx(ctl0, ctl1, q);
+    }
+
+    fn cx(&mut self, ctl: usize, q: usize) {
+        self.backend.cx(ctl, q);
+    }
+
+    fn cy(&mut self, ctl: usize, q: usize) {
+        self.backend.cy(ctl, q);
+    }
+
+    fn cz(&mut self, ctl: usize, q: usize) {
+        self.backend.cz(ctl, q);
+    }
+
+    fn h(&mut self, q: usize) {
+        self.backend.h(q);
+    }
+
+    fn m(&mut self, q: usize) -> Self::ResultType {
+        let _ = self.backend.m(q);
+        self.next_result()
+    }
+
+    fn mresetz(&mut self, q: usize) -> Self::ResultType {
+        let _ = self.backend.mresetz(q);
+        self.next_result()
+    }
+
+    fn measure_pauli(&mut self, paulis: &[(Pauli, usize)]) -> Self::ResultType {
+        let _ = self.backend.measure_pauli(paulis);
+        self.next_result()
+    }
+
+    fn reset(&mut self, q: usize) {
+        self.backend.reset(q);
+    }
+
+    fn rx(&mut self, theta: f64, q: usize) {
+        self.backend.rx(theta, q);
+    }
+
+    fn rxx(&mut self, theta: f64, q0: usize, q1: usize) {
+        self.backend.rxx(theta, q0, q1);
+    }
+
+    fn ry(&mut self, theta: f64, q: usize) {
+        self.backend.ry(theta, q);
+    }
+
+    fn ryy(&mut self, theta: f64, q0: usize, q1: usize) {
+        self.backend.ryy(theta, q0, q1);
+    }
+
+    fn rz(&mut self, theta: f64, q: usize) {
+        self.backend.rz(theta, q);
+    }
+
+    fn rzz(&mut self, theta: f64, q0: usize, q1: usize) {
+        self.backend.rzz(theta, q0, q1);
+    }
+
+    fn sadj(&mut self, q: usize) {
+        self.backend.sadj(q);
+    }
+
+    fn s(&mut self, q: usize) {
+        self.backend.s(q);
+    }
+
+    fn swap(&mut self, q0: usize, q1: usize) {
+        self.backend.swap(q0, q1);
+    }
+
+    fn tadj(&mut self, q: usize) {
+        self.backend.tadj(q);
+    }
+
+    fn t(&mut self, q: usize) {
+        self.backend.t(q);
+    }
+
+    fn x(&mut self, q: usize) {
+        self.backend.x(q);
+    }
+
+    fn y(&mut self, q: usize) {
+        self.backend.y(q);
+    }
+
+    fn z(&mut self, q: usize) {
+        self.backend.z(q);
+    }
+
+    fn qubit_allocate(&mut self) -> usize {
+        self.backend.qubit_allocate()
+    }
+
+    fn qubit_release(&mut self, q: usize) {
+        self.backend.qubit_release(q);
+    }
+
+    fn capture_quantum_state(&mut self) -> (Vec<(BigUint, Complex<f64>)>, usize) {
+        self.backend.capture_quantum_state()
+    }
+
+    fn qubit_is_zero(&mut self, q: usize) -> bool {
+        self.backend.qubit_is_zero(q)
+    }
+
+    fn qubit_borrow(&mut self) -> usize {
+        self.backend.qubit_borrow()
+    }
+
+    fn qubit_return(&mut self, q: usize) -> bool {
+        self.backend.qubit_return(q)
+    }
+
+    fn can_measure(&mut self) -> bool {
+        self.results.len() > 0 && self.backend.can_measure()
+    }
+
+    fn custom_intrinsic(&mut self, name: &str, arg: Value) -> Option<Result<Value, String>> {
+        self.backend.custom_intrinsic(name, arg)
+    }
+
+    fn set_seed(&mut self, seed: Option<u64>) {
+        self.backend.set_seed(seed);
+    }
+
+    fn enter_operation(&mut self, name: &str) {
+        self.backend.enter_operation(name);
+    }
+
+    fn exit_operation(&mut self) {
+        self.backend.exit_operation();
+    }
+
+    fn enter_conditional(&mut self, result: usize) {
+        self.backend.enter_conditional(result);
+    }
+
+    fn exit_conditional(&mut self) {
+        self.backend.exit_conditional();
+    }
+
+    fn apply_batch(&mut self, ops: &[GateOp]) {
+        self.backend.apply_batch(ops);
+    }
+}
+
+/// Backend that tracks qubit allocation but applies no gates, with every measurement returning
+/// `Zero`. Running a program against it executes all of its classical logic along the path
+/// where each measurement is `Zero` without the cost of simulating the quantum state.
+#[derive(Default)]
+pub struct DryRun {
+    next_qubit: usize,
+    free_qubits: Vec<usize>,
+}
+
+impl Backend for DryRun {
+    type ResultType = val::Result;
+
+    fn ccx(&mut self, _ctl0: usize, _ctl1: usize, _q: usize) {}
+
+    fn cx(&mut self, _ctl: usize, _q: usize) {}
+
+    fn cy(&mut self, _ctl: usize, _q: usize) {}
+
+    fn cz(&mut self, _ctl: usize, _q: usize) {}
+
+    fn h(&mut self, _q: usize) {}
+
+    fn m(&mut self, _q: usize) -> Self::ResultType {
+        val::Result::Val(false)
+    }
+
+    fn mresetz(&mut self, _q: usize) -> Self::ResultType {
+        val::Result::Val(false)
+    }
+
+    fn reset(&mut self, _q: usize) {}
+
+    fn rx(&mut self, _theta: f64, _q: usize) {}
+
+    fn rxx(&mut self, _theta: f64, _q0: usize, _q1: usize) {}
+
+    fn ry(&mut self, _theta: f64, _q: usize) {}
+
+    fn ryy(&mut self, _theta: f64, _q0: usize, _q1: usize) {}
+
+    fn rz(&mut self, _theta: f64, _q: usize) {}
+
+    fn rzz(&mut self, _theta: f64, _q0: usize, _q1: usize) {}
+
+    fn sadj(&mut self, _q: usize) {}
+
+    fn s(&mut self, _q: usize) {}
+
+    fn swap(&mut self, _q0: usize, _q1: usize) {}
+
+    fn tadj(&mut self, _q: usize) {}
+
+    fn t(&mut self, _q: usize) {}
+
+    fn x(&mut self, _q: usize) {}
+
+    fn y(&mut self, _q: usize) {}
+
+    fn z(&mut self, _q: usize) {}
+
+    fn qubit_allocate(&mut self) -> usize {
+        self.free_qubits.pop().unwrap_or_else(|| {
+            let q = self.next_qubit;
+            self.next_qubit += 1;
+            q
+        })
+    }
+
+    fn qubit_release(&mut self, q: usize) {
+        self.free_qubits.push(q);
+    }
+
+    fn capture_quantum_state(&mut self) -> (Vec<(BigUint, Complex<f64>)>, usize) {
+        (Vec::new(), self.next_qubit - self.free_qubits.len())
+    }
+
+    fn qubit_is_zero(&mut self, _q: usize) -> bool {
+        true
+    }
+
+    fn custom_intrinsic(&mut self, name: &str, _arg: Value) -> Option<Result<Value, String>> {
+        match name {
+            "GlobalPhase" => Some(Ok(Value::unit())),
+            _ => estimate_intrinsic(name),
+        }
+    }
 }
@@ -0,0 +1,633 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+#[cfg(test)]
+mod tests;
+
+use num_bigint::BigUint;
+use num_complex::Complex;
+use rand::{rngs::StdRng, Rng, RngCore, SeedableRng};
+
+use super::{estimate_intrinsic, Backend, GateOp, SparseSim};
+use crate::val::Value;
+
+/// Backend that simulates Clifford operations on a stabilizer tableau, so that circuits made of
+/// Clifford gates and measurements run in polynomial time regardless of the number of qubits.
+///
+/// The first non-Clifford operation (such as `T`, `CCNOT`, or a rotation by an angle that is
+/// not a multiple of π/2) converts the tableau into a [`SparseSim`] that continues the
+/// simulation from the same state. Stabilizer states do not carry a global phase, so the
+/// converted state and any state captured from the tableau match the sparse simulation only
+/// up to a global phase.
+pub struct StabilizerSim {
+    tableau: Tableau,
+    rng: StdRng,
+    fallback: Option<SparseSim>,
+}
+
+impl Default for StabilizerSim {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl StabilizerSim {
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            tableau: Tableau::default(),
+            rng: StdRng::seed_from_u64(rand::thread_rng().next_u64()),
+            fallback: None,
+        }
+    }
+
+    /// Returns `true` while every operation so far has been a Clifford operation, so the state
+    /// is still simulated on the stabilizer tableau.
+    #[must_use]
+    pub fn is_clifford_only(&self) -> bool {
+        self.fallback.is_none()
+    }
+
+    /// The sparse simulator that continues the simulation, converting the tableau on first use.
+    fn sparse(&mut self) -> &mut SparseSim {
+        if self.fallback.is_none() {
+            let seed = self.rng.next_u64();
+            self.fallback = Some(self.tableau.to_sparse_sim(seed));
+        }
+        self.fallback
+            .as_mut()
+            .expect("fallback simulator should be initialized")
+    }
+
+    fn rz_clifford(&mut self, turns: u8, q: usize) {
+        match turns {
+            1 => self.tableau.s(q),
+            2 => self.tableau.z(q),
+            3 => self.tableau.sadj(q),
+            _ => {}
+        }
+    }
+}
+
+/// The number of quarter turns that a rotation by `theta` corresponds to, if it is a Clifford
+/// rotation. Rotations are only Clifford when the angle is a multiple of π/2.
+fn quarter_turns(theta: f64) -> Option<u8> {
+    let turns = theta / std::f64::consts::FRAC_PI_2;
+    let rounded = turns.round();
+    if (turns - rounded).abs() > 1e-9 {
+        return None;
+    }
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    // The remainder is in `0..4`.
+    let turns = rounded.rem_euclid(4.0) as u8;
+    Some(turns)
+}
+
+impl Backend for StabilizerSim {
+    type ResultType = bool;
+
+    fn ccx(&mut self, ctl0: usize, ctl1: usize, q: usize) {
+        self.sparse().ccx(ctl0, ctl1, q);
+    }
+
+    fn cx(&mut self, ctl: usize, q: usize) {
+        match &mut self.fallback {
+            Some(sim) => sim.cx(ctl, q),
+            None => self.tableau.cx(ctl, q),
+        }
+    }
+
+    fn cy(&mut self, ctl: usize, q: usize) {
+        if let Some(sim) = &mut self.fallback {
+            sim.cy(ctl, q);
+        } else {
+            self.tableau.sadj(q);
+            self.tableau.cx(ctl, q);
+            self.tableau.s(q);
+        }
+    }
+
+    fn cz(&mut self, ctl: usize, q: usize) {
+        match &mut self.fallback {
+            Some(sim) => sim.cz(ctl, q),
+            None => self.tableau.cz(ctl, q),
+        }
+    }
+
+    fn h(&mut self, q: usize) {
+        match &mut self.fallback {
+            Some(sim) => sim.h(q),
+            None => self.tableau.h(q),
+        }
+    }
+
+    fn m(&mut self, q: usize) -> Self::ResultType {
+        match &mut self.fallback {
+            Some(sim) => sim.m(q),
+            None => self.tableau.measure(q, &mut self.rng),
+        }
+    }
+
+    fn mresetz(&mut self, q: usize) -> Self::ResultType {
+        if let Some(sim) = &mut self.fallback {
+            return sim.mresetz(q);
+        }
+        let res = self.tableau.measure(q, &mut self.rng);
+        if res {
+            self.tableau.x(q);
+        }
+        res
+    }
+
+    fn reset(&mut self, q: usize) {
+        self.mresetz(q);
+    }
+
+    fn rx(&mut self, theta: f64, q: usize) {
+        match (quarter_turns(theta), &self.fallback) {
+            (Some(turns), None) => {
+                self.tableau.h(q);
+                self.rz_clifford(turns, q);
+                self.tableau.h(q);
+            }
+            _ => self.sparse().rx(theta, q),
+        }
+    }
+
+    fn rxx(&mut self, theta: f64, q0: usize, q1: usize) {
+        match (quarter_turns(theta), &self.fallback) {
+            (Some(turns), None) => {
+                self.tableau.h(q0);
+                self.tableau.h(q1);
+                self.tableau.cx(q1, q0);
+                self.rz_clifford(turns, q0);
+                self.tableau.cx(q1, q0);
+                self.tableau.h(q1);
+                self.tableau.h(q0);
+            }
+            _ => self.sparse().rxx(theta, q0, q1),
+        }
+    }
+
+    fn ry(&mut self, theta: f64, q: usize) {
+        match (quarter_turns(theta), &self.fallback) {
+            (Some(turns), None) => {
+                // Ry(θ) = S Rx(θ) S†
+                self.tableau.sadj(q);
+                self.tableau.h(q);
+                self.rz_clifford(turns, q);
+                self.tableau.h(q);
+                self.tableau.s(q);
+            }
+            _ => self.sparse().ry(theta, q),
+        }
+    }
+
+    fn ryy(&mut self, theta: f64, q0: usize, q1: usize) {
+        match (quarter_turns(theta), &self.fallback) {
+            (Some(_), None) => {
+                // Ryy(θ) = (S ⊗ S) Rxx(θ) (S† ⊗ S†)
+                for q in [q0, q1] {
+                    self.tableau.sadj(q);
+                }
+                self.rxx(theta, q0, q1);
+                for q in [q0, q1] {
+                    self.tableau.s(q);
+                }
+            }
+            _ => self.sparse().ryy(theta, q0, q1),
+        }
+    }
+
+    fn rz(&mut self, theta: f64, q: usize) {
+        match (quarter_turns(theta), &self.fallback) {
+            (Some(turns), None) => self.rz_clifford(turns, q),
+            _ => self.sparse().rz(theta, q),
+        }
+    }
+
+    fn rzz(&mut self, theta: f64, q0: usize, q1: usize) {
+        match (quarter_turns(theta), &self.fallback) {
+            (Some(turns), None) => {
+                self.tableau.cx(q1, q0);
+                self.rz_clifford(turns, q0);
+                self.tableau.cx(q1, q0);
+            }
+            _ => self.sparse().rzz(theta, q0, q1),
+        }
+    }
+
+    fn sadj(&mut self, q: usize) {
+        match &mut self.fallback {
+            Some(sim) => sim.sadj(q),
+            None => self.tableau.sadj(q),
+        }
+    }
+
+    fn s(&mut self, q: usize) {
+        match &mut self.fallback {
+            Some(sim) => sim.s(q),
+            None => self.tableau.s(q),
+        }
+    }
+
+    fn swap(&mut self, q0: usize, q1: usize) {
+        match &mut self.fallback {
+            Some(sim) => sim.swap(q0, q1),
+            None => self.tableau.swap(q0, q1),
+        }
+    }
+
+    fn tadj(&mut self, q: usize) {
+        self.sparse().tadj(q);
+    }
+
+    fn t(&mut self, q: usize) {
+        self.sparse().t(q);
+    }
+
+    fn x(&mut self, q: usize) {
+        match &mut self.fallback {
+            Some(sim) => sim.x(q),
+            None => self.tableau.x(q),
+        }
+    }
+
+    fn y(&mut self, q: usize) {
+        match &mut self.fallback {
+            Some(sim) => sim.y(q),
+            None => self.tableau.y(q),
+        }
+    }
+
+    fn z(&mut self, q: usize) {
+        match &mut self.fallback {
+            Some(sim) => sim.z(q),
+            None => self.tableau.z(q),
+        }
+    }
+
+    fn qubit_allocate(&mut self) -> usize {
+        match &mut self.fallback {
+            Some(sim) => sim.qubit_allocate(),
+            None => self.tableau.allocate(),
+        }
+    }
+
+    fn qubit_release(&mut self, q: usize) {
+        if let Some(sim) = &mut self.fallback {
+            sim.qubit_release(q);
+        } else {
+            if self.tableau.measure(q, &mut self.rng) {
+                self.tableau.x(q);
+            }
+            self.tableau.release(q);
+        }
+    }
+
+    fn capture_quantum_state(&mut self) -> (Vec<(BigUint, Complex<f64>)>, usize) {
+        if let Some(sim) = &mut self.fallback {
+            return sim.capture_quantum_state();
+        }
+        // Capturing the state does not change it, so the simulation stays on the tableau.
+        self.tableau.to_sparse_sim(0).capture_quantum_state()
+    }
+
+    fn qubit_is_zero(&mut self, q: usize) -> bool {
+        match &mut self.fallback {
+            Some(sim) => sim.qubit_is_zero(q),
+            None => self.tableau.peek_z(q) == Some(false),
+        }
+    }
+
+    fn custom_intrinsic(&mut self, name: &str, arg: Value) -> Option<Result<Value, String>> {
+        if let Some(sim) = &mut self.fallback {
+            return sim.custom_intrinsic(name, arg);
+        }
+        match name {
+            "GlobalPhase" => {
+                let [ctls_val, _] = &*arg.clone().unwrap_tuple() else {
+                    panic!("tuple arity for GlobalPhase intrinsic should be 2");
+                };
+                if ctls_val.clone().unwrap_array().is_empty() {
+                    // A global phase is not observable and stabilizer states do not track it.
+                    Some(Ok(Value::unit()))
+                } else {
+                    self.sparse().custom_intrinsic(name, arg)
+                }
+            }
+            _ => estimate_intrinsic(name),
+        }
+    }
+
+    fn set_seed(&mut self, seed: Option<u64>) {
+        let seed = seed.unwrap_or_else(|| rand::thread_rng().next_u64());
+        self.rng = StdRng::seed_from_u64(seed);
+        if let Some(sim) = &mut self.fallback {
+            sim.set_seed(Some(seed));
+        }
+    }
+
+    fn apply_batch(&mut self, ops: &[GateOp]) {
+        match &mut self.fallback {
+            Some(sim) => sim.apply_batch(ops),
+            None => {
+                for op in ops {
+                    self.apply_gate(*op);
+                }
+            }
+        }
+    }
+}
+
+/// A single row of the tableau: a Pauli string with a sign, stored as bit-packed
+/// X and Z components where X and Z together on a qubit represent Y.
+#[derive(Clone, Debug, Default)]
+struct Row {
+    x: Vec<u64>,
+    z: Vec<u64>,
+    negative: bool,
+}
+
+impl Row {
+    fn x(&self, q: usize) -> bool {
+        self.x[q / 64] & (1 << (q % 64)) != 0
+    }
+
+    fn z(&self, q: usize) -> bool {
+        self.z[q / 64] & (1 << (q % 64)) != 0
+    }
+
+    fn set(&mut self, q: usize, x: bool, z: bool) {
+        let mask = 1 << (q % 64);
+        if x {
+            self.x[q / 64] |= mask;
+        } else {
+            self.x[q / 64] &= !mask;
+        }
+        if z {
+            self.z[q / 64] |= mask;
+        } else {
+            self.z[q / 64] &= !mask;
+        }
+    }
+
+    /// Multiplies `other` into this row, tracking the resulting sign.
+    fn multiply(&mut self, other: &Row) {
+        // The product of the two Pauli strings has a phase of i^exponent, which is always real
+        // for commuting rows.
+        let mut exponent: i64 = 0;
+        for ((x1, z1), (x2, z2)) in other.x.iter().zip(&other.z).zip(self.x.iter().zip(&self.z)) {
+            let mut bits = x1 | z1;
+            while bits != 0 {
+                let bit = bits.trailing_zeros();
+                bits &= bits - 1;
+                let get = |w: u64| (w >> bit) & 1 == 1;
+                exponent += phase_exponent((get(*x1), get(*z1)), (get(*x2), get(*z2)));
+            }
+        }
+        exponent += 2 * i64::from(self.negative) + 2 * i64::from(other.negative);
+        self.negative = exponent.rem_euclid(4) == 2;
+        for (x, o) in self.x.iter_mut().zip(&other.x) {
+            *x ^= o;
+        }
+        for (z, o) in self.z.iter_mut().zip(&other.z) {
+            *z ^= o;
+        }
+    }
+}
+
+/// The power of i contributed by multiplying the single-qubit Pauli `left` on the left of
+/// another, with each given as its `(x, z)` bits, as defined by Aaronson and Gottesman.
+fn phase_exponent(left: (bool, bool), (x2, z2): (bool, bool)) -> i64 {
+    let (x2, z2) = (i64::from(x2), i64::from(z2));
+    match left {
+        (false, false) => 0,
+        (true, true) => z2 - x2,
+        (true, false) => z2 * (2 * x2 - 1),
+        (false, true) => x2 * (1 - 2 * z2),
+    }
+}
+
+/// A stabilizer tableau following "Improved Simulation of Stabilizer Circuits" by Aaronson and
+/// Gottesman. The first `n` rows are destabilizers and the last `n` rows are the stabilizers of
+/// the state, where `n` is the number of qubits the tableau has grown to.
+#[derive(Clone, Debug, Default)]
+struct Tableau {
+    rows: Vec<Row>,
+    qubits: usize,
+    /// Released qubits, all in the |0⟩ state, which are reused by later allocations.
+    free: Vec<usize>,
+}
+
+impl Tableau {
+    fn stabilizers(&self) -> std::ops::Range<usize> {
+        self.qubits..2 * self.qubits
+    }
+
+    /// Allocates a qubit in the |0⟩ state, reusing the lowest released qubit id first to match
+    /// the ids handed out by the sparse simulator.
+    fn allocate(&mut self) -> usize {
+        if let Some((index, _)) = self.free.iter().enumerate().min_by_key(|(_, q)| **q) {
+            return self.free.swap_remove(index);
+        }
+        let q = self.qubits;
+        let words = q / 64 + 1;
+        for row in &mut self.rows {
+            row.x.resize(words, 0);
+            row.z.resize(words, 0);
+        }
+        let mut destabilizer = Row {
+            x: vec![0; words],
+            z: vec![0; words],
+            negative: false,
+        };
+        let mut stabilizer = destabilizer.clone();
+        destabilizer.set(q, true, false);
+        stabilizer.set(q, false, true);
+        self.rows.insert(q, destabilizer);
+        self.rows.push(stabilizer);
+        self.qubits += 1;
+        q
+    }
+
+    /// Marks a qubit that is in the |0⟩ state as available for reuse.
+    fn release(&mut self, q: usize) {
+        self.free.push(q);
+    }
+
+    fn h(&mut self, q: usize) {
+        for row in &mut self.rows {
+            let (x, z) = (row.x(q), row.z(q));
+            row.negative ^= x && z;
+            row.set(q, z, x);
+        }
+    }
+
+    fn s(&mut self, q: usize) {
+        for row in &mut self.rows {
+            let (x, z) = (row.x(q), row.z(q));
+            row.negative ^= x && z;
+            row.set(q, x, z ^ x);
+        }
+    }
+
+    fn sadj(&mut self, q: usize) {
+        for row in &mut self.rows {
+            let (x, z) = (row.x(q), row.z(q));
+            row.negative ^= x && !z;
+            row.set(q, x, z ^ x);
+        }
+    }
+
+    fn x(&mut self, q: usize) {
+        for row in &mut self.rows {
+            row.negative ^= row.z(q);
+        }
+    }
+
+    fn y(&mut self, q: usize) {
+        for row in &mut self.rows {
+            row.negative ^= row.x(q) ^ row.z(q);
+        }
+    }
+
+    fn z(&mut self, q: usize) {
+        for row in &mut self.rows {
+            row.negative ^= row.x(q);
+        }
+    }
+
+    fn cx(&mut self, ctl: usize, q: usize) {
+        for row in &mut self.rows {
+            let (xc, zc, xt, zt) = (row.x(ctl), row.z(ctl), row.x(q), row.z(q));
+            row.negative ^= xc && zt && (xt == zc);
+            row.set(ctl, xc, zc ^ zt);
+            row.set(q, xt ^ xc, zt);
+        }
+    }
+
+    fn cz(&mut self, ctl: usize, q: usize) {
+        self.h(q);
+        self.cx(ctl, q);
+        self.h(q);
+    }
+
+    fn swap(&mut self, q0: usize, q1: usize) {
+        for row in &mut self.rows {
+            let (x0, z0, x1, z1) = (row.x(q0), row.z(q0), row.x(q1), row.z(q1));
+            row.set(q0, x1, z1);
+            row.set(q1, x0, z0);
+        }
+    }
+
+    /// Measures `q` in the Z basis, collapsing the state.
+    fn measure(&mut self, q: usize, rng: &mut StdRng) -> bool {
+        let Some(p) = self.stabilizers().find(|&p| self.rows[p].x(q)) else {
+            return self.peek_z(q).expect("outcome should be deterministic");
+        };
+        let pivot = self.rows[p].clone();
+        for (i, row) in self.rows.iter_mut().enumerate() {
+            if i != p && row.x(q) {
+                row.multiply(&pivot);
+            }
+        }
+        let outcome = rng.gen_bool(0.5);
+        let words = pivot.x.len();
+        self.rows[p - self.qubits] = pivot;
+        let mut stabilizer = Row {
+            x: vec![0; words],
+            z: vec![0; words],
+            negative: outcome,
+        };
+        stabilizer.set(q, false, true);
+        self.rows[p] = stabilizer;
+        outcome
+    }
+
+    /// The outcome of measuring `q` in the Z basis, if it is deterministic.
+    fn peek_z(&self, q: usize) -> Option<bool> {
+        if self.stabilizers().any(|p| self.rows[p].x(q)) {
+            return None;
+        }
+        let words = self.rows.first().map_or(0, |row| row.x.len());
+        let mut scratch = Row {
+            x: vec![0; words],
+            z: vec![0; words],
+            negative: false,
+        };
+        for i in 0..self.qubits {
+            if self.rows[i].x(q) {
+                scratch.multiply(&self.rows[i + self.qubits]);
+            }
+        }
+        Some(scratch.negative)
+    }
+
+    /// Creates a sparse simulator in the same state as this tableau, up to a global phase.
+    fn to_sparse_sim(&self, seed: u64) -> SparseSim {
+        let mut sim = SparseSim::new();
+        sim.set_seed(Some(seed));
+        for q in 0..self.qubits {
+            let id = sim.qubit_allocate();
+            assert_eq!(id, q, "qubit ids should be allocated in order");
+        }
+        for op in self.clone().preparation() {
+            sim.apply_gate(op);
+        }
+        for &q in &self.free {
+            sim.qubit_release(q);
+        }
+        sim
+    }
+
+    /// Computes a Clifford circuit that prepares this stabilizer state from |0…0⟩.
+    ///
+    /// Each qubit in turn is disentangled by conjugating the tableau until a stabilizer is
+    /// exactly `Z` on that qubit. The gates that do this map the state to |0…0⟩, so applying their
+    /// inverses in reverse order prepares the state.
+    fn preparation(mut self) -> Vec<GateOp> {
+        let n = self.qubits;
+        let mut gates = Vec::new();
+        for j in 0..n {
+            let r = (n + j..2 * n)
+                .find(|&r| self.rows[r].x(j) || self.rows[r].z(j))
+                .expect("remaining stabilizers should act on the qubit");
+            for k in j..n {
+                let (x, z) = (self.rows[r].x(k), self.rows[r].z(k));
+                if x && z {
+                    self.sadj(k);
+                    gates.push(GateOp::Sadj(k));
+                }
+                if x {
+                    self.h(k);
+                    gates.push(GateOp::H(k));
+                }
+                if k != j && (x || z) {
+                    self.cx(k, j);
+                    gates.push(GateOp::Cx(k, j));
+                }
+            }
+            self.rows.swap(r, n + j);
+            let pivot = self.rows[n + j].clone();
+            for row in &mut self.rows[n + j + 1..] {
+                if row.z(j) {
+                    row.multiply(&pivot);
+                }
+            }
+            if pivot.negative {
+                self.x(j);
+                gates.push(GateOp::X(j));
+            }
+        }
+        gates
+            .into_iter()
+            .rev()
+            .map(|op| match op {
+                GateOp::S(q) => GateOp::Sadj(q),
+                GateOp::Sadj(q) => GateOp::S(q),
+                op => op,
+            })
+            .collect()
+    }
+}
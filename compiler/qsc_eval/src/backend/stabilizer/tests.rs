@@ -0,0 +1,186 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+use super::StabilizerSim;
+use crate::backend::{Backend, SparseSim};
+use num_bigint::BigUint;
+use num_complex::Complex;
+
+/// Asserts that the two simulators hold the same state up to a global phase.
+fn assert_same_state_up_to_phase(stabilizer: &mut StabilizerSim, sparse: &mut SparseSim) {
+    let normalize = |(state, count): (Vec<(BigUint, Complex<f64>)>, usize)| {
+        let state = state
+            .into_iter()
+            .filter(|(_, amplitude)| amplitude.norm() > 1e-9)
+            .collect::<Vec<_>>();
+        let phase = state[0].1 / state[0].1.norm();
+        let state = state
+            .into_iter()
+            .map(|(index, amplitude)| (index, amplitude / phase))
+            .collect::<Vec<_>>();
+        (state, count)
+    };
+    let (expected, expected_count) = normalize(sparse.capture_quantum_state());
+    let (actual, actual_count) = normalize(stabilizer.capture_quantum_state());
+    assert_eq!(actual_count, expected_count);
+    assert_eq!(
+        actual.iter().map(|(index, _)| index).collect::<Vec<_>>(),
+        expected.iter().map(|(index, _)| index).collect::<Vec<_>>()
+    );
+    for ((_, actual), (_, expected)) in actual.iter().zip(&expected) {
+        assert!(
+            (actual - expected).norm() < 1e-9,
+            "amplitudes differ: {actual} != {expected}"
+        );
+    }
+}
+
+/// Applies the same operations to a stabilizer and a sparse simulator.
+fn apply_to_both(
+    stabilizer: &mut StabilizerSim,
+    sparse: &mut SparseSim,
+    ops: impl Fn(&mut dyn Backend<ResultType = bool>),
+) {
+    ops(stabilizer);
+    ops(sparse);
+}
+
+#[test]
+fn bell_pair_measurements_agree() {
+    let mut ones = 0;
+    for seed in 0..50 {
+        let mut sim = StabilizerSim::new();
+        sim.set_seed(Some(seed));
+        let (q0, q1) = (sim.qubit_allocate(), sim.qubit_allocate());
+        sim.h(q0);
+        sim.cx(q0, q1);
+        let (r0, r1) = (sim.m(q0), sim.m(q1));
+        assert_eq!(r0, r1);
+        ones += usize::from(r0);
+        assert!(sim.is_clifford_only());
+    }
+    assert!(ones > 0 && ones < 50, "both outcomes should occur");
+}
+
+#[test]
+fn large_ghz_state_measures_all_equal() {
+    let mut sim = StabilizerSim::new();
+    sim.set_seed(Some(42));
+    let qubits = (0..500).map(|_| sim.qubit_allocate()).collect::<Vec<_>>();
+    sim.h(qubits[0]);
+    for pair in qubits.windows(2) {
+        sim.cx(pair[0], pair[1]);
+    }
+    let first = sim.m(qubits[0]);
+    for &q in &qubits[1..] {
+        assert_eq!(sim.m(q), first);
+    }
+    assert!(sim.is_clifford_only());
+}
+
+#[test]
+fn large_uniform_superposition_has_fair_statistics() {
+    let mut sim = StabilizerSim::new();
+    sim.set_seed(Some(7));
+    let qubits = (0..1000).map(|_| sim.qubit_allocate()).collect::<Vec<_>>();
+    for &q in &qubits {
+        sim.h(q);
+    }
+    let ones = qubits.iter().filter(|&&q| sim.mresetz(q)).count();
+    assert!(
+        (400..600).contains(&ones),
+        "unexpected number of ones: {ones}"
+    );
+    assert!(qubits.iter().all(|&q| sim.qubit_is_zero(q)));
+    assert!(sim.is_clifford_only());
+}
+
+#[test]
+fn measurement_collapses_state() {
+    let mut sim = StabilizerSim::new();
+    sim.set_seed(Some(3));
+    let q = sim.qubit_allocate();
+    assert!(sim.qubit_is_zero(q));
+    sim.h(q);
+    assert!(!sim.qubit_is_zero(q));
+    let res = sim.m(q);
+    assert_eq!(sim.qubit_is_zero(q), !res);
+    assert_eq!(sim.m(q), res);
+}
+
+#[test]
+fn released_qubit_ids_are_reused() {
+    let mut sim = StabilizerSim::new();
+    let qubits = (0..3).map(|_| sim.qubit_allocate()).collect::<Vec<_>>();
+    assert_eq!(qubits, vec![0, 1, 2]);
+    sim.qubit_release(1);
+    sim.qubit_release(0);
+    assert_eq!(sim.qubit_allocate(), 0);
+    assert_eq!(sim.qubit_allocate(), 1);
+    assert_eq!(sim.qubit_allocate(), 3);
+}
+
+#[test]
+fn clifford_circuit_state_matches_sparse_simulation() {
+    let mut stabilizer = StabilizerSim::new();
+    let mut sparse = SparseSim::new();
+    apply_to_both(&mut stabilizer, &mut sparse, |sim| {
+        let q = (0..4).map(|_| sim.qubit_allocate()).collect::<Vec<_>>();
+        sim.h(q[0]);
+        sim.s(q[0]);
+        sim.cx(q[0], q[1]);
+        sim.h(q[2]);
+        sim.cz(q[2], q[1]);
+        sim.y(q[3]);
+        sim.cy(q[1], q[3]);
+        sim.sadj(q[2]);
+        sim.rx(std::f64::consts::FRAC_PI_2, q[3]);
+        sim.ry(std::f64::consts::PI, q[0]);
+        sim.rzz(-std::f64::consts::FRAC_PI_2, q[0], q[2]);
+        sim.rxx(std::f64::consts::FRAC_PI_2, q[1], q[3]);
+        sim.ryy(3.0 * std::f64::consts::FRAC_PI_2, q[0], q[1]);
+        sim.swap(q[1], q[2]);
+        sim.z(q[1]);
+        sim.x(q[2]);
+    });
+    assert!(stabilizer.is_clifford_only());
+    assert_same_state_up_to_phase(&mut stabilizer, &mut sparse);
+}
+
+#[test]
+fn non_clifford_gate_falls_back_to_sparse_simulation() {
+    let mut stabilizer = StabilizerSim::new();
+    let mut sparse = SparseSim::new();
+    apply_to_both(&mut stabilizer, &mut sparse, |sim| {
+        let q = (0..3).map(|_| sim.qubit_allocate()).collect::<Vec<_>>();
+        sim.h(q[0]);
+        sim.s(q[1]);
+        sim.cx(q[0], q[1]);
+        sim.h(q[2]);
+        sim.y(q[2]);
+    });
+    assert!(stabilizer.is_clifford_only());
+    apply_to_both(&mut stabilizer, &mut sparse, |sim| {
+        sim.t(0);
+        sim.rx(0.3, 2);
+        sim.cx(2, 1);
+    });
+    assert!(!stabilizer.is_clifford_only());
+    assert_same_state_up_to_phase(&mut stabilizer, &mut sparse);
+}
+
+#[test]
+fn released_qubits_are_released_after_fall_back() {
+    let mut stabilizer = StabilizerSim::new();
+    let mut sparse = SparseSim::new();
+    apply_to_both(&mut stabilizer, &mut sparse, |sim| {
+        let q = (0..3).map(|_| sim.qubit_allocate()).collect::<Vec<_>>();
+        sim.h(q[2]);
+        sim.qubit_release(q[1]);
+        sim.t(q[2]);
+        let q1 = sim.qubit_allocate();
+        sim.h(q1);
+    });
+    assert!(!stabilizer.is_clifford_only());
+    assert_same_state_up_to_phase(&mut stabilizer, &mut sparse);
+}
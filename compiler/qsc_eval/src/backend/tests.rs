@@ -0,0 +1,160 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+use super::{Backend, GateOp, SparseSim};
+use num_bigint::BigUint;
+use num_complex::Complex;
+use qsc_fir::fir::Pauli;
+
+fn rotation_chain(q: usize) -> Vec<GateOp> {
+    vec![
+        GateOp::H(q),
+        GateOp::Rx(0.3, q),
+        GateOp::Ry(1.1, q),
+        GateOp::Rz(-0.7, q),
+        GateOp::T(q),
+        GateOp::Rx(2.9, q),
+        GateOp::S(q),
+        GateOp::Ry(-1.4, q),
+        GateOp::Y(q),
+        GateOp::Rz(0.25, q),
+    ]
+}
+
+fn sequential_and_batched(num_qubits: usize, ops: &[GateOp]) -> (SparseSim, SparseSim) {
+    let mut sequential = SparseSim::new();
+    let mut batched = SparseSim::new();
+    for _ in 0..num_qubits {
+        sequential.qubit_allocate();
+        batched.qubit_allocate();
+    }
+    for op in ops {
+        sequential.apply_gate(*op);
+    }
+    batched.apply_batch(ops);
+    (sequential, batched)
+}
+
+fn assert_states_match(sequential: &mut SparseSim, batched: &mut SparseSim) {
+    let (expected, expected_count) = sequential.capture_quantum_state();
+    let (actual, actual_count) = batched.capture_quantum_state();
+    assert_eq!(expected_count, actual_count);
+    let amplitude = |state: &[(BigUint, Complex<f64>)], index: &BigUint| {
+        state
+            .iter()
+            .find(|(i, _)| i == index)
+            .map_or(Complex::new(0.0, 0.0), |(_, a)| *a)
+    };
+    for (index, _) in expected.iter().chain(actual.iter()) {
+        let diff = amplitude(&expected, index) - amplitude(&actual, index);
+        assert!(
+            diff.norm() < 1e-10,
+            "amplitude mismatch for |{index}⟩: expected {expected:?}, got {actual:?}"
+        );
+    }
+}
+
+#[test]
+fn batched_rotation_chain_matches_sequential() {
+    let (mut sequential, mut batched) = sequential_and_batched(1, &rotation_chain(0));
+    assert_states_match(&mut sequential, &mut batched);
+}
+
+#[test]
+fn batched_rotation_chains_interleaved_with_entangling_gates_match_sequential() {
+    let mut ops = rotation_chain(0);
+    ops.extend(rotation_chain(1));
+    ops.push(GateOp::Cx(0, 1));
+    ops.extend(rotation_chain(1));
+    ops.push(GateOp::Rzz(0.8, 1, 2));
+    ops.extend(rotation_chain(2));
+    ops.extend(rotation_chain(0));
+    let (mut sequential, mut batched) = sequential_and_batched(3, &ops);
+    assert_states_match(&mut sequential, &mut batched);
+}
+
+#[test]
+fn short_runs_are_applied_individually() {
+    let ops = [GateOp::H(0), GateOp::T(0), GateOp::Cx(0, 1), GateOp::X(1)];
+    let (mut sequential, mut batched) = sequential_and_batched(2, &ops);
+    assert_states_match(&mut sequential, &mut batched);
+}
+
+#[test]
+fn dense_vector_of_two_qubit_state() {
+    let mut sim = SparseSim::new();
+    let q0 = sim.qubit_allocate();
+    let q1 = sim.qubit_allocate();
+    sim.h(q0);
+    sim.x(q1);
+
+    let vector = sim.to_dense_vector();
+    let amplitude = std::f64::consts::FRAC_1_SQRT_2;
+    let expected = [
+        Complex::new(0.0, 0.0),
+        Complex::new(amplitude, 0.0),
+        Complex::new(0.0, 0.0),
+        Complex::new(amplitude, 0.0),
+    ];
+    assert_eq!(vector.len(), expected.len());
+    for (actual, expected) in vector.iter().zip(expected) {
+        assert!(
+            (actual - expected).norm() < 1e-10,
+            "expected {expected}, got {actual}"
+        );
+    }
+}
+
+#[test]
+fn measure_pauli_x_on_plus_state_is_deterministic_and_preserves_state() {
+    for seed in 0..20 {
+        let mut sim = SparseSim::new();
+        sim.set_seed(Some(seed));
+        let q = sim.qubit_allocate();
+        sim.h(q);
+        assert!(!sim.measure_pauli(&[(Pauli::X, q)]));
+        sim.h(q);
+        assert!(sim.qubit_is_zero(q));
+    }
+}
+
+#[test]
+fn measure_pauli_joint_parity_on_bell_state_is_even() {
+    for seed in 0..20 {
+        let mut sim = SparseSim::new();
+        sim.set_seed(Some(seed));
+        let (q0, q1) = (sim.qubit_allocate(), sim.qubit_allocate());
+        sim.h(q0);
+        sim.cx(q0, q1);
+        for pauli in [Pauli::Z, Pauli::X] {
+            assert!(!sim.measure_pauli(&[(pauli, q0), (pauli, q1)]));
+        }
+        assert!(sim.measure_pauli(&[(Pauli::Y, q0), (Pauli::Y, q1)]));
+        assert_eq!(sim.m(q0), sim.m(q1));
+    }
+}
+
+#[test]
+fn measure_pauli_collapses_onto_parity_eigenspace() {
+    let mut ones = 0;
+    for seed in 0..50 {
+        let mut sim = SparseSim::new();
+        sim.set_seed(Some(seed));
+        let (q0, q1) = (sim.qubit_allocate(), sim.qubit_allocate());
+        sim.h(q0);
+        sim.h(q1);
+        let parity = sim.measure_pauli(&[(Pauli::Z, q0), (Pauli::Z, q1)]);
+        assert_eq!(sim.m(q0) ^ sim.m(q1), parity);
+        ones += usize::from(parity);
+    }
+    assert!(ones > 0 && ones < 50, "both parities should occur");
+}
+
+#[test]
+fn measure_pauli_identity_is_zero() {
+    let mut sim = SparseSim::new();
+    let q = sim.qubit_allocate();
+    sim.h(q);
+    assert!(!sim.measure_pauli(&[(Pauli::I, q)]));
+    assert!(!sim.measure_pauli(&[]));
+}
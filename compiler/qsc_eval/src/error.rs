@@ -6,7 +6,7 @@ use qsc_data_structures::span::Span;
 use qsc_hir::hir::PackageId;
 use std::fmt::Debug;
 
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub struct PackageSpan {
     pub package: PackageId,
     pub span: Span,
@@ -14,7 +14,7 @@ use crate::{
     Error,
 };
 use num_bigint::BigInt;
-use rand::{rngs::StdRng, Rng};
+use rand::{Rng, RngCore};
 use rustc_hash::FxHashSet;
 use std::array;
 
@@ -25,7 +25,7 @@ pub(crate) fn call(
     arg: Value,
     arg_span: PackageSpan,
     sim: &mut dyn Backend<ResultType = impl Into<val::Result>>,
-    rng: &mut StdRng,
+    rng: &mut dyn RngCore,
     out: &mut dyn Receiver,
 ) -> Result<Value, Error> {
     match name {
@@ -55,7 +55,7 @@ pub(crate) fn call(
             let (state, qubit_count) = sim.capture_quantum_state();
             let state = utils::split_state(&qubits, &state, qubit_count)
                 .map_err(|()| Error::QubitsNotSeparable(arg_span))?;
-            match out.state(state, qubits.len()) {
+            match out.register_state(&qubits, state) {
                 Ok(()) => Ok(Value::unit()),
                 Err(_) => Err(Error::OutputFail(name_span)),
             }
@@ -112,6 +112,15 @@ pub(crate) fn call(
                 Err(Error::ReleasedQubitNotZero(qubit, arg_span))
             }
         }
+        "__quantum__rt__qubit_borrow" => Ok(Value::Qubit(Qubit(sim.qubit_borrow()))),
+        "__quantum__rt__qubit_return" => {
+            let qubit = arg.unwrap_qubit().0;
+            if sim.qubit_return(qubit) {
+                Ok(Value::unit())
+            } else {
+                Err(Error::BorrowedQubitNotRestored(qubit, arg_span))
+            }
+        }
         "__quantum__qis__ccx__body" => {
             three_qubit_gate(|ctl0, ctl1, q| sim.ccx(ctl0, ctl1, q), arg, arg_span)
         }
@@ -146,6 +155,36 @@ pub(crate) fn call(
         "__quantum__qis__z__body" => Ok(one_qubit_gate(|q| sim.z(q), arg)),
         "__quantum__qis__swap__body" => two_qubit_gate(|q0, q1| sim.swap(q0, q1), arg, arg_span),
         "__quantum__qis__reset__body" => Ok(one_qubit_gate(|q| sim.reset(q), arg)),
+        "Measure" | "__quantum__qis__m__body" | "__quantum__qis__mresetz__body"
+            if !sim.can_measure() =>
+        {
+            Err(Error::ResultsExhausted(name_span))
+        }
+        "Measure" => {
+            let [bases, qubits] = unwrap_tuple(arg);
+            let bases = bases.unwrap_array();
+            let qubits = qubits
+                .unwrap_array()
+                .iter()
+                .map(|q| q.clone().unwrap_qubit().0)
+                .collect::<Vec<_>>();
+            if bases.len() != qubits.len() {
+                return Err(Error::MeasureLengthMismatch(
+                    bases.len(),
+                    qubits.len(),
+                    arg_span,
+                ));
+            }
+            if qubits.len() != qubits.iter().collect::<FxHashSet<_>>().len() {
+                return Err(Error::QubitUniqueness(arg_span));
+            }
+            let paulis = bases
+                .iter()
+                .map(|basis| basis.clone().unwrap_pauli())
+                .zip(qubits)
+                .collect::<Vec<_>>();
+            Ok(Value::Result(sim.measure_pauli(&paulis).into()))
+        }
         "__quantum__qis__m__body" => Ok(Value::Result(sim.m(arg.unwrap_qubit().0).into())),
         "__quantum__qis__mresetz__body" => {
             Ok(Value::Result(sim.mresetz(arg.unwrap_qubit().0).into()))
@@ -1206,6 +1206,95 @@ fn m() {
     );
 }
 
+#[test]
+fn measure_pauli_x_on_plus_state_is_deterministic() {
+    check_intrinsic_result(
+        "",
+        indoc! {"{
+            use q = Qubit();
+            mutable ones = 0;
+            for _ in 1..20 {
+                H(q);
+                if Measure([PauliX], [q]) == One {
+                    set ones += 1;
+                }
+                Reset(q);
+            }
+            ones
+        }"},
+        &expect!["0"],
+    );
+}
+
+#[test]
+fn measure_joint_zz_parity_on_bell_state_is_even() {
+    check_intrinsic_result(
+        "",
+        indoc! {"{
+            use qs = Qubit[2];
+            mutable (odd, mismatched) = (0, 0);
+            for _ in 1..20 {
+                H(qs[0]);
+                CNOT(qs[0], qs[1]);
+                if Measure([PauliZ, PauliZ], qs) == One {
+                    set odd += 1;
+                }
+                if M(qs[0]) != M(qs[1]) {
+                    set mismatched += 1;
+                }
+                ResetAll(qs);
+            }
+            (odd, mismatched)
+        }"},
+        &expect!["(0, 0)"],
+    );
+}
+
+#[test]
+fn measure_joint_yy_parity_on_bell_state_is_odd() {
+    check_intrinsic_result(
+        "",
+        indoc! {"{
+            use qs = Qubit[2];
+            mutable even = 0;
+            for _ in 1..20 {
+                H(qs[0]);
+                CNOT(qs[0], qs[1]);
+                if Measure([PauliY, PauliY], qs) == Zero {
+                    set even += 1;
+                }
+                ResetAll(qs);
+            }
+            even
+        }"},
+        &expect!["0"],
+    );
+}
+
+#[test]
+fn measure_bases_and_qubits_length_mismatch_error() {
+    check_intrinsic_output(
+        "",
+        indoc! {"{
+            use qs = Qubit[2];
+            Measure([PauliZ], qs)
+        }"},
+        &expect!["bases and qubits have different lengths: 1 and 2"],
+    );
+}
+
+#[test]
+fn measure_qubit_not_unique_error() {
+    check_intrinsic_output(
+        "",
+        indoc! {"{
+            use q = Qubit();
+            Measure([PauliX, PauliZ], [q, q])
+        }"},
+        &expect!["qubits in invocation are not unique"],
+    );
+}
+
 #[test]
 fn mresetz() {
     check_intrinsic_result(
@@ -36,13 +36,13 @@ use num_bigint::BigInt;
 use output::Receiver;
 use qsc_data_structures::{functors::FunctorApp, index_map::IndexMap, span::Span};
 use qsc_fir::fir::{
-    self, BinOp, CallableImpl, ExecGraphNode, Expr, ExprId, ExprKind, Field, FieldAssign, Global,
-    Lit, LocalItemId, LocalVarId, PackageId, PackageStoreLookup, PatId, PatKind, PrimField, Res,
-    StmtId, StoreItemId, StringComponent, UnOp,
+    self, BinOp, CallableImpl, CallableKind, ExecGraphNode, Expr, ExprId, ExprKind, Field,
+    FieldAssign, Global, Lit, LocalItemId, LocalVarId, PackageId, PackageStoreLookup, PatId,
+    PatKind, PrimField, Res, StmtId, StoreItemId, StringComponent, UnOp,
 };
 use qsc_fir::ty::Ty;
 use qsc_lowerer::map_fir_package_to_hir;
-use rand::{rngs::StdRng, SeedableRng};
+use rand::{rngs::StdRng, RngCore, SeedableRng};
 use std::ops;
 use std::{
     cell::RefCell,
@@ -56,10 +56,21 @@ use val::update_functor_app;
 
 #[derive(Clone, Debug, Diagnostic, Error)]
 pub enum Error {
+    #[error("integer overflow")]
+    #[diagnostic(help(
+        "the result does not fit in a 64-bit `Int`, consider using a `BigInt` instead"
+    ))]
+    #[diagnostic(code("Qsc.Eval.ArithmeticOverflow"))]
+    ArithmeticOverflow(#[label("this operation overflows")] PackageSpan),
+
     #[error("array too large")]
     #[diagnostic(code("Qsc.Eval.ArrayTooLarge"))]
     ArrayTooLarge(#[label("this array has too many items")] PackageSpan),
 
+    #[error("assertion failed: {0}")]
+    #[diagnostic(code("Qsc.Eval.AssertionFailed"))]
+    AssertionFailed(String, #[label("failed assertion")] PackageSpan),
+
     #[error("invalid array length: {0}")]
     #[diagnostic(code("Qsc.Eval.InvalidArrayLength"))]
     InvalidArrayLength(i64, #[label("cannot be used as a length")] PackageSpan),
@@ -96,6 +107,14 @@ pub enum Error {
     #[diagnostic(code("Qsc.Eval.InvalidNegativeInt"))]
     InvalidNegativeInt(i64, #[label("invalid negative integer")] PackageSpan),
 
+    #[error("bases and qubits have different lengths: {0} and {1}")]
+    #[diagnostic(code("Qsc.Eval.MeasureLengthMismatch"))]
+    MeasureLengthMismatch(
+        usize,
+        usize,
+        #[label("each qubit needs exactly one basis")] PackageSpan,
+    ),
+
     #[error("output failure")]
     #[diagnostic(code("Qsc.Eval.OutputFail"))]
     OutputFail(#[label("failed to generate output")] PackageSpan),
@@ -113,16 +132,56 @@ pub enum Error {
     #[diagnostic(code("Qsc.Eval.RangeStepZero"))]
     RangeStepZero(#[label("invalid range")] PackageSpan),
 
+    #[error("maximum call depth of {0} exceeded")]
+    #[diagnostic(help("this is usually caused by unbounded recursion"))]
+    #[diagnostic(code("Qsc.Eval.RecursionLimitExceeded"))]
+    RecursionLimitExceeded(
+        usize,
+        #[label("this call exceeds the maximum call depth")] PackageSpan,
+    ),
+
+    #[error("maximum number of loop iterations of {0} exceeded")]
+    #[diagnostic(help("this is usually caused by a loop condition that never becomes false"))]
+    #[diagnostic(code("Qsc.Eval.LoopLimitExceeded"))]
+    LoopLimitExceeded(
+        u64,
+        #[label("this loop exceeds the maximum number of iterations")] PackageSpan,
+    ),
+
+    #[error("maximum number of measurements of {0} exceeded")]
+    #[diagnostic(help(
+        "this is usually caused by a measurement inside a loop that runs more often than intended"
+    ))]
+    #[diagnostic(code("Qsc.Eval.MeasurementBudgetExceeded"))]
+    MeasurementBudgetExceeded(
+        u64,
+        #[label("this measurement exceeds the maximum number of measurements")] PackageSpan,
+    ),
+
     #[error("Qubit{0} released while not in |0⟩ state")]
     #[diagnostic(help("qubits should be returned to the |0⟩ state before being released to satisfy the assumption that allocated qubits start in the |0⟩ state"))]
     #[diagnostic(code("Qsc.Eval.ReleasedQubitNotZero"))]
     ReleasedQubitNotZero(usize, #[label("Qubit{0}")] PackageSpan),
 
+    #[error("Qubit{0} returned while not in the state it was borrowed in")]
+    #[diagnostic(help(
+        "borrowed qubits should be restored to their original state before being returned"
+    ))]
+    #[diagnostic(code("Qsc.Eval.BorrowedQubitNotRestored"))]
+    BorrowedQubitNotRestored(usize, #[label("Qubit{0}")] PackageSpan),
+
     #[error("cannot compare measurement results")]
     #[diagnostic(code("Qsc.Eval.ResultComparisonUnsupported"))]
     #[diagnostic(help("comparing measurement results is not supported when performing circuit synthesis or base profile QIR generation"))]
     ResultComparisonUnsupported(#[label("cannot compare to result")] PackageSpan),
 
+    #[error("no measurement results left")]
+    #[diagnostic(help(
+        "the sequence of measurement results to replay ran out before the program finished"
+    ))]
+    #[diagnostic(code("Qsc.Eval.ResultsExhausted"))]
+    ResultsExhausted(#[label("this measurement has no result")] PackageSpan),
+
     #[error("name is not bound")]
     #[diagnostic(code("Qsc.Eval.UnboundName"))]
     UnboundName(#[label] PackageSpan),
@@ -148,7 +207,9 @@ impl Error {
     #[must_use]
     pub fn span(&self) -> &PackageSpan {
         match self {
-            Error::ArrayTooLarge(span)
+            Error::ArithmeticOverflow(span)
+            | Error::ArrayTooLarge(span)
+            | Error::AssertionFailed(_, span)
             | Error::DivZero(span)
             | Error::EmptyRange(span)
             | Error::IndexOutOfRange(_, span)
@@ -157,12 +218,18 @@ impl Error {
             | Error::IntTooLarge(_, span)
             | Error::InvalidRotationAngle(_, span)
             | Error::InvalidNegativeInt(_, span)
+            | Error::MeasureLengthMismatch(_, _, span)
             | Error::OutputFail(span)
             | Error::QubitUniqueness(span)
             | Error::QubitsNotSeparable(span)
             | Error::RangeStepZero(span)
+            | Error::RecursionLimitExceeded(_, span)
+            | Error::LoopLimitExceeded(_, span)
+            | Error::MeasurementBudgetExceeded(_, span)
             | Error::ReleasedQubitNotZero(_, span)
+            | Error::BorrowedQubitNotRestored(_, span)
             | Error::ResultComparisonUnsupported(span)
+            | Error::ResultsExhausted(span)
             | Error::UnboundName(span)
             | Error::UnknownIntrinsic(_, span)
             | Error::UnsupportedIntrinsicType(_, span)
@@ -218,6 +285,73 @@ pub fn exec_graph_section(
         .into()
 }
 
+/// Options that configure a single evaluation by [`eval`] or [`invoke`]. Limits and checks
+/// are off by default, except for the call depth, which is limited to
+/// [`DEFAULT_MAX_CALL_DEPTH`].
+#[derive(Clone, Default)]
+pub struct EvalOptions {
+    /// The seed for classical randomness, used when `classical_rng` is not set.
+    pub classical_seed: Option<u64>,
+    /// The generator for classical randomness, see [`State::set_rng`].
+    pub classical_rng: Option<Rc<RefCell<dyn RngCore>>>,
+    /// The call depth limit to use instead of [`DEFAULT_MAX_CALL_DEPTH`], see
+    /// [`State::set_max_call_depth`].
+    pub max_call_depth: Option<usize>,
+    /// See [`State::set_max_loop_iterations`].
+    pub max_loop_iterations: Option<u64>,
+    /// See [`State::set_checked_arithmetic`].
+    pub checked_arithmetic: bool,
+    /// See [`State::set_double_precision`].
+    pub double_precision: Option<usize>,
+    /// See [`State::set_max_measurements`].
+    pub max_measurements: Option<u64>,
+    /// The log that the events of the evaluation are appended to, if they are recorded, see
+    /// [`State::record_events`].
+    pub events: Option<Rc<RefCell<Vec<Event>>>>,
+    /// The log that assignments and the gates, measurements, qubit allocations and messages of
+    /// intrinsic calls are appended to, if they are recorded, see
+    /// [`State::record_intrinsic_events`].
+    pub log: Option<Rc<RefCell<Vec<LogEvent>>>>,
+}
+
+impl EvalOptions {
+    fn state(&self, package: PackageId, exec_graph: Rc<[ExecGraphNode]>) -> State {
+        let mut state = State::new(package, exec_graph, self.classical_seed);
+        if let Some(rng) = &self.classical_rng {
+            state.set_rng(rng.clone());
+        }
+        if let Some(depth) = self.max_call_depth {
+            state.set_max_call_depth(depth);
+        }
+        if let Some(limit) = self.max_loop_iterations {
+            state.set_max_loop_iterations(limit);
+        }
+        state.set_checked_arithmetic(self.checked_arithmetic);
+        if let Some(digits) = self.double_precision {
+            state.set_double_precision(digits);
+        }
+        if let Some(limit) = self.max_measurements {
+            state.set_max_measurements(limit);
+        }
+        if self.events.is_some() {
+            state.record_events();
+        }
+        if self.log.is_some() {
+            state.record_intrinsic_events();
+        }
+        state
+    }
+
+    fn log_events(&self, state: &mut State) {
+        if let Some(events) = &self.events {
+            events.borrow_mut().extend(state.take_events());
+        }
+        if let Some(log) = &self.log {
+            log.borrow_mut().extend(state.take_log());
+        }
+    }
+}
+
 /// Evaluates the given code with the given context.
 /// # Errors
 /// Returns the first error encountered during execution.
@@ -225,16 +359,58 @@ pub fn exec_graph_section(
 /// On internal error where no result is returned.
 pub fn eval(
     package: PackageId,
-    seed: Option<u64>,
+    options: &EvalOptions,
     exec_graph: Rc<[ExecGraphNode]>,
     globals: &impl PackageStoreLookup,
     env: &mut Env,
     sim: &mut impl Backend<ResultType = impl Into<val::Result>>,
     receiver: &mut impl Receiver,
 ) -> Result<Value, (Error, Vec<Frame>)> {
-    let mut state = State::new(package, exec_graph, seed);
-    let res = state.eval(globals, env, sim, receiver, &[], StepAction::Continue)?;
-    let StepResult::Return(value) = res else {
+    let mut state = options.state(package, exec_graph);
+    let res = state.eval(globals, env, sim, receiver, &[], StepAction::Continue);
+    // The events leading up to a runtime error are kept, since they help explain it.
+    options.log_events(&mut state);
+    let StepResult::Return(value) = res? else {
+        panic!("eval should always return a value");
+    };
+    Ok(value)
+}
+
+/// Calls the given callable value with the given argument, evaluating it to completion.
+/// # Errors
+/// Returns the first error encountered during execution.
+/// # Panics
+/// If `callable` is not a callable value.
+#[allow(clippy::too_many_arguments)]
+pub fn invoke(
+    package: PackageId,
+    options: &EvalOptions,
+    globals: &impl PackageStoreLookup,
+    env: &mut Env,
+    sim: &mut impl Backend<ResultType = impl Into<val::Result>>,
+    receiver: &mut impl Receiver,
+    callable: Value,
+    args: Value,
+) -> Result<Value, (Error, Vec<Frame>)> {
+    let mut state = options.state(package, Vec::new().into());
+    // The call pops the callable from the value stack and takes the argument from the register,
+    // then either runs an intrinsic directly or pushes the frame of the callable for evaluation.
+    state.set_val_register(callable);
+    state.push_val();
+    state.set_val_register(args);
+    let res = state
+        .eval_call(
+            env,
+            sim,
+            globals,
+            Span::default(),
+            Span::default(),
+            receiver,
+        )
+        .map_err(|e| (e, state.get_stack_frames()))
+        .and_then(|()| state.eval(globals, env, sim, receiver, &[], StepAction::Continue));
+    options.log_events(&mut state);
+    let StepResult::Return(value) = res? else {
         panic!("eval should always return a value");
     };
     Ok(value)
@@ -283,6 +459,47 @@ pub struct Variable {
     pub span: Span,
 }
 
+/// An event recorded during evaluation, see [`State::record_events`].
+#[derive(Clone, Debug, PartialEq)]
+pub enum Event {
+    /// A mutable variable was updated by a `set` statement.
+    Assign {
+        name: Rc<str>,
+        old: Value,
+        new: Value,
+        span: PackageSpan,
+    },
+}
+
+/// An event of an evaluation whose intrinsic calls are recorded, see
+/// [`State::record_intrinsic_events`].
+#[derive(Clone, Debug, PartialEq)]
+pub enum LogEvent {
+    /// A mutable variable was updated by a `set` statement.
+    Assign(Event),
+    /// A gate was applied.
+    Gate { gate: backend::GateOp },
+    /// A qubit was measured, and reset to |0⟩ afterwards if `reset` is set.
+    Measure {
+        qubit: usize,
+        result: val::Result,
+        reset: bool,
+    },
+    /// The joint parity of qubits was measured, each in its own Pauli basis.
+    MeasurePauli {
+        paulis: Vec<(fir::Pauli, usize)>,
+        result: val::Result,
+    },
+    /// A qubit was reset to |0⟩.
+    Reset { qubit: usize },
+    /// A qubit was allocated, or borrowed.
+    Allocate { qubit: usize },
+    /// A qubit was released, or returned.
+    Release { qubit: usize },
+    /// A message was output, such as by `Message`.
+    Message { message: Rc<str> },
+}
+
 #[derive(Debug, Clone)]
 pub struct VariableInfo {
     pub value: Value,
@@ -321,6 +538,7 @@ impl Range {
     }
 }
 
+#[derive(Clone)]
 pub struct Env(Vec<Scope>);
 
 impl Default for Env {
@@ -431,12 +649,13 @@ impl Env {
     }
 }
 
-#[derive(Default)]
+#[derive(Clone, Default)]
 struct Scope {
     bindings: IndexMap<LocalVarId, Variable>,
     frame_id: usize,
 }
 
+#[derive(Clone)]
 pub struct State {
     exec_graph_stack: Vec<Rc<[ExecGraphNode]>>,
     idx: u32,
@@ -447,9 +666,41 @@ pub struct State {
     package: PackageId,
     call_stack: CallStack,
     current_span: Span,
-    rng: RefCell<StdRng>,
+    /// The generator for classical randomness, which is shared rather than copied when the state
+    /// is cloned.
+    rng: Rc<RefCell<dyn RngCore>>,
+    max_call_depth: usize,
+    max_loop_iterations: Option<u64>,
+    /// The number of times each running loop has jumped back to its condition, keyed by the call
+    /// stack depth and the position of the condition in the execution graph.
+    loop_iterations: Vec<(usize, u32, u64)>,
+    /// Whether `Int` addition, multiplication and exponentiation fail on overflow rather than wrap.
+    checked_arithmetic: bool,
+    /// The number of significant digits of `Double` values interpolated into strings, if limited.
+    double_precision: Option<usize>,
+    /// The number of qubit measurements beyond which evaluation fails, if limited, see
+    /// [`State::set_max_measurements`].
+    max_measurements: Option<u64>,
+    /// The number of qubit measurements performed so far.
+    measurements: u64,
+    /// Call stack depths of the frames for which the backend was notified of entering an operation.
+    entered_operations: Vec<usize>,
+    /// The events recorded so far, if recording is enabled.
+    events: Option<Vec<Event>>,
+    /// The events recorded so far along with intrinsic calls, if recording them is enabled.
+    log: Option<Vec<LogEvent>>,
+    /// The ids of the measurement results compared in the current statement, which the backend
+    /// resolved to their outcomes, see [`Backend::result_outcome`].
+    compared_results: Vec<usize>,
+    /// The branches taken because of measurement outcomes, innermost last, each given by the
+    /// depth of its execution graph, the range of its nodes in that graph and the number of
+    /// results it depends on.
+    conditionals: Vec<(usize, ops::Range<u32>, usize)>,
 }
 
+/// The default limit on the number of nested calls during evaluation.
+pub const DEFAULT_MAX_CALL_DEPTH: usize = 10_000;
+
 impl State {
     #[must_use]
     pub fn new(
@@ -457,9 +708,9 @@ impl State {
         exec_graph: Rc<[ExecGraphNode]>,
         classical_seed: Option<u64>,
     ) -> Self {
-        let rng = match classical_seed {
-            Some(seed) => RefCell::new(StdRng::seed_from_u64(seed)),
-            None => RefCell::new(StdRng::from_entropy()),
+        let rng: Rc<RefCell<dyn RngCore>> = match classical_seed {
+            Some(seed) => Rc::new(RefCell::new(StdRng::seed_from_u64(seed))),
+            None => Rc::new(RefCell::new(StdRng::from_entropy())),
         };
         Self {
             exec_graph_stack: vec![exec_graph],
@@ -472,9 +723,91 @@ impl State {
             call_stack: CallStack::default(),
             current_span: Span::default(),
             rng,
+            max_call_depth: DEFAULT_MAX_CALL_DEPTH,
+            max_loop_iterations: None,
+            loop_iterations: Vec::new(),
+            checked_arithmetic: false,
+            double_precision: None,
+            max_measurements: None,
+            measurements: 0,
+            entered_operations: Vec::new(),
+            events: None,
+            log: None,
+            compared_results: Vec::new(),
+            conditionals: Vec::new(),
         }
     }
 
+    /// Replaces the generator used by intrinsics that produce classical random numbers.
+    /// Sharing one generator across evaluations continues its sequence rather than
+    /// restarting it from the seed given to [`State::new`].
+    pub fn set_rng(&mut self, rng: Rc<RefCell<dyn RngCore>>) {
+        self.rng = rng;
+    }
+
+    /// Sets the maximum number of nested calls, beyond which evaluation fails with
+    /// [`Error::RecursionLimitExceeded`] rather than growing without bound. Defaults to
+    /// [`DEFAULT_MAX_CALL_DEPTH`].
+    pub fn set_max_call_depth(&mut self, depth: usize) {
+        self.max_call_depth = depth;
+    }
+
+    /// Sets the maximum number of iterations of any single loop, beyond which evaluation fails
+    /// with [`Error::LoopLimitExceeded`]. Loops are not limited by default.
+    pub fn set_max_loop_iterations(&mut self, limit: u64) {
+        self.max_loop_iterations = Some(limit);
+    }
+
+    /// Sets whether `Int` addition, multiplication and exponentiation fail with
+    /// [`Error::ArithmeticOverflow`] when the result does not fit in 64 bits, instead of wrapping
+    /// around. Arithmetic is unchecked by default.
+    pub fn set_checked_arithmetic(&mut self, checked: bool) {
+        self.checked_arithmetic = checked;
+    }
+
+    /// Sets the number of significant digits used for `Double` values interpolated into strings,
+    /// such as the message of `Message($"{x}")`. Interpolated values are written in full by
+    /// default.
+    /// # Panics
+    /// Panics if `digits` is 0.
+    pub fn set_double_precision(&mut self, digits: usize) {
+        assert!(
+            digits > 0,
+            "at least one significant digit should be written"
+        );
+        self.double_precision = Some(digits);
+    }
+
+    /// Sets the maximum number of qubit measurements, counting both `M` and `MResetZ`, beyond
+    /// which evaluation fails with [`Error::MeasurementBudgetExceeded`]. Measurements are not
+    /// limited by default.
+    pub fn set_max_measurements(&mut self, limit: u64) {
+        self.max_measurements = Some(limit);
+    }
+
+    /// Starts recording an [`Event::Assign`] for every variable updated by a `set` statement.
+    /// Recording keeps a copy of each assigned value, so it is off by default.
+    pub fn record_events(&mut self) {
+        self.events.get_or_insert_with(Vec::new);
+    }
+
+    /// Returns the events recorded since the last call, in the order they happened.
+    pub fn take_events(&mut self) -> Vec<Event> {
+        self.events.as_mut().map(std::mem::take).unwrap_or_default()
+    }
+
+    /// Starts recording a [`LogEvent`] for every assignment, and for every gate, measurement,
+    /// qubit allocation and release, and message of the intrinsics that are called. This log is
+    /// kept apart from the events of [`State::record_events`].
+    pub fn record_intrinsic_events(&mut self) {
+        self.log.get_or_insert_with(Vec::new);
+    }
+
+    /// Returns the log recorded since the last call, in the order it happened.
+    pub fn take_log(&mut self) -> Vec<LogEvent> {
+        self.log.as_mut().map(std::mem::take).unwrap_or_default()
+    }
+
     fn push_frame(
         &mut self,
         exec_graph: Rc<[ExecGraphNode]>,
@@ -494,6 +827,14 @@ impl State {
         self.package = id.package;
     }
 
+    /// Notifies the backend when leaving the frame of an operation it was told about on entry.
+    fn exit_operation(&mut self, sim: &mut impl Backend) {
+        if self.entered_operations.last() == Some(&self.call_stack.len()) {
+            self.entered_operations.pop();
+            sim.exit_operation();
+        }
+    }
+
     fn leave_frame(&mut self) {
         if let Some(frame) = self.call_stack.pop_frame() {
             self.package = frame.caller;
@@ -501,6 +842,98 @@ impl State {
         self.val_stack.pop();
         self.idx = self.idx_stack.pop().unwrap_or_default();
         self.exec_graph_stack.pop();
+        let depth = self.call_stack.len();
+        self.loop_iterations.retain(|&(d, _, _)| d <= depth);
+    }
+
+    /// Reports the branch at `nodes` of the current execution graph to the backend as depending on
+    /// the measurement results compared to decide it.
+    fn enter_conditional(&mut self, sim: &mut impl Backend, nodes: ops::Range<u32>) {
+        let mut results = std::mem::take(&mut self.compared_results);
+        if nodes.is_empty() {
+            return;
+        }
+        results.sort_unstable();
+        results.dedup();
+        for &result in &results {
+            sim.enter_conditional(result);
+        }
+        self.conditionals
+            .push((self.exec_graph_stack.len(), nodes, results.len()));
+    }
+
+    /// Reports leaving the branches that evaluation has moved past, or returned from, to the
+    /// backend.
+    fn leave_conditionals(&mut self, sim: &mut impl Backend) {
+        let depth = self.exec_graph_stack.len();
+        while let Some((d, nodes, results)) = self.conditionals.last() {
+            if *d < depth || (*d == depth && nodes.contains(&self.idx)) {
+                break;
+            }
+            for _ in 0..*results {
+                sim.exit_conditional();
+            }
+            self.conditionals.pop();
+        }
+    }
+
+    /// Replaces the result ids among the operands of a comparison with the outcomes of their
+    /// measurements, if the backend knows them.
+    fn resolve_compared_results(&mut self, sim: &mut impl Backend) {
+        let lhs = self.val_stack.last_mut().and_then(|vals| vals.last_mut());
+        for val in [lhs, self.val_register.as_mut()].into_iter().flatten() {
+            resolve_results(val, sim, &mut self.compared_results);
+        }
+    }
+
+    /// Counts the jump at the current node back to the condition at `cond_idx` of a loop in the
+    /// current frame, failing once the loop has run more than the maximum number of iterations.
+    fn count_loop_iteration(
+        &mut self,
+        globals: &impl PackageStoreLookup,
+        cond_idx: u32,
+    ) -> Result<(), Error> {
+        let Some(limit) = self.max_loop_iterations else {
+            return Ok(());
+        };
+        let depth = self.call_stack.len();
+        // Loops nested in the body of this one start counting afresh on its next iteration.
+        self.loop_iterations
+            .retain(|&(d, idx, _)| d < depth || idx <= cond_idx);
+        let count = if let Some((_, _, count)) = self
+            .loop_iterations
+            .iter_mut()
+            .find(|(d, idx, _)| *d == depth && *idx == cond_idx)
+        {
+            *count += 1;
+            *count
+        } else {
+            self.loop_iterations.push((depth, cond_idx, 1));
+            1
+        };
+        if count > limit {
+            // The condition of the loop is evaluated right before the jump that exits the loop,
+            // which targets the node after the jump back to the condition.
+            let exec_graph = self
+                .exec_graph_stack
+                .last()
+                .expect("should have at least one stack frame");
+            let cond = exec_graph[cond_idx as usize..self.idx as usize]
+                .windows(2)
+                .find_map(|nodes| {
+                    if let [ExecGraphNode::Expr(expr), ExecGraphNode::JumpIfNot(exit)] = nodes {
+                        (*exit == self.idx + 1).then_some(*expr)
+                    } else {
+                        None
+                    }
+                });
+            let span = match cond {
+                Some(expr) => globals.get_expr((self.package, expr).into()).span,
+                None => self.current_span,
+            };
+            return Err(Error::LoopLimitExceeded(limit, self.to_global_span(span)));
+        }
+        Ok(())
     }
 
     fn push_scope(&mut self, env: &mut Env) {
@@ -565,6 +998,9 @@ impl State {
     ) -> Result<StepResult, (Error, Vec<Frame>)> {
         let current_frame = self.call_stack.len();
         while !self.exec_graph_stack.is_empty() {
+            if !self.conditionals.is_empty() {
+                self.leave_conditionals(sim);
+            }
             let exec_graph = self
                 .exec_graph_stack
                 .last()
@@ -583,6 +1019,7 @@ impl State {
                 }
                 Some(ExecGraphNode::Stmt(stmt)) => {
                     self.idx += 1;
+                    self.compared_results.clear();
                     self.current_span = globals.get_stmt((self.package, *stmt).into()).span;
 
                     match self.check_for_break(breakpoints, *stmt, step, current_frame) {
@@ -591,10 +1028,16 @@ impl State {
                     }
                 }
                 Some(ExecGraphNode::Jump(idx)) => {
-                    self.idx = *idx;
+                    let idx = *idx;
+                    if idx < self.idx {
+                        self.count_loop_iteration(globals, idx)
+                            .map_err(|e| (e, self.get_stack_frames()))?;
+                    }
+                    self.idx = idx;
                     continue;
                 }
                 Some(ExecGraphNode::JumpIf(idx)) => {
+                    self.compared_results.clear();
                     let cond = self.val_register == Some(Value::Bool(true));
                     if cond {
                         self.idx = *idx;
@@ -604,11 +1047,25 @@ impl State {
                     continue;
                 }
                 Some(ExecGraphNode::JumpIfNot(idx)) => {
+                    let idx = *idx;
                     let cond = self.val_register == Some(Value::Bool(true));
+                    if !self.compared_results.is_empty() {
+                        // Without an else branch, the node before the target of the jump is the
+                        // last one of the true branch rather than a jump past the false branch.
+                        let branch = if cond {
+                            self.idx + 1..idx
+                        } else {
+                            match exec_graph.get(idx as usize - 1) {
+                                Some(ExecGraphNode::Jump(end)) if *end > idx => idx..*end,
+                                _ => idx..idx,
+                            }
+                        };
+                        self.enter_conditional(sim, branch);
+                    }
                     if cond {
                         self.idx += 1;
                     } else {
-                        self.idx = *idx;
+                        self.idx = idx;
                     }
                     continue;
                 }
@@ -623,11 +1080,13 @@ impl State {
                     continue;
                 }
                 Some(ExecGraphNode::Ret) => {
+                    self.exit_operation(sim);
                     self.leave_frame();
                     env.leave_scope();
                     continue;
                 }
                 Some(ExecGraphNode::RetFrame) => {
+                    self.exit_operation(sim);
                     self.leave_frame();
                     env.leave_current_frame();
                     continue;
@@ -713,6 +1172,7 @@ impl State {
     ) -> Result<(), Error> {
         let expr = globals.get_expr((self.package, expr).into());
         self.current_span = expr.span;
+        let old_values = self.assigned_values(env, globals, &expr.kind);
         match &expr.kind {
             ExprKind::Array(arr) => self.eval_arr(arr.len()),
             ExprKind::ArrayLit(arr) => self.eval_arr_lit(arr, globals),
@@ -722,18 +1182,18 @@ impl State {
                 let rhs_span = globals.get_expr((self.package, *rhs).into()).span;
                 let (is_array, is_unique) =
                     is_updatable_in_place(env, globals.get_expr((self.package, *lhs).into()));
-                if is_array {
-                    if is_unique {
-                        self.eval_array_append_in_place(env, globals, *lhs)?;
-                        return Ok(());
+                if is_array && is_unique {
+                    self.eval_array_append_in_place(env, globals, *lhs)?;
+                } else {
+                    if is_array {
+                        let rhs_val = self.take_val_register();
+                        self.eval_expr(env, sim, globals, out, *lhs)?;
+                        self.push_val();
+                        self.set_val_register(rhs_val);
                     }
-                    let rhs_val = self.take_val_register();
-                    self.eval_expr(env, sim, globals, out, *lhs)?;
-                    self.push_val();
-                    self.set_val_register(rhs_val);
+                    self.eval_binop(*op, expr.span, rhs_span)?;
+                    self.eval_assign(env, globals, *lhs)?;
                 }
-                self.eval_binop(*op, rhs_span)?;
-                self.eval_assign(env, globals, *lhs)?;
             }
             ExprKind::AssignField(record, field, _) => {
                 self.eval_update_field(field.clone());
@@ -745,16 +1205,19 @@ impl State {
                     is_updatable_in_place(env, globals.get_expr((self.package, *lhs).into()));
                 if is_unique {
                     self.eval_update_index_in_place(env, globals, *lhs, mid_span)?;
-                    return Ok(());
+                } else {
+                    self.push_val();
+                    self.eval_expr(env, sim, globals, out, *lhs)?;
+                    self.eval_update_index(mid_span)?;
+                    self.eval_assign(env, globals, *lhs)?;
                 }
-                self.push_val();
-                self.eval_expr(env, sim, globals, out, *lhs)?;
-                self.eval_update_index(mid_span)?;
-                self.eval_assign(env, globals, *lhs)?;
             }
             ExprKind::BinOp(op, _, rhs) => {
                 let rhs_span = globals.get_expr((self.package, *rhs).into()).span;
-                self.eval_binop(*op, rhs_span)?;
+                if matches!(op, BinOp::Eq | BinOp::Neq) {
+                    self.resolve_compared_results(sim);
+                }
+                self.eval_binop(*op, expr.span, rhs_span)?;
             }
             ExprKind::Block(..) => panic!("block expr should be handled by control flow"),
             ExprKind::Call(callee_expr, args_expr) => {
@@ -767,10 +1230,11 @@ impl State {
                 self.set_val_register(closure);
             }
             ExprKind::Fail(..) => {
-                return Err(Error::UserFail(
-                    self.take_val_register().unwrap_string().to_string(),
-                    self.to_global_span(expr.span),
-                ));
+                let message = self.take_val_register().unwrap_string().to_string();
+                return Err(match self.assertion_call_span(globals) {
+                    Some(span) => Error::AssertionFailed(message, span),
+                    None => Error::UserFail(message, self.to_global_span(expr.span)),
+                });
             }
             ExprKind::Field(_, field) => self.eval_field(field.clone()),
             ExprKind::Hole => panic!("hole expr should be disallowed by passes"),
@@ -807,9 +1271,76 @@ impl State {
             }
         }
 
+        self.record_assignments(env, old_values, expr.span);
         Ok(())
     }
 
+    /// Returns the variables updated by an assignment expression along with their current
+    /// values, or nothing if events are not being recorded.
+    fn assigned_values(
+        &self,
+        env: &Env,
+        globals: &impl PackageStoreLookup,
+        kind: &ExprKind,
+    ) -> Vec<(LocalVarId, Value)> {
+        let mut values = Vec::new();
+        if let (
+            true,
+            ExprKind::Assign(lhs, _)
+            | ExprKind::AssignOp(_, lhs, _)
+            | ExprKind::AssignField(lhs, _, _)
+            | ExprKind::AssignIndex(lhs, _, _),
+        ) = (self.events.is_some() || self.log.is_some(), kind)
+        {
+            self.collect_assigned_values(env, globals, *lhs, &mut values);
+        }
+        values
+    }
+
+    fn collect_assigned_values(
+        &self,
+        env: &Env,
+        globals: &impl PackageStoreLookup,
+        lhs: ExprId,
+        values: &mut Vec<(LocalVarId, Value)>,
+    ) {
+        match &globals.get_expr((self.package, lhs).into()).kind {
+            &ExprKind::Var(Res::Local(id), _) => match env.get(id) {
+                // Variables generated by the compiler, such as loop indices, start with `@`.
+                Some(var) if !var.name.starts_with('@') => values.push((id, var.value.clone())),
+                _ => {}
+            },
+            ExprKind::Tuple(items) => {
+                for item in items {
+                    self.collect_assigned_values(env, globals, *item, values);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn record_assignments(&mut self, env: &Env, old_values: Vec<(LocalVarId, Value)>, span: Span) {
+        if old_values.is_empty() {
+            return;
+        }
+        let span = self.to_global_span(span);
+        for (id, old) in old_values {
+            let var = env.get(id).expect("assigned variable should be bound");
+            let event = Event::Assign {
+                name: var.name.clone(),
+                old,
+                new: var.value.clone(),
+                span,
+            };
+            if let Some(log) = &mut self.log {
+                log.push(LogEvent::Assign(event.clone()));
+            }
+            if let Some(events) = &mut self.events {
+                events.push(event);
+            }
+        }
+    }
+
     fn collect_string(&mut self, components: &[StringComponent]) {
         if let [StringComponent::Lit(str)] = components {
             self.set_val_register(Value::String(Rc::clone(str)));
@@ -820,7 +1351,11 @@ impl State {
         for component in components.iter().rev() {
             match component {
                 StringComponent::Expr(..) => {
-                    let expr_str = format!("{}", self.pop_val());
+                    let val = self.pop_val();
+                    let expr_str = match self.double_precision {
+                        Some(digits) => val.to_string_with_precision(digits),
+                        None => val.to_string(),
+                    };
                     string.insert_str(0, &expr_str);
                 }
                 StringComponent::Lit(lit) => {
@@ -898,7 +1433,10 @@ impl State {
         self.bind_value(env, globals, pat, val);
     }
 
-    fn eval_binop(&mut self, op: BinOp, span: Span) -> Result<(), Error> {
+    fn eval_binop(&mut self, op: BinOp, expr_span: Span, span: Span) -> Result<(), Error> {
+        if self.checked_arithmetic && self.int_binop_overflows(op) {
+            return Err(Error::ArithmeticOverflow(self.to_global_span(expr_span)));
+        }
         match op {
             BinOp::Add => self.eval_binop_simple(eval_binop_add),
             BinOp::AndB => self.eval_binop_simple(eval_binop_andb),
@@ -924,6 +1462,24 @@ impl State {
         Ok(())
     }
 
+    /// Returns true if the operands of the given binary operator are `Int` values whose sum,
+    /// product or power does not fit in 64 bits.
+    fn int_binop_overflows(&self, op: BinOp) -> bool {
+        let (Some(Value::Int(lhs)), Some(Value::Int(rhs))) = (
+            self.val_stack.last().and_then(|vals| vals.last()),
+            self.val_register.as_ref(),
+        ) else {
+            return false;
+        };
+        match op {
+            BinOp::Add => lhs.checked_add(*rhs).is_none(),
+            BinOp::Mul => lhs.checked_mul(*rhs).is_none(),
+            // Negative and very large exponents are reported by the operator itself.
+            BinOp::Exp => u32::try_from(*rhs).is_ok_and(|rhs| lhs.checked_pow(rhs).is_none()),
+            _ => false,
+        }
+    }
+
     fn eval_binop_simple(&mut self, binop_func: impl FnOnce(Value, Value) -> Value) {
         let rhs_val = self.take_val_register();
         let lhs_val = self.pop_val();
@@ -942,6 +1498,80 @@ impl State {
         Ok(())
     }
 
+    /// Counts a call to a measurement intrinsic against the measurement budget, if there is one.
+    fn count_measurement(&mut self, name: &str) -> Result<(), Error> {
+        if !matches!(
+            name,
+            "Measure" | "__quantum__qis__m__body" | "__quantum__qis__mresetz__body"
+        ) {
+            return Ok(());
+        }
+        self.measurements += 1;
+        match self.max_measurements {
+            Some(limit) if self.measurements > limit => {
+                Err(Error::MeasurementBudgetExceeded(limit, self.user_span()))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Returns the span of the innermost call made from the source package, so that a limit
+    /// reached within a library callable points at the user code that led to it. This is the
+    /// current span while the source package itself is being evaluated.
+    fn user_span(&self) -> PackageSpan {
+        if self.package == self.source_package {
+            return self.to_global_span(self.current_span);
+        }
+        self.call_stack
+            .frames()
+            .iter()
+            .rev()
+            .find(|frame| frame.caller == self.source_package)
+            .map_or_else(
+                || self.to_global_span(self.current_span),
+                |frame| PackageSpan {
+                    package: map_fir_package_to_hir(frame.caller),
+                    span: frame.span,
+                },
+            )
+    }
+
+    /// Calls an intrinsic, recording the gates, measurements, qubit allocations and messages it
+    /// performs when intrinsic events are being recorded.
+    fn call_intrinsic(
+        &mut self,
+        name: &str,
+        callee_span: PackageSpan,
+        arg: Value,
+        arg_span: PackageSpan,
+        sim: &mut impl Backend<ResultType = impl Into<val::Result>>,
+        out: &mut impl Receiver,
+    ) -> Result<Value, Error> {
+        let Some(events) = &mut self.log else {
+            return intrinsic::call(
+                name,
+                callee_span,
+                arg,
+                arg_span,
+                sim,
+                &mut *self.rng.borrow_mut(),
+                out,
+            );
+        };
+        let log = RefCell::new(std::mem::take(events));
+        let val = intrinsic::call(
+            name,
+            callee_span,
+            arg,
+            arg_span,
+            &mut backend::EventRecorder::new(sim, &log),
+            &mut *self.rng.borrow_mut(),
+            &mut backend::EventRecorder::new(out, &log),
+        );
+        *events = log.into_inner();
+        val
+    }
+
     fn eval_call(
         &mut self,
         env: &mut Env,
@@ -971,21 +1601,21 @@ impl State {
 
         let callee_span = self.to_global_span(callee.span);
 
+        if self.call_stack.len() >= self.max_call_depth {
+            return Err(Error::RecursionLimitExceeded(
+                self.max_call_depth,
+                self.to_global_span(self.current_span),
+            ));
+        }
+
         let spec = spec_from_functor_app(functor);
         match &callee.implementation {
             CallableImpl::Intrinsic => {
+                self.count_measurement(&callee.name.name)?;
                 self.push_frame(Vec::new().into(), callee_id, functor);
 
-                let name = &callee.name.name;
-                let val = intrinsic::call(
-                    name,
-                    callee_span,
-                    arg,
-                    arg_span,
-                    sim,
-                    &mut self.rng.borrow_mut(),
-                    out,
-                )?;
+                let val =
+                    self.call_intrinsic(&callee.name.name, callee_span, arg, arg_span, sim, out)?;
                 if val == Value::unit() && callee.output != Ty::UNIT {
                     return Err(Error::UnsupportedIntrinsicType(
                         callee.name.name.to_string(),
@@ -1007,6 +1637,11 @@ impl State {
                 self.push_frame(spec_decl.exec_graph.clone(), callee_id, functor);
                 self.push_scope(env);
 
+                if callee.kind == CallableKind::Operation {
+                    self.entered_operations.push(self.call_stack.len());
+                    sim.enter_operation(&callee.name.name);
+                }
+
                 self.bind_args_for_spec(
                     env,
                     globals,
@@ -1433,6 +2068,32 @@ impl State {
         }
     }
 
+    /// If the innermost frame is a call to the standard library `Fact` assertion,
+    /// returns the span of that call so a failure can be reported at the assertion site.
+    fn assertion_call_span(&self, globals: &impl PackageStoreLookup) -> Option<PackageSpan> {
+        let frame = self.call_stack.last()?;
+        let item = globals.get_item(frame.id);
+        let fir::ItemKind::Callable(decl) = &item.kind else {
+            return None;
+        };
+        let parent = globals.get_item(StoreItemId {
+            package: frame.id.package,
+            item: item.parent?,
+        });
+        match &parent.kind {
+            fir::ItemKind::Namespace(ns, _)
+                if decl.name.name.as_ref() == "Fact"
+                    && ns.name.as_ref() == "Microsoft.Quantum.Diagnostics" =>
+            {
+                Some(PackageSpan {
+                    package: map_fir_package_to_hir(frame.caller),
+                    span: frame.span,
+                })
+            }
+            _ => None,
+        }
+    }
+
     fn to_global_span(&self, span: Span) -> PackageSpan {
         PackageSpan {
             package: map_fir_package_to_hir(self.package),
@@ -1520,6 +2181,41 @@ fn lit_to_val(lit: &Lit) -> Value {
     }
 }
 
+/// Replaces the result ids in `val`, including those in arrays and tuples, with the outcomes of
+/// their measurements if the backend knows them, collecting the ids it replaced.
+fn resolve_results(val: &mut Value, sim: &mut impl Backend, ids: &mut Vec<usize>) {
+    match val {
+        Value::Result(val::Result::Id(id)) => {
+            if let Some(outcome) = sim.result_outcome(*id) {
+                ids.push(*id);
+                *val = Value::Result(val::Result::Val(outcome));
+            }
+        }
+        Value::Array(items) if items.iter().any(contains_result_id) => {
+            for item in Rc::make_mut(items) {
+                resolve_results(item, sim, ids);
+            }
+        }
+        Value::Tuple(items) if items.iter().any(contains_result_id) => {
+            let mut resolved = items.to_vec();
+            for item in &mut resolved {
+                resolve_results(item, sim, ids);
+            }
+            *items = resolved.into();
+        }
+        _ => {}
+    }
+}
+
+fn contains_result_id(val: &Value) -> bool {
+    match val {
+        Value::Result(val::Result::Id(_)) => true,
+        Value::Array(items) => items.iter().any(contains_result_id),
+        Value::Tuple(items) => items.iter().any(contains_result_id),
+        _ => false,
+    }
+}
+
 fn eval_binop_eq(lhs_val: Value, rhs_val: Value, rhs_span: PackageSpan) -> Result<Value, Error> {
     match (lhs_val, rhs_val) {
         (Value::Result(val::Result::Id(_)), _) | (_, Value::Result(val::Result::Id(_))) => {
@@ -16,6 +16,20 @@ pub trait Receiver {
     /// This will return an error if handling the output fails.
     fn state(&mut self, state: Vec<(BigUint, Complex64)>, qubit_count: usize) -> Result<(), Error>;
 
+    /// Receive the state of a subset of qubits, such as from `DumpRegister`.
+    /// The state is only reported when the qubits are separable from the rest of the system,
+    /// so it is given as amplitudes over `qubits`, in the order they were requested.
+    /// By default, this is handled the same as full state output.
+    /// # Errors
+    /// This will return an error if handling the output fails.
+    fn register_state(
+        &mut self,
+        qubits: &[usize],
+        state: Vec<(BigUint, Complex64)>,
+    ) -> Result<(), Error> {
+        self.state(state, qubits.len())
+    }
+
     /// Receive generic message output
     /// # Errors
     /// This will return an error if handling the output fails.
@@ -39,6 +39,40 @@ pub fn fmt_basis_state_label(id: &BigUint, qubit_count: usize) -> String {
     format!("{:0>qubit_count$}", id.to_str_radix(2))
 }
 
+/// Writes a state vector, such as one from [`crate::backend::SparseSim::to_dense_vector`],
+/// in the `.npy` format (version 1.0) as a one-dimensional array of little-endian
+/// 128-bit complex numbers, which `numpy.load` reads as `complex128`.
+///
+/// # Errors
+///
+/// Returns any error produced by the writer.
+pub fn write_npy(vector: &[Complex64], writer: &mut impl std::io::Write) -> std::io::Result<()> {
+    const MAGIC: &[u8] = b"\x93NUMPY\x01\x00";
+    // The preamble is the magic string, the version and the two-byte header length.
+    const PREAMBLE_LEN: usize = MAGIC.len() + 2;
+    const ALIGNMENT: usize = 64;
+
+    let mut header = format!(
+        "{{'descr': '<c16', 'fortran_order': False, 'shape': ({},), }}",
+        vector.len()
+    );
+    // The header is padded with spaces and ends with a newline so that the data is aligned.
+    let unpadded_len = PREAMBLE_LEN + header.len() + 1;
+    let padding = (ALIGNMENT - unpadded_len % ALIGNMENT) % ALIGNMENT;
+    header.push_str(&" ".repeat(padding));
+    header.push('\n');
+    let header_len = u16::try_from(header.len()).expect("npy header length should fit in u16");
+
+    writer.write_all(MAGIC)?;
+    writer.write_all(&header_len.to_le_bytes())?;
+    writer.write_all(header.as_bytes())?;
+    for amplitude in vector {
+        writer.write_all(&amplitude.re.to_le_bytes())?;
+        writer.write_all(&amplitude.im.to_le_bytes())?;
+    }
+    Ok(())
+}
+
 #[must_use]
 fn is_significant(x: f64) -> bool {
     x.abs() > 1e-9
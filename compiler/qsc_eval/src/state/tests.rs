@@ -9,7 +9,7 @@ use super::{
     write_latex_for_term, AlgebraicNumber, CartesianForm, ComplexNumber, DecimalNumber, PolarForm,
     RationalNumber, RealNumber, Term,
 };
-use crate::state::{is_fractional_part_significant, is_significant};
+use crate::state::{is_fractional_part_significant, is_significant, write_npy};
 use expect_test::{expect, Expect};
 use num_complex::Complex64;
 use std::{f64::consts::PI, time::Instant};
@@ -707,3 +707,30 @@ fn check_get_latex_perf() {
         Instant::now().duration_since(start)
     );
 }
+
+#[test]
+fn npy_header_for_state_vector() {
+    let vector = [
+        Complex64::new(1.0, 0.0),
+        Complex64::new(0.0, 0.0),
+        Complex64::new(0.0, -0.5),
+        Complex64::new(0.0, 0.0),
+    ];
+    let mut bytes = Vec::new();
+    write_npy(&vector, &mut bytes).expect("writing to a vector should succeed");
+
+    assert_eq!(&bytes[..8], b"\x93NUMPY\x01\x00");
+    let header_len = usize::from(u16::from_le_bytes([bytes[8], bytes[9]]));
+    let data_start = 10 + header_len;
+    assert_eq!(data_start % 64, 0);
+    let header = std::str::from_utf8(&bytes[10..data_start]).expect("header should be ASCII");
+    expect![[r#"
+        "{'descr': '<c16', 'fortran_order': False, 'shape': (4,), }                                                           \n"
+    "#]]
+    .assert_debug_eq(&header);
+
+    assert_eq!(bytes.len(), data_start + vector.len() * 16);
+    assert_eq!(&bytes[data_start..data_start + 8], &1.0_f64.to_le_bytes());
+    let third_im = data_start + 2 * 16 + 8;
+    assert_eq!(&bytes[third_im..third_im + 8], &(-0.5_f64).to_le_bytes());
+}
@@ -4178,3 +4178,43 @@ fn partial_eval_stmt_function_calls_from_library() {
         &expect!["3"],
     );
 }
+
+#[test]
+fn fmt_double_rounds_to_significant_digits() {
+    let formatted = [
+        (3.141_592_65, 3),
+        (314.159_265, 3),
+        (12_345.6, 3),
+        (2.0, 3),
+        (-0.0001, 3),
+        (-0.0, 2),
+        (9.99, 2),
+        (0.0999, 2),
+        (99_999.0, 3),
+        (1.2345e30, 3),
+        (-1.2345e-30, 3),
+    ]
+    .map(|(v, digits)| val::fmt_double(v, digits));
+    expect![[r#"
+        [
+            "3.14",
+            "314",
+            "12300",
+            "2.00",
+            "-0.000100",
+            "0.0",
+            "10",
+            "0.10",
+            "100000",
+            "1.23e30",
+            "-1.23e-30",
+        ]
+    "#]]
+    .assert_debug_eq(&formatted);
+}
+
+#[test]
+#[should_panic(expected = "at least one significant digit should be written")]
+fn fmt_double_rejects_zero_digits() {
+    let _ = val::fmt_double(1.5, 0);
+}
@@ -160,6 +160,80 @@ impl Display for Value {
     }
 }
 
+impl Value {
+    /// Formats the value like its [`Display`] implementation, except that `Double` values,
+    /// including those within arrays and tuples, are written with `digits` significant digits.
+    #[must_use]
+    pub fn to_string_with_precision(&self, digits: usize) -> String {
+        let join = |values: &[Value]| {
+            values
+                .iter()
+                .map(|v| v.to_string_with_precision(digits))
+                .collect::<Vec<_>>()
+                .join(", ")
+        };
+        match self {
+            Value::Double(v) => fmt_double(*v, digits),
+            Value::Array(arr) => format!("[{}]", join(arr)),
+            Value::Tuple(tup) if tup.len() == 1 => format!("({},)", join(tup)),
+            Value::Tuple(tup) => format!("({})", join(tup)),
+            _ => self.to_string(),
+        }
+    }
+}
+
+/// Formats a `Double` with the given number of significant digits. Values with more digits before
+/// the decimal point are rounded, so that the digits beyond the significant ones are zero. Values
+/// of magnitude at least 1e16 or below 1e-5 are written in scientific notation, such as `1.23e30`.
+/// # Panics
+/// Panics if `digits` is 0.
+#[must_use]
+pub fn fmt_double(v: f64, digits: usize) -> String {
+    assert!(
+        digits > 0,
+        "at least one significant digit should be written"
+    );
+    if !v.is_finite() {
+        return Value::Double(v).to_string();
+    }
+    // Rounding to scientific notation gives the exponent of the rounded value, which is one more
+    // than that of `v` when rounding carries, as from 9.99 to 1.0e1.
+    let scientific = format!("{v:.0$e}", digits - 1);
+    let (mantissa, exponent) = scientific
+        .split_once('e')
+        .expect("scientific notation should have an exponent");
+    let exponent: i32 = exponent.parse().expect("exponent should be an integer");
+    let (sign, mantissa) = match mantissa.strip_prefix('-') {
+        // Format -0 as 0
+        Some(abs) if abs.chars().any(|c| c != '0' && c != '.') => ("-", abs),
+        Some(abs) => ("", abs),
+        None => ("", mantissa),
+    };
+    if !(-5..16).contains(&exponent) {
+        return format!("{sign}{mantissa}e{exponent}");
+    }
+    let significand = mantissa.replace('.', "");
+    // The number of digits before the decimal point, which is zero or negative for values below 1.
+    let whole_digits = exponent + 1;
+    let text = match usize::try_from(whole_digits) {
+        Ok(whole_digits) if whole_digits >= significand.len() => {
+            format!(
+                "{significand}{}",
+                "0".repeat(whole_digits - significand.len())
+            )
+        }
+        Ok(whole_digits) if whole_digits > 0 => {
+            let (whole, fraction) = significand.split_at(whole_digits);
+            format!("{whole}.{fraction}")
+        }
+        _ => {
+            let zeros = usize::try_from(-whole_digits).expect("value should be below 1");
+            format!("0.{}{significand}", "0".repeat(zeros))
+        }
+    };
+    format!("{sign}{text}")
+}
+
 #[derive(Clone, Copy, Debug, PartialEq)]
 pub enum VarTy {
     Boolean,
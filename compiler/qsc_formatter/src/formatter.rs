@@ -1,7 +1,7 @@
 // Copyright (c) Microsoft Corporation.
 // Licensed under the MIT License.
 
-use qsc_data_structures::span::Span;
+use qsc_data_structures::{language_features::LanguageFeatures, span::Span};
 use qsc_frontend::{
     keyword::Keyword,
     lex::{
@@ -9,6 +9,8 @@ use qsc_frontend::{
         cooked::{ClosedBinOp, StringToken, TokenKind},
         Delim, InterpolatedEnding, InterpolatedStart,
     },
+    namespaces_with_trivia,
+    trivia::CommentPosition,
 };
 
 #[cfg(test)]
@@ -19,7 +21,13 @@ mod tests;
 /// Applies formatting rules to the give code str and returns
 /// the formatted string.
 pub fn format_str(code: &str) -> String {
-    let mut edits = calculate_format_edits(code);
+    format_str_with_config(code, &FormatterConfig::default())
+}
+
+/// Applies formatting rules to the give code str using the given
+/// configuration and returns the formatted string.
+pub fn format_str_with_config(code: &str, config: &FormatterConfig) -> String {
+    let mut edits = calculate_format_edits_with_config(code, config);
     edits.sort_by_key(|edit| edit.span.hi); // sort edits by their span's hi value from lowest to highest
     edits.reverse(); // sort from highest to lowest so that that as edits are applied they don't invalidate later applications of edits
     let mut new_code = String::from(code);
@@ -35,15 +43,35 @@ pub fn format_str(code: &str) -> String {
 /// Applies formatting rules to the given code str, generating edits where
 /// the source code needs to be changed to comply with the format rules.
 pub fn calculate_format_edits(code: &str) -> Vec<TextEdit> {
+    calculate_format_edits_with_config(code, &FormatterConfig::default())
+}
+
+/// Applies formatting rules to the given code str using the given configuration,
+/// generating edits where the source code needs to be changed to comply with the format rules.
+pub fn calculate_format_edits_with_config(code: &str, config: &FormatterConfig) -> Vec<TextEdit> {
     let tokens = concrete::ConcreteTokenIterator::new(code);
     let mut edits = vec![];
 
+    let all_tokens = concrete::ConcreteTokenIterator::new(code).collect::<Vec<_>>();
     let mut formatter = Formatter {
         code,
+        max_blank_lines: config.max_blank_lines,
+        max_width: config.max_width,
         indent_level: 0,
         delim_newlines_stack: vec![],
         type_param_state: TypeParameterListState::NoState,
         spec_decl_state: SpecDeclState::NoState,
+        wrappable_lists: wrappable_lists(&all_tokens),
+        tokens: all_tokens,
+        wrapped_parens: vec![],
+        bin_op_chains: bin_op_chains(code),
+        wrapped_bin_ops: vec![],
+        line_start: 0,
+        aligned_assignments: if config.align_assignments {
+            aligned_assignments(code)
+        } else {
+            vec![]
+        },
     };
 
     // The sliding window used is over three adjacent tokens
@@ -88,11 +116,82 @@ pub fn calculate_format_edits(code: &str) -> Vec<TextEdit> {
         edits.append(&mut edits_for_triple);
     }
 
+    // The window only applies rules to a pair of adjacent tokens once a third token
+    // arrives, so the final pair has to be handled after the tokens are exhausted.
+    if let (Some(two), Some(three)) = (&two, &three) {
+        if !matches!(two.kind, ConcreteTokenKind::WhiteSpace)
+            && !matches!(three.kind, ConcreteTokenKind::WhiteSpace)
+        {
+            edits.append(&mut formatter.apply_rules(two, "", three));
+        }
+    }
+
+    // Remove blank lines and spaces at the end of a file, keeping the final newline
+    if let (Some(_), Some(three)) = (&two, &three) {
+        if matches!(three.kind, ConcreteTokenKind::WhiteSpace) {
+            let whitespace = get_token_contents(code, three);
+            let new_whitespace = if whitespace.contains("\r\n") {
+                "\r\n"
+            } else if whitespace.contains('\n') {
+                "\n"
+            } else {
+                ""
+            };
+            if whitespace != new_whitespace {
+                edits.push(TextEdit::new(new_whitespace, three.span.lo, three.span.hi));
+            }
+        }
+    }
+
+    // Comments are only trimmed when they are the left token of a pair,
+    // so a comment ending the file needs to be trimmed separately.
+    let last = match (&two, &three) {
+        (Some(two), Some(three)) if matches!(three.kind, ConcreteTokenKind::WhiteSpace) => {
+            Some(two)
+        }
+        (_, three) => three.as_ref(),
+    };
+    if let Some(last) = last {
+        if matches!(
+            last.kind,
+            ConcreteTokenKind::Comment | ConcreteTokenKind::Syntax(TokenKind::DocComment)
+        ) {
+            effect_trim_comment(last, &mut edits, code);
+        }
+    }
+
     edits
 }
 
 // Public types
 
+/// Options that control the output of the formatter.
+#[derive(Clone, Debug)]
+pub struct FormatterConfig {
+    /// The maximum number of consecutive blank lines to keep. Longer runs of
+    /// blank lines are collapsed down to this many.
+    pub max_blank_lines: usize,
+    /// The width that lines are kept within where possible. Argument lists, tuples and struct
+    /// constructors that would make their line longer are wrapped with each item on its own line,
+    /// and so are chains of binary operators, with a line break before each operator.
+    pub max_width: usize,
+    /// Whether to align the `=` of consecutive `let` and `mutable` bindings in a block. A run of
+    /// bindings is broken by a blank line, a comment on its own line or any other statement, but not
+    /// by a comment that follows a binding on the same line. Only bindings of a single name take
+    /// part, so tuple bindings and bindings with a type annotation are left as they are.
+    pub align_assignments: bool,
+}
+
+impl Default for FormatterConfig {
+    fn default() -> Self {
+        Self {
+            max_blank_lines: 1,
+            max_width: 100,
+            align_assignments: false,
+        }
+    }
+}
+
 #[derive(Debug)]
 pub struct TextEdit {
     pub new_text: String,
@@ -196,12 +295,53 @@ impl Delimiter {
     }
 }
 
+/// A parenthesized list, or the fields of a struct constructor, that can be wrapped.
+struct WrappableList {
+    /// The offset of the opening delimiter.
+    open: u32,
+    /// The offset of the matching closing delimiter.
+    close: u32,
+    /// The number of items in the list. An empty item after a trailing comma is not counted.
+    items: usize,
+    /// Whether there is a comment anywhere inside the list.
+    has_comment: bool,
+}
+
+/// A chain of binary operators at the same nesting level of an expression, e.g. `a + b * c + d`.
+struct BinOpChain {
+    /// The offset of the start of the first operand.
+    lo: u32,
+    /// The offset of the end of the last operand.
+    hi: u32,
+    /// The offsets of the operators with the lowest precedence in the chain, before which a
+    /// line break is inserted when the chain is wrapped.
+    break_points: Vec<u32>,
+}
+
 struct Formatter<'a> {
     code: &'a str,
+    max_blank_lines: usize,
+    max_width: usize,
     indent_level: usize,
     delim_newlines_stack: Vec<NewlineContext>,
     type_param_state: TypeParameterListState,
     spec_decl_state: SpecDeclState,
+    /// All the tokens of the code, including whitespace and comments.
+    tokens: Vec<ConcreteToken>,
+    /// Each parenthesized list and struct constructor, sorted by the offset of its opening
+    /// delimiter.
+    wrappable_lists: Vec<WrappableList>,
+    /// The offsets of the closing delimiters of lists that the formatter is wrapping.
+    wrapped_parens: Vec<u32>,
+    /// The chains of binary operators in the code, sorted by their first break point.
+    bin_op_chains: Vec<BinOpChain>,
+    /// The offsets of the binary operators that the formatter is wrapping onto a new line.
+    wrapped_bin_ops: Vec<u32>,
+    /// The offset of the first token on the current line of the formatted output.
+    line_start: u32,
+    /// The offsets of the `=` of aligned bindings, each with the number of spaces to put before
+    /// it, sorted by offset.
+    aligned_assignments: Vec<(u32, usize)>,
 }
 
 impl<'a> Formatter<'a> {
@@ -227,10 +367,19 @@ impl<'a> Formatter<'a> {
         let (left_delim_state, right_delim_state) =
             self.update_type_param_state(&left.kind, &right.kind);
 
+        let is_wrapped_open = !are_newlines_in_spaces
+            && !matches!(right_delim_state, Delimiter::Close)
+            && self.should_wrap(left);
+        let is_wrapped_bin_op = self.should_wrap_bin_op(right);
+        let assignment_padding = self
+            .aligned_assignments
+            .binary_search_by_key(&right.span.lo, |&(eq, _)| eq)
+            .map_or(1, |i| self.aligned_assignments[i].1);
+
         let newline_context = self.update_indent_level(
             left_delim_state,
             right_delim_state,
-            are_newlines_in_spaces,
+            are_newlines_in_spaces || is_wrapped_open,
             does_right_required_newline,
             matches!(right.kind, Comment),
         );
@@ -239,10 +388,24 @@ impl<'a> Formatter<'a> {
             (Comment | Syntax(DocComment), _) => {
                 // remove whitespace at the ends of comments
                 effect_trim_comment(left, &mut edits, self.code);
-                effect_correct_indentation(left, whitespace, right, &mut edits, self.indent_level);
+                effect_correct_indentation(
+                    left,
+                    whitespace,
+                    right,
+                    &mut edits,
+                    self.indent_level,
+                    self.max_blank_lines,
+                );
             }
             (_, Comment) if matches!(left_delim_state, Delimiter::Open) => {
-                effect_correct_indentation(left, whitespace, right, &mut edits, self.indent_level);
+                effect_correct_indentation(
+                    left,
+                    whitespace,
+                    right,
+                    &mut edits,
+                    self.indent_level,
+                    self.max_blank_lines,
+                );
             }
             (_, Comment) => {
                 if are_newlines_in_spaces {
@@ -252,6 +415,7 @@ impl<'a> Formatter<'a> {
                         right,
                         &mut edits,
                         self.indent_level,
+                        self.max_blank_lines,
                     );
                 }
                 // else do nothing, preserving the user's spaces before the comment
@@ -263,6 +427,19 @@ impl<'a> Formatter<'a> {
                     // to be able to differentiate between the unary `-` and the binary `-`
                     // which would have different spacing rules.
                 }
+                (_, Eq) if assignment_padding > 1 => {
+                    effect_spaces(left, whitespace, right, assignment_padding, &mut edits);
+                }
+                (_, _) if is_wrapped_bin_op => {
+                    effect_correct_indentation(
+                        left,
+                        whitespace,
+                        right,
+                        &mut edits,
+                        self.indent_level + 1,
+                        self.max_blank_lines,
+                    );
+                }
                 (Semi, _) if matches!(newline_context, NewlineContext::Spaces) => {
                     effect_single_space(left, whitespace, right, &mut edits);
                 }
@@ -273,6 +450,7 @@ impl<'a> Formatter<'a> {
                         right,
                         &mut edits,
                         self.indent_level,
+                        self.max_blank_lines,
                     );
                 }
                 (_, Semi) => {
@@ -301,6 +479,7 @@ impl<'a> Formatter<'a> {
                         right,
                         &mut edits,
                         self.indent_level,
+                        self.max_blank_lines,
                     );
                 }
                 (Comma, _) if matches!(newline_context, NewlineContext::Newlines) => {
@@ -310,6 +489,7 @@ impl<'a> Formatter<'a> {
                         right,
                         &mut edits,
                         self.indent_level,
+                        self.max_blank_lines,
                     );
                 }
                 (Comma, _) => {
@@ -325,7 +505,11 @@ impl<'a> Formatter<'a> {
                         right,
                         &mut edits,
                         self.indent_level,
+                        self.max_blank_lines,
                     );
+                    if self.wrapped_parens.last() == Some(&right.span.lo) {
+                        effect_trailing_comma(left, right, &mut edits);
+                    }
                 }
                 (Open(Delim::Bracket | Delim::Paren), _)
                 | (_, Close(Delim::Bracket | Delim::Paren)) => {
@@ -359,6 +543,7 @@ impl<'a> Formatter<'a> {
                         right,
                         &mut edits,
                         self.indent_level,
+                        self.max_blank_lines,
                     );
                 }
                 (_, Keyword(Keyword::Until))
@@ -385,6 +570,7 @@ impl<'a> Formatter<'a> {
                         right,
                         &mut edits,
                         self.indent_level,
+                        self.max_blank_lines,
                     );
                 }
                 (String(StringToken::Interpolated(_, InterpolatedEnding::LBrace)), _)
@@ -457,6 +643,7 @@ impl<'a> Formatter<'a> {
                         right,
                         &mut edits,
                         self.indent_level + 1,
+                        self.max_blank_lines,
                     );
                 }
                 (_, _) if is_bin_op(cooked_right) => {
@@ -466,9 +653,180 @@ impl<'a> Formatter<'a> {
             },
             _ => {}
         }
+
+        if self.wrapped_parens.last() == Some(&right.span.lo) {
+            self.wrapped_parens.pop();
+        }
+        if are_newlines_in_spaces || edits.iter().any(|edit| edit.new_text.contains('\n')) {
+            self.line_start = right.span.lo;
+        }
         edits
     }
 
+    /// Returns true if the given token opens a parenthesized list or struct constructor that
+    /// does not fit on the current line when formatted on a single line, and so should be wrapped.
+    /// Only the outermost lists on a line are wrapped, since their items then start new lines,
+    /// and the list is only wrapped if it is the last one on the line or is itself too long.
+    /// Lists with a single item or containing comments are left as they are.
+    fn should_wrap(&mut self, open: &ConcreteToken) -> bool {
+        if !matches!(
+            open.kind,
+            ConcreteTokenKind::Syntax(TokenKind::Open(Delim::Paren | Delim::Brace))
+        ) || matches!(
+            self.delim_newlines_stack.last(),
+            Some(NewlineContext::Spaces)
+        ) {
+            return false;
+        }
+        let Ok(index) = self
+            .wrappable_lists
+            .binary_search_by_key(&open.span.lo, |list| list.open)
+        else {
+            return false;
+        };
+        let list = &self.wrappable_lists[index];
+        // A trailing comma would turn a single item into a tuple.
+        if list.items < 2 || list.has_comment {
+            return false;
+        }
+
+        let close = list.close;
+        let close_hi = close + 1;
+        let line_start = self.line_start_before(open.span.lo);
+        let rest_end = self.rest_of_line_end(close_hi);
+        let width =
+            make_indent_string(self.indent_level).len() + self.flat_width(line_start, close_hi);
+        let rest_has_paren = self.tokens[self.token_index(close_hi)..self.token_index(rest_end)]
+            .iter()
+            .any(|token| token.kind == ConcreteTokenKind::Syntax(TokenKind::Open(Delim::Paren)));
+        if width > self.max_width
+            || (width + self.flat_width(close_hi, rest_end) > self.max_width && !rest_has_paren)
+        {
+            self.wrapped_parens.push(close);
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Returns true if the given token is a binary operator that should start a new line
+    /// because the chain of operators it belongs to does not fit on the current line.
+    /// Whether a chain is wrapped is decided at its first break point, and chains in lists
+    /// formatted on a single line are never wrapped.
+    fn should_wrap_bin_op(&mut self, op: &ConcreteToken) -> bool {
+        if let Ok(index) = self
+            .bin_op_chains
+            .binary_search_by_key(&op.span.lo, |chain| chain.break_points[0])
+        {
+            let chain = &self.bin_op_chains[index];
+            let (lo, hi) = (chain.lo, chain.hi);
+            let line_start = self.line_start_before(lo);
+            let width = make_indent_string(self.indent_level).len()
+                + self.flat_width(line_start, hi)
+                + self.flat_width(hi, self.rest_of_line_end(hi));
+            if width > self.max_width
+                && !matches!(
+                    self.delim_newlines_stack.last(),
+                    Some(NewlineContext::Spaces)
+                )
+            {
+                let break_points = chain.break_points.clone();
+                self.wrapped_bin_ops.extend(break_points);
+            }
+        }
+        self.wrapped_bin_ops.contains(&op.span.lo)
+    }
+
+    /// The index of the first token starting at or after the given offset.
+    fn token_index(&self, offset: u32) -> usize {
+        self.tokens.partition_point(|token| token.span.lo < offset)
+    }
+
+    /// The offset of the start of the formatted line containing the given offset.
+    fn line_start_before(&self, offset: u32) -> u32 {
+        self.tokens[..self.token_index(offset)]
+            .iter()
+            .rev()
+            .find(|token| {
+                token.kind == ConcreteTokenKind::WhiteSpace
+                    && get_token_contents(self.code, token).contains('\n')
+            })
+            .map_or(0, |token| token.span.hi)
+            .max(self.line_start)
+    }
+
+    /// The offset of the end of the line starting at the given offset. Inside a list that is
+    /// already on multiple lines, the rest of the line ends with the next item.
+    fn rest_of_line_end(&self, offset: u32) -> u32 {
+        let in_multiline_list = matches!(
+            self.delim_newlines_stack.last(),
+            Some(NewlineContext::Newlines)
+        );
+        let mut depth = 0usize;
+        for token in &self.tokens[self.token_index(offset)..] {
+            match token.kind {
+                ConcreteTokenKind::WhiteSpace
+                    if get_token_contents(self.code, token).contains('\n') =>
+                {
+                    return token.span.lo;
+                }
+                ConcreteTokenKind::Syntax(kind) if in_multiline_list => {
+                    if opens_group(kind) {
+                        depth += 1;
+                    } else if closes_group(kind) {
+                        if depth == 0 {
+                            return token.span.lo;
+                        }
+                        depth -= 1;
+                    } else if kind == TokenKind::Comma && depth == 0 {
+                        return token.span.lo;
+                    }
+                }
+                _ => {}
+            }
+        }
+        self.code
+            .len()
+            .try_into()
+            .expect("code length should fit into u32")
+    }
+
+    /// The width of the code between the given offsets once formatted onto a single line.
+    /// Runs of whitespace become a single space, except just inside brackets and parentheses,
+    /// and every comma is followed by a space.
+    fn flat_width(&self, lo: u32, hi: u32) -> usize {
+        let tokens = &self.tokens[self.token_index(lo)..self.token_index(hi)];
+        let mut width = 0;
+        let mut previous: Option<&ConcreteTokenKind> = None;
+        let mut pending_space = false;
+        for token in tokens {
+            if token.kind == ConcreteTokenKind::WhiteSpace {
+                pending_space = previous.is_some();
+                continue;
+            }
+            if let Some(previous) = previous {
+                let after_open = matches!(
+                    previous,
+                    ConcreteTokenKind::Syntax(TokenKind::Open(Delim::Paren | Delim::Bracket))
+                );
+                let before_close = matches!(
+                    token.kind,
+                    ConcreteTokenKind::Syntax(
+                        TokenKind::Close(Delim::Paren | Delim::Bracket) | TokenKind::Comma
+                    )
+                );
+                let after_comma = *previous == ConcreteTokenKind::Syntax(TokenKind::Comma);
+                if !after_open && !before_close && (pending_space || after_comma) {
+                    width += 1;
+                }
+            }
+            width += get_token_contents(self.code, token).chars().count();
+            previous = Some(&token.kind);
+            pending_space = false;
+        }
+        width
+    }
+
     fn update_spec_decl_state(&mut self, left_kind: &ConcreteTokenKind) {
         use qsc_frontend::keyword::Keyword;
         use ConcreteTokenKind::*;
@@ -621,6 +979,375 @@ fn get_token_contents<'a>(code: &'a str, token: &ConcreteToken) -> &'a str {
     &code[token.span.lo as usize..token.span.hi as usize]
 }
 
+/// Finds each parenthesized list, and the fields of each struct constructor, in the code,
+/// sorted by the offset of the opening delimiter.
+fn wrappable_lists(tokens: &[ConcreteToken]) -> Vec<WrappableList> {
+    /// A list being built, with whether the tokens since its last comma include an item.
+    struct Partial {
+        list: WrappableList,
+        in_item: bool,
+    }
+
+    let mut lists = vec![];
+    // The open groups, innermost last. Groups that are not wrappable lists, like brackets and
+    // blocks, are kept so that their commas are not counted as separating items of a list.
+    let mut stack: Vec<Option<Partial>> = vec![];
+    // Whether the tokens since the last `new` keyword are the path of a struct constructor.
+    let mut in_struct_path = false;
+    for token in tokens {
+        match token.kind {
+            ConcreteTokenKind::Comment => {
+                for partial in stack.iter_mut().flatten() {
+                    partial.list.has_comment = true;
+                }
+            }
+            ConcreteTokenKind::Syntax(TokenKind::Comma) => {
+                if let Some(Some(partial)) = stack.last_mut() {
+                    partial.list.items += usize::from(partial.in_item);
+                    partial.in_item = false;
+                }
+            }
+            ConcreteTokenKind::Syntax(kind) if closes_group(kind) => {
+                if let Some(Some(mut partial)) = stack.pop() {
+                    partial.list.items += usize::from(partial.in_item);
+                    partial.list.close = token.span.lo;
+                    lists.push(partial.list);
+                }
+            }
+            _ => {}
+        }
+        if let ConcreteTokenKind::Syntax(kind) = token.kind {
+            if let Some(Some(partial)) = stack.last_mut() {
+                partial.in_item |= kind != TokenKind::Comma && !closes_group(kind);
+            }
+            if opens_group(kind) {
+                let is_list = kind == TokenKind::Open(Delim::Paren)
+                    || (kind == TokenKind::Open(Delim::Brace) && in_struct_path);
+                stack.push(is_list.then_some(Partial {
+                    list: WrappableList {
+                        open: token.span.lo,
+                        close: token.span.lo,
+                        items: 0,
+                        has_comment: false,
+                    },
+                    in_item: false,
+                }));
+            }
+        }
+        in_struct_path = match token.kind {
+            ConcreteTokenKind::Syntax(TokenKind::Keyword(Keyword::New)) => true,
+            ConcreteTokenKind::Syntax(TokenKind::Ident | TokenKind::Dot)
+            | ConcreteTokenKind::WhiteSpace
+            | ConcreteTokenKind::Comment => in_struct_path,
+            _ => false,
+        };
+    }
+    lists.sort_unstable_by_key(|list| list.open);
+    lists
+}
+
+/// Returns true if the token opens a nested group of tokens: a delimiter, or the end of the
+/// literal part of an interpolated string before an expression.
+fn opens_group(cooked: TokenKind) -> bool {
+    matches!(
+        cooked,
+        TokenKind::Open(_)
+            | TokenKind::String(StringToken::Interpolated(_, InterpolatedEnding::LBrace))
+    )
+}
+
+/// Returns true if the token closes a nested group of tokens: a delimiter, or the start of the
+/// literal part of an interpolated string after an expression.
+fn closes_group(cooked: TokenKind) -> bool {
+    matches!(
+        cooked,
+        TokenKind::Close(_)
+            | TokenKind::String(StringToken::Interpolated(InterpolatedStart::RBrace, _))
+    )
+}
+
+/// Finds the `=` of each `let` or `mutable` binding of a single name that is followed on the next
+/// line by another such binding in the same block, or follows one, and pairs the offset of the `=`
+/// with the number of spaces needed before it to align it with the rest of its run of bindings.
+/// Bindings that already line up with a single space are left out. A binding nested in the value
+/// of another one ends the run of the outer binding.
+fn aligned_assignments(code: &str) -> Vec<(u32, usize)> {
+    fn flush(run: &mut Vec<(u32, usize)>, aligned: &mut Vec<(u32, usize)>) {
+        if run.len() > 1 {
+            let max_width = run
+                .iter()
+                .map(|&(_, width)| width)
+                .max()
+                .unwrap_or_default();
+            aligned.extend(
+                run.iter()
+                    .filter(|&&(_, width)| width < max_width)
+                    .map(|&(eq, width)| (eq, max_width - width + 1)),
+            );
+        }
+        run.clear();
+    }
+
+    let tokens = concrete::ConcreteTokenIterator::new(code).collect::<Vec<_>>();
+    let is_syntax = |i: usize, kind: TokenKind| matches!(tokens.get(i), Some(token) if token.kind == ConcreteTokenKind::Syntax(kind));
+    let is_whitespace = |i: usize| matches!(tokens.get(i), Some(token) if token.kind == ConcreteTokenKind::WhiteSpace);
+
+    // Comments that trail a statement on the same line, as the offsets of the comment and of the
+    // end of the statement. The source name only matters for code without a namespace.
+    let (_, comments, _) =
+        namespaces_with_trivia(code, Some("formatted.qs"), LanguageFeatures::default());
+    let trailing_comments = comments
+        .iter()
+        .filter(|comment| comment.position == CommentPosition::Trailing)
+        .map(|comment| (comment.span.lo, comment.node.hi))
+        .collect::<Vec<_>>();
+    // The index of the token after the comment trailing the statement that ends with the token at
+    // `i`, if there is one, and otherwise the index of the token after `i`.
+    let skip_trailing_comment = |i: usize| {
+        let comment = if is_whitespace(i + 1) { i + 2 } else { i + 1 };
+        match tokens.get(comment) {
+            Some(token)
+                if token.kind == ConcreteTokenKind::Comment
+                    && trailing_comments.contains(&(token.span.lo, tokens[i].span.hi)) =>
+            {
+                comment + 1
+            }
+            _ => i + 1,
+        }
+    };
+
+    let mut aligned = vec![];
+    // The `=` offset and the width of the text before it, for each binding in the current run.
+    let mut run = vec![];
+    // The index of the token where the next binding has to start to continue the run.
+    let mut next_in_run = None;
+    let mut i = 0;
+    while i < tokens.len() {
+        let is_binding = (is_syntax(i, TokenKind::Keyword(Keyword::Let))
+            || is_syntax(i, TokenKind::Keyword(Keyword::Mutable)))
+            && is_whitespace(i + 1)
+            && is_syntax(i + 2, TokenKind::Ident);
+        let eq = if is_whitespace(i + 3) { i + 4 } else { i + 3 };
+        if !is_binding || !is_syntax(eq, TokenKind::Eq) {
+            i += 1;
+            continue;
+        }
+
+        if next_in_run != Some(i) {
+            flush(&mut run, &mut aligned);
+        }
+        let keyword = get_token_contents(code, &tokens[i]);
+        let name = get_token_contents(code, &tokens[i + 2]);
+        run.push((tokens[eq].span.lo, keyword.len() + 1 + name.chars().count()));
+
+        // The run continues if the binding's `;` is followed by a single line break and the next
+        // binding, at the same nesting level. A comment trailing the binding does not end the run.
+        next_in_run = None;
+        let mut depth = 0usize;
+        for (j, token) in tokens.iter().enumerate().skip(eq + 1) {
+            match token.kind {
+                ConcreteTokenKind::Syntax(TokenKind::Open(_)) => depth += 1,
+                ConcreteTokenKind::Syntax(TokenKind::Close(_)) if depth == 0 => break,
+                ConcreteTokenKind::Syntax(TokenKind::Close(_)) => depth -= 1,
+                ConcreteTokenKind::Syntax(TokenKind::Semi) if depth == 0 => {
+                    let k = skip_trailing_comment(j);
+                    if is_whitespace(k)
+                        && get_token_contents(code, &tokens[k]).matches('\n').count() == 1
+                    {
+                        next_in_run = Some(k + 1);
+                    }
+                    break;
+                }
+                _ => {}
+            }
+        }
+        i = eq + 1;
+    }
+    flush(&mut run, &mut aligned);
+    aligned.sort_unstable();
+    aligned
+}
+
+/// Finds the chains of binary operators in the code, sorted by their first break point.
+/// Chains that contain comments, that are inside interpolated strings, or whose break points
+/// would include a `-`, which the formatter cannot tell apart from a unary `-`, are left out.
+fn bin_op_chains(code: &str) -> Vec<BinOpChain> {
+    /// A chain being built, at one nesting level of delimiters.
+    #[derive(Default)]
+    struct Partial {
+        lo: Option<u32>,
+        hi: u32,
+        /// Each operator with its precedence and whether a line break can be inserted before it.
+        ops: Vec<(u32, u8, bool)>,
+        expects_operand: bool,
+        has_comment: bool,
+        in_interpolation: bool,
+    }
+
+    impl Partial {
+        fn nested(&self) -> Self {
+            Self {
+                in_interpolation: self.in_interpolation,
+                ..Self::default()
+            }
+        }
+
+        fn finish(&mut self, chains: &mut Vec<BinOpChain>) {
+            let partial = std::mem::replace(self, self.nested());
+            let (Some(lo), Some(lowest)) = (
+                partial.lo,
+                partial
+                    .ops
+                    .iter()
+                    .map(|(_, precedence, _)| *precedence)
+                    .min(),
+            ) else {
+                return;
+            };
+            let break_points = partial
+                .ops
+                .iter()
+                .filter(|(_, precedence, _)| *precedence == lowest)
+                .collect::<Vec<_>>();
+            if !partial.has_comment
+                && !partial.in_interpolation
+                && break_points.iter().all(|(_, _, breakable)| *breakable)
+            {
+                chains.push(BinOpChain {
+                    lo,
+                    hi: partial.hi,
+                    break_points: break_points.iter().map(|(offset, _, _)| *offset).collect(),
+                });
+            }
+        }
+    }
+
+    let mut chains = vec![];
+    let mut stack = vec![Partial::default()];
+    for token in concrete::ConcreteTokenIterator::new(code) {
+        let partial = stack.last_mut().expect("stack should not be empty");
+        let cooked = match token.kind {
+            ConcreteTokenKind::Syntax(cooked) => cooked,
+            ConcreteTokenKind::Comment => {
+                partial.has_comment |= partial.lo.is_some();
+                continue;
+            }
+            _ => continue,
+        };
+        let precedence = match cooked {
+            TokenKind::ClosedBinOp(op) => Some(bin_op_precedence(op)),
+            TokenKind::EqEq | TokenKind::Ne | TokenKind::Lte | TokenKind::Gte => Some(3),
+            _ => None,
+        };
+        match (cooked, precedence) {
+            (_, Some(precedence)) if partial.lo.is_some() && !partial.expects_operand => {
+                let breakable = !matches!(cooked, TokenKind::ClosedBinOp(ClosedBinOp::Minus));
+                partial.ops.push((token.span.lo, precedence, breakable));
+                partial.expects_operand = true;
+            }
+            (TokenKind::ClosedBinOp(ClosedBinOp::Minus), _) => {
+                // A unary `-` at the start of an operand.
+                partial.lo.get_or_insert(token.span.lo);
+                partial.expects_operand = true;
+            }
+            (TokenKind::Open(Delim::Paren | Delim::Bracket), _) => {
+                partial.lo.get_or_insert(token.span.lo);
+                let nested = partial.nested();
+                stack.push(nested);
+            }
+            (TokenKind::Close(Delim::Paren | Delim::Bracket), _) => {
+                if stack.len() > 1 {
+                    stack
+                        .pop()
+                        .expect("stack should not be empty")
+                        .finish(&mut chains);
+                }
+                let partial = stack.last_mut().expect("stack should not be empty");
+                partial.hi = token.span.hi;
+                partial.expects_operand = false;
+            }
+            (TokenKind::String(StringToken::Interpolated(start, ending)), _) => {
+                if start == InterpolatedStart::RBrace && stack.len() > 1 {
+                    stack
+                        .pop()
+                        .expect("stack should not be empty")
+                        .finish(&mut chains);
+                }
+                let partial = stack.last_mut().expect("stack should not be empty");
+                partial.lo.get_or_insert(token.span.lo);
+                partial.hi = token.span.hi;
+                partial.expects_operand = false;
+                if ending == InterpolatedEnding::LBrace {
+                    stack.push(Partial {
+                        in_interpolation: true,
+                        ..Partial::default()
+                    });
+                }
+            }
+            (TokenKind::Open(Delim::Brace), _) => {
+                partial.finish(&mut chains);
+                let nested = partial.nested();
+                stack.push(nested);
+            }
+            (TokenKind::Close(Delim::Brace), _) => {
+                if stack.len() > 1 {
+                    stack
+                        .pop()
+                        .expect("stack should not be empty")
+                        .finish(&mut chains);
+                }
+                stack
+                    .last_mut()
+                    .expect("stack should not be empty")
+                    .finish(&mut chains);
+            }
+            (_, None) if is_chain_operand(&cooked) => {
+                partial.lo.get_or_insert(token.span.lo);
+                partial.hi = token.span.hi;
+                partial.expects_operand = is_prefix(&cooked)
+                    || matches!(cooked, TokenKind::Keyword(keyword) if is_prefix_keyword(&keyword));
+            }
+            _ => partial.finish(&mut chains),
+        }
+    }
+    for mut partial in stack {
+        partial.finish(&mut chains);
+    }
+    chains.sort_unstable_by_key(|chain| chain.break_points[0]);
+    chains
+}
+
+/// Returns true if the token can be part of an operand in a chain of binary operators.
+fn is_chain_operand(cooked: &TokenKind) -> bool {
+    is_value_lit(cooked)
+        || is_prefix_with_space(cooked)
+        || matches!(
+            cooked,
+            TokenKind::Ident
+                | TokenKind::AposIdent
+                | TokenKind::Bang
+                | TokenKind::ColonColon
+                | TokenKind::Dot
+        )
+        || matches!(cooked, TokenKind::Keyword(keyword) if is_keyword_value(keyword) || is_prefix_keyword(keyword))
+}
+
+/// The precedence of a binary operator that can be part of a chain, from `or`, which binds the
+/// loosest, to `^`.
+fn bin_op_precedence(op: ClosedBinOp) -> u8 {
+    match op {
+        ClosedBinOp::Or => 1,
+        ClosedBinOp::And => 2,
+        ClosedBinOp::BarBarBar => 4,
+        ClosedBinOp::CaretCaretCaret => 5,
+        ClosedBinOp::AmpAmpAmp => 6,
+        ClosedBinOp::GtGtGt | ClosedBinOp::LtLtLt => 7,
+        ClosedBinOp::Plus | ClosedBinOp::Minus => 8,
+        ClosedBinOp::Percent | ClosedBinOp::Slash | ClosedBinOp::Star => 9,
+        ClosedBinOp::Caret => 10,
+    }
+}
+
 // Rule Conditions
 
 fn is_bin_op(cooked: &TokenKind) -> bool {
@@ -794,6 +1521,19 @@ fn effect_single_space(
     }
 }
 
+fn effect_spaces(
+    left: &ConcreteToken,
+    whitespace: &str,
+    right: &ConcreteToken,
+    count: usize,
+    edits: &mut Vec<TextEdit>,
+) {
+    let spaces = " ".repeat(count);
+    if whitespace != spaces {
+        edits.push(TextEdit::new(&spaces, left.span.hi, right.span.lo));
+    }
+}
+
 fn effect_trim_comment(left: &ConcreteToken, edits: &mut Vec<TextEdit>, code: &str) {
     let comment_contents = get_token_contents(code, left);
     let new_comment_contents = comment_contents.trim_end();
@@ -806,19 +1546,32 @@ fn effect_trim_comment(left: &ConcreteToken, edits: &mut Vec<TextEdit>, code: &s
     }
 }
 
+/// Adds a comma after the last item of a wrapped list.
+fn effect_trailing_comma(left: &ConcreteToken, right: &ConcreteToken, edits: &mut Vec<TextEdit>) {
+    // Merge with the edit to the whitespace before the closing delimiter, if any,
+    // so that there are never two edits at the same position.
+    match edits
+        .iter_mut()
+        .find(|edit| edit.span.lo == left.span.hi && edit.span.hi == right.span.lo)
+    {
+        Some(edit) => edit.new_text.insert(0, ','),
+        None => edits.push(TextEdit::new(",", left.span.hi, left.span.hi)),
+    }
+}
+
 fn effect_correct_indentation(
     left: &ConcreteToken,
     whitespace: &str,
     right: &ConcreteToken,
     edits: &mut Vec<TextEdit>,
     indent_level: usize,
+    max_blank_lines: usize,
 ) {
-    let mut count_newlines = whitespace.chars().filter(|c| *c == '\n').count();
+    let count_newlines = whitespace.chars().filter(|c| *c == '\n').count();
 
-    // There should always be at least one newline
-    if count_newlines < 1 {
-        count_newlines = 1;
-    }
+    // There should always be at least one newline, and at most the
+    // newline ending the line plus the allowed number of blank lines
+    let count_newlines = count_newlines.clamp(1, max_blank_lines.saturating_add(1));
 
     let mut new_whitespace = if whitespace.contains("\r\n") {
         "\r\n".repeat(count_newlines)
@@ -612,6 +612,38 @@ fn single_space_before_types() {
     );
 }
 
+// Newtype definitions have single spaces around `=` and `:` and after commas
+
+#[test]
+fn compressed_newtype_is_spaced() {
+    check(
+        indoc! {"
+        newtype Foo=(A:Int,B:Int);
+        newtype Bar=(A:Int,Inner:(B:Int,C:(D:Double,E:Result)));
+        newtype Baz=(Op:Qubit=>Unit is Adj,Fn:Int->Int);
+        "},
+        &expect![[r#"
+            newtype Foo = (A : Int, B : Int);
+            newtype Bar = (A : Int, Inner : (B : Int, C : (D : Double, E : Result)));
+            newtype Baz = (Op : Qubit => Unit is Adj, Fn : Int -> Int);
+        "#]],
+    );
+}
+
+#[test]
+fn over_spaced_newtype_is_spaced() {
+    check(
+        indoc! {"
+        newtype   Foo   =   (  A   :   Int  ,   B   :   Int  )  ;
+        newtype Bar =  (  A  :  Int  ,  (  B  :  Int  ,  C  :  Double  )  ,  Int  );
+        "},
+        &expect![[r#"
+            newtype Foo = (A : Int, B : Int);
+            newtype Bar = (A : Int, (B : Int, C : Double), Int);
+        "#]],
+    );
+}
+
 // Single space before variables
 
 #[test]
@@ -886,7 +918,6 @@ fn delimiter_newlines_indentation() {
             let y = [a, b, c];
             let z = [
 
-
                 a,
                 b,
                 c
@@ -1091,6 +1122,339 @@ fn single_space_after_spec_decl_ellipse() {
     )
 }
 
+// Runs of blank lines are collapsed to the configured maximum
+
+#[test]
+fn collapse_triple_blank_line() {
+    check(
+        indoc! {r#"
+            namespace Foo {
+                function A() : Unit {}
+
+
+
+                function B() : Unit {}
+            }
+        "#},
+        &expect![[r#"
+            namespace Foo {
+                function A() : Unit {}
+
+                function B() : Unit {}
+            }
+        "#]],
+    );
+}
+
+#[test]
+fn preserve_single_blank_line() {
+    let input = indoc! {r#"
+        namespace Foo {
+            function A() : Unit {}
+
+            function B() : Unit {
+                let x = 1;
+
+                let y = 2;
+            }
+        }
+    "#};
+
+    assert!(super::calculate_format_edits(input).is_empty());
+}
+
+#[test]
+fn collapse_blank_lines_to_configured_maximum() {
+    let input = indoc! {r#"
+        let x = 1;
+
+
+
+
+        let y = 2;
+
+        let z = 3;
+    "#};
+
+    let config = super::FormatterConfig {
+        max_blank_lines: 2,
+        ..Default::default()
+    };
+    expect![[r#"
+        let x = 1;
+
+
+        let y = 2;
+
+        let z = 3;
+    "#]]
+    .assert_eq(&super::format_str_with_config(input, &config));
+
+    let config = super::FormatterConfig {
+        max_blank_lines: 0,
+        ..Default::default()
+    };
+    expect![[r#"
+        let x = 1;
+        let y = 2;
+        let z = 3;
+    "#]]
+    .assert_eq(&super::format_str_with_config(input, &config));
+}
+
+// Wrapping long argument lists
+
+#[test]
+fn long_call_arguments_are_wrapped() {
+    check(
+        indoc! {r#"
+        operation Foo() : Unit {
+            ApplyOperationToEachQubit(firstArgumentName, secondArgumentName, thirdArgumentName, fourthArgument);
+        }
+        "#},
+        &expect![[r#"
+            operation Foo() : Unit {
+                ApplyOperationToEachQubit(
+                    firstArgumentName,
+                    secondArgumentName,
+                    thirdArgumentName,
+                    fourthArgument,
+                );
+            }
+        "#]],
+    );
+}
+
+#[test]
+fn long_tuple_with_nested_call_wraps_outer_list_only() {
+    check(
+        indoc! {r#"
+        let tuple = (FirstFunction(argumentOne, argumentTwo), SecondFunction(argumentThree), fourthTupleValue);
+        "#},
+        &expect![[r#"
+            let tuple = (
+                FirstFunction(argumentOne, argumentTwo),
+                SecondFunction(argumentThree),
+                fourthTupleValue,
+            );
+        "#]],
+    );
+}
+
+#[test]
+fn wrapped_arguments_keep_existing_trailing_comma() {
+    check(
+        indoc! {r#"
+        let tuple = (FirstFunction(argumentOne, argumentTwo), SecondFunction(argumentThree), fourthTupleValue,);
+        "#},
+        &expect![[r#"
+            let tuple = (
+                FirstFunction(argumentOne, argumentTwo),
+                SecondFunction(argumentThree),
+                fourthTupleValue,
+            );
+        "#]],
+    );
+}
+
+#[test]
+fn short_call_arguments_stay_on_one_line() {
+    check(
+        indoc! {r#"
+        operation Foo() : Unit {
+            ApplyToEach(H, qubits);
+        }
+        "#},
+        &expect![[r#"
+            operation Foo() : Unit {
+                ApplyToEach(H, qubits);
+            }
+        "#]],
+    );
+}
+
+#[test]
+fn long_single_argument_with_comma_in_string_is_not_wrapped() {
+    check(
+        indoc! {r#"
+        operation Foo() : Unit {
+            Message("This message is long enough to go past the maximum width, so it could be wrapped.");
+        }
+        "#},
+        &expect![[r#"
+            operation Foo() : Unit {
+                Message("This message is long enough to go past the maximum width, so it could be wrapped.");
+            }
+        "#]],
+    );
+}
+
+#[test]
+fn long_call_with_delimiters_in_string_arguments_wraps_each_argument() {
+    check(
+        indoc! {r#"
+        operation Foo() : Unit {
+            LogMessages("an opening paren ( and a comma, then", "a closing paren ) and another comma,", count);
+        }
+        "#},
+        &expect![[r#"
+            operation Foo() : Unit {
+                LogMessages(
+                    "an opening paren ( and a comma, then",
+                    "a closing paren ) and another comma,",
+                    count,
+                );
+            }
+        "#]],
+    );
+}
+
+fn check_with_max_width(input: &str, max_width: usize, expect: &Expect) {
+    let config = super::FormatterConfig {
+        max_width,
+        ..Default::default()
+    };
+    let actual = super::format_str_with_config(input, &config);
+    expect.assert_eq(&actual);
+    assert_eq!(
+        super::format_str_with_config(&actual, &config),
+        actual,
+        "formatting should be idempotent"
+    );
+}
+
+#[test]
+fn long_binary_expression_wraps_at_max_width() {
+    let input = indoc! {r#"
+        operation Foo() : Unit {
+            let total = firstValue * scale + secondValue * scale + thirdValue;
+        }
+    "#};
+    check_with_max_width(
+        input,
+        40,
+        &expect![[r#"
+        operation Foo() : Unit {
+            let total = firstValue * scale
+                + secondValue * scale
+                + thirdValue;
+        }
+    "#]],
+    );
+    check_with_max_width(
+        input,
+        120,
+        &expect![[r#"
+        operation Foo() : Unit {
+            let total = firstValue * scale + secondValue * scale + thirdValue;
+        }
+    "#]],
+    );
+}
+
+#[test]
+fn long_struct_constructor_wraps_at_max_width() {
+    let input = indoc! {r#"
+        function Foo() : Unit {
+            let pair = new Pair { First = firstValue, Second = secondValue };
+        }
+    "#};
+    check_with_max_width(
+        input,
+        40,
+        &expect![[r#"
+        function Foo() : Unit {
+            let pair = new Pair {
+                First = firstValue,
+                Second = secondValue,
+            };
+        }
+    "#]],
+    );
+    check_with_max_width(
+        input,
+        120,
+        &expect![[r#"
+        function Foo() : Unit {
+            let pair = new Pair { First = firstValue, Second = secondValue };
+        }
+    "#]],
+    );
+}
+
+#[test]
+fn long_binary_expression_with_string_operands_wraps_at_max_width() {
+    let input = indoc! {r#"
+        function Foo() : Unit {
+            let message = "first, (" + $"second {x + y}, )" + "third";
+        }
+    "#};
+    check_with_max_width(
+        input,
+        40,
+        &expect![[r#"
+        function Foo() : Unit {
+            let message = "first, ("
+                + $"second {x + y}, )"
+                + "third";
+        }
+    "#]],
+    );
+    check_with_max_width(
+        input,
+        120,
+        &expect![[r#"
+        function Foo() : Unit {
+            let message = "first, (" + $"second {x + y}, )" + "third";
+        }
+    "#]],
+    );
+}
+
+#[test]
+fn long_struct_constructor_with_string_fields_wraps_at_max_width() {
+    let input = indoc! {r#"
+        function Foo() : Unit {
+            let pair = new Pair { First = "a, (b", Second = $"{c}, d)" };
+            let label = new Label { Text = "one field, with a comma" };
+        }
+    "#};
+    check_with_max_width(
+        input,
+        40,
+        &expect![[r#"
+        function Foo() : Unit {
+            let pair = new Pair {
+                First = "a, (b",
+                Second = $"{c}, d)",
+            };
+            let label = new Label { Text = "one field, with a comma" };
+        }
+    "#]],
+    );
+    check_with_max_width(
+        input,
+        120,
+        &expect![[r#"
+        function Foo() : Unit {
+            let pair = new Pair { First = "a, (b", Second = $"{c}, d)" };
+            let label = new Label { Text = "one field, with a comma" };
+        }
+    "#]],
+    );
+}
+
+#[test]
+fn remove_blank_lines_from_end_of_code() {
+    check(
+        "namespace Foo {}\n\n\n  ",
+        &expect![[r#"
+        namespace Foo {}
+    "#]],
+    );
+    check("namespace Foo {}   ", &expect!["namespace Foo {}"]);
+}
+
 // Remove extra whitespace from start of code
 
 #[test]
@@ -1105,6 +1469,77 @@ fn remove_extra_whitespace_from_start_of_code() {
     check(input, &expect!["namespace Foo {}"]);
 }
 
+// Formatting is idempotent and independent of a trailing newline
+
+/// Inputs that are formatted twice to check that formatting is stable.
+const IDEMPOTENCE_CORPUS: &[&str] = &[
+    // structs
+    "struct A {x:Int,\ny:Int}",
+    indoc! {r#"
+        namespace Foo {
+            /// Doc comment   
+            struct Pair { First : Int,   Second : Double }
+            function MakePair() : Pair {
+                let p = new Pair {First = 1, Second = 2.0};
+                new Pair { ...p, First = 3 }
+            }
+        }"#},
+    // nested blocks
+    "operation Foo() : Unit { if true { if false { let x = 1; } else { let y = 2; } } }",
+    indoc! {r#"
+        operation Foo(qs : Qubit[]) : Unit {
+        for q in qs { within { H(q); }
+        apply {
+                    if M(q) == One { X(q); } // flip
+            }
+        }
+        }   // trailing comment   "#},
+    // import lists
+    "import Foo.Bar, Foo.Baz as B,Foo.Qux.*;",
+    indoc! {r#"
+        namespace Test {
+            import Std.Arrays.{Head,   Tail};
+            export Foo,
+                Bar as Baz;
+        }"#},
+];
+
+fn check_idempotent(input: &str) {
+    let once = super::format_str(input);
+    let twice = super::format_str(&once);
+    assert_eq!(once, twice, "formatting should be idempotent for:\n{input}");
+
+    let with_newline = super::format_str(&format!("{input}\n"));
+    assert_eq!(
+        format!("{once}\n"),
+        with_newline,
+        "formatting should not depend on a trailing newline for:\n{input}"
+    );
+}
+
+#[test]
+fn formatting_is_idempotent() {
+    for input in IDEMPOTENCE_CORPUS {
+        check_idempotent(input);
+    }
+}
+
+#[test]
+fn last_tokens_are_formatted_without_trailing_newline() {
+    check(
+        "struct A {x:Int,\ny:Int}",
+        &expect!["struct A { x : Int, y : Int }"],
+    );
+}
+
+#[test]
+fn trailing_comment_at_end_of_file_is_trimmed() {
+    check(
+        "let x = 3; // comment   ",
+        &expect!["let x = 3; // comment"],
+    );
+}
+
 // Extra test cases for sanity
 
 #[test]
@@ -1223,3 +1658,104 @@ fn sample_has_no_formatting_changes() {
         "#};
     assert!(super::calculate_format_edits(input).is_empty());
 }
+
+// Aligned assignments
+
+#[test]
+fn align_assignments_aligns_run_of_let_bindings() {
+    let input = indoc! {r#"
+        operation Foo() : Unit {
+            let x = 1;
+            let count = 2;
+            mutable total = x + count;
+
+            let y = 3;
+            let longName = 4;
+        }
+    "#};
+
+    let config = super::FormatterConfig {
+        align_assignments: true,
+        ..Default::default()
+    };
+    expect![[r#"
+        operation Foo() : Unit {
+            let x         = 1;
+            let count     = 2;
+            mutable total = x + count;
+
+            let y        = 3;
+            let longName = 4;
+        }
+    "#]]
+    .assert_eq(&super::format_str_with_config(input, &config));
+
+    let formatted = super::format_str_with_config(input, &config);
+    assert!(super::calculate_format_edits_with_config(&formatted, &config).is_empty());
+}
+
+#[test]
+fn align_assignments_leaves_tuple_binding_unaffected() {
+    let input = indoc! {r#"
+        operation Foo() : Unit {
+            let x = 1;
+            let (a, b) = (2, 3);
+            let count = 4;
+            let y = 5;
+        }
+    "#};
+
+    let config = super::FormatterConfig {
+        align_assignments: true,
+        ..Default::default()
+    };
+    expect![[r#"
+        operation Foo() : Unit {
+            let x = 1;
+            let (a, b) = (2, 3);
+            let count = 4;
+            let y     = 5;
+        }
+    "#]]
+    .assert_eq(&super::format_str_with_config(input, &config));
+}
+
+#[test]
+fn align_assignments_continues_run_past_trailing_comment() {
+    let input = indoc! {r#"
+        operation Foo() : Unit {
+            let x = 1; // one
+            let count = 2;
+            // two
+            let y = 3;
+            let longName = 4;
+        }
+    "#};
+
+    let config = super::FormatterConfig {
+        align_assignments: true,
+        ..Default::default()
+    };
+    expect![[r#"
+        operation Foo() : Unit {
+            let x     = 1; // one
+            let count = 2;
+            // two
+            let y        = 3;
+            let longName = 4;
+        }
+    "#]]
+    .assert_eq(&super::format_str_with_config(input, &config));
+}
+
+#[test]
+fn align_assignments_is_off_by_default() {
+    let input = indoc! {r#"
+        operation Foo() : Unit {
+            let x = 1;
+            let count = 2;
+        }
+    "#};
+
+    assert!(super::calculate_format_edits(input).is_empty());
+}
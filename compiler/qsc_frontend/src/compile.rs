@@ -25,17 +25,26 @@ use qsc_ast::{
 use qsc_data_structures::{
     index_map::{self, IndexMap},
     language_features::LanguageFeatures,
+    line_column::{Encoding, Range},
     span::Span,
     target::TargetCapabilityFlags,
 };
 use qsc_hir::{
     assigner::Assigner as HirAssigner,
     global::{self},
-    hir::{self, PackageId},
+    hir::{self, Functor, ItemId, PackageId},
+    mut_visit::{self as hir_mut_visit, MutVisitor as HirMutVisitor},
+    ty::Ty,
     validate::Validator as HirValidator,
     visit::Visitor as _,
 };
-use std::{fmt::Debug, sync::Arc};
+use rustc_hash::{FxHashMap, FxHashSet};
+use std::{
+    fmt::Debug,
+    rc::Rc,
+    sync::Arc,
+    time::{Duration, Instant},
+};
 use thiserror::Error;
 
 #[derive(Debug, Default)]
@@ -48,6 +57,79 @@ pub struct CompileUnit {
     pub dropped_names: Vec<TrackedName>,
 }
 
+/// An item exported from a namespace of a compilation unit, see [`CompileUnit::exported_items`].
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ExportedItem {
+    /// The fully qualified name of the exporting namespace, e.g. `Foo.Bar`.
+    pub namespace: Rc<str>,
+    /// The name the item is exported under, which is its alias if it has one.
+    pub name: Rc<str>,
+    /// The exported item. Re-exports refer to the original declaration, which is in a dependency
+    /// when [`ItemId::package`] is set. Exported namespaces refer to their namespace item.
+    pub item: ItemId,
+    /// The alias the item is exported under, if any.
+    pub alias: Option<Rc<str>>,
+}
+
+impl CompileUnit {
+    /// Returns the items exported by each namespace of the unit, in source order, as resolved
+    /// during compilation. This includes aliased exports, re-exports of items declared in other
+    /// namespaces or dependencies, and exports of namespaces declared in the unit. Exports that
+    /// failed to resolve are skipped.
+    pub fn exported_items(&self) -> impl Iterator<Item = ExportedItem> + '_ {
+        self.ast
+            .package
+            .nodes
+            .iter()
+            .filter_map(|node| match node {
+                TopLevelNode::Namespace(namespace) => Some(namespace),
+                TopLevelNode::Stmt(_) => None,
+            })
+            .flat_map(move |namespace| {
+                let namespace_name = namespace.name.name();
+                namespace
+                    .items
+                    .iter()
+                    .filter_map(|item| match &*item.kind {
+                        ast::ItemKind::ImportOrExport(decl) if decl.is_export() => Some(decl),
+                        _ => None,
+                    })
+                    .flat_map(ast::ImportOrExportDecl::items)
+                    .filter(|export| !export.is_glob)
+                    .filter_map(move |export| {
+                        let item = match resolve::resolved_target(&self.ast.names, export) {
+                            Some(resolve::Res::Item(id, _)) => id,
+                            Some(_) => return None,
+                            None => self.namespace_item(&export.path)?,
+                        };
+                        Some(ExportedItem {
+                            namespace: namespace_name.clone(),
+                            name: export.name().name.clone(),
+                            item,
+                            alias: export.alias.as_ref().map(|alias| alias.name.clone()),
+                        })
+                    })
+            })
+    }
+
+    /// Finds the item of the namespace declared in this unit with the fully qualified name `path`.
+    fn namespace_item(&self, path: &ast::Path) -> Option<ItemId> {
+        let name = ast::Idents::from(path.clone()).name();
+        self.package
+            .items
+            .iter()
+            .find_map(|(id, item)| match &item.kind {
+                hir::ItemKind::Namespace(namespace, _) if namespace.name() == name => {
+                    Some(ItemId {
+                        package: None,
+                        item: id,
+                    })
+                }
+                _ => None,
+            })
+    }
+}
+
 #[derive(Debug, Default)]
 pub struct AstPackage {
     pub package: ast::Package,
@@ -333,6 +415,28 @@ pub fn compile(
     sources: SourceMap,
     capabilities: TargetCapabilityFlags,
     language_features: LanguageFeatures,
+) -> CompileUnit {
+    compile_with_features(
+        store,
+        dependencies,
+        sources,
+        capabilities,
+        language_features,
+        &[],
+    )
+}
+
+/// Compiles the given sources, keeping items marked with `@Config(Feature("name"))`
+/// only when `name` is one of the enabled `features`, and items marked with
+/// `@Config(not Feature("name"))` only when it is not.
+#[must_use]
+pub fn compile_with_features(
+    store: &PackageStore,
+    dependencies: &[PackageId],
+    sources: SourceMap,
+    capabilities: TargetCapabilityFlags,
+    language_features: LanguageFeatures,
+    features: &[&str],
 ) -> CompileUnit {
     let (ast_package, parse_errors) = parse_all(&sources, language_features);
 
@@ -342,41 +446,197 @@ pub fn compile(
         ast_package,
         sources,
         capabilities,
+        features,
+        parse_errors,
+    )
+}
+
+/// Compiles the given sources like [`compile`], but with prelude shadowing as an error: a name
+/// that would refer to an item in the prelude, but resolves to a different item through an open,
+/// an import or a declaration in the enclosing namespace, is reported as
+/// [`resolve::Error::PreludeShadowed`]. This guarantees standard library semantics for prelude
+/// names.
+#[must_use]
+pub fn compile_with_strict_prelude(
+    store: &PackageStore,
+    dependencies: &[PackageId],
+    sources: SourceMap,
+    capabilities: TargetCapabilityFlags,
+    language_features: LanguageFeatures,
+) -> CompileUnit {
+    let (ast_package, parse_errors) = parse_all(&sources, language_features);
+    compile_ast_with_timings(
+        store,
+        dependencies,
+        ast_package,
+        sources,
+        capabilities,
+        &[],
         parse_errors,
+        true,
+        None,
     )
 }
 
+/// The wall-clock duration of each phase of a compilation, see [`compile_timed`].
+#[derive(Clone, Copy, Debug, Default)]
+pub struct PhaseTimings {
+    pub parse: Duration,
+    pub preprocess: Duration,
+    pub assign: Duration,
+    pub resolve: Duration,
+    pub typeck: Duration,
+    pub lower: Duration,
+}
+
+/// Compiles the given sources like [`compile`], also measuring how long each phase took so that
+/// slow phases can be identified on large inputs.
+#[must_use]
+pub fn compile_timed(
+    store: &PackageStore,
+    dependencies: &[PackageId],
+    sources: SourceMap,
+    capabilities: TargetCapabilityFlags,
+    language_features: LanguageFeatures,
+) -> (CompileUnit, PhaseTimings) {
+    let mut timings = PhaseTimings::default();
+    let (ast_package, parse_errors) = time_phase(Some(&mut timings.parse), || {
+        parse_all(&sources, language_features)
+    });
+    let unit = compile_ast_with_timings(
+        store,
+        dependencies,
+        ast_package,
+        sources,
+        capabilities,
+        &[],
+        parse_errors,
+        false,
+        Some(&mut timings),
+    );
+    (unit, timings)
+}
+
+/// Runs a compilation phase, storing its wall-clock duration in `duration` if one is given.
+/// Timing is opt-in because the clock is not available on every target.
+fn time_phase<T>(duration: Option<&mut Duration>, phase: impl FnOnce() -> T) -> T {
+    match duration {
+        Some(duration) => {
+            let start = Instant::now();
+            let result = phase();
+            *duration = start.elapsed();
+            result
+        }
+        None => phase(),
+    }
+}
+
+/// Compiles the given sources for tooling that needs as much of the package as possible
+/// even when it contains errors, such as an editor showing hover information.
+///
+/// Lowering already continues past name resolution errors, so every item is present in
+/// the resulting HIR. In addition, each reference that failed to resolve is replaced by a
+/// hole expression, so consumers see an explicit hole rather than a variable with an
+/// erroneous resolution. Errors are reported on the unit as usual.
+#[must_use]
+pub fn compile_lenient(
+    store: &PackageStore,
+    dependencies: &[PackageId],
+    sources: SourceMap,
+    capabilities: TargetCapabilityFlags,
+    language_features: LanguageFeatures,
+) -> CompileUnit {
+    let mut unit = compile(
+        store,
+        dependencies,
+        sources,
+        capabilities,
+        language_features,
+    );
+    UnresolvedToHole.visit_package(&mut unit.package);
+    unit
+}
+
+struct UnresolvedToHole;
+
+impl HirMutVisitor for UnresolvedToHole {
+    fn visit_expr(&mut self, expr: &mut hir::Expr) {
+        if let hir::ExprKind::Var(hir::Res::Err, _) = expr.kind {
+            expr.kind = hir::ExprKind::Hole;
+        } else {
+            hir_mut_visit::walk_expr(self, expr);
+        }
+    }
+}
+
 #[allow(clippy::module_name_repetitions)]
 pub fn compile_ast(
     store: &PackageStore,
     dependencies: &[PackageId],
-    mut ast_package: ast::Package,
+    ast_package: ast::Package,
     sources: SourceMap,
     capabilities: TargetCapabilityFlags,
+    features: &[&str],
     parse_errors: Vec<qsc_parse::Error>,
 ) -> CompileUnit {
-    let mut cond_compile = preprocess::Conditional::new(capabilities);
-    cond_compile.visit_package(&mut ast_package);
-    let dropped_names = cond_compile.into_names();
-
-    let mut ast_assigner = AstAssigner::new();
-    ast_assigner.visit_package(&mut ast_package);
-    AstValidator::default().visit_package(&ast_package);
-    let mut hir_assigner = HirAssigner::new();
-    let (names, locals, name_errors) = resolve_all(
+    compile_ast_with_timings(
         store,
         dependencies,
-        &mut hir_assigner,
-        &ast_package,
-        dropped_names.clone(),
-    );
-    let (tys, ty_errors) = typeck_all(store, dependencies, &ast_package, &names);
-    let mut lowerer = Lowerer::new();
-    let package = lowerer
-        .with(&mut hir_assigner, &names, &tys)
-        .lower_package(&ast_package);
-    HirValidator::default().visit_package(&package);
-    let lower_errors = lowerer.drain_errors();
+        ast_package,
+        sources,
+        capabilities,
+        features,
+        parse_errors,
+        false,
+        None,
+    )
+}
+
+#[allow(clippy::too_many_arguments)]
+fn compile_ast_with_timings(
+    store: &PackageStore,
+    dependencies: &[PackageId],
+    mut ast_package: ast::Package,
+    sources: SourceMap,
+    capabilities: TargetCapabilityFlags,
+    features: &[&str],
+    parse_errors: Vec<qsc_parse::Error>,
+    strict_prelude: bool,
+    mut timings: Option<&mut PhaseTimings>,
+) -> CompileUnit {
+    let dropped_names = time_phase(timings.as_mut().map(|t| &mut t.preprocess), || {
+        let mut cond_compile = preprocess::Conditional::with_features(capabilities, features);
+        cond_compile.visit_package(&mut ast_package);
+        cond_compile.into_names()
+    });
+
+    time_phase(timings.as_mut().map(|t| &mut t.assign), || {
+        let mut ast_assigner = AstAssigner::new();
+        ast_assigner.visit_package(&mut ast_package);
+        AstValidator::default().visit_package(&ast_package);
+    });
+    let mut hir_assigner = HirAssigner::new();
+    let (names, locals, name_errors) = time_phase(timings.as_mut().map(|t| &mut t.resolve), || {
+        resolve_all(
+            store,
+            dependencies,
+            &mut hir_assigner,
+            &ast_package,
+            dropped_names.clone(),
+            strict_prelude,
+        )
+    });
+    let (tys, ty_errors) = time_phase(timings.as_mut().map(|t| &mut t.typeck), || {
+        typeck_all(store, dependencies, &ast_package, &names)
+    });
+    let (package, lower_errors) = time_phase(timings.map(|t| &mut t.lower), || {
+        let mut lowerer = Lowerer::new();
+        let package = lowerer
+            .with(&mut hir_assigner, &names, &tys)
+            .lower_package(&ast_package);
+        HirValidator::default().visit_package(&package);
+        (package, lowerer.drain_errors().collect::<Vec<_>>())
+    });
 
     let errors = parse_errors
         .into_iter()
@@ -456,6 +716,840 @@ pub fn std(store: &PackageStore, capabilities: TargetCapabilityFlags) -> Compile
     unit
 }
 
+/// The kind of a declaration listed in a symbol table.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum SymbolKind {
+    Operation,
+    Function,
+    Newtype,
+    Struct,
+    Field,
+}
+
+/// A single declaration in a symbol table.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SymbolEntry {
+    /// The fully qualified name, e.g. `Foo.Bar.Baz`. Fields are qualified by their type.
+    pub name: Rc<str>,
+    pub kind: SymbolKind,
+    /// The span of the declaration.
+    pub span: Span,
+    /// The visibility of the declaration. Fields share the visibility of their type.
+    pub visibility: hir::Visibility,
+}
+
+/// Returns a flat list of the top-level declarations in a compilation unit, in source order,
+/// including struct fields and named fields of newtypes.
+#[must_use]
+pub fn symbol_table(unit: &CompileUnit) -> Vec<SymbolEntry> {
+    let mut entries = Vec::new();
+    for node in &*unit.ast.package.nodes {
+        let TopLevelNode::Namespace(namespace) = node else {
+            continue;
+        };
+        let namespace_name = namespace.name.name();
+        for item in &*namespace.items {
+            let visibility = match item.visibility.as_ref().map(|v| v.kind) {
+                Some(ast::VisibilityKind::Internal) => hir::Visibility::Internal,
+                Some(ast::VisibilityKind::Public) | None => hir::Visibility::Public,
+            };
+            let mut push = |name: &str, kind, span| {
+                entries.push(SymbolEntry {
+                    name: name.into(),
+                    kind,
+                    span,
+                    visibility,
+                });
+            };
+            match &*item.kind {
+                ast::ItemKind::Callable(decl) => {
+                    let kind = match decl.kind {
+                        ast::CallableKind::Function => SymbolKind::Function,
+                        ast::CallableKind::Operation => SymbolKind::Operation,
+                    };
+                    push(
+                        &format!("{namespace_name}.{}", decl.name.name),
+                        kind,
+                        item.span,
+                    );
+                }
+                ast::ItemKind::Ty(name, def) => {
+                    let ty_name = format!("{namespace_name}.{}", name.name);
+                    push(&ty_name, SymbolKind::Newtype, item.span);
+                    push_ty_def_fields(&ty_name, def, &mut push);
+                }
+                ast::ItemKind::Struct(decl) => {
+                    let ty_name = format!("{namespace_name}.{}", decl.name.name);
+                    push(&ty_name, SymbolKind::Struct, item.span);
+                    for field in &*decl.fields {
+                        push(
+                            &format!("{ty_name}.{}", field.name.name),
+                            SymbolKind::Field,
+                            field.span,
+                        );
+                    }
+                }
+                ast::ItemKind::Err
+                | ast::ItemKind::Open(..)
+                | ast::ItemKind::ImportOrExport(..) => {}
+            }
+        }
+    }
+    entries
+}
+
+fn push_ty_def_fields(
+    ty_name: &str,
+    def: &ast::TyDef,
+    push: &mut impl FnMut(&str, SymbolKind, Span),
+) {
+    match &*def.kind {
+        ast::TyDefKind::Field(Some(name), _) => {
+            push(
+                &format!("{ty_name}.{}", name.name),
+                SymbolKind::Field,
+                def.span,
+            );
+        }
+        ast::TyDefKind::Paren(def) => push_ty_def_fields(ty_name, def, push),
+        ast::TyDefKind::Tuple(defs) => {
+            for def in &**defs {
+                push_ty_def_fields(ty_name, def, push);
+            }
+        }
+        ast::TyDefKind::Field(None, _) | ast::TyDefKind::Err => {}
+    }
+}
+
+/// The syntax that the source of a compilation unit uses, as found by [`used_language_features`].
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct UsedSyntax {
+    /// Whether the source has struct declarations or `new` struct expressions.
+    pub struct_syntax: bool,
+    /// Whether the source has glob imports or exports, such as `import Foo.*;`.
+    pub glob_imports: bool,
+}
+
+/// Returns the syntax that the source of a compilation unit actually uses, so that tooling can
+/// point out language features that are enabled but unused.
+///
+/// [`LanguageFeatures::V2PreviewSyntax`] only removes the block form of qubit allocation
+/// statements, so no source exercises it and it has no counterpart in [`UsedSyntax`].
+#[must_use]
+pub fn used_language_features(unit: &CompileUnit) -> UsedSyntax {
+    let mut usage = LanguageFeatureUsage::default();
+    usage.visit_package(&unit.ast.package);
+    usage.used
+}
+
+#[derive(Default)]
+struct LanguageFeatureUsage {
+    used: UsedSyntax,
+}
+
+impl<'a> qsc_ast::visit::Visitor<'a> for LanguageFeatureUsage {
+    fn visit_item(&mut self, item: &'a ast::Item) {
+        match &*item.kind {
+            ast::ItemKind::Struct(_) => self.used.struct_syntax = true,
+            ast::ItemKind::ImportOrExport(decl) if decl.items.iter().any(|item| item.is_glob) => {
+                self.used.glob_imports = true;
+            }
+            _ => {}
+        }
+        qsc_ast::visit::walk_item(self, item);
+    }
+
+    fn visit_expr(&mut self, expr: &'a ast::Expr) {
+        if let ast::ExprKind::Struct(..) = &*expr.kind {
+            self.used.struct_syntax = true;
+        }
+        qsc_ast::visit::walk_expr(self, expr);
+    }
+}
+
+/// Returns the literal text of every string in the lowered package, in source order, so that
+/// tooling can find user-facing messages such as those passed to `Message` or `fail`.
+///
+/// Each literal component of an interpolated string is returned separately. Components do not
+/// carry their own spans, so every entry is paired with the span of the string expression that
+/// contains it.
+#[must_use]
+pub fn extract_string_literals(unit: &CompileUnit) -> Vec<(String, Span)> {
+    let mut collector = StringLiteralCollector::default();
+    collector.visit_package(&unit.package);
+    collector.literals
+}
+
+#[derive(Default)]
+struct StringLiteralCollector {
+    literals: Vec<(String, Span)>,
+}
+
+impl<'a> qsc_hir::visit::Visitor<'a> for StringLiteralCollector {
+    fn visit_expr(&mut self, expr: &'a hir::Expr) {
+        if let hir::ExprKind::String(components) = &expr.kind {
+            for component in components {
+                if let hir::StringComponent::Lit(lit) = component {
+                    self.literals.push((lit.to_string(), expr.span));
+                }
+            }
+        }
+        qsc_hir::visit::walk_expr(self, expr);
+    }
+}
+
+/// Returns the inferred type of the innermost expression in the lowered package whose span
+/// covers the given span, formatted as it would appear in source, e.g. for hover information.
+///
+/// Returns `None` if no expression covers the span or if its type could not be inferred.
+#[must_use]
+pub fn type_at_span(unit: &CompileUnit, span: Span) -> Option<String> {
+    let mut finder = ExprAtSpanFinder { span, expr: None };
+    finder.visit_package(&unit.package);
+    finder
+        .expr
+        .filter(|expr| expr.ty != Ty::Err)
+        .map(|expr| expr.ty.display())
+}
+
+struct ExprAtSpanFinder<'a> {
+    span: Span,
+    expr: Option<&'a hir::Expr>,
+}
+
+impl<'a> qsc_hir::visit::Visitor<'a> for ExprAtSpanFinder<'a> {
+    fn visit_expr(&mut self, expr: &'a hir::Expr) {
+        let covers = |outer: Span, inner: Span| outer.lo <= inner.lo && inner.hi <= outer.hi;
+        let is_inner = match self.expr {
+            Some(best) => covers(best.span, expr.span),
+            None => true,
+        };
+        if is_inner && covers(expr.span, self.span) {
+            self.expr = Some(expr);
+        }
+        qsc_hir::visit::walk_expr(self, expr);
+    }
+}
+
+/// Returns the span of every hole (`_`) expression in the source of the unit, in source order,
+/// along with the type the hole is expected to have, e.g. for "fill in the hole" completions.
+///
+/// The type is formatted as it would appear in source, and is `None` if the type checker could
+/// not fully infer it.
+#[must_use]
+pub fn holes(unit: &CompileUnit) -> Vec<(Span, Option<String>)> {
+    let mut finder = HoleFinder {
+        tys: &unit.ast.tys,
+        holes: Vec::new(),
+    };
+    finder.visit_package(&unit.ast.package);
+    finder.holes
+}
+
+struct HoleFinder<'a> {
+    tys: &'a Table,
+    holes: Vec<(Span, Option<String>)>,
+}
+
+impl<'a> qsc_ast::visit::Visitor<'a> for HoleFinder<'_> {
+    fn visit_expr(&mut self, expr: &'a ast::Expr) {
+        if let ast::ExprKind::Hole = &*expr.kind {
+            let ty = self
+                .tys
+                .terms
+                .get(expr.id)
+                .filter(|ty| is_inferred(ty))
+                .map(Ty::display);
+            self.holes.push((expr.span, ty));
+        }
+        qsc_ast::visit::walk_expr(self, expr);
+    }
+}
+
+fn is_inferred(ty: &Ty) -> bool {
+    match ty {
+        Ty::Array(item) => is_inferred(item),
+        Ty::Arrow(arrow) => is_inferred(&arrow.input) && is_inferred(&arrow.output),
+        Ty::Tuple(items) => items.iter().all(is_inferred),
+        Ty::Param(..) | Ty::Prim(_) | Ty::Udt(..) => true,
+        Ty::Infer(_) | Ty::Err => false,
+    }
+}
+
+/// Returns the span of every reference to the given item in the lowered package, in source order,
+/// e.g. for finding all references to a callable or type. Items declared in the package itself
+/// are identified by an [`ItemId`] with no package.
+///
+/// The entry expression generated for the entry point is not part of the source, so it is not
+/// searched.
+#[must_use]
+pub fn references_to(unit: &CompileUnit, target: ItemId) -> Vec<Span> {
+    let mut finder = ItemReferenceFinder {
+        target,
+        spans: Vec::new(),
+    };
+    for item in unit.package.items.values() {
+        finder.visit_item(item);
+    }
+    for stmt in &unit.package.stmts {
+        finder.visit_stmt(stmt);
+    }
+    finder.spans.sort_by_key(|span| span.lo);
+    finder.spans
+}
+
+/// The functors supported by an operation.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct Functors {
+    /// Whether the operation supports the `Adjoint` functor.
+    pub adj: bool,
+    /// Whether the operation supports the `Controlled` functor.
+    pub ctl: bool,
+}
+
+/// Returns the functors supported by the given callable, e.g. for library documentation. Items
+/// declared in the unit itself are identified by an [`ItemId`] with no package, while items of
+/// its dependencies are looked up in `store`.
+///
+/// Both the functors declared with `is` and those implied by explicit specializations are
+/// included, whether the specializations are written out or left to be generated. Functions and
+/// other items support no functors.
+#[must_use]
+pub fn functor_support(store: &PackageStore, unit: &CompileUnit, item: ItemId) -> Functors {
+    match callable_decl(store, unit, item) {
+        Some(decl) => Functors {
+            adj: decl.functors.contains(&Functor::Adj),
+            ctl: decl.functors.contains(&Functor::Ctl),
+        },
+        None => Functors::default(),
+    }
+}
+
+/// Returns the declaration of the callable that `id` refers to from `unit`, looking up items of
+/// other packages in `store`.
+fn callable_decl<'a>(
+    store: &'a PackageStore,
+    unit: &'a CompileUnit,
+    id: ItemId,
+) -> Option<&'a hir::CallableDecl> {
+    let package = match id.package {
+        None => &unit.package,
+        Some(package) => &store.get(package)?.package,
+    };
+    match &package.items.get(id.item)?.kind {
+        hir::ItemKind::Callable(decl) => Some(decl),
+        _ => None,
+    }
+}
+
+struct ItemReferenceFinder {
+    target: ItemId,
+    spans: Vec<Span>,
+}
+
+impl<'a> qsc_hir::visit::Visitor<'a> for ItemReferenceFinder {
+    fn visit_expr(&mut self, expr: &'a hir::Expr) {
+        if let hir::ExprKind::Var(hir::Res::Item(id), _) = &expr.kind {
+            if *id == self.target {
+                self.spans.push(expr.span);
+            }
+        }
+        qsc_hir::visit::walk_expr(self, expr);
+    }
+}
+
+/// Returns the call graph of the lowered package, mapping each callable in the package to the
+/// callables it refers to.
+///
+/// A reference to a callable counts as a call even when the callable is passed as a value rather
+/// than invoked directly, since it may be invoked through that value. Lambdas are lifted into
+/// callables of their own, so the callable that creates a lambda refers to the lifted callable,
+/// which in turn refers to the callables in the lambda body. Calls through callable values that
+/// come from elsewhere, such as parameters, have no edge.
+#[must_use]
+pub fn call_graph(unit: &CompileUnit) -> FxHashMap<ItemId, FxHashSet<ItemId>> {
+    let mut graph = FxHashMap::default();
+    for (id, item) in unit.package.items.iter() {
+        if let hir::ItemKind::Callable(decl) = &item.kind {
+            let mut collector = CalleeCollector::default();
+            collector.visit_callable_decl(decl);
+            graph.insert(
+                ItemId {
+                    package: None,
+                    item: id,
+                },
+                collector.callees,
+            );
+        }
+    }
+    graph
+}
+
+/// Returns, for each intrinsic callable, the callables in the unit that call it, whether directly
+/// or through other callables, sorted by item. This helps to assess the impact of a target that
+/// does not support an intrinsic.
+///
+/// Calls are found as in [`call_graph`], so callables lifted from lambdas are included as callers.
+/// Calls into dependencies are followed through their declarations in `store`, so a call to a
+/// library operation counts as a call to the intrinsics it uses. Every intrinsic declared in the
+/// unit has an entry, while intrinsics of dependencies only have one if they are called.
+#[must_use]
+pub fn intrinsic_callers(
+    store: &PackageStore,
+    unit: &CompileUnit,
+) -> FxHashMap<String, Vec<ItemId>> {
+    let intrinsic_name = |id: ItemId| {
+        callable_decl(store, unit, id)
+            .filter(|decl| matches!(decl.body.body, hir::SpecBody::Gen(hir::SpecGen::Intrinsic)))
+            .map(|decl| decl.name.name.to_string())
+    };
+
+    let mut graph = call_graph(unit);
+    let mut callers: FxHashMap<String, Vec<ItemId>> = graph
+        .keys()
+        .filter_map(|&id| intrinsic_name(id))
+        .map(|name| (name, Vec::new()))
+        .collect();
+    let mut ids = graph.keys().copied().collect::<Vec<_>>();
+    ids.sort_unstable();
+    for caller in ids {
+        if intrinsic_name(caller).is_some() {
+            continue;
+        }
+        let mut seen = FxHashSet::default();
+        let mut stack = vec![caller];
+        while let Some(id) = stack.pop() {
+            if !graph.contains_key(&id) {
+                graph.insert(id, dependency_callees(store, id));
+            }
+            for &callee in &graph[&id] {
+                if seen.insert(callee) {
+                    if let Some(name) = intrinsic_name(callee) {
+                        callers.entry(name).or_default().push(caller);
+                    }
+                    stack.push(callee);
+                }
+            }
+        }
+    }
+    callers
+}
+
+/// Returns the callables that a callable of a dependency refers to, with the items of that
+/// dependency identified by its package, or no callables if `id` is not a callable of a package
+/// in `store`.
+fn dependency_callees(store: &PackageStore, id: ItemId) -> FxHashSet<ItemId> {
+    let Some(package) = id.package else {
+        return FxHashSet::default();
+    };
+    let Some(hir::ItemKind::Callable(decl)) = store
+        .get(package)
+        .and_then(|unit| unit.package.items.get(id.item))
+        .map(|item| &item.kind)
+    else {
+        return FxHashSet::default();
+    };
+    let mut collector = CalleeCollector::default();
+    collector.visit_callable_decl(decl);
+    collector
+        .callees
+        .into_iter()
+        .map(|callee| ItemId {
+            package: callee.package.or(Some(package)),
+            item: callee.item,
+        })
+        .collect()
+}
+
+#[derive(Default)]
+struct CalleeCollector {
+    callees: FxHashSet<ItemId>,
+}
+
+impl<'a> qsc_hir::visit::Visitor<'a> for CalleeCollector {
+    fn visit_expr(&mut self, expr: &'a hir::Expr) {
+        match &expr.kind {
+            hir::ExprKind::Var(hir::Res::Item(id), _) => {
+                // A type name refers to the constructor of the type, which is not a callable.
+                let is_constructor = matches!(
+                    &expr.ty,
+                    Ty::Arrow(arrow) if matches!(&*arrow.output, Ty::Udt(_, hir::Res::Item(udt)) if udt == id)
+                );
+                if !is_constructor {
+                    self.callees.insert(*id);
+                }
+            }
+            hir::ExprKind::Closure(_, item) => {
+                self.callees.insert(ItemId {
+                    package: None,
+                    item: *item,
+                });
+            }
+            _ => {}
+        }
+        qsc_hir::visit::walk_expr(self, expr);
+    }
+}
+
+/// The classification of an identifier in a [`SemanticToken`].
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum SemanticTokenKind {
+    Operation,
+    Function,
+    Type,
+    Parameter,
+    Variable,
+    Namespace,
+}
+
+/// An identifier in the source of a compilation unit, classified by what it refers to.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SemanticToken {
+    /// The name of the source that contains the token.
+    pub source: SourceName,
+    /// The zero-based line of the start of the token.
+    pub line: u32,
+    /// The zero-based column of the start of the token, in the requested encoding.
+    pub column: u32,
+    /// The length of the token, in the requested encoding.
+    pub length: u32,
+    pub kind: SemanticTokenKind,
+}
+
+/// Returns the identifiers in the source of a compilation unit, in source order, classified
+/// by the declarations they resolve to, e.g. for syntax highlighting in an editor.
+///
+/// Columns and lengths are expressed in code units of the given encoding. Identifiers that do
+/// not resolve, as well as field names, are left out. Items referred to as values are classified
+/// by their type, so the name of a type used as a constructor is a type, and the parameters of
+/// callables, specializations and lambdas are distinguished from other local variables.
+#[must_use]
+pub fn semantic_tokens(unit: &CompileUnit, encoding: Encoding) -> Vec<SemanticToken> {
+    let mut collector = SemanticTokenCollector {
+        names: &unit.ast.names,
+        tys: &unit.ast.tys,
+        in_params: false,
+        params: FxHashSet::default(),
+        tokens: Vec::new(),
+    };
+    collector.visit_package(&unit.ast.package);
+    collector.tokens.sort_by_key(|(span, _)| span.lo);
+    collector
+        .tokens
+        .into_iter()
+        .filter_map(|(span, kind)| {
+            let source = unit.sources.find_by_offset(span.lo)?;
+            let range = Range::from_span(
+                encoding,
+                &source.contents,
+                &Span {
+                    lo: span.lo - source.offset,
+                    hi: span.hi - source.offset,
+                },
+            );
+            Some(SemanticToken {
+                source: source.name.clone(),
+                line: range.start.line,
+                column: range.start.column,
+                length: range.end.column - range.start.column,
+                kind,
+            })
+        })
+        .collect()
+}
+
+struct SemanticTokenCollector<'a> {
+    names: &'a Names,
+    tys: &'a Table,
+    in_params: bool,
+    params: FxHashSet<ast::NodeId>,
+    tokens: Vec<(Span, SemanticTokenKind)>,
+}
+
+impl SemanticTokenCollector<'_> {
+    fn push_namespace(&mut self, idents: &ast::Idents) {
+        for ident in idents {
+            self.tokens.push((ident.span, SemanticTokenKind::Namespace));
+        }
+    }
+
+    fn visit_params(&mut self, pat: &ast::Pat) {
+        let in_params = std::mem::replace(&mut self.in_params, true);
+        self.visit_pat(pat);
+        self.in_params = in_params;
+    }
+
+    /// Pushes a path that resolves to an item, classifying its name as `kind`.
+    fn push_item_path(&mut self, path: &ast::Path, kind: SemanticTokenKind) {
+        if let Some(namespace) = &path.namespace {
+            self.push_namespace(namespace);
+        }
+        self.tokens.push((path.name.span, kind));
+    }
+
+    fn push_ty_path(&mut self, path: &ast::Path) {
+        if matches!(
+            self.names.get(path.id),
+            Some(resolve::Res::Item(..) | resolve::Res::PrimTy(_) | resolve::Res::UnitTy)
+        ) {
+            self.push_item_path(path, SemanticTokenKind::Type);
+        }
+    }
+}
+
+impl<'a> qsc_ast::visit::Visitor<'a> for SemanticTokenCollector<'_> {
+    fn visit_namespace(&mut self, namespace: &'a ast::Namespace) {
+        self.push_namespace(&namespace.name);
+        qsc_ast::visit::walk_namespace(self, namespace);
+    }
+
+    fn visit_item(&mut self, item: &'a ast::Item) {
+        match &*item.kind {
+            ast::ItemKind::Open(namespace, alias) => {
+                self.push_namespace(namespace);
+                if let Some(alias) = alias {
+                    self.tokens.push((alias.span, SemanticTokenKind::Namespace));
+                }
+            }
+            ast::ItemKind::Ty(name, def) => {
+                self.tokens.push((name.span, SemanticTokenKind::Type));
+                self.visit_ty_def(def);
+            }
+            _ => qsc_ast::visit::walk_item(self, item),
+        }
+    }
+
+    fn visit_callable_decl(&mut self, decl: &'a ast::CallableDecl) {
+        let kind = match decl.kind {
+            ast::CallableKind::Function => SemanticTokenKind::Function,
+            ast::CallableKind::Operation => SemanticTokenKind::Operation,
+        };
+        self.tokens.push((decl.name.span, kind));
+        for param in &*decl.generics {
+            self.tokens.push((param.span, SemanticTokenKind::Type));
+        }
+        self.visit_params(&decl.input);
+        self.visit_ty(&decl.output);
+        match &*decl.body {
+            ast::CallableBody::Block(block) => self.visit_block(block),
+            ast::CallableBody::Specs(specs) => {
+                for spec in &**specs {
+                    if let ast::SpecBody::Impl(input, block) = &spec.body {
+                        self.visit_params(input);
+                        self.visit_block(block);
+                    }
+                }
+            }
+        }
+    }
+
+    fn visit_struct_decl(&mut self, decl: &'a ast::StructDecl) {
+        self.tokens.push((decl.name.span, SemanticTokenKind::Type));
+        qsc_ast::visit::walk_struct_decl(self, decl);
+    }
+
+    fn visit_ty(&mut self, ty: &'a ast::Ty) {
+        match &*ty.kind {
+            ast::TyKind::Path(path) => self.push_ty_path(path),
+            ast::TyKind::Param(name) => self.tokens.push((name.span, SemanticTokenKind::Type)),
+            _ => qsc_ast::visit::walk_ty(self, ty),
+        }
+    }
+
+    fn visit_expr(&mut self, expr: &'a ast::Expr) {
+        match &*expr.kind {
+            ast::ExprKind::Path(path) => match self.names.get(path.id) {
+                Some(resolve::Res::Item(id, _)) => {
+                    let kind = match self.tys.terms.get(expr.id) {
+                        Some(Ty::Arrow(arrow)) if matches!(&*arrow.output, Ty::Udt(_, hir::Res::Item(udt)) if udt == id) => {
+                            SemanticTokenKind::Type
+                        }
+                        Some(Ty::Arrow(arrow)) if arrow.kind == hir::CallableKind::Operation => {
+                            SemanticTokenKind::Operation
+                        }
+                        _ => SemanticTokenKind::Function,
+                    };
+                    self.push_item_path(path, kind);
+                }
+                Some(resolve::Res::Local(id)) => {
+                    let kind = if self.params.contains(id) {
+                        SemanticTokenKind::Parameter
+                    } else {
+                        SemanticTokenKind::Variable
+                    };
+                    self.tokens.push((path.name.span, kind));
+                }
+                _ => {}
+            },
+            ast::ExprKind::Struct(path, copy, fields) => {
+                self.push_ty_path(path);
+                if let Some(copy) = copy {
+                    self.visit_expr(copy);
+                }
+                for field in &**fields {
+                    self.visit_field_assign(field);
+                }
+            }
+            ast::ExprKind::Lambda(_, input, body) => {
+                self.visit_params(input);
+                self.visit_expr(body);
+            }
+            _ => qsc_ast::visit::walk_expr(self, expr),
+        }
+    }
+
+    fn visit_pat(&mut self, pat: &'a ast::Pat) {
+        if let ast::PatKind::Bind(name, _) = &*pat.kind {
+            let kind = if self.in_params {
+                self.params.insert(name.id);
+                SemanticTokenKind::Parameter
+            } else {
+                SemanticTokenKind::Variable
+            };
+            self.tokens.push((name.span, kind));
+        }
+        qsc_ast::visit::walk_pat(self, pat);
+    }
+
+    fn visit_path(&mut self, path: &'a ast::Path) {
+        // Paths in imports and exports: only the namespace is known to be a namespace, since the
+        // name may refer to a namespace, a callable or a type.
+        if matches!(self.names.get(path.id), Some(resolve::Res::Item(..))) {
+            if let Some(namespace) = &path.namespace {
+                self.push_namespace(namespace);
+            }
+        }
+    }
+}
+
+/// A local binding that shadows another binding of the same name that is in scope.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ShadowEvent {
+    pub name: Rc<str>,
+    /// The span of the name in the binding that is shadowed.
+    pub outer: Span,
+    /// The span of the name in the binding that shadows it.
+    pub shadowing: Span,
+}
+
+/// Returns the local bindings in a compilation unit that shadow another binding, in source order.
+///
+/// Shadowing is legal, both within a block and from a nested block, but tooling may want to point
+/// it out. Callable parameters and the bindings of `for` loops, lambdas and qubit allocations count
+/// as bindings. The variables of a callable are not in scope in the callables declared inside it,
+/// so those cannot be shadowed there.
+#[must_use]
+pub fn shadowing_events(unit: &CompileUnit) -> Vec<ShadowEvent> {
+    let mut finder = ShadowFinder::default();
+    finder.visit_package(&unit.ast.package);
+    finder.events.sort_by_key(|event| event.shadowing.lo);
+    finder.events
+}
+
+#[derive(Default)]
+struct ShadowFinder {
+    /// The bindings in each enclosing scope, innermost last, and whether the scope is that of a
+    /// callable, which cannot see the bindings of the scopes around it.
+    scopes: Vec<(bool, FxHashMap<Rc<str>, Span>)>,
+    events: Vec<ShadowEvent>,
+}
+
+impl ShadowFinder {
+    fn with_scope(&mut self, callable: bool, f: impl FnOnce(&mut Self)) {
+        self.scopes.push((callable, FxHashMap::default()));
+        f(self);
+        self.scopes.pop();
+    }
+
+    fn bind(&mut self, pat: &ast::Pat) {
+        match &*pat.kind {
+            ast::PatKind::Bind(name, _) => {
+                for (callable, vars) in self.scopes.iter().rev() {
+                    if let Some(&outer) = vars.get(&name.name) {
+                        self.events.push(ShadowEvent {
+                            name: name.name.clone(),
+                            outer,
+                            shadowing: name.span,
+                        });
+                        break;
+                    }
+                    if *callable {
+                        break;
+                    }
+                }
+                if let Some((_, vars)) = self.scopes.last_mut() {
+                    vars.insert(name.name.clone(), name.span);
+                }
+            }
+            ast::PatKind::Paren(pat) => self.bind(pat),
+            ast::PatKind::Tuple(pats) => pats.iter().for_each(|pat| self.bind(pat)),
+            ast::PatKind::Discard(_) | ast::PatKind::Elided | ast::PatKind::Err => {}
+        }
+    }
+}
+
+impl<'a> qsc_ast::visit::Visitor<'a> for ShadowFinder {
+    fn visit_callable_decl(&mut self, decl: &'a ast::CallableDecl) {
+        self.with_scope(true, |finder| {
+            finder.bind(&decl.input);
+            match &*decl.body {
+                ast::CallableBody::Block(block) => finder.visit_block(block),
+                ast::CallableBody::Specs(specs) => {
+                    for spec in &**specs {
+                        if let ast::SpecBody::Impl(input, block) = &spec.body {
+                            finder.with_scope(false, |finder| {
+                                finder.bind(input);
+                                finder.visit_block(block);
+                            });
+                        }
+                    }
+                }
+            }
+        });
+    }
+
+    fn visit_block(&mut self, block: &'a ast::Block) {
+        self.with_scope(false, |finder| qsc_ast::visit::walk_block(finder, block));
+    }
+
+    fn visit_stmt(&mut self, stmt: &'a ast::Stmt) {
+        match &*stmt.kind {
+            ast::StmtKind::Local(_, pat, value) => {
+                self.visit_expr(value);
+                self.bind(pat);
+            }
+            ast::StmtKind::Qubit(_, pat, init, block) => {
+                self.visit_qubit_init(init);
+                match block {
+                    Some(block) => self.with_scope(false, |finder| {
+                        finder.bind(pat);
+                        finder.visit_block(block);
+                    }),
+                    None => self.bind(pat),
+                }
+            }
+            _ => qsc_ast::visit::walk_stmt(self, stmt),
+        }
+    }
+
+    fn visit_expr(&mut self, expr: &'a ast::Expr) {
+        match &*expr.kind {
+            ast::ExprKind::For(pat, iter, block) => {
+                self.visit_expr(iter);
+                self.with_scope(false, |finder| {
+                    finder.bind(pat);
+                    finder.visit_block(block);
+                });
+            }
+            ast::ExprKind::Lambda(_, input, body) => self.with_scope(false, |finder| {
+                finder.bind(input);
+                finder.visit_expr(body);
+            }),
+            _ => qsc_ast::visit::walk_expr(self, expr),
+        }
+    }
+}
+
 fn parse_all(
     sources: &SourceMap,
     features: LanguageFeatures,
@@ -499,6 +1593,7 @@ fn resolve_all(
     assigner: &mut HirAssigner,
     package: &ast::Package,
     mut dropped_names: Vec<TrackedName>,
+    strict_prelude: bool,
 ) -> (Names, Locals, Vec<resolve::Error>) {
     let mut globals = resolve::GlobalTable::new();
     if let Some(unit) = store.get(PackageId::CORE) {
@@ -517,6 +1612,7 @@ fn resolve_all(
     // bind all symbols in `add_local_package`
     let mut errors = globals.add_local_package(assigner, package);
     let mut resolver = Resolver::new(globals, dropped_names);
+    resolver.set_strict_prelude(strict_prelude);
 
     // bind all exported symbols in a follow-on step
     resolver.bind_and_resolve_imports_and_exports(package);
@@ -3,10 +3,12 @@
 
 use core::str::FromStr;
 use qsc_ast::{
-    ast::{Attr, ExprKind, ItemKind, Namespace, Stmt, StmtKind, UnOp},
+    ast::{Attr, Expr, ExprKind, ItemKind, Lit, Namespace, Stmt, StmtKind, UnOp},
     mut_visit::MutVisitor,
 };
+use qsc_data_structures::span::Span;
 use qsc_hir::hir;
+use rustc_hash::FxHashSet;
 use std::rc::Rc;
 
 use super::TargetCapabilityFlags;
@@ -20,21 +22,55 @@ pub struct TrackedName {
     pub namespace: Rc<str>,
 }
 
+/// The capabilities named by the `@Config` attributes of an item, either as required or, negated
+/// with `not`, as excluded. `Base` names no capabilities, so it is not recorded in either set.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct ConfigCapabilities {
+    pub required: TargetCapabilityFlags,
+    pub excluded: TargetCapabilityFlags,
+}
+
+/// A global item removed by conditional compilation.
+#[derive(Clone, Debug)]
+pub struct DroppedItem {
+    pub name: TrackedName,
+    /// The capabilities named by the item's `@Config` attributes.
+    pub capabilities: ConfigCapabilities,
+    /// The span of the item's name.
+    pub span: Span,
+}
+
 pub(crate) struct Conditional {
     capabilities: TargetCapabilityFlags,
+    features: FxHashSet<Rc<str>>,
     dropped_names: Vec<TrackedName>,
+    dropped_items: Vec<DroppedItem>,
     included_names: Vec<TrackedName>,
 }
 
 impl Conditional {
     pub(crate) fn new(capabilities: TargetCapabilityFlags) -> Self {
+        Self::with_features(capabilities, &[])
+    }
+
+    /// Creates a conditional compilation visitor where items gated by
+    /// `@Config(Feature("name"))` are kept only when `name` is in `features`.
+    pub(crate) fn with_features(capabilities: TargetCapabilityFlags, features: &[&str]) -> Self {
         Self {
             capabilities,
+            features: features.iter().map(|&f| f.into()).collect(),
             dropped_names: Vec::new(),
+            dropped_items: Vec::new(),
             included_names: Vec::new(),
         }
     }
 
+    /// Takes the global items dropped so far, including those for which another declaration
+    /// of the same name was kept.
+    pub(crate) fn take_dropped_items(&mut self) -> Vec<DroppedItem> {
+        std::mem::take(&mut self.dropped_items)
+    }
+
     pub(crate) fn into_names(self) -> Vec<TrackedName> {
         self.dropped_names
             .into_iter()
@@ -49,7 +85,7 @@ impl MutVisitor for Conditional {
             .items
             .iter()
             .filter_map(|item| {
-                if matches_config(&item.attrs, self.capabilities) {
+                if matches_config(&item.attrs, self.capabilities, &self.features) {
                     match item.kind.as_ref() {
                         ItemKind::Callable(callable) => {
                             self.included_names.push(TrackedName {
@@ -65,18 +101,22 @@ impl MutVisitor for Conditional {
                     }
                     Some(item.clone())
                 } else {
-                    match item.kind.as_ref() {
-                        ItemKind::Callable(callable) => {
-                            self.dropped_names.push(TrackedName {
-                                name: callable.name.name.clone(),
-                                namespace: namespace.name.name(),
-                            });
-                        }
-                        ItemKind::Ty(ident, _) => self.dropped_names.push(TrackedName {
+                    let ident = match item.kind.as_ref() {
+                        ItemKind::Callable(callable) => Some(&callable.name),
+                        ItemKind::Ty(ident, _) => Some(ident),
+                        _ => None,
+                    };
+                    if let Some(ident) = ident {
+                        let name = TrackedName {
                             name: ident.name.clone(),
                             namespace: namespace.name.name(),
-                        }),
-                        _ => {}
+                        };
+                        self.dropped_items.push(DroppedItem {
+                            name: name.clone(),
+                            capabilities: config_capabilities(&item.attrs),
+                            span: ident.span,
+                        });
+                        self.dropped_names.push(name);
                     }
                     None
                 }
@@ -87,7 +127,7 @@ impl MutVisitor for Conditional {
 
     fn visit_stmt(&mut self, stmt: &mut Stmt) {
         if let StmtKind::Item(item) = stmt.kind.as_mut() {
-            if matches_config(&item.attrs, self.capabilities) {
+            if matches_config(&item.attrs, self.capabilities, &self.features) {
                 match item.kind.as_ref() {
                     ItemKind::Callable(callable) => {
                         self.included_names.push(TrackedName {
@@ -121,7 +161,41 @@ impl MutVisitor for Conditional {
     }
 }
 
-fn matches_config(attrs: &[Box<Attr>], capabilities: TargetCapabilityFlags) -> bool {
+/// Returns the capabilities named by the `@Config` attributes in `attrs`. Feature flags are
+/// ignored, so an item without a capability gate yields no capabilities.
+pub(crate) fn config_capabilities(attrs: &[Box<Attr>]) -> ConfigCapabilities {
+    let capability = |expr: &Expr| match expr.kind.as_ref() {
+        ExprKind::Path(path) => TargetCapabilityFlags::from_str(path.name.name.as_ref()).ok(),
+        _ => None,
+    };
+    let mut capabilities = ConfigCapabilities::default();
+    for attr in attrs
+        .iter()
+        .filter(|attr| hir::Attr::from_str(attr.name.name.as_ref()) == Ok(hir::Attr::Config))
+    {
+        if let ExprKind::Paren(inner) = attr.arg.kind.as_ref() {
+            match inner.kind.as_ref() {
+                ExprKind::UnOp(UnOp::NotL, negated) => {
+                    if let Some(excluded) = capability(negated) {
+                        capabilities.excluded |= excluded;
+                    }
+                }
+                _ => {
+                    if let Some(required) = capability(inner) {
+                        capabilities.required |= required;
+                    }
+                }
+            }
+        }
+    }
+    capabilities
+}
+
+fn matches_config(
+    attrs: &[Box<Attr>],
+    capabilities: TargetCapabilityFlags,
+    features: &FxHashSet<Rc<str>>,
+) -> bool {
     let attrs: Vec<_> = attrs
         .iter()
         .filter(|attr| hir::Attr::from_str(attr.name.name.as_ref()) == Ok(hir::Attr::Config))
@@ -134,6 +208,8 @@ fn matches_config(attrs: &[Box<Attr>], capabilities: TargetCapabilityFlags) -> b
     let mut disallowed_capabilities = TargetCapabilityFlags::empty();
     let mut base = false;
     let mut not_base = false;
+    let mut has_capability_config = false;
+    let mut features_match = true;
 
     // When checking attributes, anything we don't recognize (invalid form or invalid capability) gets
     // left in the compilation by returning true. This ensures that later compilation steps, specifically lowering
@@ -148,10 +224,18 @@ fn matches_config(attrs: &[Box<Attr>], capabilities: TargetCapabilityFlags) -> b
                             base = true;
                         }
                         found_capabilities |= capability;
+                        has_capability_config = true;
                     } else {
                         return true; // Unknown capability, so we assume it matches
                     }
                 }
+                ExprKind::Call(..) => {
+                    if let Some(feature) = feature_name(inner) {
+                        features_match &= features.contains(feature);
+                    } else {
+                        return true; // Unknown config attribute, so we assume it matches
+                    }
+                }
                 ExprKind::UnOp(UnOp::NotL, inner) => {
                     if let ExprKind::Path(path) = inner.kind.as_ref() {
                         if let Ok(capability) =
@@ -161,9 +245,12 @@ fn matches_config(attrs: &[Box<Attr>], capabilities: TargetCapabilityFlags) -> b
                                 not_base = true;
                             }
                             disallowed_capabilities |= capability;
+                            has_capability_config = true;
                         } else {
                             return true; // Unknown capability, so we assume it matches
                         }
+                    } else if let Some(feature) = feature_name(inner) {
+                        features_match &= !features.contains(feature);
                     } else {
                         return true; // Unknown config attribute, so we assume it matches
                     }
@@ -175,6 +262,13 @@ fn matches_config(attrs: &[Box<Attr>], capabilities: TargetCapabilityFlags) -> b
             return true;
         }
     }
+    if !features_match {
+        return false;
+    }
+    if !has_capability_config {
+        // Only feature flags were specified, and they all matched.
+        return true;
+    }
     if found_capabilities.is_empty() && disallowed_capabilities.is_empty() {
         if not_base && !base {
             // There was at least one config attribute, but it was "not Base" so
@@ -193,3 +287,23 @@ fn matches_config(attrs: &[Box<Attr>], capabilities: TargetCapabilityFlags) -> b
     capabilities.contains(found_capabilities)
         && (disallowed_capabilities.is_empty() || !capabilities.contains(disallowed_capabilities))
 }
+
+/// Gets the flag name from a config of the form `Feature("name")`.
+pub(crate) fn feature_name(expr: &Expr) -> Option<&str> {
+    let ExprKind::Call(callee, arg) = expr.kind.as_ref() else {
+        return None;
+    };
+    match (callee.kind.as_ref(), arg.kind.as_ref()) {
+        (ExprKind::Path(path), ExprKind::Paren(arg))
+            if path.namespace.is_none() && path.name.name.as_ref() == "Feature" =>
+        {
+            if let ExprKind::Lit(lit) = arg.kind.as_ref() {
+                if let Lit::String(name) = lit.as_ref() {
+                    return Some(name);
+                }
+            }
+            None
+        }
+        _ => None,
+    }
+}
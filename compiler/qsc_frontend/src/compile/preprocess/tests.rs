@@ -1,8 +1,10 @@
 // Copyright (c) Microsoft Corporation.
 // Licensed under the MIT License.
 
-use qsc_ast::ast::{Attr, Expr, ExprKind, Ident, NodeId, Path};
+use qsc_ast::ast::{Attr, Expr, ExprKind, Ident, Lit, NodeId, Path, UnOp};
 use qsc_data_structures::span::Span;
+use rustc_hash::FxHashSet;
+use std::rc::Rc;
 
 use crate::compile::{preprocess::matches_config, TargetCapabilityFlags};
 
@@ -55,14 +57,19 @@ fn name_value_attr(name: &str, value: &str) -> Attr {
 
 #[test]
 fn no_attrs_matches() {
-    assert!(matches_config(&[], TargetCapabilityFlags::empty()));
+    assert!(matches_config(
+        &[],
+        TargetCapabilityFlags::empty(),
+        &FxHashSet::default()
+    ));
 }
 
 #[test]
 fn unknown_attrs_matches() {
     assert!(matches_config(
         &[Box::new(named_attr("unknown"))],
-        TargetCapabilityFlags::empty()
+        TargetCapabilityFlags::empty(),
+        &FxHashSet::default()
     ));
 }
 
@@ -70,7 +77,8 @@ fn unknown_attrs_matches() {
 fn none_attrs_matches_empty() {
     assert!(matches_config(
         &[Box::new(name_value_attr("Config", "Base"))],
-        TargetCapabilityFlags::empty()
+        TargetCapabilityFlags::empty(),
+        &FxHashSet::default()
     ));
 }
 
@@ -78,7 +86,8 @@ fn none_attrs_matches_empty() {
 fn none_attrs_does_not_match_all() {
     assert!(!matches_config(
         &[Box::new(name_value_attr("Config", "Base"))],
-        TargetCapabilityFlags::all()
+        TargetCapabilityFlags::all(),
+        &FxHashSet::default()
     ));
 }
 
@@ -86,7 +95,8 @@ fn none_attrs_does_not_match_all() {
 fn none_attrs_does_not_match_adaptive() {
     assert!(!matches_config(
         &[Box::new(name_value_attr("Config", "Base"))],
-        TargetCapabilityFlags::Adaptive
+        TargetCapabilityFlags::Adaptive,
+        &FxHashSet::default()
     ));
 }
 
@@ -94,7 +104,8 @@ fn none_attrs_does_not_match_adaptive() {
 fn adaptive_attrs_does_not_match_empty() {
     assert!(!matches_config(
         &[Box::new(name_value_attr("Config", "Adaptive"))],
-        TargetCapabilityFlags::empty()
+        TargetCapabilityFlags::empty(),
+        &FxHashSet::default()
     ));
 }
 
@@ -102,7 +113,8 @@ fn adaptive_attrs_does_not_match_empty() {
 fn integercomputations_attrs_does_not_match_empty() {
     assert!(!matches_config(
         &[Box::new(name_value_attr("Config", "IntegerComputations"))],
-        TargetCapabilityFlags::empty()
+        TargetCapabilityFlags::empty(),
+        &FxHashSet::default()
     ));
 }
 
@@ -113,7 +125,8 @@ fn floatingpointcomputations_attrs_does_not_match_empty() {
             "Config",
             "FloatingPointComputations"
         ))],
-        TargetCapabilityFlags::empty()
+        TargetCapabilityFlags::empty(),
+        &FxHashSet::default()
     ));
 }
 
@@ -121,7 +134,8 @@ fn floatingpointcomputations_attrs_does_not_match_empty() {
 fn unrestricted_attrs_does_not_match_empty() {
     assert!(!matches_config(
         &[Box::new(name_value_attr("Config", "Unrestricted"))],
-        TargetCapabilityFlags::empty()
+        TargetCapabilityFlags::empty(),
+        &FxHashSet::default()
     ));
 }
 
@@ -129,6 +143,116 @@ fn unrestricted_attrs_does_not_match_empty() {
 fn unrestricted_attrs_matches_all() {
     assert!(matches_config(
         &[Box::new(name_value_attr("Config", "Unrestricted"))],
-        TargetCapabilityFlags::all()
+        TargetCapabilityFlags::all(),
+        &FxHashSet::default()
+    ));
+}
+
+fn feature_attr(feature: &str, negated: bool) -> Attr {
+    let lit = Expr {
+        id: NodeId::default(),
+        span: Span::default(),
+        kind: Box::new(ExprKind::Lit(Box::new(Lit::String(feature.into())))),
+    };
+    let call = Expr {
+        id: NodeId::default(),
+        span: Span::default(),
+        kind: Box::new(ExprKind::Call(
+            Box::new(Expr {
+                id: NodeId::default(),
+                span: Span::default(),
+                kind: Box::new(ExprKind::Path(Box::new(Path {
+                    id: NodeId::default(),
+                    span: Span::default(),
+                    namespace: None,
+                    name: Box::new(Ident {
+                        name: "Feature".into(),
+                        span: Span::default(),
+                        id: NodeId::default(),
+                    }),
+                }))),
+            }),
+            Box::new(Expr {
+                id: NodeId::default(),
+                span: Span::default(),
+                kind: Box::new(ExprKind::Paren(Box::new(lit))),
+            }),
+        )),
+    };
+    let inner = if negated {
+        Expr {
+            id: NodeId::default(),
+            span: Span::default(),
+            kind: Box::new(ExprKind::UnOp(UnOp::NotL, Box::new(call))),
+        }
+    } else {
+        call
+    };
+    Attr {
+        name: Box::new(Ident {
+            name: "Config".into(),
+            span: Span::default(),
+            id: NodeId::default(),
+        }),
+        arg: Box::new(Expr {
+            id: NodeId::default(),
+            span: Span::default(),
+            kind: Box::new(ExprKind::Paren(Box::new(inner))),
+        }),
+        span: Span::default(),
+        id: NodeId::default(),
+    }
+}
+
+#[test]
+fn feature_attrs_match_enabled_feature() {
+    let features = FxHashSet::from_iter([Rc::from("experimental")]);
+    assert!(matches_config(
+        &[Box::new(feature_attr("experimental", false))],
+        TargetCapabilityFlags::all(),
+        &features
+    ));
+    assert!(!matches_config(
+        &[Box::new(feature_attr("experimental", true))],
+        TargetCapabilityFlags::all(),
+        &features
+    ));
+}
+
+#[test]
+fn feature_attrs_do_not_match_disabled_feature() {
+    assert!(!matches_config(
+        &[Box::new(feature_attr("experimental", false))],
+        TargetCapabilityFlags::all(),
+        &FxHashSet::default()
+    ));
+    assert!(matches_config(
+        &[Box::new(feature_attr("experimental", true))],
+        TargetCapabilityFlags::all(),
+        &FxHashSet::default()
+    ));
+}
+
+#[test]
+fn feature_and_capability_attrs_must_both_match() {
+    let features = FxHashSet::from_iter([Rc::from("experimental")]);
+    let attrs = [
+        Box::new(feature_attr("experimental", false)),
+        Box::new(name_value_attr("Config", "Adaptive")),
+    ];
+    assert!(matches_config(
+        &attrs,
+        TargetCapabilityFlags::Adaptive,
+        &features
+    ));
+    assert!(!matches_config(
+        &attrs,
+        TargetCapabilityFlags::empty(),
+        &features
+    ));
+    assert!(!matches_config(
+        &attrs,
+        TargetCapabilityFlags::Adaptive,
+        &FxHashSet::default()
     ));
 }
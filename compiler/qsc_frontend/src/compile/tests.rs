@@ -3,13 +3,19 @@
 
 #![allow(clippy::needless_raw_string_hashes)]
 
-use super::{compile, longest_common_prefix, CompileUnit, Error, PackageStore, SourceMap};
+use super::{
+    call_graph, compile, compile_lenient, compile_timed, compile_with_features,
+    compile_with_strict_prelude, extract_string_literals, functor_support, holes,
+    intrinsic_callers, longest_common_prefix, references_to, semantic_tokens, shadowing_events,
+    symbol_table, type_at_span, used_language_features, CompileUnit, Error, Functors, PackageStore,
+    SourceMap, UsedSyntax,
+};
 use crate::compile::TargetCapabilityFlags;
 
 use expect_test::expect;
 use indoc::indoc;
 use miette::Diagnostic;
-use qsc_data_structures::{language_features::LanguageFeatures, span::Span};
+use qsc_data_structures::{language_features::LanguageFeatures, line_column::Encoding, span::Span};
 use qsc_hir::{
     global,
     hir::{
@@ -19,6 +25,7 @@ use qsc_hir::{
     mut_visit::MutVisitor,
     ty::{Prim, Ty},
 };
+use std::time::{Duration, Instant};
 
 fn error_span(error: &Error) -> Span {
     let label = error
@@ -856,6 +863,64 @@ fn introduce_prelude_ambiguity() {
     );
 }
 
+fn compile_feature_gated_source(features: &[&str]) -> CompileUnit {
+    let sources = SourceMap::new(
+        [(
+            "test".into(),
+            indoc! {r#"
+                namespace Foo {
+                    @Config(Feature("experimental"))
+                    function Experimental() : Int { 1 }
+
+                    function Main() : Int { Experimental() }
+                }
+            "#}
+            .into(),
+        )],
+        None,
+    );
+
+    compile_with_features(
+        &PackageStore::new(super::core()),
+        &[],
+        sources,
+        TargetCapabilityFlags::all(),
+        LanguageFeatures::default(),
+        features,
+    )
+}
+
+#[test]
+fn feature_gated_callable_dropped_when_feature_disabled() {
+    let unit = compile_feature_gated_source(&[]);
+    let errors: Vec<Error> = unit.errors;
+    assert!(
+        errors.len() == 1
+            && matches!(
+                errors[0],
+                Error(super::ErrorKind::Resolve(
+                    super::resolve::Error::NotAvailable(..)
+                ))
+            ),
+        "{errors:#?}"
+    );
+    assert!(unit
+        .dropped_names
+        .iter()
+        .any(|n| n.name.as_ref() == "Experimental"));
+}
+
+#[test]
+fn feature_gated_callable_retained_when_feature_enabled() {
+    let unit = compile_feature_gated_source(&["experimental"]);
+    assert!(unit.errors.is_empty(), "{:#?}", unit.errors);
+    assert!(unit.dropped_names.is_empty());
+    assert!(unit.package.items.values().any(|item| matches!(
+        &item.kind,
+        ItemKind::Callable(decl) if decl.name.name.as_ref() == "Experimental"
+    )));
+}
+
 #[test]
 fn entry_parse_error() {
     let sources = SourceMap::new(
@@ -1468,3 +1533,1222 @@ fn test_longest_common_prefix_only_root_common() {
 fn test_longest_common_prefix_only_root_common_no_leading() {
     expect![""].assert_eq(longest_common_prefix(&["a/b", "b/c"]));
 }
+
+#[test]
+fn symbol_table_lists_declarations_and_fields() {
+    let sources = SourceMap::new(
+        [(
+            "test".into(),
+            indoc! {"
+                namespace Foo.Bar {
+                    struct Pair { First : Int, Second : Double }
+                    internal newtype Complex = (Real : Double, Imag : Double);
+                    operation Apply(p : Pair) : Unit {}
+                    internal function Helper() : Int { 0 }
+                }
+            "}
+            .into(),
+        )],
+        None,
+    );
+    let unit = default_compile(sources);
+    assert!(unit.errors.is_empty(), "{:#?}", unit.errors);
+    expect![[r#"
+        [
+            SymbolEntry {
+                name: "Foo.Bar.Pair",
+                kind: Struct,
+                span: Span {
+                    lo: 24,
+                    hi: 68,
+                },
+                visibility: Public,
+            },
+            SymbolEntry {
+                name: "Foo.Bar.Pair.First",
+                kind: Field,
+                span: Span {
+                    lo: 38,
+                    hi: 49,
+                },
+                visibility: Public,
+            },
+            SymbolEntry {
+                name: "Foo.Bar.Pair.Second",
+                kind: Field,
+                span: Span {
+                    lo: 51,
+                    hi: 66,
+                },
+                visibility: Public,
+            },
+            SymbolEntry {
+                name: "Foo.Bar.Complex",
+                kind: Newtype,
+                span: Span {
+                    lo: 73,
+                    hi: 131,
+                },
+                visibility: Internal,
+            },
+            SymbolEntry {
+                name: "Foo.Bar.Complex.Real",
+                kind: Field,
+                span: Span {
+                    lo: 101,
+                    hi: 114,
+                },
+                visibility: Internal,
+            },
+            SymbolEntry {
+                name: "Foo.Bar.Complex.Imag",
+                kind: Field,
+                span: Span {
+                    lo: 116,
+                    hi: 129,
+                },
+                visibility: Internal,
+            },
+            SymbolEntry {
+                name: "Foo.Bar.Apply",
+                kind: Operation,
+                span: Span {
+                    lo: 136,
+                    hi: 171,
+                },
+                visibility: Public,
+            },
+            SymbolEntry {
+                name: "Foo.Bar.Helper",
+                kind: Function,
+                span: Span {
+                    lo: 176,
+                    hi: 214,
+                },
+                visibility: Internal,
+            },
+        ]
+    "#]]
+    .assert_debug_eq(&symbol_table(&unit));
+}
+
+#[test]
+fn used_language_features_ignores_qubit_allocation_without_block() {
+    let sources = SourceMap::new(
+        [(
+            "test".into(),
+            indoc! {"
+                namespace Foo {
+                    operation Main() : Unit {
+                        use q = Qubit();
+                    }
+                }
+            "}
+            .into(),
+        )],
+        None,
+    );
+    let unit = compile(
+        &PackageStore::new(super::core()),
+        &[],
+        sources,
+        TargetCapabilityFlags::all(),
+        LanguageFeatures::V2PreviewSyntax,
+    );
+    assert!(unit.errors.is_empty(), "{:#?}", unit.errors);
+    assert_eq!(used_language_features(&unit), UsedSyntax::default());
+}
+
+#[test]
+fn used_language_features_reports_struct_and_glob_syntax() {
+    let sources = SourceMap::new(
+        [(
+            "test".into(),
+            indoc! {"
+                namespace Foo.Bar {
+                    struct Pair { First : Int, Second : Int }
+                }
+                namespace Foo {
+                    import Foo.Bar.*;
+                    function Make() : Pair { new Pair { First = 1, Second = 2 } }
+                }
+            "}
+            .into(),
+        )],
+        None,
+    );
+    let unit = compile(
+        &PackageStore::new(super::core()),
+        &[],
+        sources,
+        TargetCapabilityFlags::all(),
+        LanguageFeatures::all(),
+    );
+    assert!(unit.errors.is_empty(), "{:#?}", unit.errors);
+    assert_eq!(
+        used_language_features(&unit),
+        UsedSyntax {
+            struct_syntax: true,
+            glob_imports: true,
+        }
+    );
+}
+
+#[test]
+fn used_language_features_reports_struct_syntax_without_glob_import() {
+    let sources = SourceMap::new(
+        [(
+            "test".into(),
+            indoc! {"
+                namespace Foo.Bar {
+                    struct Pair { First : Int, Second : Int }
+                }
+                namespace Foo {
+                    import Foo.Bar.Pair;
+                    function Make() : Pair { new Pair { First = 1, Second = 2 } }
+                }
+            "}
+            .into(),
+        )],
+        None,
+    );
+    let unit = compile(
+        &PackageStore::new(super::core()),
+        &[],
+        sources,
+        TargetCapabilityFlags::all(),
+        LanguageFeatures::all(),
+    );
+    assert!(unit.errors.is_empty(), "{:#?}", unit.errors);
+    assert_eq!(
+        used_language_features(&unit),
+        UsedSyntax {
+            struct_syntax: true,
+            glob_imports: false,
+        }
+    );
+}
+
+#[test]
+fn used_language_features_reports_glob_import_without_struct_syntax() {
+    let sources = SourceMap::new(
+        [(
+            "test".into(),
+            indoc! {"
+                namespace Foo.Bar {
+                    function Two() : Int { 2 }
+                }
+                namespace Foo {
+                    import Foo.Bar.*;
+                    function Four() : Int { 2 * Two() }
+                }
+            "}
+            .into(),
+        )],
+        None,
+    );
+    let unit = compile(
+        &PackageStore::new(super::core()),
+        &[],
+        sources,
+        TargetCapabilityFlags::all(),
+        LanguageFeatures::all(),
+    );
+    assert!(unit.errors.is_empty(), "{:#?}", unit.errors);
+    assert_eq!(
+        used_language_features(&unit),
+        UsedSyntax {
+            struct_syntax: false,
+            glob_imports: true,
+        }
+    );
+}
+
+#[test]
+fn compile_lenient_lowers_valid_items_and_marks_unresolved_references() {
+    let sources = SourceMap::new(
+        [(
+            "test".into(),
+            indoc! {"
+                namespace Foo {
+                    function Broken() : Int { Missing() + 1 }
+                    function Valid() : Int { Broken() }
+                }
+            "}
+            .into(),
+        )],
+        None,
+    );
+    let unit = compile_lenient(
+        &PackageStore::new(super::core()),
+        &[],
+        sources,
+        TargetCapabilityFlags::all(),
+        LanguageFeatures::default(),
+    );
+    expect![[r#"
+        [
+            Error(
+                Resolve(
+                    NotFound(
+                        "Missing",
+                        Span {
+                            lo: 46,
+                            hi: 53,
+                        },
+                    ),
+                ),
+            ),
+        ]
+    "#]]
+    .assert_debug_eq(&unit.errors);
+    expect![[r#"
+        Package:
+            Item 0 [0-103] (Public):
+                Namespace (Ident 20 [10-13] "Foo"): Item 1, Item 2
+            Item 1 [20-61] (Public):
+                Parent: 0
+                Callable 0 [20-61] (function):
+                    name: Ident 1 [29-35] "Broken"
+                    input: Pat 2 [35-37] [Type Unit]: Unit
+                    output: Int
+                    functors: empty set
+                    body: SpecDecl 3 [20-61]: Impl:
+                        Block 4 [44-61] [Type Int]:
+                            Stmt 5 [46-59]: Expr: Expr 6 [46-59] [Type Int]: BinOp (Add):
+                                Expr 7 [46-55] [Type Int]: Call:
+                                    Expr 8 [46-53] [Type ?]: Hole
+                                    Expr 9 [53-55] [Type Unit]: Unit
+                                Expr 10 [58-59] [Type Int]: Lit: Int(1)
+                    adj: <none>
+                    ctl: <none>
+                    ctl-adj: <none>
+            Item 2 [66-101] (Public):
+                Parent: 0
+                Callable 11 [66-101] (function):
+                    name: Ident 12 [75-80] "Valid"
+                    input: Pat 13 [80-82] [Type Unit]: Unit
+                    output: Int
+                    functors: empty set
+                    body: SpecDecl 14 [66-101]: Impl:
+                        Block 15 [89-101] [Type Int]:
+                            Stmt 16 [91-99]: Expr: Expr 17 [91-99] [Type Int]: Call:
+                                Expr 18 [91-97] [Type (Unit -> Int)]: Var: Item 1
+                                Expr 19 [97-99] [Type Unit]: Unit
+                    adj: <none>
+                    ctl: <none>
+                    ctl-adj: <none>"#]]
+    .assert_eq(&unit.package.to_string());
+}
+
+#[test]
+fn extract_string_literals_finds_message_and_fail_strings() {
+    let mut store = PackageStore::new(super::core());
+    let std = store.insert(super::std(&store, TargetCapabilityFlags::all()));
+    let sources = SourceMap::new(
+        [(
+            "test".into(),
+            indoc! {r#"
+                namespace Foo {
+                    open Microsoft.Quantum.Intrinsic;
+
+                    operation Main(count : Int) : Unit {
+                        Message("Starting");
+                        if count < 0 {
+                            fail $"Invalid count {count}.";
+                        }
+                    }
+                }
+            "#}
+            .into(),
+        )],
+        None,
+    );
+    let unit = compile(
+        &store,
+        &[std],
+        sources,
+        TargetCapabilityFlags::all(),
+        LanguageFeatures::default(),
+    );
+    assert!(unit.errors.is_empty(), "{:#?}", unit.errors);
+    expect![[r#"
+        [
+            (
+                "Starting",
+                Span {
+                    lo: 112,
+                    hi: 122,
+                },
+            ),
+            (
+                "Invalid count ",
+                Span {
+                    lo: 165,
+                    hi: 190,
+                },
+            ),
+            (
+                ".",
+                Span {
+                    lo: 165,
+                    hi: 190,
+                },
+            ),
+        ]
+    "#]]
+    .assert_debug_eq(&extract_string_literals(&unit));
+}
+
+/// Returns the call graph of the unit with callables identified by name, sorted for stable output.
+fn named_call_graph(unit: &CompileUnit) -> Vec<(String, Vec<String>)> {
+    let name = |id: &ItemId| {
+        assert!(id.package.is_none(), "callee should be local");
+        match &unit
+            .package
+            .items
+            .get(id.item)
+            .expect("item should exist")
+            .kind
+        {
+            ItemKind::Callable(decl) => decl.name.name.to_string(),
+            kind => panic!("expected callable, found {kind}"),
+        }
+    };
+    let mut graph = call_graph(unit)
+        .iter()
+        .map(|(caller, callees)| {
+            let mut callees = callees.iter().map(name).collect::<Vec<_>>();
+            callees.sort();
+            (name(caller), callees)
+        })
+        .collect::<Vec<_>>();
+    graph.sort();
+    graph
+}
+
+#[test]
+fn call_graph_local_function_use_before_declare() {
+    let sources = SourceMap::new(
+        [(
+            "test".into(),
+            indoc! {"
+                namespace A {
+                    function Foo() : () {
+                        Bar();
+                        function Bar() : () {}
+                    }
+                }
+            "}
+            .into(),
+        )],
+        None,
+    );
+    let unit = default_compile(sources);
+    assert!(unit.errors.is_empty(), "{:#?}", unit.errors);
+    expect![[r#"
+        [
+            (
+                "Bar",
+                [],
+            ),
+            (
+                "Foo",
+                [
+                    "Bar",
+                ],
+            ),
+        ]
+    "#]]
+    .assert_debug_eq(&named_call_graph(&unit));
+}
+
+#[test]
+fn call_graph_use_bound_item_in_another_bound_item() {
+    let sources = SourceMap::new(
+        [(
+            "test".into(),
+            indoc! {"
+                namespace A {
+                    function B() : Unit {
+                        function C() : Unit {
+                            D();
+                        }
+                        function D() : Unit {}
+                    }
+                }
+            "}
+            .into(),
+        )],
+        None,
+    );
+    let unit = default_compile(sources);
+    assert!(unit.errors.is_empty(), "{:#?}", unit.errors);
+    expect![[r#"
+        [
+            (
+                "B",
+                [],
+            ),
+            (
+                "C",
+                [
+                    "D",
+                ],
+            ),
+            (
+                "D",
+                [],
+            ),
+        ]
+    "#]]
+    .assert_debug_eq(&named_call_graph(&unit));
+}
+
+#[test]
+fn call_graph_includes_callables_passed_as_values_and_lambdas() {
+    let sources = SourceMap::new(
+        [(
+            "test".into(),
+            indoc! {"
+                namespace A {
+                    newtype Wrapper = Int;
+                    function Apply(f : Int -> Int, x : Int) : Int { f(x) }
+                    function Double(x : Int) : Int { x * 2 }
+                    function Recurse(x : Int) : Int { x > 0 ? Recurse(x - 1) | 0 }
+                    function Main() : Wrapper {
+                        let y = Apply(Double, 1);
+                        Wrapper(Apply(x -> Recurse(x), y))
+                    }
+                }
+            "}
+            .into(),
+        )],
+        None,
+    );
+    let unit = default_compile(sources);
+    assert!(unit.errors.is_empty(), "{:#?}", unit.errors);
+    expect![[r#"
+        [
+            (
+                "Apply",
+                [],
+            ),
+            (
+                "Double",
+                [],
+            ),
+            (
+                "Main",
+                [
+                    "Apply",
+                    "Double",
+                    "lambda",
+                ],
+            ),
+            (
+                "Recurse",
+                [
+                    "Recurse",
+                ],
+            ),
+            (
+                "lambda",
+                [
+                    "Recurse",
+                ],
+            ),
+        ]
+    "#]]
+    .assert_debug_eq(&named_call_graph(&unit));
+}
+
+#[test]
+fn type_at_span_reports_innermost_expression_type() {
+    let source = indoc! {"
+        namespace Test {
+            function Square(x : Int) : Int { x * x }
+            operation Main() : Unit {
+                use qubit = Qubit();
+                let n = 42;
+                let alias = qubit;
+                let r = Square(n);
+            }
+        }
+    "};
+    let sources = SourceMap::new([("test".into(), source.into())], None);
+    let unit = default_compile(sources);
+    assert!(unit.errors.is_empty(), "{:?}", unit.errors);
+
+    // Finds the span of `text` within the first occurrence of `context` in the source.
+    let span_of = |context: &str, text: &str| {
+        let lo = source.find(context).expect("context should be in source")
+            + context.find(text).expect("text should be in context");
+        Span {
+            lo: u32::try_from(lo).expect("offset should fit in u32"),
+            hi: u32::try_from(lo + text.len()).expect("offset should fit in u32"),
+        }
+    };
+    let type_at = |context: &str, text: &str| type_at_span(&unit, span_of(context, text));
+
+    assert_eq!(type_at("42", "42").as_deref(), Some("Int"));
+    assert_eq!(type_at("alias = qubit", "qubit").as_deref(), Some("Qubit"));
+    assert_eq!(type_at("Square(n)", "Square(n)").as_deref(), Some("Int"));
+    assert_eq!(
+        type_at("Square(n)", "Square").as_deref(),
+        Some("(Int -> Int)")
+    );
+    assert_eq!(type_at("namespace", "namespace"), None);
+}
+
+#[test]
+fn holes_report_expected_type() {
+    let source = indoc! {"
+        namespace Test {
+            function Square(x : Int) : Int { x * x }
+            function Main() : Unit {
+                let n : Double = _;
+                let f = Square(_);
+                let unknown = _;
+            }
+        }
+    "};
+    let sources = SourceMap::new([("test".into(), source.into())], None);
+    let unit = default_compile(sources);
+
+    let span_of = |context: &str| {
+        let lo = source.find(context).expect("context should be in source")
+            + context.find('_').expect("context should contain a hole");
+        let lo = u32::try_from(lo).expect("offset should fit in u32");
+        Span { lo, hi: lo + 1 }
+    };
+    assert_eq!(
+        holes(&unit),
+        vec![
+            (span_of("Double = _"), Some("Double".to_string())),
+            (span_of("Square(_)"), Some("Int".to_string())),
+            (span_of("unknown = _"), None),
+        ]
+    );
+}
+
+#[test]
+fn references_to_function_called_from_two_places() {
+    let source = indoc! {"
+        namespace Test {
+            function Square(x : Int) : Int { x * x }
+            function Twice(x : Int) : Int { Square(x) + Square(x) }
+            function Main() : Int { Square(3) }
+        }
+    "};
+    let sources = SourceMap::new([("test".into(), source.into())], None);
+    let unit = default_compile(sources);
+    assert!(unit.errors.is_empty(), "{:?}", unit.errors);
+
+    let square = unit
+        .package
+        .items
+        .iter()
+        .find_map(|(id, item)| match &item.kind {
+            ItemKind::Callable(decl) if &*decl.name.name == "Square" => Some(ItemId {
+                package: None,
+                item: id,
+            }),
+            _ => None,
+        })
+        .expect("package should declare Square");
+    assert_eq!(
+        references_to(&unit, square),
+        [
+            source
+                .find("Square(x) +")
+                .expect("source should call Square"),
+            source
+                .find("Square(x) }")
+                .expect("source should call Square"),
+            source.find("Square(3)").expect("source should call Square"),
+        ]
+        .map(|lo| {
+            let lo = u32::try_from(lo).expect("offset should fit in u32");
+            Span { lo, hi: lo + 6 }
+        })
+    );
+}
+
+#[test]
+fn functor_support_accounts_for_declared_and_generated_specializations() {
+    let sources = SourceMap::new(
+        [(
+            "test".into(),
+            indoc! {"
+                namespace Test {
+                    operation Flip(q : Qubit) : Unit is Adj + Ctl {
+                        body intrinsic;
+                    }
+                    operation Measure(q : Qubit) : Result {
+                        body intrinsic;
+                    }
+                    operation Explicit(q : Qubit) : Unit {
+                        body ... { Flip(q); }
+                        adjoint ... { Flip(q); }
+                    }
+                    operation Auto(q : Qubit) : Unit is Adj + Ctl {
+                        Flip(q);
+                    }
+                    operation Measured(q : Qubit) : Result {
+                        Measure(q)
+                    }
+                }
+            "}
+            .into(),
+        )],
+        None,
+    );
+    let store = PackageStore::new(super::core());
+    let unit = compile(
+        &store,
+        &[],
+        sources,
+        TargetCapabilityFlags::all(),
+        LanguageFeatures::default(),
+    );
+    assert!(unit.errors.is_empty(), "{:?}", unit.errors);
+
+    let functors_of = |name: &str| {
+        let item = unit
+            .package
+            .items
+            .iter()
+            .find_map(|(id, item)| match &item.kind {
+                ItemKind::Callable(decl) if &*decl.name.name == name => Some(ItemId {
+                    package: None,
+                    item: id,
+                }),
+                _ => None,
+            })
+            .expect("package should declare the operation");
+        functor_support(&store, &unit, item)
+    };
+    assert_eq!(
+        functors_of("Explicit"),
+        Functors {
+            adj: true,
+            ctl: false
+        }
+    );
+    assert_eq!(
+        functors_of("Auto"),
+        Functors {
+            adj: true,
+            ctl: true
+        }
+    );
+    assert_eq!(functors_of("Measured"), Functors::default());
+}
+
+#[test]
+fn functor_support_looks_up_items_of_dependencies() {
+    let mut store = PackageStore::new(super::core());
+    let std = store.insert(super::std(&store, TargetCapabilityFlags::all()));
+    let sources = SourceMap::new(
+        [(
+            "test".into(),
+            indoc! {"
+                namespace Test {
+                    operation Main() : Unit {}
+                }
+            "}
+            .into(),
+        )],
+        None,
+    );
+    let unit = compile(
+        &store,
+        &[std],
+        sources,
+        TargetCapabilityFlags::all(),
+        LanguageFeatures::default(),
+    );
+    assert!(unit.errors.is_empty(), "{:?}", unit.errors);
+
+    let functors_of = |name: &str| {
+        let item = store
+            .get(std)
+            .expect("store should contain std")
+            .package
+            .items
+            .iter()
+            .find_map(|(id, item)| match &item.kind {
+                ItemKind::Callable(decl) if &*decl.name.name == name => Some(ItemId {
+                    package: Some(std),
+                    item: id,
+                }),
+                _ => None,
+            })
+            .expect("std should declare the operation");
+        functor_support(&store, &unit, item)
+    };
+    assert_eq!(
+        functors_of("H"),
+        Functors {
+            adj: true,
+            ctl: true
+        }
+    );
+    assert_eq!(functors_of("M"), Functors::default());
+}
+
+#[test]
+fn intrinsic_callers_include_direct_and_transitive_callers() {
+    let mut store = PackageStore::new(super::core());
+    let std = store.insert(super::std(&store, TargetCapabilityFlags::all()));
+    let sources = SourceMap::new(
+        [(
+            "test".into(),
+            indoc! {"
+                namespace Test {
+                    open Microsoft.Quantum.Intrinsic;
+                    operation Prepare(q : Qubit) : Unit {
+                        H(q);
+                    }
+                    operation PrepareAll(qs : Qubit[]) : Unit {
+                        for q in qs {
+                            Prepare(q);
+                        }
+                    }
+                    operation Flip(q : Qubit) : Unit {
+                        H(q);
+                        Z(q);
+                        H(q);
+                    }
+                    operation Unrelated() : Unit {}
+                }
+            "}
+            .into(),
+        )],
+        None,
+    );
+    let unit = compile(
+        &store,
+        &[std],
+        sources,
+        TargetCapabilityFlags::all(),
+        LanguageFeatures::default(),
+    );
+    assert!(unit.errors.is_empty(), "{:?}", unit.errors);
+
+    let callers = intrinsic_callers(&store, &unit);
+    let names = callers["__quantum__qis__h__body"]
+        .iter()
+        .map(|id| {
+            assert_eq!(id.package, None, "caller should be declared in the unit");
+            match &unit.package.items.get(id.item).map(|item| &item.kind) {
+                Some(ItemKind::Callable(decl)) => decl.name.name.to_string(),
+                _ => panic!("caller should be a callable"),
+            }
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(names, ["Prepare", "PrepareAll", "Flip"]);
+    assert!(callers.values().flatten().all(|id| id.package.is_none()));
+}
+
+#[test]
+fn semantic_tokens_classify_identifiers_by_resolution() {
+    let source = indoc! {"
+        namespace Test {
+            newtype Pair = (Int, Int);
+            operation Apply(q : Qubit) : Unit {}
+            operation Main() : Unit {
+                let pair = Pair(1, 2);
+                use q = Qubit();
+                Apply(q);
+            }
+        }
+    "};
+    let sources = SourceMap::new([("test".into(), source.into())], None);
+    let unit = default_compile(sources);
+    assert!(unit.errors.is_empty(), "{:?}", unit.errors);
+
+    let lines = source.lines().collect::<Vec<_>>();
+    let tokens = semantic_tokens(&unit, Encoding::Utf8)
+        .into_iter()
+        .map(|token| {
+            let line = lines[token.line as usize];
+            let start = token.column as usize;
+            let text = &line[start..start + token.length as usize];
+            format!("{}:{} {text} {:?}", token.line, token.column, token.kind)
+        })
+        .collect::<Vec<_>>();
+    expect![[r#"
+        [
+            "0:10 Test Namespace",
+            "1:12 Pair Type",
+            "1:20 Int Type",
+            "1:25 Int Type",
+            "2:14 Apply Operation",
+            "2:20 q Parameter",
+            "2:24 Qubit Type",
+            "2:33 Unit Type",
+            "3:14 Main Operation",
+            "3:23 Unit Type",
+            "4:12 pair Variable",
+            "4:19 Pair Type",
+            "5:12 q Variable",
+            "6:8 Apply Operation",
+            "6:14 q Variable",
+        ]
+    "#]]
+    .assert_debug_eq(&tokens);
+}
+
+#[test]
+fn shadowing_events_report_shadow_in_same_block() {
+    let source = indoc! {"
+        namespace Foo {
+            function A() : Int {
+                let x = 0;
+                let x = x + 1;
+                x
+            }
+        }
+    "};
+    let sources = SourceMap::new([("test".into(), source.into())], None);
+    let unit = default_compile(sources);
+    assert!(unit.errors.is_empty(), "{:?}", unit.errors);
+
+    expect![[r#"
+        [
+            ShadowEvent {
+                name: "x",
+                outer: Span {
+                    lo: 53,
+                    hi: 54,
+                },
+                shadowing: Span {
+                    lo: 72,
+                    hi: 73,
+                },
+            },
+        ]
+    "#]]
+    .assert_debug_eq(&shadowing_events(&unit));
+}
+
+#[test]
+fn shadowing_events_report_parameter_shadowed_in_nested_block_only() {
+    let source = indoc! {"
+        namespace Foo {
+            function A(x : Int) : Int {
+                let y = {
+                    let x = 1;
+                    x
+                };
+                function B() : Int {
+                    let x = 2;
+                    x
+                }
+                x + y
+            }
+        }
+    "};
+    let sources = SourceMap::new([("test".into(), source.into())], None);
+    let unit = default_compile(sources);
+    assert!(unit.errors.is_empty(), "{:?}", unit.errors);
+
+    expect![[r#"
+        [
+            ShadowEvent {
+                name: "x",
+                outer: Span {
+                    lo: 31,
+                    hi: 32,
+                },
+                shadowing: Span {
+                    lo: 82,
+                    hi: 83,
+                },
+            },
+        ]
+    "#]]
+    .assert_debug_eq(&shadowing_events(&unit));
+}
+
+#[test]
+fn compile_timed_measures_every_phase() {
+    let source = indoc! {"
+        namespace Foo {
+            newtype Pair = (Fst : Int, Snd : Int);
+
+            @Config(Adaptive)
+            function Adaptive() : Unit {}
+
+            function Sum(pairs : Pair[]) : Int {
+                mutable total = 0;
+                for pair in pairs {
+                    set total += pair::Fst + pair::Snd;
+                }
+                total
+            }
+
+            operation Main() : Int {
+                use qs = Qubit[2];
+                let pairs = [Pair(1, 2), Pair(3, 4)];
+                Sum(pairs)
+            }
+        }
+    "};
+    let sources = SourceMap::new([("test".into(), source.into())], None);
+    let start = Instant::now();
+    let (unit, timings) = compile_timed(
+        &PackageStore::new(super::core()),
+        &[],
+        sources,
+        TargetCapabilityFlags::all(),
+        LanguageFeatures::default(),
+    );
+    let elapsed = start.elapsed();
+    assert!(unit.errors.is_empty(), "{:?}", unit.errors);
+    assert_eq!(unit.package.items.values().count(), 5);
+
+    // Individual phases of a small input can be faster than the clock resolution, so only their
+    // total is required to be measurable.
+    let total = timings.parse
+        + timings.preprocess
+        + timings.assign
+        + timings.resolve
+        + timings.typeck
+        + timings.lower;
+    assert!(
+        total > Duration::ZERO,
+        "the phases should have a measured duration"
+    );
+    assert!(
+        total <= elapsed,
+        "the phases should not take longer than the whole compilation"
+    );
+}
+
+#[test]
+fn strict_prelude_rejects_shadowing_prelude_item() {
+    let source = indoc! {"
+        namespace Foo {
+            function Length(xs : Int[]) : Int { 0 }
+
+            function B() : Int {
+                let n = Length([1, 2]);
+                n + Microsoft.Quantum.Core.Length([3])
+            }
+        }
+    "};
+    let unit = default_compile(SourceMap::new([("test".into(), source.into())], None));
+    assert!(unit.errors.is_empty(), "{:?}", unit.errors);
+
+    let unit = compile_with_strict_prelude(
+        &PackageStore::new(super::core()),
+        &[],
+        SourceMap::new([("test".into(), source.into())], None),
+        TargetCapabilityFlags::all(),
+        LanguageFeatures::default(),
+    );
+    expect![[r#"
+        [
+            Error(
+                Resolve(
+                    PreludeShadowed {
+                        name: "Length",
+                        prelude_item: "Microsoft.Quantum.Core.Length",
+                        span: Span {
+                            lo: 102,
+                            hi: 108,
+                        },
+                    },
+                ),
+            ),
+        ]
+    "#]]
+    .assert_debug_eq(&unit.errors);
+}
+
+#[test]
+fn strict_prelude_rejects_parent_namespace_shadowing_prelude_item() {
+    let source = indoc! {"
+        namespace Microsoft.Quantum.Core {
+            function A() : Unit {}
+        }
+
+        namespace Foo {
+            function A() : Unit {}
+
+            function B() : Unit {
+                A();
+            }
+        }
+    "};
+    let unit = default_compile(SourceMap::new([("test".into(), source.into())], None));
+    assert!(unit.errors.is_empty(), "{:?}", unit.errors);
+
+    let unit = compile_with_strict_prelude(
+        &PackageStore::new(super::core()),
+        &[],
+        SourceMap::new([("test".into(), source.into())], None),
+        TargetCapabilityFlags::all(),
+        LanguageFeatures::default(),
+    );
+    expect![[r#"
+        [
+            Error(
+                Resolve(
+                    PreludeShadowed {
+                        name: "A",
+                        prelude_item: "Microsoft.Quantum.Core.A",
+                        span: Span {
+                            lo: 143,
+                            hi: 144,
+                        },
+                    },
+                ),
+            ),
+        ]
+    "#]]
+    .assert_debug_eq(&unit.errors);
+}
+
+#[test]
+fn exported_items_include_aliases_and_namespaces() {
+    let source = indoc! {"
+        namespace Foo {
+            operation ApplyX() : Unit {}
+            export ApplyX as SomeAlias;
+        }
+        namespace Main {
+            open Foo;
+            export Foo.SomeAlias as AnotherAlias;
+        }
+        namespace Bar {
+            export Baz;
+        }
+        namespace Baz {
+            export Bar;
+            operation Hello() : Unit {}
+        }
+    "};
+    let unit = default_compile(SourceMap::new([("test".into(), source.into())], None));
+    assert!(unit.errors.is_empty(), "{:?}", unit.errors);
+
+    expect![[r#"
+        [
+            ExportedItem {
+                namespace: "Foo",
+                name: "SomeAlias",
+                item: ItemId {
+                    package: None,
+                    item: LocalItemId(
+                        1,
+                    ),
+                },
+                alias: Some(
+                    "SomeAlias",
+                ),
+            },
+            ExportedItem {
+                namespace: "Main",
+                name: "AnotherAlias",
+                item: ItemId {
+                    package: None,
+                    item: LocalItemId(
+                        1,
+                    ),
+                },
+                alias: Some(
+                    "AnotherAlias",
+                ),
+            },
+            ExportedItem {
+                namespace: "Bar",
+                name: "Baz",
+                item: ItemId {
+                    package: None,
+                    item: LocalItemId(
+                        4,
+                    ),
+                },
+                alias: None,
+            },
+            ExportedItem {
+                namespace: "Baz",
+                name: "Bar",
+                item: ItemId {
+                    package: None,
+                    item: LocalItemId(
+                        3,
+                    ),
+                },
+                alias: None,
+            },
+        ]
+    "#]]
+    .assert_debug_eq(&unit.exported_items().collect::<Vec<_>>());
+}
+
+#[test]
+fn exported_items_report_dependency_package_of_reexports() {
+    let mut store = PackageStore::new(super::core());
+    let lib_sources = SourceMap::new(
+        [(
+            "lib".into(),
+            "namespace Lib { function Foo() : Int { 1 } }".into(),
+        )],
+        None,
+    );
+    let lib = compile(
+        &store,
+        &[],
+        lib_sources,
+        TargetCapabilityFlags::all(),
+        LanguageFeatures::default(),
+    );
+    assert!(lib.errors.is_empty(), "{:?}", lib.errors);
+    let lib = store.insert(lib);
+
+    let sources = SourceMap::new(
+        [(
+            "test".into(),
+            "namespace Main { export Lib.Foo as Bar; }".into(),
+        )],
+        None,
+    );
+    let unit = compile(
+        &store,
+        &[lib],
+        sources,
+        TargetCapabilityFlags::all(),
+        LanguageFeatures::default(),
+    );
+    assert!(unit.errors.is_empty(), "{:?}", unit.errors);
+
+    let exported = unit.exported_items().collect::<Vec<_>>();
+    assert_eq!(exported.len(), 1);
+    assert_eq!(exported[0].item.package, Some(lib));
+    expect![[r#"
+        [
+            ExportedItem {
+                namespace: "Main",
+                name: "Bar",
+                item: ItemId {
+                    package: Some(
+                        PackageId(
+                            1,
+                        ),
+                    ),
+                    item: LocalItemId(
+                        1,
+                    ),
+                },
+                alias: Some(
+                    "Bar",
+                ),
+            },
+        ]
+    "#]]
+    .assert_debug_eq(&exported);
+}
@@ -18,7 +18,9 @@ use qsc_ast::{
     validate::Validator as AstValidator,
     visit::Visitor as AstVisitor,
 };
-use qsc_data_structures::{language_features::LanguageFeatures, target::TargetCapabilityFlags};
+use qsc_data_structures::{
+    language_features::LanguageFeatures, namespaces::NamespaceId, target::TargetCapabilityFlags,
+};
 use qsc_hir::{
     assigner::Assigner as HirAssigner,
     hir::{self, PackageId},
@@ -188,6 +190,17 @@ impl Compiler {
         })
     }
 
+    /// Returns every declaration of `name` in `namespace` compiled so far, including those
+    /// dropped by conditional compilation for the current target capabilities.
+    #[must_use]
+    pub fn all_definitions_of(
+        &self,
+        name: &str,
+        namespace: NamespaceId,
+    ) -> Vec<resolve::Definition> {
+        self.resolver.all_definitions_of(name, namespace)
+    }
+
     /// Compiles an entry expression.
     ///
     /// Uses the assigners and other mutable state from the passed in
@@ -252,9 +265,11 @@ impl Compiler {
 
         self.ast_assigner.visit_package(ast);
 
+        let dropped_items = cond_compile.take_dropped_items();
         self.resolver
             .extend_dropped_names(cond_compile.into_names());
         self.resolver.bind_fragments(ast, &mut unit.assigner);
+        self.resolver.extend_dropped_items(dropped_items);
         self.resolver.bind_and_resolve_imports_and_exports(ast);
         self.resolver.with(&mut unit.assigner).visit_package(ast);
 
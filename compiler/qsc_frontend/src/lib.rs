@@ -11,3 +11,5 @@ pub mod typeck;
 
 pub use qsc_parse::keyword;
 pub use qsc_parse::lex;
+pub use qsc_parse::namespaces_with_trivia;
+pub use qsc_parse::trivia;
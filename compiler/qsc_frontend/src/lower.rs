@@ -6,6 +6,7 @@ mod tests;
 
 use crate::{
     closure::{self, Lambda, PartialApp},
+    compile::preprocess::feature_name,
     resolve::{self, Names},
     typeck::{self, convert},
 };
@@ -255,6 +256,14 @@ impl With<'_> {
                     if TargetCapabilityFlags::from_str(path.as_ref().name.name.as_ref()).is_ok())) =>
                         {}
 
+                    // @Config(Feature("name"))
+                    ast::ExprKind::Paren(inner) if feature_name(inner).is_some() => {}
+
+                    // @Config(not Feature("name"))
+                    ast::ExprKind::Paren(inner)
+                        if matches!(inner.kind.as_ref(), ast::ExprKind::UnOp(ast::UnOp::NotL, inner)
+                        if feature_name(inner).is_some()) => {}
+
                     // Any other form is not valid so generates an error.
                     _ => {
                         self.lowerer.errors.push(Error::InvalidAttrArgs(
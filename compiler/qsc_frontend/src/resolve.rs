@@ -29,11 +29,15 @@ use std::cmp::Ordering;
 use std::{collections::hash_map::Entry, rc::Rc, str::FromStr, vec};
 use thiserror::Error;
 
-use crate::compile::preprocess::TrackedName;
+use crate::compile::preprocess::{self, DroppedItem, TrackedName};
 
 // All AST Path nodes get mapped
 // All AST Ident nodes get mapped, except those under AST Path nodes
-pub(super) type Names = IndexMap<NodeId, Res>;
+pub type Names = IndexMap<NodeId, Res>;
+
+/// A declaration of a global name: the item, unless conditional compilation dropped it, the
+/// capabilities named by its `@Config` attributes, and the span of its name.
+pub type Definition = (Option<ItemId>, preprocess::ConfigCapabilities, Span);
 
 /// A resolution. This connects a usage of a name with the declaration of that name by uniquely
 /// identifying the node that declared it.
@@ -68,20 +72,65 @@ pub(super) enum Error {
     },
 
     #[error("`{name}` could refer to the item in `{candidate_a}` or an item in `{candidate_b}`")]
-    #[diagnostic(help("both namespaces are implicitly opened by the prelude"))]
+    #[diagnostic(help(
+        "both namespaces are implicitly opened by the prelude, to disambiguate {}",
+        suggestions.join(" or ")
+    ))]
     #[diagnostic(code("Qsc.Resolve.AmbiguousPrelude"))]
     AmbiguousPrelude {
         name: String,
         candidate_a: String,
         candidate_b: String,
+        /// Suggested fully qualified fixes, one per candidate namespace.
+        suggestions: Vec<String>,
         #[label("ambiguous name")]
         span: Span,
     },
 
+    #[error("`{name}` shadows the prelude item `{prelude_item}`")]
+    #[diagnostic(help(
+        "strict prelude mode requires prelude names to keep their standard library meaning, rename the item or refer to it by its full path"
+    ))]
+    #[diagnostic(code("Qsc.Resolve.PreludeShadowed"))]
+    PreludeShadowed {
+        name: String,
+        prelude_item: String,
+        #[label]
+        span: Span,
+    },
+
     #[error("duplicate declaration of `{0}` in namespace `{1}`")]
     #[diagnostic(code("Qsc.Resolve.Duplicate"))]
     Duplicate(String, String, #[label] Span),
 
+    #[error("declaration of `{0}` shadows the built-in or prelude item `{0}`")]
+    #[diagnostic(help(
+        "uses of `{0}` in this namespace and in namespaces that open it would no longer refer to the built-in or prelude item"
+    ))]
+    #[diagnostic(code("Qsc.Resolve.ShadowsBuiltin"))]
+    ShadowsBuiltin(String, #[label] Span),
+
+    #[error("field `{name}` is declared by more than one type in scope")]
+    #[diagnostic(help(
+        "the field is looked up in the type of the expression, which may not be the type a reader expects"
+    ))]
+    #[diagnostic(severity(Warning))]
+    #[diagnostic(code("Qsc.Resolve.AmbiguousField"))]
+    AmbiguousField {
+        name: String,
+        #[label]
+        span: Span,
+    },
+
+    #[error("duplicate field `{name}` in struct declaration")]
+    #[diagnostic(help("each field of a struct must have a unique name"))]
+    #[diagnostic(code("Qsc.Resolve.DuplicateField"))]
+    DuplicateField {
+        name: String,
+        #[label]
+        span: Span,
+    },
+
     #[error("duplicate name `{0}` in pattern")]
     #[diagnostic(help("a name cannot shadow another name in the same pattern"))]
     #[diagnostic(code("Qsc.Resolve.DuplicateBinding"))]
@@ -98,10 +147,34 @@ pub(super) enum Error {
     #[diagnostic(code("Qsc.Resolve.DuplicateExport"))]
     DuplicateExport(String, #[label] Span),
 
+    #[error("ambiguous re-export of `{0}` in namespace `{1}`")]
+    #[diagnostic(help(
+        "`{0}` is already exported from this namespace and refers to a different item"
+    ))]
+    #[diagnostic(code("Qsc.Resolve.AmbiguousReexport"))]
+    AmbiguousReexport(String, String, #[label] Span),
+
     #[error("`{0}` not found")]
     #[diagnostic(code("Qsc.Resolve.NotFound"))]
     NotFound(String, #[label] Span),
 
+    #[error("`{0}` not found")]
+    #[diagnostic(help("`{0}` is available via {1}"))]
+    #[diagnostic(code("Qsc.Resolve.NotFound"))]
+    NotFoundImportable(String, String, #[label] Span),
+
+    #[error("exported item `{0}` is not in scope")]
+    #[diagnostic(help(
+        "`{0}` is declared in {1}, export it by its full path or import it into this namespace first"
+    ))]
+    #[diagnostic(code("Qsc.Resolve.ExportNotInScope"))]
+    ExportNotInScope(String, String, #[label] Span),
+
+    #[error("`{0}` not found")]
+    #[diagnostic(help("did you mean {1}?"))]
+    #[diagnostic(code("Qsc.Resolve.NotFound"))]
+    NotFoundSimilar(String, String, #[label] Span),
+
     #[error("`{0}` not found")]
     #[diagnostic(help(
         "found a matching item `{1}` that is not available for the current compilation configuration"
@@ -140,6 +213,48 @@ pub(super) enum Error {
     #[error("glob exports are not supported")]
     #[diagnostic(code("Qsc.Resolve.GlobExportNotSupported"))]
     GlobExportNotSupported(#[label] Span),
+
+    #[error("type-only glob imports are not supported")]
+    #[diagnostic(help("import the types by name, such as `import type Foo.Bar;`"))]
+    #[diagnostic(code("Qsc.Resolve.GlobTypeImportNotSupported"))]
+    GlobTypeImportNotSupported(#[label] Span),
+
+    #[error("exported item `{name}` is not available for the current compilation configuration")]
+    #[diagnostic(help(
+        "the item is removed by a `@Config` attribute, so this export does not refer to anything"
+    ))]
+    #[diagnostic(severity(Warning))]
+    #[diagnostic(code("Qsc.Resolve.ExportOfDroppedItem"))]
+    ExportOfDroppedItem {
+        name: String,
+        #[label]
+        span: Span,
+    },
+
+    #[error("import cycle {names} does not refer to any item")]
+    #[diagnostic(help(
+        "each import or export in the cycle refers to the next one, so none of them refers to a callable or type"
+    ))]
+    #[diagnostic(severity(Warning))]
+    #[diagnostic(code("Qsc.Resolve.UnresolvableImportCycle"))]
+    UnresolvableImportCycle {
+        names: String,
+        #[label]
+        span: Span,
+    },
+
+    #[error("namespace `{b}` differs from namespace `{a}` only in case")]
+    #[diagnostic(help(
+        "the two namespaces collide when mapped to files on a case-insensitive file system"
+    ))]
+    #[diagnostic(severity(Warning))]
+    #[diagnostic(code("Qsc.Resolve.CaseInsensitiveNamespaceCollision"))]
+    CaseInsensitiveNamespaceCollision {
+        a: String,
+        b: String,
+        #[label]
+        span: Span,
+    },
 }
 
 #[derive(Debug, Clone)]
@@ -316,6 +431,9 @@ pub struct GlobalScope {
     terms: IndexMap<NamespaceId, FxHashMap<Rc<str>, Res>>,
     namespaces: NamespaceTreeRoot,
     intrinsics: FxHashSet<Rc<str>>,
+    /// Every declaration of each global name in the local packages, including those dropped by
+    /// conditional compilation.
+    definitions: IndexMap<NamespaceId, FxHashMap<Rc<str>, Vec<Definition>>>,
 }
 
 impl GlobalScope {
@@ -331,6 +449,36 @@ impl GlobalScope {
         items.get(namespace).and_then(|items| items.get(name))
     }
 
+    /// Formats the imports that would bring an item with the given name into scope, one for
+    /// each namespace other than the root that declares it, e.g. "`import Foo.Bar;`".
+    /// Returns an empty string if there are none.
+    fn import_suggestions(&self, kind: NameKind, name: &str) -> String {
+        self.declaring_namespaces(kind, name)
+            .iter()
+            .map(|namespace| format!("`import {namespace}.{name};`"))
+            .collect::<Vec<_>>()
+            .join(" or ")
+    }
+
+    /// Returns the sorted names of the namespaces, other than the root, with an item called `name`.
+    fn declaring_namespaces(&self, kind: NameKind, name: &str) -> Vec<String> {
+        let items = match kind {
+            NameKind::Ty => &self.tys,
+            NameKind::Term => &self.terms,
+        };
+        let root = self.namespaces.root_id();
+        let mut namespaces = items
+            .iter()
+            .filter(|(namespace, items)| *namespace != root && items.contains_key(name))
+            .map(|(namespace, _)| {
+                let (names, _) = self.namespaces.find_namespace_by_id(&namespace);
+                names.join(".")
+            })
+            .collect::<Vec<_>>();
+        namespaces.sort();
+        namespaces
+    }
+
     /// Creates a namespace in the namespace mapping. Note that namespaces are tracked separately from their
     /// item contents. This returns a [`NamespaceId`] which you can use to add more tys and terms to the scope.
     fn insert_or_find_namespace(&mut self, name: impl Into<Vec<Rc<str>>>) -> NamespaceId {
@@ -383,10 +531,21 @@ pub(super) struct Resolver {
     names: Names,
     dropped_names: Vec<TrackedName>,
     curr_params: Option<FxHashSet<Rc<str>>>,
+    /// The innermost callable being resolved, which is never suggested for a name that is not found.
+    curr_callable: Option<ItemId>,
     curr_scope_chain: Vec<ScopeId>,
     globals: GlobalScope,
     locals: Locals,
     errors: Vec<Error>,
+    /// The item each exported name refers to, per namespace, for the current compilation unit.
+    exports: FxHashMap<(NamespaceId, Rc<str>), ItemId>,
+    /// The name in another namespace that each name imported or exported by a namespace
+    /// refers to, for the current compilation unit.
+    import_targets: FxHashMap<(NamespaceId, Rc<str>), (NamespaceId, Rc<str>)>,
+    /// The names of the types declared in the current compilation unit that declare each field.
+    fields: FxHashMap<Rc<str>, Vec<Ident>>,
+    /// Whether a name that resolves to an item shadowing a prelude item is an error.
+    strict_prelude: bool,
 }
 
 /// This visitor is used for an intermediate step between binding and full resolution.
@@ -467,6 +626,8 @@ impl AstVisitor<'_> for ExportImportVisitor<'_> {
 
 impl Resolver {
     pub(crate) fn bind_and_resolve_imports_and_exports(&mut self, package: &Package) {
+        self.exports.clear();
+        self.collect_import_targets(package);
         let mut visitor = ExportImportVisitor { resolver: self };
         visitor.visit_package(package);
     }
@@ -476,10 +637,15 @@ impl Resolver {
             names: globals.names,
             dropped_names,
             curr_params: None,
+            curr_callable: None,
             globals: globals.scope,
             locals: Locals::default(),
             curr_scope_chain: Vec::new(),
             errors: Vec::new(),
+            exports: FxHashMap::default(),
+            import_targets: FxHashMap::default(),
+            fields: globals.fields,
+            strict_prelude: false,
         }
     }
 
@@ -499,13 +665,25 @@ impl Resolver {
             names: globals.names,
             dropped_names,
             curr_params: None,
+            curr_callable: None,
             globals: globals.scope,
             locals,
             curr_scope_chain: vec![scope_id],
             errors: Vec::new(),
+            exports: FxHashMap::default(),
+            import_targets: FxHashMap::default(),
+            fields: globals.fields,
+            strict_prelude: false,
         }
     }
 
+    /// Sets whether names that resolve to an item other than the prelude item of the same name,
+    /// e.g. through an open or a declaration in the enclosing namespace, are reported as
+    /// [`Error::PreludeShadowed`].
+    pub(super) fn set_strict_prelude(&mut self, strict: bool) {
+        self.strict_prelude = strict;
+    }
+
     pub(super) fn names(&self) -> &Names {
         &self.names
     }
@@ -538,6 +716,36 @@ impl Resolver {
         self.dropped_names.extend(dropped_names);
     }
 
+    /// Records the declarations dropped by conditional compilation, so that they are reported by
+    /// [`Self::all_definitions_of`]. The namespaces of the items should already be bound.
+    pub(super) fn extend_dropped_items(&mut self, dropped_items: Vec<DroppedItem>) {
+        for item in dropped_items {
+            if let Some(namespace) = self.globals.find_namespace(item.name.namespace.split('.')) {
+                self.globals
+                    .definitions
+                    .get_mut_or_default(namespace)
+                    .entry(item.name.name)
+                    .or_default()
+                    .push((None, item.capabilities, item.span));
+            }
+        }
+    }
+
+    /// Returns every declaration of `name` in `namespace`, in source order, whether or not it was
+    /// kept for the current target capabilities. Declarations dropped by conditional compilation
+    /// have no item.
+    pub(super) fn all_definitions_of(&self, name: &str, namespace: NamespaceId) -> Vec<Definition> {
+        let mut definitions = self
+            .globals
+            .definitions
+            .get(namespace)
+            .and_then(|definitions| definitions.get(name))
+            .cloned()
+            .unwrap_or_default();
+        definitions.sort_by_key(|(_, _, span)| span.lo);
+        definitions
+    }
+
     pub(super) fn bind_fragments(&mut self, ast: &ast::Package, assigner: &mut Assigner) {
         for node in &mut ast.nodes.iter() {
             match node {
@@ -593,6 +801,9 @@ impl Resolver {
         ) {
             Ok(res) => {
                 self.check_item_status(res, path.name.name.to_string(), path.span);
+                if self.strict_prelude && namespace.is_none() {
+                    self.check_prelude_shadowing(kind, name, res);
+                }
                 self.names.insert(path.id, res);
                 Ok(res)
             }
@@ -606,6 +817,18 @@ impl Resolver {
                             format!("{}.{}", dropped_name.namespace, dropped_name.name),
                             span,
                         ))
+                    } else if let Some(imports) = namespace
+                        .is_none()
+                        .then(|| self.globals.import_suggestions(kind, &name))
+                        .filter(|imports| !imports.is_empty())
+                    {
+                        Err(Error::NotFoundImportable(name, imports, span))
+                    } else if let Some(similar) = namespace
+                        .is_none()
+                        .then(|| self.similar_names(kind, &name, span.lo))
+                        .filter(|similar| !similar.is_empty())
+                    {
+                        Err(Error::NotFoundSimilar(name, similar, span))
                     } else {
                         Err(Error::NotFound(name, span))
                     }
@@ -616,6 +839,137 @@ impl Resolver {
         }
     }
 
+    /// Lists the names similar to `name` that are likely meant instead, e.g. "`Bar` or `Baz.Bar`".
+    /// Locals and items in opened namespaces are suggested by name, while the items of other
+    /// namespaces are suggested by their full path. Names that differ only in case rank above
+    /// names within a small edit distance, which is only allowed for names of at least four
+    /// characters. Items dropped by `@Config` and the enclosing callable are not suggested.
+    fn similar_names(&self, kind: NameKind, name: &str, offset: u32) -> String {
+        const MAX_SUGGESTIONS: usize = 3;
+        let len = name.chars().count();
+        let max_distance = if len >= 4 { len / 3 } else { 0 };
+
+        let mut visible_namespaces = prelude_namespace_ids(&self.globals)
+            .into_iter()
+            .map(|(namespace, _)| namespace)
+            .collect::<FxHashSet<_>>();
+        visible_namespaces.insert(self.globals.namespaces.root_id());
+        // each candidate is the name to compare, the name to suggest, and the item it refers to
+        let mut candidates: Vec<(Rc<str>, Rc<str>, Option<ItemId>)> = Vec::new();
+        let mut vars = true;
+        for scope in self.locals.get_scopes(&self.curr_scope_chain) {
+            if let Some(opens) = scope.opens.get(&Vec::new()) {
+                visible_namespaces.extend(opens.iter().map(|open| open.namespace));
+            }
+            let names = match kind {
+                NameKind::Term => scope
+                    .terms
+                    .iter()
+                    .map(|(name, entry)| (name, Some(entry.id)))
+                    .chain(
+                        scope
+                            .vars
+                            .iter()
+                            .filter(|(_, (valid_at, _))| vars && *valid_at <= offset)
+                            .map(|(name, _)| (name, None)),
+                    )
+                    .collect::<Vec<_>>(),
+                NameKind::Ty => scope
+                    .tys
+                    .iter()
+                    .map(|(name, entry)| (name, Some(entry.id)))
+                    .chain(scope.ty_vars.keys().map(|name| (name, None)))
+                    .collect(),
+            };
+            candidates.extend(
+                names
+                    .into_iter()
+                    .map(|(name, id)| (name.clone(), name.clone(), id)),
+            );
+            // local callables are not closures, so the variables of enclosing callables are hidden
+            if scope.kind == ScopeKind::Callable {
+                vars = false;
+            }
+        }
+
+        let items = match kind {
+            NameKind::Ty => &self.globals.tys,
+            NameKind::Term => &self.globals.terms,
+        };
+        for (namespace, items) in items.iter() {
+            let (names, _) = self.globals.namespaces.find_namespace_by_id(&namespace);
+            let namespace_name = names.join(".");
+            let is_visible = visible_namespaces.contains(&namespace);
+            for (item_name, res) in items {
+                let is_dropped = self.dropped_names.iter().any(|dropped| {
+                    dropped.name == *item_name && *dropped.namespace == *namespace_name
+                });
+                let Res::Item(id, ItemStatus::Available) = res else {
+                    continue;
+                };
+                if is_dropped {
+                    continue;
+                }
+                let suggestion = if is_visible {
+                    item_name.clone()
+                } else {
+                    format!("{namespace_name}.{item_name}").into()
+                };
+                candidates.push((item_name.clone(), suggestion, Some(*id)));
+            }
+        }
+
+        let lowercase_name = name.to_lowercase();
+        let mut ranked = candidates
+            .into_iter()
+            .filter(|(candidate, _, id)| {
+                **candidate != *name && (id.is_none() || *id != self.curr_callable)
+            })
+            .filter_map(|(candidate, suggestion, _)| {
+                let rank = if candidate.to_lowercase() == lowercase_name {
+                    0
+                } else {
+                    let distance = edit_distance(name, &candidate);
+                    (distance <= max_distance).then_some(distance)?
+                };
+                Some((rank, suggestion))
+            })
+            .collect::<Vec<_>>();
+        ranked.sort();
+        ranked.dedup();
+        ranked
+            .into_iter()
+            .take(MAX_SUGGESTIONS)
+            .map(|(_, suggestion)| format!("`{suggestion}`"))
+            .collect::<Vec<_>>()
+            .join(" or ")
+    }
+
+    fn check_prelude_shadowing(&mut self, kind: NameKind, name: &Ident, res: Res) {
+        if !matches!(res, Res::Item(..)) {
+            return;
+        }
+        let prelude = prelude_namespace_ids(&self.globals);
+        let candidates = find_symbol_in_namespaces(
+            kind,
+            &self.globals,
+            &None,
+            name,
+            prelude.clone().into_iter(),
+            &std::iter::once((vec![], prelude)).collect(),
+        );
+        if let Some((_, namespace)) = candidates
+            .into_iter()
+            .find(|(candidate, _)| *candidate != res)
+        {
+            self.errors.push(Error::PreludeShadowed {
+                name: name.name.to_string(),
+                prelude_item: format!("{namespace}.{}", name.name),
+                span: name.span,
+            });
+        }
+    }
+
     /// # Arguments
     ///
     /// * `pat` - The pattern to bind.
@@ -663,18 +1017,23 @@ impl Resolver {
             .globals
             .namespaces
             .find_namespace_by_id(&current_namespace);
-        // try scoping from the current namespace, and then use the absolute namespace as the backup
-        let id = if let Some(id) = (*current_namespace)
+        // try scoping from the current namespace, and then use the absolute namespace as the backup,
+        // and finally check for an alias of a namespace brought into scope by an import or open
+        let ids = if let Some(id) = (*current_namespace)
             .borrow()
             .get_namespace_id(name.str_iter())
         {
-            id
+            vec![id]
         } else if let Some(id) = self.globals.namespaces.get_namespace_id(name.str_iter()) {
-            id
+            vec![id]
         } else {
-            let error = Error::NotFound(name.name().to_string(), name.span());
-            self.errors.push(error);
-            return;
+            let ids = self.namespaces_for_alias(name);
+            if ids.is_empty() {
+                let error = Error::NotFound(name.name().to_string(), name.span());
+                self.errors.push(error);
+                return;
+            }
+            ids
         };
 
         let alias = alias.as_ref().map_or(vec![], |a| vec![Rc::clone(&a.name)]);
@@ -685,16 +1044,30 @@ impl Resolver {
                 .entry(alias.clone())
                 .or_default();
 
-            let open = Open {
-                namespace: id,
-                span: name.span(),
-            };
-            if !current_opens.contains(&open) {
-                current_opens.push(open);
+            for id in ids {
+                let open = Open {
+                    namespace: id,
+                    span: name.span(),
+                };
+                if !current_opens.contains(&open) {
+                    current_opens.push(open);
+                }
             }
         }
     }
 
+    /// Returns the namespaces bound to an alias in the current scope chain, e.g. by
+    /// `import Foo as Bar;`, innermost scope first.
+    fn namespaces_for_alias(&self, name: &Idents) -> Vec<NamespaceId> {
+        let alias: Vec<Rc<str>> = name.into();
+        self.curr_scope_chain
+            .iter()
+            .rev()
+            .find_map(|scope| self.locals.get_scope(*scope).opens.get(&alias))
+            .map(|opens| opens.iter().map(|open| open.namespace).collect())
+            .unwrap_or_default()
+    }
+
     pub(super) fn bind_local_item(
         &mut self,
         assigner: &mut Assigner,
@@ -793,23 +1166,65 @@ impl Resolver {
                 if let Some(ref current_namespace_name) = current_namespace_name {
                     let item_as_tracked_name =
                         path_as_tracked_name(&item.path, current_namespace_name);
-                    !self.dropped_names.contains(&item_as_tracked_name)
-                } else {
-                    true
+                    if self.dropped_names.contains(&item_as_tracked_name) {
+                        // another declaration of the same name may have been kept for this configuration
+                        if [NameKind::Term, NameKind::Ty].into_iter().any(|kind| {
+                            resolve(
+                                kind,
+                                &self.globals,
+                                self.locals.get_scopes(&self.curr_scope_chain),
+                                &item.path.name,
+                                &item.path.namespace,
+                            )
+                            .is_ok()
+                        }) {
+                            return true;
+                        }
+                        if is_export {
+                            self.errors.push(Error::ExportOfDroppedItem {
+                                name: item.path.name.name.to_string(),
+                                span: item.path.span,
+                            });
+                        }
+                        return false;
+                    }
                 }
+                true
             })
             .collect::<Vec<_>>()
         {
             if item.is_glob {
-                self.bind_glob_import_or_export(item, decl.is_export());
+                // a glob opens the whole namespace, which would bring constructors into scope too
+                if decl.is_type_only() {
+                    self.errors
+                        .push(Error::GlobTypeImportNotSupported(item.path.span));
+                } else {
+                    self.bind_glob_import_or_export(item, decl.is_export());
+                }
                 continue;
             }
-            let (term_result, ty_result) = (
-                self.resolve_path(NameKind::Term, &item.path),
-                self.resolve_path(NameKind::Ty, &item.path),
-            );
+            // a type-only import never binds a term, so the constructor of an imported UDT stays
+            // out of scope, while struct expressions still resolve since they name the type
+            let term_result = if decl.is_type_only() {
+                Err(Error::NotFound(
+                    item.path.name.name.to_string(),
+                    item.path.span,
+                ))
+            } else {
+                self.resolve_path(NameKind::Term, &item.path)
+            };
+            let ty_result = self.resolve_path(NameKind::Ty, &item.path);
 
             if let (Err(err), Err(_)) = (&term_result, &ty_result) {
+                // the cycle explains why the name is not found, but does not replace the error
+                if let Some(names) = current_namespace
+                    .and_then(|namespace| self.import_cycle(namespace, &item.name().name))
+                {
+                    self.errors.push(Error::UnresolvableImportCycle {
+                        names,
+                        span: item.path.span,
+                    });
+                }
                 // try to see if it is a namespace
                 self.handle_namespace_import_or_export(is_export, item, current_namespace, err);
                 continue;
@@ -817,6 +1232,33 @@ impl Resolver {
 
             let local_name = item.name().name.clone();
 
+            // exporting the same name from a namespace twice is only allowed if both refer to the same item
+            let item_id = match (&term_result, &ty_result) {
+                (Ok(Res::Item(id, _)), _) | (_, Ok(Res::Item(id, _))) => Some(*id),
+                _ => None,
+            };
+            if let (true, Some(namespace), Some(namespace_name), Some(id)) = (
+                is_export,
+                current_namespace,
+                &current_namespace_name,
+                item_id,
+            ) {
+                match self.exports.entry((namespace, local_name.clone())) {
+                    Entry::Occupied(entry) if *entry.get() != id => {
+                        self.errors.push(Error::AmbiguousReexport(
+                            local_name.to_string(),
+                            namespace_name.to_string(),
+                            item.name().span,
+                        ));
+                        continue;
+                    }
+                    Entry::Occupied(_) => {}
+                    Entry::Vacant(entry) => {
+                        entry.insert(id);
+                    }
+                }
+            }
+
             {
                 let scope = self.current_scope_mut();
                 // if the item already exists in the scope, return a duplicate error
@@ -934,6 +1376,35 @@ impl Resolver {
         });
     }
 
+    /// Warns when an unqualified field name is declared by more than one type whose name is in
+    /// scope, since the field alone does not say which type is meant.
+    fn check_field(&mut self, field: &Ident) {
+        let Some(tys) = self.fields.get(&field.name) else {
+            return;
+        };
+        let mut candidates = Vec::new();
+        for ty in tys {
+            let in_scope = resolve(
+                NameKind::Ty,
+                &self.globals,
+                self.locals.get_scopes(&self.curr_scope_chain),
+                ty,
+                &None,
+            );
+            if let (Ok(res), Some(declared)) = (in_scope, self.names.get(ty.id)) {
+                if res == *declared && !candidates.contains(&res) {
+                    candidates.push(res);
+                }
+            }
+        }
+        if candidates.len() > 1 {
+            self.errors.push(Error::AmbiguousField {
+                name: field.name.to_string(),
+                span: field.span,
+            });
+        }
+    }
+
     fn push_scope(&mut self, span: Span, kind: ScopeKind) {
         let scope_id = self.locals.push_scope(kind, span);
         self.curr_scope_chain.push(scope_id);
@@ -955,6 +1426,76 @@ impl Resolver {
         self.locals.get_scope_mut(scope_id)
     }
 
+    /// Records the target of every non-glob import and export in a namespace of the package
+    /// whose path names a namespace, so that cycles between them can be detected.
+    fn collect_import_targets(&mut self, package: &Package) {
+        self.import_targets.clear();
+        for node in &*package.nodes {
+            let TopLevelNode::Namespace(namespace) = node else {
+                continue;
+            };
+            let Some(namespace_id) = self.globals.find_namespace(namespace.name.str_iter()) else {
+                continue;
+            };
+            for item in &*namespace.items {
+                let ItemKind::ImportOrExport(decl) = &*item.kind else {
+                    continue;
+                };
+                for item in decl.items().filter(|item| !item.is_glob) {
+                    let target_namespace = item
+                        .path
+                        .namespace
+                        .as_ref()
+                        .and_then(|name| self.namespace_relative_to(namespace_id, name));
+                    if let Some(target_namespace) = target_namespace {
+                        self.import_targets.insert(
+                            (namespace_id, item.name().name.clone()),
+                            (target_namespace, item.path.name.name.clone()),
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    /// Finds a namespace by name, first relative to the given namespace and then from the root.
+    fn namespace_relative_to(&self, namespace: NamespaceId, name: &Idents) -> Option<NamespaceId> {
+        let (_, node) = self.globals.namespaces.find_namespace_by_id(&namespace);
+        let relative = node.borrow().get_namespace_id(name.str_iter());
+        relative.or_else(|| self.globals.find_namespace(name.str_iter()))
+    }
+
+    /// Follows the imports and exports starting from the given name in the given namespace.
+    /// If they lead back to a name already visited without reaching a declared item, returns
+    /// the names along the way, e.g. "`A.Foo` -> `B.Foo` -> `A.Foo`".
+    fn import_cycle(&self, namespace: NamespaceId, name: &Rc<str>) -> Option<String> {
+        let mut visited = vec![(namespace, name.clone())];
+        loop {
+            let target = self
+                .import_targets
+                .get(visited.last().expect("visited names should not be empty"))?;
+            let is_declared = [NameKind::Term, NameKind::Ty]
+                .into_iter()
+                .any(|kind| self.globals.get(kind, target.0, &target.1).is_some());
+            if is_declared {
+                return None;
+            }
+            let is_cycle = visited.contains(target);
+            visited.push(target.clone());
+            if is_cycle {
+                break;
+            }
+        }
+        let names = visited
+            .iter()
+            .map(|(namespace, name)| {
+                let (namespace, _) = self.globals.namespaces.find_namespace_by_id(namespace);
+                format!("`{}.{name}`", namespace.join("."))
+            })
+            .collect::<Vec<_>>();
+        Some(names.join(" -> "))
+    }
+
     fn handle_namespace_import_or_export(
         &mut self,
         is_export: bool,
@@ -981,10 +1522,46 @@ impl Resolver {
                 // for imports, we just bind the namespace as an open
                 self.bind_open(&items, &alias, ns);
             }
+        } else if let Some(namespaces) = (is_export
+            && item.path.namespace.is_none()
+            && matches!(err, Error::NotFound(..) | Error::NotFoundImportable(..)))
+        .then(|| self.namespaces_declaring(&item.path.name.name))
+        .filter(|namespaces| !namespaces.is_empty())
+        {
+            // the name exists, but only in namespaces that are not visible from this export
+            self.errors.push(Error::ExportNotInScope(
+                item.path.name.name.to_string(),
+                namespaces,
+                item.path.span,
+            ));
         } else {
             self.errors.push(err.clone());
         }
     }
+
+    /// Lists the namespaces that declare a term or type called `name`, for diagnostics.
+    fn namespaces_declaring(&self, name: &str) -> String {
+        let mut namespaces = self.globals.declaring_namespaces(NameKind::Term, name);
+        namespaces.extend(self.globals.declaring_namespaces(NameKind::Ty, name));
+        namespaces.sort();
+        namespaces.dedup();
+        namespaces
+            .iter()
+            .map(|namespace| format!("`{namespace}`"))
+            .collect::<Vec<_>>()
+            .join(" and ")
+    }
+}
+
+/// Returns what an import or export item resolved to, e.g. the callable or type it refers to.
+/// This is only available after resolution has run over the package that contains the item.
+/// Items that refer to namespaces have no resolution and return `None`.
+#[must_use]
+pub fn resolved_target(names: &Names, item: &ImportOrExportItem) -> Option<Res> {
+    names
+        .get(item.path.id)
+        .or_else(|| names.get(item.name().id))
+        .copied()
 }
 
 fn path_as_tracked_name(path: &ast::Path, current_namespace_name: &Rc<str>) -> TrackedName {
@@ -1097,6 +1674,19 @@ impl AstVisitor<'_> for With<'_> {
         }
     }
 
+    fn visit_struct_decl(&mut self, decl: &ast::StructDecl) {
+        let mut names = FxHashSet::default();
+        for field in &*decl.fields {
+            if !names.insert(Rc::clone(&field.name.name)) {
+                self.resolver.errors.push(Error::DuplicateField {
+                    name: field.name.name.to_string(),
+                    span: field.name.span,
+                });
+            }
+        }
+        ast_visit::walk_struct_decl(self, decl);
+    }
+
     fn visit_callable_decl(&mut self, decl: &CallableDecl) {
         fn collect_param_names(pat: &ast::Pat, names: &mut FxHashSet<Rc<str>>) {
             match &*pat.kind {
@@ -1113,6 +1703,10 @@ impl AstVisitor<'_> for With<'_> {
         let mut param_names = FxHashSet::default();
         collect_param_names(&decl.input, &mut param_names);
         let prev_param_names = self.resolver.curr_params.replace(param_names);
+        let prev_callable = self.resolver.curr_callable;
+        if let Some(&Res::Item(id, _)) = self.resolver.names.get(decl.name.id) {
+            self.resolver.curr_callable = Some(id);
+        }
         self.with_scope(decl.span, ScopeKind::Callable, |visitor| {
             visitor.resolver.bind_type_parameters(decl);
             // The parameter bindings are valid after the end of the input pattern.
@@ -1121,6 +1715,7 @@ impl AstVisitor<'_> for With<'_> {
             ast_visit::walk_callable_decl(visitor, decl);
         });
         self.resolver.curr_params = prev_param_names;
+        self.resolver.curr_callable = prev_callable;
     }
 
     fn visit_spec_decl(&mut self, decl: &ast::SpecDecl) {
@@ -1210,17 +1805,25 @@ impl AstVisitor<'_> for With<'_> {
             ast::ExprKind::TernOp(ast::TernOp::Update, container, index, replace)
             | ast::ExprKind::AssignUpdate(container, index, replace) => {
                 self.visit_expr(container);
-                if !is_field_update(
+                if is_field_update(
                     &self.resolver.globals,
                     self.resolver
                         .locals
                         .get_scopes(&self.resolver.curr_scope_chain),
                     index,
                 ) {
+                    if let ast::ExprKind::Path(path) = &*index.kind {
+                        self.resolver.check_field(&path.name);
+                    }
+                } else {
                     self.visit_expr(index);
                 }
                 self.visit_expr(replace);
             }
+            ast::ExprKind::Field(record, field) => {
+                self.visit_expr(record);
+                self.resolver.check_field(field);
+            }
             ast::ExprKind::Struct(path, copy, fields) => {
                 if let Err(e) = self.resolver.resolve_path(NameKind::Ty, path) {
                     self.resolver.errors.push(e);
@@ -1233,28 +1836,31 @@ impl AstVisitor<'_> for With<'_> {
     }
 }
 
+/// The types built into the language, which are declared in `Microsoft.Quantum.Core`.
+const BUILTIN_TYS: [(&str, Res); 10] = [
+    ("BigInt", Res::PrimTy(Prim::BigInt)),
+    ("Bool", Res::PrimTy(Prim::Bool)),
+    ("Double", Res::PrimTy(Prim::Double)),
+    ("Int", Res::PrimTy(Prim::Int)),
+    ("Pauli", Res::PrimTy(Prim::Pauli)),
+    ("Qubit", Res::PrimTy(Prim::Qubit)),
+    ("Range", Res::PrimTy(Prim::Range)),
+    ("Result", Res::PrimTy(Prim::Result)),
+    ("String", Res::PrimTy(Prim::String)),
+    ("Unit", Res::UnitTy),
+];
+
 pub(super) struct GlobalTable {
     names: Names,
     scope: GlobalScope,
+    fields: FxHashMap<Rc<str>, Vec<Ident>>,
 }
 
 impl GlobalTable {
     pub(super) fn new() -> Self {
-        let builtins: [(Rc<str>, Res); 10] = [
-            ("BigInt".into(), Res::PrimTy(Prim::BigInt)),
-            ("Bool".into(), Res::PrimTy(Prim::Bool)),
-            ("Double".into(), Res::PrimTy(Prim::Double)),
-            ("Int".into(), Res::PrimTy(Prim::Int)),
-            ("Pauli".into(), Res::PrimTy(Prim::Pauli)),
-            ("Qubit".into(), Res::PrimTy(Prim::Qubit)),
-            ("Range".into(), Res::PrimTy(Prim::Range)),
-            ("Result".into(), Res::PrimTy(Prim::Result)),
-            ("String".into(), Res::PrimTy(Prim::String)),
-            ("Unit".into(), Res::UnitTy),
-        ];
         let mut core: FxHashMap<Rc<str>, Res> = FxHashMap::default();
-        for (name, res) in builtins {
-            core.insert(name, res);
+        for (name, res) in BUILTIN_TYS {
+            core.insert(name.into(), res);
         }
 
         let mut scope = GlobalScope::default();
@@ -1274,7 +1880,9 @@ impl GlobalTable {
                 terms: IndexMap::default(),
                 namespaces: NamespaceTreeRoot::default(),
                 intrinsics: FxHashSet::default(),
+                definitions: IndexMap::default(),
             },
+            fields: FxHashMap::default(),
         }
     }
 
@@ -1284,9 +1892,11 @@ impl GlobalTable {
         package: &ast::Package,
     ) -> Vec<Error> {
         let mut errors = Vec::new();
+        let mut normalized_namespaces = FxHashMap::default();
         for node in &*package.nodes {
             match node {
                 TopLevelNode::Namespace(namespace) => {
+                    check_namespace_case(&mut normalized_namespaces, namespace, &mut errors);
                     bind_global_items(
                         &mut self.names,
                         &mut self.scope,
@@ -1294,6 +1904,9 @@ impl GlobalTable {
                         assigner,
                         &mut errors,
                     );
+                    for item in &*namespace.items {
+                        collect_fields(&mut self.fields, item);
+                    }
                 }
                 TopLevelNode::Stmt(_) => {
                     unimplemented!("did not expect top-level statements in the ast")
@@ -1339,6 +1952,30 @@ impl GlobalTable {
     }
 }
 
+/// Warns when `namespace` differs only in case from a namespace declared before it, keyed in
+/// `seen` by normalized name.
+fn check_namespace_case(
+    seen: &mut FxHashMap<String, Rc<str>>,
+    namespace: &ast::Namespace,
+    errors: &mut Vec<Error>,
+) {
+    let name = namespace.name.name();
+    match seen.entry(namespace.name.normalized_name()) {
+        Entry::Occupied(entry) => {
+            if *entry.get() != name {
+                errors.push(Error::CaseInsensitiveNamespaceCollision {
+                    a: entry.get().to_string(),
+                    b: name.to_string(),
+                    span: namespace.name.span(),
+                });
+            }
+        }
+        Entry::Vacant(entry) => {
+            entry.insert(name);
+        }
+    }
+}
+
 /// Given some namespace `namespace`, add all the globals declared within it to the global scope.
 fn bind_global_items(
     names: &mut IndexMap<NodeId, Res>,
@@ -1368,6 +2005,40 @@ fn bind_global_items(
     }
 }
 
+/// Records the name of the type declared by `item`, if any, under each named field it declares.
+fn collect_fields(fields: &mut FxHashMap<Rc<str>, Vec<Ident>>, item: &ast::Item) {
+    fn collect_ty_def(fields: &mut FxHashMap<Rc<str>, Vec<Ident>>, ty: &Ident, def: &ast::TyDef) {
+        match &*def.kind {
+            ast::TyDefKind::Field(Some(field), _) => {
+                fields
+                    .entry(field.name.clone())
+                    .or_default()
+                    .push(ty.clone());
+            }
+            ast::TyDefKind::Paren(def) => collect_ty_def(fields, ty, def),
+            ast::TyDefKind::Tuple(defs) => {
+                for def in &**defs {
+                    collect_ty_def(fields, ty, def);
+                }
+            }
+            ast::TyDefKind::Field(None, _) | ast::TyDefKind::Err => {}
+        }
+    }
+
+    match &*item.kind {
+        ast::ItemKind::Ty(name, def) => collect_ty_def(fields, name, def),
+        ast::ItemKind::Struct(decl) => {
+            for field in &*decl.fields {
+                fields
+                    .entry(field.name.name.clone())
+                    .or_default()
+                    .push((*decl.name).clone());
+            }
+        }
+        _ => {}
+    }
+}
+
 /// Tries to extract a field name from an expression in cases where it is syntactically ambiguous
 /// whether the expression is a field name or a variable name. This applies to the index operand in
 /// a ternary update operator.
@@ -1439,6 +2110,7 @@ fn bind_callable(
     let status = ItemStatus::from_attrs(&ast_attrs_as_hir_attrs(item.attrs.as_ref()));
     let res = Res::Item(item_id, status);
     names.insert(decl.name.id, res);
+    add_definition(scope, namespace, &decl.name, item_id, item);
     let mut errors = Vec::new();
 
     match scope
@@ -1487,6 +2159,8 @@ fn bind_ty(
     let status = ItemStatus::from_attrs(&ast_attrs_as_hir_attrs(item.attrs.as_ref()));
     let res = Res::Item(item_id, status);
     names.insert(name.id, res);
+    add_definition(scope, namespace, name, item_id, item);
+    let shadows_builtin = shadows_prelude(scope, namespace, &name.name);
     match (
         scope
             .terms
@@ -1512,11 +2186,56 @@ fn bind_ty(
         (Entry::Vacant(term_entry), Entry::Vacant(ty_entry)) => {
             term_entry.insert(res);
             ty_entry.insert(res);
-            Ok(())
+            if shadows_builtin {
+                Err(vec![Error::ShadowsBuiltin(
+                    name.name.to_string(),
+                    name.span,
+                )])
+            } else {
+                Ok(())
+            }
         }
     }
 }
 
+/// Returns `true` if a type declared in `namespace` with the given name shadows a built-in type or
+/// another item of the prelude namespaces, since the type and its constructor take precedence over
+/// the prelude item wherever `namespace` is open. Items declared in the prelude namespaces
+/// themselves are reported as duplicates instead.
+///
+/// Callables with the names of prelude items are only reported in strict prelude mode, as
+/// `PreludeShadowed` errors, since user code commonly declares operations such as `SWAP`.
+fn shadows_prelude(scope: &GlobalScope, namespace: NamespaceId, name: &str) -> bool {
+    let prelude = PRELUDE
+        .iter()
+        .filter_map(|prelude_namespace| scope.find_namespace(prelude_namespace.iter().copied()))
+        .collect::<Vec<_>>();
+    !prelude.contains(&namespace)
+        && prelude.into_iter().any(|prelude_namespace| {
+            scope.get(NameKind::Ty, prelude_namespace, name).is_some()
+                || scope.get(NameKind::Term, prelude_namespace, name).is_some()
+        })
+}
+
+fn add_definition(
+    scope: &mut GlobalScope,
+    namespace: NamespaceId,
+    name: &Ident,
+    item_id: ItemId,
+    item: &ast::Item,
+) {
+    scope
+        .definitions
+        .get_mut_or_default(namespace)
+        .entry(Rc::clone(&name.name))
+        .or_default()
+        .push((
+            Some(item_id),
+            preprocess::config_capabilities(&item.attrs),
+            name.span,
+        ));
+}
+
 fn decl_is_intrinsic(decl: &CallableDecl) -> bool {
     if let CallableBody::Specs(specs) = decl.body.as_ref() {
         specs
@@ -1563,9 +2282,9 @@ fn resolve<'a>(
             globals,
             provided_namespace_name,
             provided_symbol_name,
-            prelude_namespaces(globals).into_iter(),
+            prelude_namespace_ids(globals).into_iter(),
             // prelude is opened by default
-            &(std::iter::once((vec![], prelude_namespaces(globals))).collect()),
+            &(std::iter::once((vec![], prelude_namespace_ids(globals))).collect()),
         );
 
         if prelude_candidates.len() > 1 {
@@ -1584,9 +2303,14 @@ fn resolve<'a>(
             let candidate_b = candidates
                 .next()
                 .expect("infallible as per length check above");
+            let name = provided_symbol_name.name.to_string();
             return Err(Error::AmbiguousPrelude {
                 span: provided_symbol_name.span,
-                name: provided_symbol_name.name.to_string(),
+                suggestions: vec![
+                    format!("use `{candidate_a}.{name}`"),
+                    format!("use `{candidate_b}.{name}`"),
+                ],
+                name,
                 candidate_a,
                 candidate_b,
             });
@@ -1883,8 +2607,35 @@ where
     false
 }
 
+/// Returns the fully qualified names of the prelude namespaces, which are implicitly open in
+/// every namespace, in the order in which they are searched.
+#[must_use]
+pub fn prelude_namespaces() -> &'static [&'static str] {
+    &[
+        "Microsoft.Quantum.Canon",
+        "Microsoft.Quantum.Core",
+        "Microsoft.Quantum.Intrinsic",
+        "Microsoft.Quantum.Measurement",
+    ]
+}
+
+/// The number of single character insertions, deletions and substitutions that turn `a` into `b`.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b = b.chars().collect::<Vec<_>>();
+    let mut previous = (0..=b.len()).collect::<Vec<_>>();
+    for (i, a_char) in a.chars().enumerate() {
+        let mut current = vec![i + 1; b.len() + 1];
+        for (j, b_char) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(a_char != *b_char);
+            current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+        previous = current;
+    }
+    previous[b.len()]
+}
+
 /// Fetch the name and namespace ID of all prelude namespaces.
-pub fn prelude_namespaces(globals: &GlobalScope) -> Vec<(NamespaceId, String)> {
+fn prelude_namespace_ids(globals: &GlobalScope) -> Vec<(NamespaceId, String)> {
     let mut prelude = Vec::with_capacity(PRELUDE.len());
 
     // add prelude to the list of candidate namespaces last, as they are the final fallback for a symbol
@@ -3,13 +3,14 @@
 
 #![allow(clippy::needless_raw_string_hashes)]
 
-use super::{Error, Locals, Names, Res};
+use super::{prelude_namespaces, resolved_target, Error, Locals, Names, Res};
 use crate::{
     compile,
     resolve::{LocalKind, Resolver},
 };
 use expect_test::{expect, Expect};
 use indoc::indoc;
+use miette::Diagnostic;
 use qsc_ast::ast::{Idents, Item, ItemKind};
 use qsc_ast::{
     assigner::Assigner as AstAssigner,
@@ -20,7 +21,7 @@ use qsc_ast::{
 
 use qsc_data_structures::{
     language_features::LanguageFeatures,
-    namespaces::{NamespaceId, NamespaceTreeRoot},
+    namespaces::{NamespaceId, NamespaceTreeRoot, PRELUDE},
     span::Span,
     target::TargetCapabilityFlags,
 };
@@ -291,6 +292,24 @@ fn global_callable_duplicate_error() {
     );
 }
 
+#[test]
+fn namespaces_differing_only_in_case_warn() {
+    check(
+        indoc! {"
+            namespace Foo {}
+            namespace foo {}
+            namespace Foo {}
+        "},
+        &expect![[r#"
+            namespace namespace7 {}
+            namespace namespace8 {}
+            namespace namespace7 {}
+
+            // CaseInsensitiveNamespaceCollision { a: "Foo", b: "foo", span: Span { lo: 27, hi: 30 } }
+        "#]],
+    );
+}
+
 #[test]
 fn global_path() {
     check(
@@ -516,11 +535,51 @@ fn ambiguous_prelude() {
                 }
             }
 
-            // AmbiguousPrelude { name: "A", candidate_a: "Microsoft.Quantum.Canon", candidate_b: "Microsoft.Quantum.Core", span: Span { lo: 181, hi: 182 } }
+            // AmbiguousPrelude { name: "A", candidate_a: "Microsoft.Quantum.Canon", candidate_b: "Microsoft.Quantum.Core", suggestions: ["use `Microsoft.Quantum.Canon.A`", "use `Microsoft.Quantum.Core.A`"], span: Span { lo: 181, hi: 182 } }
         "#]],
     );
 }
 
+#[test]
+fn ambiguous_prelude_suggests_fully_qualified_names() {
+    let (_, _, _, errors, _) = compile(
+        indoc! {"
+        namespace Microsoft.Quantum.Canon {
+            function A() : Unit {}
+        }
+
+        namespace Microsoft.Quantum.Core {
+            function A() : Unit {}
+        }
+
+        namespace Foo {
+            function B() : Unit {
+                A();
+            }
+        }
+        "},
+        LanguageFeatures::default(),
+        TargetCapabilityFlags::all(),
+    );
+    let [Error::AmbiguousPrelude { suggestions, .. }] = errors.as_slice() else {
+        panic!("expected a single ambiguous prelude error, got {errors:#?}");
+    };
+    assert_eq!(
+        suggestions,
+        &[
+            "use `Microsoft.Quantum.Canon.A`",
+            "use `Microsoft.Quantum.Core.A`",
+        ]
+    );
+    expect![[r#"
+        both namespaces are implicitly opened by the prelude, to disambiguate use `Microsoft.Quantum.Canon.A` or use `Microsoft.Quantum.Core.A`
+    "#]]
+    .assert_eq(&format!(
+        "{}\n",
+        errors[0].help().expect("error should have help")
+    ));
+}
+
 #[test]
 fn local_var() {
     check(
@@ -1017,6 +1076,175 @@ fn struct_decl_duplicate_error_on_built_in_ty() {
     );
 }
 
+#[test]
+fn ty_decl_shadows_built_in_ty_in_user_namespace() {
+    check(
+        indoc! {"
+            namespace Foo {
+                newtype Result = Unit;
+                function Bar(r : Result) : Result { r }
+            }
+        "},
+        &expect![[r#"
+            namespace namespace7 {
+                newtype item1 = Unit;
+                function item2(local14 : item1) : item1 { local14 }
+            }
+
+            // ShadowsBuiltin("Result", Span { lo: 28, hi: 34 })
+        "#]],
+    );
+}
+
+#[test]
+fn struct_decl_shadows_built_in_ty_in_user_namespace() {
+    check(
+        indoc! {"
+            namespace Foo {
+                struct Result { Value : Bool }
+            }
+            namespace Baz {
+                open Foo;
+                function Qux(r : Result) : Unit {}
+            }
+        "},
+        &expect![[r#"
+            namespace namespace7 {
+                struct item1 { Value : Bool }
+            }
+            namespace namespace8 {
+                open namespace7;
+                function item3(local20 : item1) : Unit {}
+            }
+
+            // ShadowsBuiltin("Result", Span { lo: 27, hi: 33 })
+        "#]],
+    );
+}
+
+#[test]
+fn ty_decl_shadows_prelude_callable_in_user_namespace() {
+    check(
+        indoc! {"
+            namespace Microsoft.Quantum.Intrinsic {
+                operation H() : Unit {}
+            }
+            namespace Foo {
+                newtype H = Int;
+            }
+        "},
+        &expect![[r#"
+            namespace namespace5 {
+                operation item1() : Unit {}
+            }
+            namespace namespace7 {
+                newtype item3 = Int;
+            }
+
+            // ShadowsBuiltin("H", Span { lo: 98, hi: 99 })
+        "#]],
+    );
+}
+
+#[test]
+fn field_declared_by_struct_and_newtype_in_scope_is_ambiguous() {
+    check(
+        indoc! {"
+            namespace Foo {
+                struct Pair { First : Int, Second : Int }
+                newtype Wrapper = (First : Int, Last : Int);
+                function Bar(p : Pair, w : Wrapper) : Int {
+                    let q = p w/ First <- 1;
+                    q::First + w::Last
+                }
+            }
+        "},
+        &expect![[r#"
+            namespace namespace7 {
+                struct item1 { First : Int, Second : Int }
+                newtype item2 = (First : Int, Last : Int);
+                function item3(local34 : item1, local39 : item2) : Int {
+                    let local49 = local34 w/ First <- 1;
+                    local49::First + local39::Last
+                }
+            }
+
+            // AmbiguousField { name: "First", span: Span { lo: 180, hi: 185 } }
+            // AmbiguousField { name: "First", span: Span { lo: 203, hi: 208 } }
+        "#]],
+    );
+}
+
+#[test]
+fn field_declared_by_one_type_in_scope_is_not_ambiguous() {
+    check(
+        indoc! {"
+            namespace Foo {
+                struct Pair { First : Int, Second : Int }
+            }
+            namespace Baz {
+                newtype Wrapper = (First : Int, Last : Int);
+            }
+            namespace Qux {
+                open Foo;
+                function Bar(p : Pair) : Int {
+                    let q = p w/ First <- 1;
+                    q::First
+                }
+            }
+        "},
+        &expect![[r#"
+            namespace namespace7 {
+                struct item1 { First : Int, Second : Int }
+            }
+            namespace namespace8 {
+                newtype item3 = (First : Int, Last : Int);
+            }
+            namespace namespace9 {
+                open namespace7;
+                function item5(local40 : item1) : Int {
+                    let local50 = local40 w/ First <- 1;
+                    local50::First
+                }
+            }
+        "#]],
+    );
+}
+
+#[test]
+fn struct_decl_duplicate_field_error() {
+    check(
+        indoc! {"
+            namespace Foo {
+                struct A { x : Int, x : Bool }
+            }
+        "},
+        &expect![[r#"
+            namespace namespace7 {
+                struct item1 { x : Int, x : Bool }
+            }
+
+            // DuplicateField { name: "x", span: Span { lo: 40, hi: 41 } }
+        "#]],
+    );
+}
+
+#[test]
+fn struct_decl_distinct_fields_no_error() {
+    check(
+        indoc! {"
+            namespace Foo {
+                struct A { x : Int, y : Bool }
+            }
+        "},
+        &expect![[r#"
+            namespace namespace7 {
+                struct item1 { x : Int, y : Bool }
+            }
+        "#]],
+    );
+}
+
 #[test]
 fn ty_decl_in_ty_decl() {
     check(
@@ -1846,6 +2074,74 @@ fn local_open_shadows_parent_open() {
     );
 }
 
+#[test]
+fn local_open_in_inner_block_does_not_leak_to_enclosing_block() {
+    check(
+        indoc! {"
+            namespace A {
+                function Foo() : () {
+                    {
+                        open B;
+                        Bar();
+                    }
+                    Bar();
+                }
+            }
+
+            namespace B { function Bar() : () {} }
+        "},
+        &expect![[r#"
+            namespace namespace7 {
+                function item1() : () {
+                    {
+                        open namespace8;
+                        item3();
+                    }
+                    Bar();
+                }
+            }
+
+            namespace namespace8 { function item3() : () {} }
+
+            // NotFoundImportable("Bar", "`import B.Bar;`", Span { lo: 107, hi: 110 })
+        "#]],
+    );
+}
+
+#[test]
+fn local_open_in_expr_block_is_visible_to_local_function() {
+    check(
+        indoc! {"
+            namespace A {
+                function Foo() : Int {
+                    let x = {
+                        open B;
+                        function Local() : Int { Bar() }
+                        Local()
+                    };
+                    x
+                }
+            }
+
+            namespace B { function Bar() : Int { 1 } }
+        "},
+        &expect![[r#"
+            namespace namespace7 {
+                function item1() : Int {
+                    let local13 = {
+                        open namespace8;
+                        function item4() : Int { item3() }
+                        item4()
+                    };
+                    local13
+                }
+            }
+
+            namespace namespace8 { function item3() : Int { 1 } }
+        "#]],
+    );
+}
+
 #[test]
 fn update_array_index_var() {
     check(
@@ -2005,58 +2301,118 @@ fn update_udt_unknown_field_name_known_global() {
 }
 
 #[test]
-fn unknown_namespace() {
+fn field_access_name_known_global() {
     check(
         indoc! {"
             namespace A {
-                open Microsoft.Quantum.Fake;
+                newtype Triple = (First : Int, Second : Int, Third : Int);
+
+                function Third() : () {}
+
+                function Foo() : () {
+                    let t = Triple(1, 2, 3);
+                    let x = t::Third;
+                }
             }
         "},
         &expect![[r#"
             namespace namespace7 {
-                open Microsoft.Quantum.Fake;
-            }
+                newtype item1 = (First : Int, Second : Int, Third : Int);
 
-            // NotFound("Microsoft.Quantum.Fake", Span { lo: 23, hi: 45 })
+                function item2() : () {}
+
+                function item3() : () {
+                    let local35 = item1(1, 2, 3);
+                    let local46 = local35::Third;
+                }
+            }
         "#]],
     );
 }
 
 #[test]
-fn empty_namespace_works() {
+fn field_access_unknown_field_name_known_global() {
     check(
         indoc! {"
             namespace A {
-                open B;
-                function foo(): Unit{}
+                newtype Pair = (First : Int, Second : Int);
+
+                function Third() : () {}
+
+                function Foo() : () {
+                    let p = Pair(1, 2);
+                    let x = p::Third;
+                }
             }
-            namespace B {}
         "},
         &expect![[r#"
             namespace namespace7 {
-                open namespace8;
-                function item1(): Unit{}
+                newtype item1 = (First : Int, Second : Int);
+
+                function item2() : () {}
+
+                function item3() : () {
+                    let local30 = item1(1, 2);
+                    let local40 = local30::Third;
+                }
             }
-            namespace namespace8 {}
         "#]],
     );
 }
 
 #[test]
-fn cyclic_namespace_dependency_supported() {
+fn unknown_namespace() {
     check(
         indoc! {"
             namespace A {
-                open B;
-                operation Foo() : Unit {
-                    Bar();
-                }
+                open Microsoft.Quantum.Fake;
             }
-            namespace B {
-                open A;
-                operation Bar() : Unit {
-                    Foo();
-                }
+        "},
+        &expect![[r#"
+            namespace namespace7 {
+                open Microsoft.Quantum.Fake;
+            }
+
+            // NotFound("Microsoft.Quantum.Fake", Span { lo: 23, hi: 45 })
+        "#]],
+    );
+}
+
+#[test]
+fn empty_namespace_works() {
+    check(
+        indoc! {"
+            namespace A {
+                open B;
+                function foo(): Unit{}
+            }
+            namespace B {}
+        "},
+        &expect![[r#"
+            namespace namespace7 {
+                open namespace8;
+                function item1(): Unit{}
+            }
+            namespace namespace8 {}
+        "#]],
+    );
+}
+
+#[test]
+fn cyclic_namespace_dependency_supported() {
+    check(
+        indoc! {"
+            namespace A {
+                open B;
+                operation Foo() : Unit {
+                    Bar();
+                }
+            }
+            namespace B {
+                open A;
+                operation Bar() : Unit {
+                    Foo();
+                }
             }
         "},
         &expect![[r#"
@@ -2228,6 +2584,72 @@ fn dropped_base_callable_from_unrestricted() {
     );
 }
 
+#[test]
+fn export_of_dropped_base_callable_from_unrestricted() {
+    check_with_capabilities(
+        indoc! {"
+            namespace A {
+                @Config(Base)
+                function Dropped() : Unit {}
+
+                export Dropped;
+            }
+        "},
+        TargetCapabilityFlags::all(),
+        &expect![[r#"
+            namespace namespace7 {
+                @Config(Base)
+                function Dropped() : Unit {}
+
+                export Dropped;
+            }
+
+            // ExportOfDroppedItem { name: "Dropped", span: Span { lo: 77, hi: 84 } }
+        "#]],
+    );
+}
+
+#[test]
+fn export_of_callable_with_dropped_and_kept_declarations() {
+    check_with_capabilities(
+        indoc! {"
+            namespace A {
+                @Config(Base)
+                function Foo() : Unit {}
+
+                @Config(not Base)
+                function Foo() : Unit {}
+
+                export Foo;
+            }
+            namespace B {
+                open A;
+                function C() : Unit {
+                    Foo();
+                }
+            }
+        "},
+        TargetCapabilityFlags::all(),
+        &expect![[r#"
+            namespace namespace7 {
+                @Config(Base)
+                function Foo() : Unit {}
+
+                @Config(not Base)
+                function item1() : Unit {}
+
+                export item1;
+            }
+            namespace namespace8 {
+                open namespace7;
+                function item3() : Unit {
+                    item1();
+                }
+            }
+        "#]],
+    );
+}
+
 #[test]
 fn dropped_base_callable_from_adaptive() {
     check_with_capabilities(
@@ -2762,12 +3184,124 @@ fn multiple_definition_dropped_is_not_found() {
                 }
             }
 
-            // NotFound("B", Span { lo: 257, hi: 258 })
-            // NotFound("C", Span { lo: 270, hi: 271 })
+            // NotFoundImportable("B", "`import A.B;`", Span { lo: 257, hi: 258 })
+            // NotFoundImportable("C", "`import A.C;`", Span { lo: 270, hi: 271 })
         "#]],
     );
 }
 
+fn all_definitions_of(
+    input: &str,
+    capabilities: TargetCapabilityFlags,
+    namespace: &str,
+    name: &str,
+) -> String {
+    let (namespaces, parse_errors) =
+        qsc_parse::namespaces(input, None, LanguageFeatures::default());
+    assert!(parse_errors.is_empty(), "parse failed: {parse_errors:#?}");
+    let mut package = Package {
+        id: NodeId::default(),
+        nodes: namespaces
+            .into_iter()
+            .map(TopLevelNode::Namespace)
+            .collect::<Vec<_>>()
+            .into_boxed_slice(),
+        entry: None,
+    };
+
+    AstAssigner::new().visit_package(&mut package);
+
+    let mut cond_compile = compile::preprocess::Conditional::new(capabilities);
+    cond_compile.visit_package(&mut package);
+    let dropped_items = cond_compile.take_dropped_items();
+
+    let mut assigner = HirAssigner::new();
+    let mut globals = super::GlobalTable::new();
+    let errors = globals.add_local_package(&mut assigner, &package);
+    assert!(errors.is_empty(), "binding failed: {errors:#?}");
+    let mut resolver = Resolver::new(globals, cond_compile.into_names());
+    resolver.extend_dropped_items(dropped_items);
+    let namespace = resolver
+        .globals
+        .find_namespace(namespace.split('.'))
+        .expect("namespace should exist");
+    let mut output = String::new();
+    for (item, capabilities, span) in resolver.all_definitions_of(name, namespace) {
+        let item = item.map(|item| item.item);
+        writeln!(
+            output,
+            "{item:?} {:?} not {:?} {span}",
+            capabilities.required, capabilities.excluded
+        )
+        .expect("string should be writable");
+    }
+    output
+}
+
+#[test]
+fn all_definitions_of_includes_dropped_config_variants() {
+    let input = indoc! {"
+        namespace A {
+            @Config(Adaptive)
+            operation B() : Unit {}
+            @Config(Base)
+            operation B() : Unit {}
+            @Config(Base)
+            operation C() : Unit {}
+            @Config(Adaptive)
+            operation C() : Unit {}
+            operation D() : Unit {}
+        }
+    "};
+    let capabilities = TargetCapabilityFlags::all();
+    expect![[r"
+        Some(LocalItemId(1)) TargetCapabilityFlags(Adaptive) not TargetCapabilityFlags(0x0) [50-51]
+        None TargetCapabilityFlags(0x0) not TargetCapabilityFlags(0x0) [96-97]
+    "]]
+    .assert_eq(&all_definitions_of(input, capabilities, "A", "B"));
+    expect![[r"
+        None TargetCapabilityFlags(0x0) not TargetCapabilityFlags(0x0) [142-143]
+        Some(LocalItemId(2)) TargetCapabilityFlags(Adaptive) not TargetCapabilityFlags(0x0) [192-193]
+    "]]
+    .assert_eq(&all_definitions_of(input, capabilities, "A", "C"));
+    expect![[r"
+        Some(LocalItemId(3)) TargetCapabilityFlags(0x0) not TargetCapabilityFlags(0x0) [220-221]
+    "]]
+    .assert_eq(&all_definitions_of(input, capabilities, "A", "D"));
+    expect![[r"
+        None TargetCapabilityFlags(Adaptive) not TargetCapabilityFlags(0x0) [50-51]
+        Some(LocalItemId(1)) TargetCapabilityFlags(0x0) not TargetCapabilityFlags(0x0) [96-97]
+    "]]
+    .assert_eq(&all_definitions_of(
+        input,
+        TargetCapabilityFlags::empty(),
+        "A",
+        "B",
+    ));
+}
+
+#[test]
+fn all_definitions_of_records_negated_capabilities() {
+    let input = indoc! {"
+        namespace A {
+            @Config(not Adaptive)
+            operation B() : Unit {}
+            @Config(Adaptive)
+            operation B() : Unit {}
+        }
+    "};
+    expect![[r"
+        None TargetCapabilityFlags(0x0) not TargetCapabilityFlags(Adaptive) [54-55]
+        Some(LocalItemId(1)) TargetCapabilityFlags(Adaptive) not TargetCapabilityFlags(0x0) [104-105]
+    "]]
+    .assert_eq(&all_definitions_of(
+        input,
+        TargetCapabilityFlags::all(),
+        "A",
+        "B",
+    ));
+}
+
 #[test]
 fn disallow_duplicate_intrinsic() {
     check(
@@ -3630,6 +4164,56 @@ namespace Foo.Bar.Graule {
     );
 }
 
+#[test]
+fn resolved_targets_of_complicated_nested_export_statement() {
+    let source = indoc! {"
+            namespace Foo {
+                export Foo.Bar.Baz.Quux.HelloWorld;
+            }
+            namespace Foo.Bar.Baz.Quux {
+                function HelloWorld() : Unit {}
+            }
+            namespace Foo.Bar {
+                export Baz.Quux.HelloWorld;
+            }
+            namespace Foo.Bar.Baz {
+                export Quux.HelloWorld;
+            }
+            namespace Foo.Bar.Graule {
+                export Foo.HelloWorld;
+            }
+        "};
+    let (package, names, _, errors, _) = compile(
+        source,
+        LanguageFeatures::default(),
+        TargetCapabilityFlags::all(),
+    );
+    assert!(errors.is_empty(), "{errors:#?}");
+
+    let mut targets = String::new();
+    for node in &*package.nodes {
+        let TopLevelNode::Namespace(namespace) = node else {
+            continue;
+        };
+        for item in &*namespace.items {
+            if let ItemKind::ImportOrExport(decl) = &*item.kind {
+                for item in decl.items() {
+                    let target = resolved_target(&names, item);
+                    let path = &source[item.path.span];
+                    writeln!(targets, "{}: {path} -> {target:?}", namespace.name.name())
+                        .expect("string should be writable");
+                }
+            }
+        }
+    }
+    expect![[r#"
+        Foo: Foo.Bar.Baz.Quux.HelloWorld -> Some(Item(ItemId { package: None, item: LocalItemId(2) }, Available))
+        Foo.Bar: Baz.Quux.HelloWorld -> Some(Item(ItemId { package: None, item: LocalItemId(2) }, Available))
+        Foo.Bar.Baz: Quux.HelloWorld -> Some(Item(ItemId { package: None, item: LocalItemId(2) }, Available))
+        Foo.Bar.Graule: Foo.HelloWorld -> Some(Item(ItemId { package: None, item: LocalItemId(2) }, Available))
+    "#]].assert_eq(&targets);
+}
+
 #[test]
 fn exports_aware_of_opens() {
     check(
@@ -3709,6 +4293,57 @@ fn export_non_existent_symbol() {
     );
 }
 
+#[test]
+fn export_symbol_from_unopened_namespace() {
+    check(
+        indoc! {"
+            namespace Bar {
+                operation ApplyX() : Unit {}
+                newtype Pair = (Int, Int);
+            }
+            namespace Foo {
+                export ApplyX, Pair;
+            }
+        "},
+        &expect![[r#"
+            namespace namespace7 {
+                operation item1() : Unit {}
+                newtype item2 = (Int, Int);
+            }
+            namespace namespace8 {
+                export ApplyX, Pair;
+            }
+
+            // ExportNotInScope("ApplyX", "`Bar`", Span { lo: 109, hi: 115 })
+            // ExportNotInScope("Pair", "`Bar`", Span { lo: 117, hi: 121 })
+        "#]],
+    );
+}
+
+#[test]
+fn export_symbol_from_opened_namespace() {
+    check(
+        indoc! {"
+            namespace Bar {
+                operation ApplyX() : Unit {}
+            }
+            namespace Foo {
+                open Bar;
+                export ApplyX;
+            }
+        "},
+        &expect![[r#"
+            namespace namespace7 {
+                operation item1() : Unit {}
+            }
+            namespace namespace8 {
+                open namespace7;
+                export item1;
+            }
+        "#]],
+    );
+}
+
 #[test]
 fn export_symbol_from_nested_namespace() {
     check(
@@ -3960,82 +4595,182 @@ fn disallow_repeated_exports_inline() {
 }
 
 #[test]
-fn order_of_exports_does_not_matter() {
+fn reexport_of_different_items_under_same_name_is_ambiguous() {
     check(
         indoc! {"
+            namespace Foo {
+                operation X() : Unit {}
+            }
             namespace Bar {
-                export Foo.ApplyX;
-                export ApplyY;
-                operation ApplyY() : Unit {}
+                operation X() : Unit {}
             }
-            namespace Foo {
-                operation ApplyX() : Unit {}
+            namespace Main {
+                export Foo.X;
+            }
+            namespace Main {
+                export Bar.X;
             }
-
         "},
         &expect![[r#"
             namespace namespace7 {
-                export item3;
-                export item1;
                 operation item1() : Unit {}
             }
             namespace namespace8 {
                 operation item3() : Unit {}
             }
+            namespace namespace9 {
+                export item1;
+            }
+            namespace namespace9 {
+                export item3;
+            }
 
+            // AmbiguousReexport("X", "Main", Span { lo: 161, hi: 162 })
         "#]],
     );
 }
 
 #[test]
-fn export_udt_and_construct_it() {
+fn reexport_of_different_items_under_same_name_inline_is_ambiguous() {
     check(
         indoc! {"
             namespace Foo {
-                newtype Pair = (First: Int, Second: Int);
-                export Pair;
+                operation X() : Unit {}
+            }
+            namespace Bar {
+                operation Y() : Unit {}
             }
             namespace Main {
-                open Foo;
-                operation Main() : Unit {
-                    let z: Pair = Pair(1, 2);
-                }
+                export Foo.X, Bar.Y as X;
             }
         "},
         &expect![[r#"
             namespace namespace7 {
-                newtype item1 = (First: Int, Second: Int);
-                export item1;
+                operation item1() : Unit {}
             }
             namespace namespace8 {
-                open namespace7;
-                operation item3() : Unit {
-                    let local33: item1 = item1(1, 2);
-                }
+                operation item3() : Unit {}
+            }
+            namespace namespace9 {
+                export item1, item3;
             }
+
+            // AmbiguousReexport("X", "Main", Span { lo: 136, hi: 137 })
         "#]],
     );
 }
+
 #[test]
-fn import_single_item() {
+fn reexport_of_same_item_through_different_paths_is_allowed() {
     check(
         indoc! {"
             namespace Foo {
-                function Bar() : Unit {}
+                operation X() : Unit {}
+            }
+            namespace Bar {
+                export Foo.X;
             }
             namespace Main {
-                import Foo.Bar;
-                operation Main() : Unit {
-                    Bar();
-                }
+                export Foo.X;
+            }
+            namespace Main {
+                export Bar.X;
             }
         "},
         &expect![[r#"
             namespace namespace7 {
-                function item1() : Unit {}
+                operation item1() : Unit {}
             }
             namespace namespace8 {
-                import item1;
+                export item1;
+            }
+            namespace namespace9 {
+                export item1;
+            }
+            namespace namespace9 {
+                export item1;
+            }
+        "#]],
+    );
+}
+
+#[test]
+fn order_of_exports_does_not_matter() {
+    check(
+        indoc! {"
+            namespace Bar {
+                export Foo.ApplyX;
+                export ApplyY;
+                operation ApplyY() : Unit {}
+            }
+            namespace Foo {
+                operation ApplyX() : Unit {}
+            }
+
+        "},
+        &expect![[r#"
+            namespace namespace7 {
+                export item3;
+                export item1;
+                operation item1() : Unit {}
+            }
+            namespace namespace8 {
+                operation item3() : Unit {}
+            }
+
+        "#]],
+    );
+}
+
+#[test]
+fn export_udt_and_construct_it() {
+    check(
+        indoc! {"
+            namespace Foo {
+                newtype Pair = (First: Int, Second: Int);
+                export Pair;
+            }
+            namespace Main {
+                open Foo;
+                operation Main() : Unit {
+                    let z: Pair = Pair(1, 2);
+                }
+            }
+        "},
+        &expect![[r#"
+            namespace namespace7 {
+                newtype item1 = (First: Int, Second: Int);
+                export item1;
+            }
+            namespace namespace8 {
+                open namespace7;
+                operation item3() : Unit {
+                    let local33: item1 = item1(1, 2);
+                }
+            }
+        "#]],
+    );
+}
+#[test]
+fn import_single_item() {
+    check(
+        indoc! {"
+            namespace Foo {
+                function Bar() : Unit {}
+            }
+            namespace Main {
+                import Foo.Bar;
+                operation Main() : Unit {
+                    Bar();
+                }
+            }
+        "},
+        &expect![[r#"
+            namespace namespace7 {
+                function item1() : Unit {}
+            }
+            namespace namespace8 {
+                import item1;
                 operation item3() : Unit {
                     item1();
                 }
@@ -4418,6 +5153,48 @@ fn import_then_export() {
     );
 }
 
+#[test]
+fn import_with_alias_then_export_alias() {
+    check(
+        indoc! {"
+            namespace Foo {
+                operation Bar() : Unit {}
+            }
+            namespace Main {
+                import Foo.Bar as Baz;
+                export Baz;
+            }
+            namespace Consumer {
+                import Main.Baz;
+                operation Test() : Unit {
+                    Baz();
+                    Main.Baz();
+                    Main.Bar();
+                }
+            }
+        "},
+        &expect![[r#"
+            namespace namespace7 {
+                operation item1() : Unit {}
+            }
+            namespace namespace8 {
+                import item1;
+                export item1;
+            }
+            namespace namespace9 {
+                import item1;
+                operation item4() : Unit {
+                    item1();
+                    item1();
+                    namespace8.Bar();
+                }
+            }
+
+            // NotFound("Main.Bar", Span { lo: 225, hi: 233 })
+        "#]],
+    );
+}
+
 #[test]
 fn import_namespace_advanced() {
     check(
@@ -4533,7 +5310,7 @@ fn import_namespace_does_not_open_it() {
                 }
             }
 
-            // NotFound("DumpMachine", Span { lo: 214, hi: 225 })
+            // NotFoundImportable("DumpMachine", "`import Microsoft.Quantum.Diagnostics.DumpMachine;`", Span { lo: 214, hi: 225 })
         "#]],
     );
 }
@@ -4790,6 +5567,36 @@ fn import_aliased_glob() {
     );
 }
 
+#[test]
+fn open_import_alias_of_namespace() {
+    check(
+        indoc! {"
+            namespace Foo {
+                operation ApplyX() : Unit {}
+            }
+            namespace Main {
+                import Foo as Bar;
+                open Bar;
+                operation Main() : Unit {
+                    ApplyX();
+                }
+            }
+        "},
+        &expect![[r#"
+            namespace namespace7 {
+                operation item1() : Unit {}
+            }
+            namespace namespace8 {
+                import namespace7;
+                open Bar;
+                operation item3() : Unit {
+                    item1();
+                }
+            }
+        "#]],
+    );
+}
+
 #[test]
 fn disallow_glob_export() {
     check(
@@ -4933,3 +5740,355 @@ fn import_newtype() {
             }"#]],
     );
 }
+
+#[test]
+fn import_type_only_newtype() {
+    check(
+        indoc! {r#"
+                namespace Foo {
+                    import type Bar.NewType;
+
+                    operation FooOperation() : Unit {
+                        let x: NewType = NewType("a");
+                    }
+                }
+
+                namespace Bar {
+                    newtype NewType = String;
+                    export NewType;
+                }"#},
+        &expect![[r#"
+            namespace namespace7 {
+                import type item3;
+
+                operation item1() : Unit {
+                    let local17: item3 = NewType("a");
+                }
+            }
+
+            namespace namespace8 {
+                newtype item3 = String;
+                export item3;
+            }
+            // NotFoundImportable("NewType", "`import Bar.NewType;`", Span { lo: 109, hi: 116 })
+        "#]],
+    );
+}
+
+#[test]
+fn import_type_only_struct() {
+    check(
+        indoc! {r#"
+                namespace Foo {
+                    import type Bar.Pair;
+
+                    function Identity(p : Pair) : Pair {
+                        p
+                    }
+
+                    function MakePair() : Pair {
+                        Pair(1, 2)
+                    }
+                }
+
+                namespace Bar {
+                    struct Pair { First : Int, Second : Int }
+                    export Pair;
+                }"#},
+        &expect![[r#"
+            namespace namespace7 {
+                import type item4;
+
+                function item1(local12 : item4) : item4 {
+                    local12
+                }
+
+                function item2() : item4 {
+                    Pair(1, 2)
+                }
+            }
+
+            namespace namespace8 {
+                struct item4 { First : Int, Second : Int }
+                export item4;
+            }
+            // NotFoundImportable("Pair", "`import Bar.Pair;`", Span { lo: 142, hi: 146 })
+        "#]],
+    );
+}
+
+#[test]
+fn import_type_only_struct_expression_resolves() {
+    check(
+        indoc! {r#"
+                namespace Foo {
+                    import type Bar.Pair;
+
+                    function MakePair() : Pair {
+                        new Pair { First = 1, Second = 2 }
+                    }
+                }
+
+                namespace Bar {
+                    struct Pair { First : Int, Second : Int }
+                    export Pair;
+                }"#},
+        &expect![[r#"
+            namespace namespace7 {
+                import type item3;
+
+                function item1() : item3 {
+                    new item3 { First = 1, Second = 2 }
+                }
+            }
+
+            namespace namespace8 {
+                struct item3 { First : Int, Second : Int }
+                export item3;
+            }"#]],
+    );
+}
+
+#[test]
+fn disallow_glob_import_type() {
+    check(
+        indoc! {"
+            namespace Foo {
+                import type Bar.*;
+            }
+            namespace Bar {
+                struct Pair { First : Int, Second : Int }
+            }
+        "},
+        &expect![[r#"
+            namespace namespace7 {
+                import type namespace8.*;
+            }
+            namespace namespace8 {
+                struct item2 { First : Int, Second : Int }
+            }
+
+            // GlobTypeImportNotSupported(Span { lo: 32, hi: 35 })
+        "#]],
+    );
+}
+
+#[test]
+fn not_found_suggests_similar_names() {
+    check(
+        indoc! {"
+            namespace A {
+                function Bar() : Unit {}
+                function Frobnicate() : Unit {}
+                function BAZ() : Unit {}
+                newtype Pair = (Int, Int);
+            }
+            namespace B {
+                open A;
+                function Main() : Unit {
+                    let counter = 0;
+                    Barr();
+                    bar();
+                    BAR();
+                    let x = countr;
+                    let p : pair = Pair(1, 2);
+                    Frobnicte();
+                    Qux();
+                }
+            }
+            namespace C {
+                function Main() : Unit {
+                    Barr();
+                }
+            }
+        "},
+        &expect![[r#"
+            namespace namespace7 {
+                function item1() : Unit {}
+                function item2() : Unit {}
+                function item3() : Unit {}
+                newtype item4 = (Int, Int);
+            }
+            namespace namespace8 {
+                open namespace7;
+                function item6() : Unit {
+                    let local51 = 0;
+                    Barr();
+                    bar();
+                    BAR();
+                    let local73 = countr;
+                    let local79 : pair = item4(1, 2);
+                    Frobnicte();
+                    Qux();
+                }
+            }
+            namespace namespace9 {
+                function item8() : Unit {
+                    Barr();
+                }
+            }
+
+            // NotFoundSimilar("Barr", "`Bar`", Span { lo: 229, hi: 233 })
+            // NotFoundSimilar("bar", "`Bar`", Span { lo: 245, hi: 248 })
+            // NotFoundSimilar("BAR", "`Bar`", Span { lo: 260, hi: 263 })
+            // NotFoundSimilar("countr", "`counter`", Span { lo: 283, hi: 289 })
+            // NotFoundSimilar("pair", "`Pair`", Span { lo: 307, hi: 311 })
+            // NotFoundSimilar("Frobnicte", "`Frobnicate`", Span { lo: 334, hi: 343 })
+            // NotFound("Qux", Span { lo: 355, hi: 358 })
+            // NotFoundSimilar("Barr", "`A.Bar`", Span { lo: 421, hi: 425 })
+        "#]],
+    );
+}
+
+#[test]
+fn not_found_does_not_suggest_enclosing_callable() {
+    check(
+        indoc! {"
+            namespace A {
+                function Bars() : Unit {}
+                function Barz() : Unit {
+                    Barx();
+                }
+            }
+        "},
+        &expect![[r#"
+            namespace namespace7 {
+                function item1() : Unit {}
+                function item2() : Unit {
+                    Barx();
+                }
+            }
+
+            // NotFoundSimilar("Barx", "`Bars`", Span { lo: 81, hi: 85 })
+        "#]],
+    );
+}
+
+#[test]
+fn not_found_does_not_suggest_dropped_items() {
+    check(
+        indoc! {"
+            namespace A {
+                @Config(Unrestricted)
+                @Config(not Unrestricted)
+                function Bar() : Unit {}
+                function Main() : Unit {
+                    Barr();
+                }
+            }
+        "},
+        &expect![[r#"
+            namespace namespace7 {
+                @Config(Unrestricted)
+                @Config(not Unrestricted)
+                function Bar() : Unit {}
+                function item1() : Unit {
+                    Barr();
+                }
+            }
+
+            // NotFound("Barr", Span { lo: 136, hi: 140 })
+        "#]],
+    );
+}
+
+#[test]
+fn not_found_suggests_import_from_other_namespace() {
+    check(
+        indoc! {"
+            namespace A {
+                function Foo() : Unit {}
+                newtype Pair = (Int, Int);
+            }
+            namespace B {
+                function Foo() : Unit {}
+            }
+            namespace C {
+                function Bar() : Unit {
+                    Foo();
+                    let p : Pair = A.Pair(1, 2);
+                    Baz();
+                }
+            }
+        "},
+        &expect![[r#"
+            namespace namespace7 {
+                function item1() : Unit {}
+                newtype item2 = (Int, Int);
+            }
+            namespace namespace8 {
+                function item4() : Unit {}
+            }
+            namespace namespace9 {
+                function item6() : Unit {
+                    Foo();
+                    let local49 : Pair = item2(1, 2);
+                    Baz();
+                }
+            }
+
+            // NotFoundImportable("Foo", "`import A.Foo;` or `import B.Foo;`", Span { lo: 171, hi: 174 })
+            // NotFoundImportable("Pair", "`import A.Pair;`", Span { lo: 194, hi: 198 })
+            // NotFound("Baz", Span { lo: 223, hi: 226 })
+        "#]],
+    );
+}
+
+#[test]
+fn mutual_export_cycle_without_item_is_unresolvable() {
+    check(
+        indoc! {"
+            namespace A {
+                export B.Foo;
+            }
+            namespace B {
+                export A.Foo;
+            }
+        "},
+        &expect![[r#"
+            namespace namespace7 {
+                export B.Foo;
+            }
+            namespace namespace8 {
+                export A.Foo;
+            }
+
+            // UnresolvableImportCycle { names: "`A.Foo` -> `B.Foo` -> `A.Foo`", span: Span { lo: 25, hi: 30 } }
+            // NotFound("B.Foo", Span { lo: 25, hi: 30 })
+            // UnresolvableImportCycle { names: "`B.Foo` -> `A.Foo` -> `B.Foo`", span: Span { lo: 59, hi: 64 } }
+            // NotFound("A.Foo", Span { lo: 59, hi: 64 })
+        "#]],
+    );
+}
+
+#[test]
+fn self_import_of_declared_item_is_not_a_cycle() {
+    check(
+        indoc! {"
+            namespace Main {
+                import Main.Foo;
+                operation Foo() : Unit {}
+                operation Bar() : Unit { Foo(); }
+            }
+        "},
+        &expect![[r#"
+            namespace namespace7 {
+                import item1;
+                operation item1() : Unit {}
+                operation item2() : Unit { item1(); }
+            }
+        "#]],
+    );
+}
+
+#[test]
+fn prelude_namespaces_match_implicitly_opened_namespaces() {
+    let names = prelude_namespaces();
+    assert!(names.contains(&"Microsoft.Quantum.Core"));
+    assert!(names.contains(&"Microsoft.Quantum.Canon"));
+    assert_eq!(
+        names,
+        PRELUDE.map(|namespace| namespace.join(".")),
+        "names should match the namespaces the resolver opens"
+    );
+}
@@ -43,7 +43,21 @@ enum ErrorKind {
     #[error("expected {0}, found {1}")]
     #[diagnostic(code("Qsc.TypeCk.CallableMismatch"))]
     CallableMismatch(CallableKind, CallableKind, #[label] Span),
+    #[error("{found} called from a {expected}")]
+    #[diagnostic(help(
+        "functions cannot call operations, declare the caller as an operation instead"
+    ))]
+    #[diagnostic(code("Qsc.TypeCk.CallableKindMismatch"))]
+    CallableKindMismatch {
+        expected: CallableKind,
+        found: CallableKind,
+        #[label]
+        span: Span,
+    },
     #[error("expected {0}, found {1}")]
+    #[diagnostic(help(
+        "an operation supports exactly the functors listed after `is` in its declaration, which must match the functors expected here"
+    ))]
     #[diagnostic(code("Qsc.TypeCk.FunctorMismatch"))]
     FunctorMismatch(FunctorSet, FunctorSet, #[label] Span),
     #[error("type {0} does not support plus")]
@@ -104,6 +118,9 @@ enum ErrorKind {
     #[diagnostic(code("Qsc.TypeCk.MissingClassUnwrap"))]
     MissingClassUnwrap(String, #[label] Span),
     #[error("expected superset of {0}, found {1}")]
+    #[diagnostic(help(
+        "declare the operation with at least `is {0}` so that it supports the functors used here"
+    ))]
     #[diagnostic(code("Qsc.TypeCk.MissingFunctor"))]
     MissingFunctor(FunctorSet, FunctorSet, #[label] Span),
     #[error("missing type in item signature")]
@@ -127,6 +127,7 @@ impl Checker {
             ast::CallableBody::Block(block) => self.check_spec(
                 names,
                 SpecImpl {
+                    kind: convert::callable_kind_from_ast(decl.kind),
                     spec: ast::Spec::Body,
                     callable_input: &decl.input,
                     spec_input: None,
@@ -141,6 +142,7 @@ impl Checker {
                         self.check_spec(
                             names,
                             SpecImpl {
+                                kind: convert::callable_kind_from_ast(decl.kind),
                                 spec: spec.spec,
                                 callable_input: &decl.input,
                                 spec_input: Some(input),
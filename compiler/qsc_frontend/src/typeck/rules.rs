@@ -40,6 +40,10 @@ struct Context<'a> {
     globals: &'a FxHashMap<ItemId, Scheme>,
     table: &'a mut Table,
     return_ty: Option<Ty>,
+    /// The kind of the callable or lambda whose body is being inferred, if any.
+    callable_kind: Option<hir::CallableKind>,
+    /// The callee and span of each call made from the body of a function.
+    function_calls: Vec<(NodeId, Span)>,
     typed_holes: Vec<(NodeId, Span)>,
     new: Vec<NodeId>,
     inferrer: &'a mut Inferrer,
@@ -58,6 +62,8 @@ impl<'a> Context<'a> {
             globals,
             table,
             return_ty: None,
+            callable_kind: None,
+            function_calls: Vec::new(),
             typed_holes: Vec::new(),
             new,
             inferrer,
@@ -79,6 +85,7 @@ impl<'a> Context<'a> {
         }
 
         self.return_ty = Some(spec.output.clone());
+        self.callable_kind = Some(spec.kind);
         let block = self.infer_block(spec.block);
         if let Some(return_ty) = self.return_ty.take() {
             if block.ty == Ty::UNIT {
@@ -227,8 +234,11 @@ impl<'a> Context<'a> {
             }
             ExprKind::BinOp(op, lhs, rhs) => self.infer_binop(expr.span, *op, lhs, rhs),
             ExprKind::Block(block) => self.infer_block(block),
-            ExprKind::Call(callee, input) => {
-                let callee = self.infer_expr(callee);
+            ExprKind::Call(callee_expr, input) => {
+                if self.callable_kind == Some(hir::CallableKind::Function) {
+                    self.function_calls.push((callee_expr.id, expr.span));
+                }
+                let callee = self.infer_expr(callee_expr);
                 let input = self.infer_hole_tuple(
                     ArgTy::Hole,
                     ArgTy::Given,
@@ -355,7 +365,11 @@ impl<'a> Context<'a> {
                 let prev_ret_ty = self.return_ty.take();
                 let output_ty = self.inferrer.fresh_ty(TySource::not_divergent(body.span));
                 self.return_ty = Some(output_ty);
+                let prev_kind = self
+                    .callable_kind
+                    .replace(convert::callable_kind_from_ast(*kind));
                 let body_partial = self.infer_expr(body);
+                self.callable_kind = prev_kind;
                 let output_ty = self
                     .return_ty
                     .take()
@@ -842,6 +856,21 @@ impl<'a> Context<'a> {
             }
         }
 
+        // Calls are checked once types are solved, so that calls through values of inferred
+        // types are caught too.
+        for (callee, span) in self.function_calls {
+            let ty = self.table.terms.get(callee).expect("node should have type");
+            if let Ty::Arrow(arrow) = ty {
+                if arrow.kind == hir::CallableKind::Operation {
+                    errs.push(Error(super::ErrorKind::CallableKindMismatch {
+                        expected: hir::CallableKind::Function,
+                        found: arrow.kind,
+                        span,
+                    }));
+                }
+            }
+        }
+
         for (id, span) in self.typed_holes {
             let ty = self.table.terms.get_mut(id).expect("node should have type");
             errs.push(Error(super::ErrorKind::TyHole(ty.display(), span)));
@@ -853,6 +882,7 @@ impl<'a> Context<'a> {
 
 #[derive(Clone, Copy)]
 pub(super) struct SpecImpl<'a> {
+    pub(super) kind: hir::CallableKind,
     pub(super) spec: Spec,
     pub(super) callable_input: &'a Pat,
     pub(super) spec_input: Option<&'a Pat>,
@@ -10,6 +10,7 @@ use crate::{
 };
 use expect_test::{expect, Expect};
 use indoc::indoc;
+use miette::Diagnostic;
 use qsc_ast::{
     assigner::Assigner as AstAssigner,
     ast::{Block, Expr, NodeId, Package, Pat, QubitInit, TopLevelNode},
@@ -79,6 +80,27 @@ fn check(input: &str, entry_expr: &str, expect: &Expect) {
     expect.assert_eq(&actual);
 }
 
+/// Checks the innermost message and the help text of each error.
+fn check_help(input: &str, entry_expr: &str, expect: &Expect) {
+    let (_, _, errors) = compile(input, entry_expr);
+    let mut actual = String::new();
+    for error in errors {
+        let mut message = error.to_string();
+        let mut source = std::error::Error::source(&error);
+        while let Some(inner) = source {
+            message = inner.to_string();
+            source = inner.source();
+        }
+        let help = error
+            .help()
+            .map(|help| help.to_string())
+            .unwrap_or_default();
+        writeln!(actual, "{message}\n  help: {help}").expect("string should be writable");
+    }
+
+    expect.assert_eq(&actual);
+}
+
 fn compile(input: &str, entry_expr: &str) -> (Package, super::Table, Vec<compile::Error>) {
     let mut package = parse(input, entry_expr);
     AstAssigner::new().visit_package(&mut package);
@@ -1171,6 +1193,76 @@ fn ternop_update_udt_unknown_field_name_known_global() {
     );
 }
 
+#[test]
+fn udt_field_access_name_known_global() {
+    check(
+        indoc! {"
+            namespace A {
+                newtype Triple = (First : Int, Second : Int, Third : Int);
+
+                function Third() : () {}
+
+                function Foo() : Int {
+                    let t = Triple(1, 2, 3);
+                    t::Third
+                }
+            }
+        "},
+        "",
+        &expect![[r#"
+            #24 96-98 "()" : Unit
+            #26 104-106 "{}" : Unit
+            #30 124-126 "()" : Unit
+            #34 133-190 "{\n        let t = Triple(1, 2, 3);\n        t::Third\n    }" : Int
+            #36 147-148 "t" : UDT<"Triple": Item 1>
+            #38 151-166 "Triple(1, 2, 3)" : UDT<"Triple": Item 1>
+            #39 151-157 "Triple" : ((Int, Int, Int) -> UDT<"Triple": Item 1>)
+            #42 157-166 "(1, 2, 3)" : (Int, Int, Int)
+            #43 158-159 "1" : Int
+            #44 161-162 "2" : Int
+            #45 164-165 "3" : Int
+            #47 176-184 "t::Third" : Int
+            #48 176-177 "t" : UDT<"Triple": Item 1>
+        "#]],
+    );
+}
+
+#[test]
+fn udt_field_access_unknown_field_name_known_global() {
+    check(
+        indoc! {"
+            namespace A {
+                newtype Pair = (First : Int, Second : Int);
+
+                function Third() : () {}
+
+                function Foo() : () {
+                    let p = Pair(1, 2);
+                    let x = p::Third;
+                }
+            }
+        "},
+        "",
+        &expect![[r#"
+            #19 81-83 "()" : Unit
+            #21 89-91 "{}" : Unit
+            #25 109-111 "()" : Unit
+            #27 117-178 "{\n        let p = Pair(1, 2);\n        let x = p::Third;\n    }" : Unit
+            #29 131-132 "p" : UDT<"Pair": Item 1>
+            #31 135-145 "Pair(1, 2)" : UDT<"Pair": Item 1>
+            #32 135-139 "Pair" : ((Int, Int) -> UDT<"Pair": Item 1>)
+            #35 139-145 "(1, 2)" : (Int, Int)
+            #36 140-141 "1" : Int
+            #37 143-144 "2" : Int
+            #39 159-160 "x" : ?3
+            #41 163-171 "p::Third" : ?3
+            #42 163-164 "p" : UDT<"Pair": Item 1>
+            Error(Type(Error(MissingClassHasField("Pair", "Third", Span { lo: 163, hi: 171 }))))
+            Error(Type(Error(AmbiguousTy(Span { lo: 163, hi: 171 }))))
+        "#]],
+    );
+}
+
 #[test]
 fn ternop_update_array_range_takes_array() {
     check(
@@ -1569,6 +1661,39 @@ fn ctl_non_ctl() {
     );
 }
 
+#[test]
+fn ctl_non_ctl_help_names_missing_functor() {
+    check_help(
+        indoc! {"
+            namespace A {
+                operation Foo() : () is Adj {}
+            }
+        "},
+        "Controlled A.Foo",
+        &expect![[r#"
+            expected superset of Ctl, found Adj
+              help: declare the operation with at least `is Ctl` so that it supports the functors used here
+        "#]],
+    );
+}
+
+#[test]
+fn functor_mismatch_help_explains_declared_functors() {
+    check_help(
+        indoc! {"
+            namespace A {
+                operation Foo() : Unit is Ctl {}
+                function ReturnsFoo() : (Unit => Unit is Adj) { Foo }
+            }
+        "},
+        "",
+        &expect![[r#"
+            expected Adj, found Ctl
+              help: an operation supports exactly the functors listed after `is` in its declaration, which must match the functors expected here
+        "#]],
+    );
+}
+
 #[test]
 fn fail_diverges() {
     check(
@@ -4106,3 +4231,80 @@ fn lambda_on_array_where_item_used_in_call_should_be_inferred() {
         "#]],
     );
 }
+
+#[test]
+fn operation_called_from_function_is_callable_kind_mismatch() {
+    check(
+        indoc! {"
+            namespace A {
+                operation B() : Unit {}
+                function C() : Unit {
+                    B();
+                }
+            }
+        "},
+        "",
+        &expect![[r##"
+            #6 29-31 "()" : Unit
+            #10 39-41 "{}" : Unit
+            #14 56-58 "()" : Unit
+            #18 66-86 "{\n        B();\n    }" : Unit
+            #20 76-79 "B()" : Unit
+            #21 76-77 "B" : (Unit => Unit)
+            #24 77-79 "()" : Unit
+            Error(Type(Error(CallableKindMismatch { expected: Function, found: Operation, span: Span { lo: 76, hi: 79 } })))
+        "##]],
+    );
+}
+
+#[test]
+fn operation_called_from_operation_is_allowed() {
+    check(
+        indoc! {"
+            namespace A {
+                operation B() : Unit {}
+                operation C() : Unit {
+                    B();
+                }
+            }
+        "},
+        "",
+        &expect![[r##"
+            #6 29-31 "()" : Unit
+            #10 39-41 "{}" : Unit
+            #14 57-59 "()" : Unit
+            #18 67-87 "{\n        B();\n    }" : Unit
+            #20 77-80 "B()" : Unit
+            #21 77-78 "B" : (Unit => Unit)
+            #24 78-80 "()" : Unit
+        "##]],
+    );
+}
+
+#[test]
+fn operation_called_from_function_lambda_is_callable_kind_mismatch() {
+    check(
+        indoc! {"
+            namespace A {
+                operation B() : Unit {}
+                operation C() : Unit {
+                    let f = () -> B();
+                }
+            }
+        "},
+        "",
+        &expect![[r##"
+            #6 29-31 "()" : Unit
+            #10 39-41 "{}" : Unit
+            #14 57-59 "()" : Unit
+            #18 67-101 "{\n        let f = () -> B();\n    }" : Unit
+            #20 81-82 "f" : (Unit -> Unit)
+            #22 85-94 "() -> B()" : (Unit -> Unit)
+            #23 85-87 "()" : Unit
+            #24 91-94 "B()" : Unit
+            #25 91-92 "B" : (Unit => Unit)
+            #28 92-94 "()" : Unit
+            Error(Type(Error(CallableKindMismatch { expected: Function, found: Operation, span: Span { lo: 91, hi: 94 } })))
+        "##]],
+    );
+}
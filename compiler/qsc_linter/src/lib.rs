@@ -66,5 +66,5 @@ mod lints;
 #[cfg(test)]
 mod tests;
 
-pub use linter::{run_lints, Lint, LintConfig, LintKind, LintLevel};
+pub use linter::{merge_configs, run_lints, Lint, LintConfig, LintKind, LintLevel};
 pub use lints::{ast::AstLint, hir::HirLint};
@@ -28,6 +28,33 @@ pub fn run_lints(compile_unit: &CompileUnit, config: Option<&[LintConfig]>) -> V
         .collect()
 }
 
+/// Merges two lint configurations, such as a project-wide `base` and the `overrides` of a
+/// directory within the project, into a single configuration for [`run_lints`].
+///
+/// The precedence, from lowest to highest, is:
+///  1. the default level of each lint,
+///  2. the entries in `base`,
+///  3. the entries in `overrides`.
+///
+/// An entry in `overrides` replaces the entry in `base` for the same [`LintKind`], keeping its
+/// position, and kinds only present in `overrides` are appended in their order. Within a single
+/// configuration, the last entry for a kind wins, as it does in [`run_lints`], so the result
+/// holds at most one entry per kind.
+#[must_use]
+pub fn merge_configs(base: &[LintConfig], overrides: &[LintConfig]) -> Vec<LintConfig> {
+    let mut merged: Vec<LintConfig> = Vec::new();
+    for config in base.iter().chain(overrides) {
+        match merged.iter_mut().find(|entry| entry.kind == config.kind) {
+            Some(entry) => {
+                entry.level = config.level;
+                entry.threshold = config.threshold.or(entry.threshold);
+            }
+            None => merged.push(config.clone()),
+        }
+    }
+    merged
+}
+
 /// A lint emited by the linter.
 #[derive(Debug, Clone, thiserror::Error)]
 pub struct Lint {
@@ -110,6 +137,10 @@ pub struct LintConfig {
     pub kind: LintKind,
     /// The lint level.
     pub level: LintLevel,
+    /// A numeric limit for lints that take one, such as the number of qubits above which
+    /// `largeQubitAllocation` fires. Ignored by other lints; when absent, the lint's default is used.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub threshold: Option<usize>,
 }
 
 /// Represents a lint name.
@@ -17,12 +17,12 @@ use qsc_ast::{
 /// as input and outputs a [`Vec<Lint>`](Lint).
 #[must_use]
 pub fn run_ast_lints(package: &qsc_ast::ast::Package, config: Option<&[LintConfig]>) -> Vec<Lint> {
-    let config: Vec<(AstLint, LintLevel)> = config
+    let config: Vec<(AstLint, LintLevel, Option<usize>)> = config
         .unwrap_or(&[])
         .iter()
         .filter_map(|lint_config| {
             if let LintKind::Ast(kind) = lint_config.kind {
-                Some((kind, lint_config.level))
+                Some((kind, lint_config.level, lint_config.threshold))
             } else {
                 None
             }
@@ -108,6 +108,7 @@ macro_rules! declare_ast_lints {
     (@LINT_STRUCT $lint_name:ident, $default_level:expr, $msg:expr, $help:expr) => {
         pub(crate) struct $lint_name {
             level: LintLevel,
+            threshold: Option<usize>,
             message: &'static str,
             help: &'static str,
             kind: LintKind,
@@ -115,13 +116,13 @@ macro_rules! declare_ast_lints {
 
         impl Default for $lint_name {
             fn default() -> Self {
-                Self { level: Self::DEFAULT_LEVEL, message: $msg, help: $help, kind: LintKind::Ast(AstLint::$lint_name) }
+                Self { level: Self::DEFAULT_LEVEL, threshold: None, message: $msg, help: $help, kind: LintKind::Ast(AstLint::$lint_name) }
             }
         }
 
         impl From<LintLevel> for $lint_name {
             fn from(value: LintLevel) -> Self {
-                Self { level: value, message: $msg, help: $help, kind: LintKind::Ast(AstLint::$lint_name) }
+                Self { level: value, threshold: None, message: $msg, help: $help, kind: LintKind::Ast(AstLint::$lint_name) }
             }
         }
 
@@ -171,11 +172,16 @@ macro_rules! declare_ast_lints {
 
         // Most of the calls here are empty methods and they get optimized at compile time to a no-op.
         impl CombinedAstLints {
-            pub fn from_config(config: Vec<(AstLint, LintLevel)>) -> Self {
+            pub fn from_config(config: Vec<(AstLint, LintLevel, Option<usize>)>) -> Self {
                 let mut combined_ast_lints = Self::default();
-                for (lint, level) in config {
+                for (lint, level, threshold) in config {
                     match lint {
-                        $(AstLint::$lint_name => combined_ast_lints.$lint_name.level = level),*
+                        $(AstLint::$lint_name => {
+                            combined_ast_lints.$lint_name.level = level;
+                            if threshold.is_some() {
+                                combined_ast_lints.$lint_name.threshold = threshold;
+                            }
+                        }),*
                     }
                 }
                 combined_ast_lints
@@ -3,13 +3,24 @@
 
 use super::lint;
 use crate::linter::ast::declare_ast_lints;
-use qsc_ast::ast::{BinOp, ExprKind, Lit, StmtKind};
+use qsc_ast::{
+    ast::{
+        BinOp, Block, CallableBody, CallableKind, ExprKind, Item, ItemKind, Lit, Mutability, Pat,
+        PatKind, QubitInit, QubitInitKind, QubitSource, StmtKind,
+    },
+    visit::{self, Visitor},
+};
 use qsc_data_structures::span::Span;
+use std::rc::Rc;
 
 declare_ast_lints! {
     (DivisionByZero, LintLevel::Error, "attempt to divide by zero", "division by zero will fail at runtime"),
+    (EmptyBlock, LintLevel::Allow, "empty block", "remove the block, or document why it is intentionally empty"),
+    (LargeQubitAllocation, LintLevel::Warn, "allocation of too many qubits to simulate", "simulating more than the threshold number of qubits is infeasible; use resource estimation instead"),
     (NeedlessParens, LintLevel::Allow, "unnecessary parentheses", "remove the extra parentheses for clarity"),
     (RedundantSemicolons, LintLevel::Warn, "redundant semicolons", "remove the redundant semicolons"),
+    (UnnecessaryMutable, LintLevel::Warn, "mutable variable is never reassigned", "declare the variable with `let` instead"),
+    (UnusedQubit, LintLevel::Warn, "qubit is allocated but never used", "remove the allocation, or use `_` if the allocation is intentional"),
 }
 
 impl AstLintPass for DivisionByZero {
@@ -24,6 +35,79 @@ impl AstLintPass for DivisionByZero {
     }
 }
 
+impl EmptyBlock {
+    fn push_if_empty(&self, block: &Block, buffer: &mut Vec<Lint>) {
+        if block.stmts.is_empty() {
+            buffer.push(lint!(self, block.span));
+        }
+    }
+}
+
+impl AstLintPass for EmptyBlock {
+    /// Checks operation bodies. Entry points and operations that declare functor support
+    /// are often empty on purpose, as stubs or identity implementations, so they are skipped.
+    fn check_item(&self, item: &Item, buffer: &mut Vec<Lint>) {
+        if let ItemKind::Callable(decl) = &*item.kind {
+            let is_entry_point = item
+                .attrs
+                .iter()
+                .any(|attr| attr.name.name.as_ref() == "EntryPoint");
+            if decl.kind == CallableKind::Operation && decl.functors.is_none() && !is_entry_point {
+                if let CallableBody::Block(block) = &*decl.body {
+                    self.push_if_empty(block, buffer);
+                }
+            }
+        }
+    }
+
+    /// Checks the branches of conditionals.
+    fn check_expr(&self, expr: &qsc_ast::ast::Expr, buffer: &mut Vec<Lint>) {
+        if let ExprKind::If(_, body, els) = &*expr.kind {
+            self.push_if_empty(body, buffer);
+            if let Some(els) = els {
+                if let ExprKind::Block(block) = &*els.kind {
+                    self.push_if_empty(block, buffer);
+                }
+            }
+        }
+    }
+}
+
+/// The number of qubits above which [`LargeQubitAllocation`] fires when no threshold is configured.
+const DEFAULT_QUBIT_THRESHOLD: usize = 30;
+
+impl LargeQubitAllocation {
+    fn push_if_large(&self, init: &QubitInit, threshold: usize, buffer: &mut Vec<Lint>) {
+        match &*init.kind {
+            QubitInitKind::Array(size) => {
+                if let ExprKind::Lit(lit) = &*size.kind {
+                    if let Lit::Int(n) = **lit {
+                        if usize::try_from(n).is_ok_and(|n| n > threshold) {
+                            buffer.push(lint!(self, init.span));
+                        }
+                    }
+                }
+            }
+            QubitInitKind::Paren(init) => self.push_if_large(init, threshold, buffer),
+            QubitInitKind::Tuple(inits) => inits
+                .iter()
+                .for_each(|init| self.push_if_large(init, threshold, buffer)),
+            QubitInitKind::Single | QubitInitKind::Err => {}
+        }
+    }
+}
+
+impl AstLintPass for LargeQubitAllocation {
+    /// Checks `use` statements for qubit arrays whose size is an integer literal above the
+    /// threshold. Sizes computed at runtime are not known here and never fire.
+    fn check_stmt(&self, stmt: &qsc_ast::ast::Stmt, buffer: &mut Vec<Lint>) {
+        if let StmtKind::Qubit(QubitSource::Fresh, _, init, _) = &*stmt.kind {
+            let threshold = self.threshold.unwrap_or(DEFAULT_QUBIT_THRESHOLD);
+            self.push_if_large(init, threshold, buffer);
+        }
+    }
+}
+
 impl NeedlessParens {
     /// The idea is that if we find a expr of the form:
     /// a + (expr)
@@ -122,3 +206,137 @@ fn precedence(expr: &qsc_ast::ast::Expr) -> u8 {
         _ => 0,
     }
 }
+
+impl AstLintPass for UnusedQubit {
+    /// Checks each qubit allocation in the block for a use of the bound name in the
+    /// rest of its scope. For qubit arrays, using any element counts as a use.
+    /// Names are matched without resolution, so a later shadowing binding can hide
+    /// an unused qubit but never causes a false positive.
+    fn check_block(&self, block: &Block, buffer: &mut Vec<Lint>) {
+        for (i, stmt) in block.stmts.iter().enumerate() {
+            if let StmtKind::Qubit(_, pat, _, scope) = &*stmt.kind {
+                let mut bindings = Vec::new();
+                collect_bindings(pat, &mut bindings);
+                for (name, span) in bindings {
+                    let mut finder = FindName { name, found: false };
+                    match scope {
+                        Some(scope) => finder.visit_block(scope),
+                        None => block.stmts[i + 1..]
+                            .iter()
+                            .for_each(|stmt| finder.visit_stmt(stmt)),
+                    }
+                    if !finder.found {
+                        buffer.push(lint!(self, span));
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// The length of the `mutable` keyword, which starts every mutable binding statement.
+const MUTABLE_KEYWORD_LEN: u32 = 7;
+
+impl AstLintPass for UnnecessaryMutable {
+    /// Checks each `mutable` binding in the block for a `set` statement targeting one of the
+    /// bound names in the rest of the block. Plain assignments, compound assignments like `+=`,
+    /// and copy-and-update assignments like `w/=` all count as reassignments. As with
+    /// [`UnusedQubit`], names are matched without resolution, so shadowing can only hide a lint.
+    fn check_block(&self, block: &Block, buffer: &mut Vec<Lint>) {
+        for (i, stmt) in block.stmts.iter().enumerate() {
+            if let StmtKind::Local(Mutability::Mutable, pat, _) = &*stmt.kind {
+                let mut bindings = Vec::new();
+                collect_bindings(pat, &mut bindings);
+                let mut finder = FindAssignment {
+                    names: bindings.into_iter().map(|(name, _)| name).collect(),
+                    found: false,
+                };
+                block.stmts[i + 1..]
+                    .iter()
+                    .for_each(|stmt| finder.visit_stmt(stmt));
+                if !finder.found {
+                    let keyword = Span {
+                        lo: stmt.span.lo,
+                        hi: stmt.span.lo + MUTABLE_KEYWORD_LEN,
+                    };
+                    buffer.push(lint!(self, keyword));
+                }
+            }
+        }
+    }
+}
+
+fn collect_bindings(pat: &Pat, bindings: &mut Vec<(Rc<str>, Span)>) {
+    match &*pat.kind {
+        PatKind::Bind(ident, _) => bindings.push((ident.name.clone(), ident.span)),
+        PatKind::Paren(pat) => collect_bindings(pat, bindings),
+        PatKind::Tuple(pats) => pats.iter().for_each(|pat| collect_bindings(pat, bindings)),
+        PatKind::Discard(_) | PatKind::Elided | PatKind::Err => {}
+    }
+}
+
+/// Helper to check whether a local name is referenced, either directly or
+/// as the start of a field access path.
+struct FindName {
+    name: Rc<str>,
+    found: bool,
+}
+
+impl Visitor<'_> for FindName {
+    fn visit_expr(&mut self, expr: &qsc_ast::ast::Expr) {
+        if self.found {
+            return;
+        }
+        if let ExprKind::Path(path) = &*expr.kind {
+            let first = match &path.namespace {
+                Some(namespace) => namespace.iter().next().map(|ident| &ident.name),
+                None => Some(&path.name.name),
+            };
+            if first == Some(&self.name) {
+                self.found = true;
+                return;
+            }
+        }
+        visit::walk_expr(self, expr);
+    }
+}
+
+/// Helper to check whether any of the given local names is the target of an assignment.
+struct FindAssignment {
+    names: Vec<Rc<str>>,
+    found: bool,
+}
+
+impl FindAssignment {
+    fn check_target(&mut self, target: &qsc_ast::ast::Expr) {
+        match &*target.kind {
+            ExprKind::Path(path) => {
+                let first = match &path.namespace {
+                    Some(namespace) => namespace.iter().next().map(|ident| &ident.name),
+                    None => Some(&path.name.name),
+                };
+                if first.is_some_and(|first| self.names.contains(first)) {
+                    self.found = true;
+                }
+            }
+            ExprKind::Paren(expr) => self.check_target(expr),
+            ExprKind::Tuple(exprs) => exprs.iter().for_each(|expr| self.check_target(expr)),
+            _ => {}
+        }
+    }
+}
+
+impl Visitor<'_> for FindAssignment {
+    fn visit_expr(&mut self, expr: &qsc_ast::ast::Expr) {
+        if self.found {
+            return;
+        }
+        match &*expr.kind {
+            ExprKind::Assign(lhs, _)
+            | ExprKind::AssignOp(_, lhs, _)
+            | ExprKind::AssignUpdate(lhs, _, _) => self.check_target(lhs),
+            _ => {}
+        }
+        visit::walk_expr(self, expr);
+    }
+}
@@ -2,8 +2,11 @@
 // Licensed under the MIT License.
 
 use qsc_hir::{
-    hir::{CallableDecl, CallableKind, Expr, ExprKind, SpecBody, SpecDecl, Stmt, StmtKind},
-    ty::Ty,
+    hir::{
+        CallableDecl, CallableKind, Expr, ExprKind, Functor, SpecBody, SpecDecl, Stmt, StmtKind,
+        UnOp,
+    },
+    ty::{FunctorSet, Ty},
     visit::{self, Visitor},
 };
 
@@ -12,7 +15,10 @@ use crate::linter::hir::declare_hir_lints;
 use super::lint;
 
 declare_hir_lints! {
-    (NeedlessOperation, LintLevel::Allow, "operation does not contain any quantum operations", "this callable can be declared as a function instead")
+    (DoubleAdjoint, LintLevel::Warn, "redundant double `Adjoint`", "the two `Adjoint` functors cancel out, so use the operation on its own"),
+    (EmptyControls, LintLevel::Warn, "`Controlled` call with no control qubits", "call the operation directly instead"),
+    (MissingControlledFunctor, LintLevel::Error, "operation does not support the `Controlled` functor", "declare the operation with `is Ctl` so that it can be called under control"),
+    (NeedlessOperation, LintLevel::Allow, "operation does not contain any quantum operations", "this callable can be declared as a function instead"),
 }
 
 /// Helper to check if an operation has desired operation characteristics
@@ -104,3 +110,49 @@ impl HirLintPass for NeedlessOperation {
         }
     }
 }
+
+/// HIR Lint for [`MissingControlledFunctor`]. The controlled call is also a type error, but naming
+/// the missing `is Ctl` is clearer than the functor sets in the type error. Operations whose
+/// functors come from a functor parameter are not checked, since they depend on the caller.
+impl HirLintPass for MissingControlledFunctor {
+    fn check_expr(&self, expr: &Expr, buffer: &mut Vec<Lint>) {
+        if let ExprKind::UnOp(UnOp::Functor(Functor::Ctl), operand) = &expr.kind {
+            if let Ty::Arrow(arrow) = &operand.ty {
+                if let FunctorSet::Value(functors) = arrow.functors {
+                    if arrow.kind == CallableKind::Operation && !functors.contains(&Functor::Ctl) {
+                        buffer.push(lint!(self, operand.span));
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// HIR Lint for [`DoubleAdjoint`]. Parentheses are not part of the HIR, so this covers both
+/// `Adjoint Adjoint Op` and `Adjoint (Adjoint Op)`.
+impl HirLintPass for DoubleAdjoint {
+    fn check_expr(&self, expr: &Expr, buffer: &mut Vec<Lint>) {
+        if let ExprKind::UnOp(UnOp::Functor(Functor::Adj), operand) = &expr.kind {
+            if let ExprKind::UnOp(UnOp::Functor(Functor::Adj), _) = &operand.kind {
+                buffer.push(lint!(self, expr.span));
+            }
+        }
+    }
+}
+
+/// HIR Lint for [`EmptyControls`]. Only an empty array literal is detected, since the length of
+/// any other array of controls is not known until runtime.
+impl HirLintPass for EmptyControls {
+    fn check_expr(&self, expr: &Expr, buffer: &mut Vec<Lint>) {
+        if let ExprKind::Call(callee, args) = &expr.kind {
+            if let (ExprKind::UnOp(UnOp::Functor(Functor::Ctl), _), ExprKind::Tuple(args)) =
+                (&callee.kind, &args.kind)
+            {
+                if matches!(args.first(), Some(Expr { kind: ExprKind::Array(controls), .. }) if controls.is_empty())
+                {
+                    buffer.push(lint!(self, expr.span));
+                }
+            }
+        }
+    }
+}
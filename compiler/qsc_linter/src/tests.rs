@@ -3,7 +3,8 @@
 
 use crate::{
     linter::{ast::run_ast_lints, hir::run_hir_lints},
-    Lint, LintConfig, LintLevel,
+    lints::{ast::AstLint, hir::HirLint},
+    merge_configs, Lint, LintConfig, LintKind, LintLevel,
 };
 use expect_test::{expect, Expect};
 use indoc::indoc;
@@ -266,7 +267,457 @@ fn needless_operation_partial_application() {
     );
 }
 
+#[test]
+fn empty_if_branch() {
+    check(
+        &wrap_in_callable(
+            "let x = 1; if x == 1 {} else { Message(\"not one\"); }",
+            CallableKind::Function,
+        ),
+        &expect![[r#"
+            [
+                SrcLint {
+                    source: "{}",
+                    level: Allow,
+                    message: "empty block",
+                    help: "remove the block, or document why it is intentionally empty",
+                },
+            ]
+        "#]],
+    );
+}
+
+#[test]
+fn empty_else_branch() {
+    check(
+        &wrap_in_callable(
+            "let x = 1; if x == 1 { Message(\"one\"); } else {}",
+            CallableKind::Function,
+        ),
+        &expect![[r#"
+            [
+                SrcLint {
+                    source: "{}",
+                    level: Allow,
+                    message: "empty block",
+                    help: "remove the block, or document why it is intentionally empty",
+                },
+            ]
+        "#]],
+    );
+}
+
+#[test]
+fn empty_block_no_lint_for_entry_point_stub() {
+    check(
+        indoc! {"
+        @EntryPoint()
+        operation Main() : Unit {}
+    "},
+        &expect![[r"
+            []
+        "]],
+    );
+}
+
+#[test]
+fn empty_block_in_exported_operation_is_configurable() {
+    let source = indoc! {"
+        operation Placeholder() : Unit {}
+        export Placeholder;
+    "};
+    check(
+        source,
+        &expect![[r#"
+        [
+            SrcLint {
+                source: "{}",
+                level: Allow,
+                message: "empty block",
+                help: "remove the block, or document why it is intentionally empty",
+            },
+        ]
+    "#]],
+    );
+    check_with_config(
+        source,
+        Some(&[LintConfig {
+            kind: LintKind::Ast(AstLint::EmptyBlock),
+            level: LintLevel::Warn,
+            threshold: None,
+        }]),
+        &expect![[r#"
+            [
+                SrcLint {
+                    source: "{}",
+                    level: Warn,
+                    message: "empty block",
+                    help: "remove the block, or document why it is intentionally empty",
+                },
+            ]
+        "#]],
+    );
+}
+
+#[test]
+fn merged_config_override_allows_lint_warned_in_base() {
+    let source = wrap_in_callable("let x = 2 / 0;", CallableKind::Function);
+    let base = [LintConfig {
+        kind: LintKind::Ast(AstLint::DivisionByZero),
+        level: LintLevel::Warn,
+        threshold: None,
+    }];
+    let overrides = [LintConfig {
+        kind: LintKind::Ast(AstLint::DivisionByZero),
+        level: LintLevel::Allow,
+        threshold: None,
+    }];
+    check_with_config(
+        &source,
+        Some(&merge_configs(&base, &[])),
+        &expect![[r#"
+            [
+                SrcLint {
+                    source: "2 / 0",
+                    level: Warn,
+                    message: "attempt to divide by zero",
+                    help: "division by zero will fail at runtime",
+                },
+            ]
+        "#]],
+    );
+    check_with_config(
+        &source,
+        Some(&merge_configs(&base, &overrides)),
+        &expect![[r#"
+            [
+                SrcLint {
+                    source: "2 / 0",
+                    level: Allow,
+                    message: "attempt to divide by zero",
+                    help: "division by zero will fail at runtime",
+                },
+            ]
+        "#]],
+    );
+}
+
+#[test]
+fn merge_configs_replaces_by_kind_and_appends_new_kinds() {
+    let config = |kind, level| LintConfig {
+        kind,
+        level,
+        threshold: None,
+    };
+    let base = [
+        config(LintKind::Ast(AstLint::DivisionByZero), LintLevel::Warn),
+        config(LintKind::Hir(HirLint::NeedlessOperation), LintLevel::Warn),
+    ];
+    let overrides = [
+        config(LintKind::Ast(AstLint::EmptyBlock), LintLevel::Error),
+        config(LintKind::Ast(AstLint::DivisionByZero), LintLevel::Allow),
+        config(LintKind::Ast(AstLint::EmptyBlock), LintLevel::Warn),
+    ];
+    assert_eq!(
+        merge_configs(&base, &overrides),
+        [
+            config(LintKind::Ast(AstLint::DivisionByZero), LintLevel::Allow),
+            config(LintKind::Hir(HirLint::NeedlessOperation), LintLevel::Warn),
+            config(LintKind::Ast(AstLint::EmptyBlock), LintLevel::Warn),
+        ]
+    );
+}
+
+#[test]
+fn unused_qubit() {
+    check(
+        &wrap_in_callable(
+            "use q = Qubit(); use r = Qubit(); H(r);",
+            CallableKind::Operation,
+        ),
+        &expect![[r#"
+            [
+                SrcLint {
+                    source: "q",
+                    level: Warn,
+                    message: "qubit is allocated but never used",
+                    help: "remove the allocation, or use `_` if the allocation is intentional",
+                },
+            ]
+        "#]],
+    );
+}
+
+#[test]
+fn unused_qubit_no_lint_when_measured() {
+    check(
+        &wrap_in_callable("use q = Qubit(); let r = M(q);", CallableKind::Operation),
+        &expect![[r"
+            []
+        "]],
+    );
+}
+
+#[test]
+fn unused_qubit_array() {
+    check(
+        &wrap_in_callable(
+            "use qs = Qubit[3]; use (a, b) = (Qubit(), Qubit[2]); X(b[1]);",
+            CallableKind::Operation,
+        ),
+        &expect![[r#"
+            [
+                SrcLint {
+                    source: "qs",
+                    level: Warn,
+                    message: "qubit is allocated but never used",
+                    help: "remove the allocation, or use `_` if the allocation is intentional",
+                },
+                SrcLint {
+                    source: "a",
+                    level: Warn,
+                    message: "qubit is allocated but never used",
+                    help: "remove the allocation, or use `_` if the allocation is intentional",
+                },
+            ]
+        "#]],
+    );
+}
+
+#[test]
+fn unused_qubit_no_lint_when_array_element_used() {
+    check(
+        &wrap_in_callable("use qs = Qubit[3]; H(qs[2]);", CallableKind::Operation),
+        &expect![[r"
+            []
+        "]],
+    );
+}
+
+#[test]
+fn unused_qubit_in_scoped_allocation() {
+    check(
+        &wrap_in_callable(
+            "use q = Qubit() { Message(\"unused\"); } use r = Qubit() { H(r); }",
+            CallableKind::Operation,
+        ),
+        &expect![[r#"
+            [
+                SrcLint {
+                    source: "q",
+                    level: Warn,
+                    message: "qubit is allocated but never used",
+                    help: "remove the allocation, or use `_` if the allocation is intentional",
+                },
+            ]
+        "#]],
+    );
+}
+
+#[test]
+fn large_qubit_allocation() {
+    check(
+        &wrap_in_callable("use qs = Qubit[40]; H(qs[0]);", CallableKind::Operation),
+        &expect![[r#"
+            [
+                SrcLint {
+                    source: "Qubit[40]",
+                    level: Warn,
+                    message: "allocation of too many qubits to simulate",
+                    help: "simulating more than the threshold number of qubits is infeasible; use resource estimation instead",
+                },
+            ]
+        "#]],
+    );
+}
+
+#[test]
+fn large_qubit_allocation_no_lint_below_threshold() {
+    check(
+        &wrap_in_callable("use qs = Qubit[5]; H(qs[0]);", CallableKind::Operation),
+        &expect![[r"
+            []
+        "]],
+    );
+}
+
+#[test]
+fn large_qubit_allocation_uses_configured_threshold() {
+    check_with_config(
+        &wrap_in_callable("use qs = Qubit[5]; H(qs[0]);", CallableKind::Operation),
+        Some(&[LintConfig {
+            kind: LintKind::Ast(AstLint::LargeQubitAllocation),
+            level: LintLevel::Warn,
+            threshold: Some(4),
+        }]),
+        &expect![[r#"
+            [
+                SrcLint {
+                    source: "Qubit[5]",
+                    level: Warn,
+                    message: "allocation of too many qubits to simulate",
+                    help: "simulating more than the threshold number of qubits is infeasible; use resource estimation instead",
+                },
+            ]
+        "#]],
+    );
+}
+
+#[test]
+fn unnecessary_mutable() {
+    check(
+        &wrap_in_callable(
+            "mutable x = 1; mutable y = 2; set y = x + y;",
+            CallableKind::Function,
+        ),
+        &expect![[r#"
+            [
+                SrcLint {
+                    source: "mutable",
+                    level: Warn,
+                    message: "mutable variable is never reassigned",
+                    help: "declare the variable with `let` instead",
+                },
+            ]
+        "#]],
+    );
+}
+
+#[test]
+fn unnecessary_mutable_no_lint_when_reassigned() {
+    check(
+        &wrap_in_callable(
+            "mutable x = 1; if x > 0 { set x += 1; }",
+            CallableKind::Function,
+        ),
+        &expect![[r"
+            []
+        "]],
+    );
+}
+
+#[test]
+fn unnecessary_mutable_no_lint_when_updated() {
+    check(
+        &wrap_in_callable(
+            "mutable arr = [1, 2, 3]; set arr w/= 0 <- 4;",
+            CallableKind::Function,
+        ),
+        &expect![[r"
+            []
+        "]],
+    );
+}
+
+#[test]
+fn missing_controlled_functor_on_non_controllable_operation() {
+    check(
+        indoc! {"
+        operation Foo(q : Qubit) : Unit is Adj {}
+        operation Bar(cs : Qubit[], q : Qubit) : Unit {
+            Controlled Foo(cs, q);
+        }
+    "},
+        &expect![[r#"
+            [
+                SrcLint {
+                    source: "Foo",
+                    level: Error,
+                    message: "operation does not support the `Controlled` functor",
+                    help: "declare the operation with `is Ctl` so that it can be called under control",
+                },
+            ]
+        "#]],
+    );
+}
+
+#[test]
+fn missing_controlled_functor_not_reported_for_controllable_operation() {
+    check(
+        indoc! {"
+        operation Foo(q : Qubit) : Unit is Adj + Ctl {}
+        operation Bar(cs : Qubit[], q : Qubit) : Unit {
+            Controlled Foo(cs, q);
+        }
+    "},
+        &expect![[r"
+            []
+        "]],
+    );
+}
+
+#[test]
+fn double_adjoint() {
+    check(
+        indoc! {"
+        operation Foo(q : Qubit) : Unit is Adj {}
+        operation Bar(q : Qubit) : Unit {
+            Adjoint Adjoint Foo(q);
+            Adjoint (Adjoint Foo)(q);
+        }
+    "},
+        &expect![[r#"
+            [
+                SrcLint {
+                    source: "Adjoint Adjoint Foo",
+                    level: Warn,
+                    message: "redundant double `Adjoint`",
+                    help: "the two `Adjoint` functors cancel out, so use the operation on its own",
+                },
+                SrcLint {
+                    source: "Adjoint (Adjoint Foo)",
+                    level: Warn,
+                    message: "redundant double `Adjoint`",
+                    help: "the two `Adjoint` functors cancel out, so use the operation on its own",
+                },
+            ]
+        "#]],
+    );
+}
+
+#[test]
+fn single_adjoint_is_not_reported() {
+    check(
+        indoc! {"
+        operation Foo(q : Qubit) : Unit is Adj {}
+        operation Bar(q : Qubit) : Unit {
+            Adjoint Foo(q);
+        }
+    "},
+        &expect![[r"
+            []
+        "]],
+    );
+}
+
+#[test]
+fn controlled_call_with_empty_controls() {
+    check(
+        indoc! {"
+        operation Foo(q : Qubit) : Unit is Ctl {}
+        operation Bar(cs : Qubit[], q : Qubit) : Unit {
+            Controlled Foo([], q);
+            Controlled Foo(cs, q);
+        }
+    "},
+        &expect![[r#"
+            [
+                SrcLint {
+                    source: "Controlled Foo([], q)",
+                    level: Warn,
+                    message: "`Controlled` call with no control qubits",
+                    help: "call the operation directly instead",
+                },
+            ]
+        "#]],
+    );
+}
+
 fn check(source: &str, expected: &Expect) {
+    check_with_config(source, None, expected);
+}
+
+fn check_with_config(source: &str, config: Option<&[LintConfig]>, expected: &Expect) {
     let source = wrap_in_namespace(source);
     let mut store = PackageStore::new(compile::core());
     let std = store.insert(compile::std(&store, TargetCapabilityFlags::all()));
@@ -280,7 +731,7 @@ fn check(source: &str, expected: &Expect) {
         LanguageFeatures::default(),
     );
 
-    let actual: Vec<SrcLint> = run_lints(&package, None)
+    let actual: Vec<SrcLint> = run_lints(&package, config)
         .into_iter()
         .map(|lint| SrcLint::from(&lint, &source))
         .collect();
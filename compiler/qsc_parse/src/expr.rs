@@ -13,7 +13,7 @@ use crate::{
         ClosedBinOp, Delim, InterpolatedEnding, InterpolatedStart, Radix, StringToken, Token,
         TokenKind,
     },
-    prim::{ident, opt, pat, path, recovering_token, seq, shorten, token},
+    prim::{ident, opt, pat, path, recovering_token, seq, shorten, token, FinalSep},
     scan::ParserContext,
     stmt, Error, ErrorKind, Result,
 };
@@ -152,7 +152,7 @@ fn expr_op(s: &mut ParserContext, context: OpContext) -> Result<Box<Expr>> {
 fn expr_base(s: &mut ParserContext) -> Result<Box<Expr>> {
     let lo = s.peek().span.lo;
     let kind = if token(s, TokenKind::Open(Delim::Paren)).is_ok() {
-        let (exprs, final_sep) = seq(s, expr)?;
+        let (exprs, final_sep) = with_trailing_lambdas(s, true, |s| seq(s, expr))?;
         token(s, TokenKind::Close(Delim::Paren))?;
         Ok(Box::new(final_sep.reify(
             exprs,
@@ -168,7 +168,7 @@ fn expr_base(s: &mut ParserContext) -> Result<Box<Expr>> {
     } else if token(s, TokenKind::Keyword(Keyword::For)).is_ok() {
         let vars = pat(s)?;
         token(s, TokenKind::Keyword(Keyword::In))?;
-        let iter = expr(s)?;
+        let iter = cond_expr(s)?;
         let body = stmt::parse_block(s)?;
         Ok(Box::new(ExprKind::For(vars, iter, body)))
     } else if token(s, TokenKind::Keyword(Keyword::If)).is_ok() {
@@ -180,7 +180,7 @@ fn expr_base(s: &mut ParserContext) -> Result<Box<Expr>> {
     } else if token(s, TokenKind::Keyword(Keyword::Repeat)).is_ok() {
         let body = stmt::parse_block(s)?;
         token(s, TokenKind::Keyword(Keyword::Until))?;
-        let cond = expr(s)?;
+        let cond = condition(s, expr)?;
         let fixup = if token(s, TokenKind::Keyword(Keyword::Fixup)).is_ok() {
             Some(stmt::parse_block(s)?)
         } else {
@@ -192,7 +192,10 @@ fn expr_base(s: &mut ParserContext) -> Result<Box<Expr>> {
     } else if token(s, TokenKind::Keyword(Keyword::Set)).is_ok() {
         expr_set(s)
     } else if token(s, TokenKind::Keyword(Keyword::While)).is_ok() {
-        Ok(Box::new(ExprKind::While(expr(s)?, stmt::parse_block(s)?)))
+        Ok(Box::new(ExprKind::While(
+            condition(s, cond_expr)?,
+            stmt::parse_block(s)?,
+        )))
     } else if token(s, TokenKind::Keyword(Keyword::Within)).is_ok() {
         let outer = stmt::parse_block(s)?;
         token(s, TokenKind::Keyword(Keyword::Apply))?;
@@ -257,7 +260,7 @@ fn parse_field_assign(s: &mut ParserContext) -> Result<Box<FieldAssign>> {
 }
 
 fn expr_if(s: &mut ParserContext) -> Result<Box<ExprKind>> {
-    let cond = expr(s)?;
+    let cond = condition(s, cond_expr)?;
     let body = stmt::parse_block(s)?;
     let lo = s.peek().span.lo;
 
@@ -431,9 +434,17 @@ fn lit_token(lexeme: &str, token: Token) -> Result<Option<Lit>> {
         }
         TokenKind::Int(radix) => {
             let offset = if radix == Radix::Decimal { 0 } else { 2 };
-            let value = lit_int(&lexeme[offset..], radix.into())
-                .ok_or(Error(ErrorKind::Lit("integer", token.span)))?;
-            Ok(Some(Lit::Int(value)))
+            let lexeme = &lexeme[offset..];
+            match lit_int(lexeme, radix.into()) {
+                Some(value) => Ok(Some(Lit::Int(value))),
+                // Non-decimal literals that don't fit in an `Int` are promoted to `BigInt`.
+                None if radix != Radix::Decimal => {
+                    let value = BigInt::from_str_radix(lexeme, radix.into())
+                        .map_err(|_| Error(ErrorKind::Lit("integer", token.span)))?;
+                    Ok(Some(Lit::BigInt(Box::new(value))))
+                }
+                None => Err(Error(ErrorKind::Lit("integer", token.span))),
+            }
         }
         TokenKind::String(StringToken::Normal) => {
             let lexeme = shorten(1, 1, lexeme);
@@ -678,8 +689,12 @@ fn index_op(s: &mut ParserContext, lhs: Box<Expr>) -> Result<Box<ExprKind>> {
 
 fn call_op(s: &mut ParserContext, lhs: Box<Expr>) -> Result<Box<ExprKind>> {
     let lo = s.span(0).hi - 1;
-    let (args, final_sep) = seq(s, expr)?;
+    let (mut args, mut final_sep) = with_trailing_lambdas(s, true, |s| seq(s, expr))?;
     token(s, TokenKind::Close(Delim::Paren))?;
+    if s.trailing_lambdas() && token(s, TokenKind::Open(Delim::Brace)).is_ok() {
+        args.push(trailing_lambda(s)?);
+        final_sep = FinalSep::Missing;
+    }
     let rhs = Box::new(Expr {
         id: NodeId::default(),
         span: s.span(lo),
@@ -688,6 +703,62 @@ fn call_op(s: &mut ParserContext, lhs: Box<Expr>) -> Result<Box<ExprKind>> {
     Ok(Box::new(ExprKind::Call(lhs, rhs)))
 }
 
+/// Parses the lambda of a call like `ApplyToEach(qubits) { q => H(q) }` after the opening brace,
+/// so that it can be appended to the call's arguments as if it were written `ApplyToEach(qubits, q => H(q))`.
+fn trailing_lambda(s: &mut ParserContext) -> Result<Box<Expr>> {
+    let lambda = with_trailing_lambdas(s, true, expr)?;
+    if !matches!(*lambda.kind, ExprKind::Lambda(..)) {
+        return Err(Error(ErrorKind::Convert(
+            "lambda",
+            "expression",
+            lambda.span,
+        )));
+    }
+    token(s, TokenKind::Close(Delim::Brace))?;
+    Ok(lambda)
+}
+
+/// Parses an expression that is immediately followed by a block, such as the condition of an `if`
+/// expression. A block after a call in this position is the body of the enclosing expression, so it
+/// is never parsed as a trailing lambda.
+fn cond_expr(s: &mut ParserContext) -> Result<Box<Expr>> {
+    with_trailing_lambdas(s, false, expr)
+}
+
+/// Parses the condition of an `if`, `elif`, `while` or `until` with `p`. A `=` following the
+/// condition is most likely a mistyped `==`, so it is reported and the condition is recovered
+/// as an equality comparison.
+fn condition(
+    s: &mut ParserContext,
+    mut p: impl FnMut(&mut ParserContext) -> Result<Box<Expr>>,
+) -> Result<Box<Expr>> {
+    let lo = s.peek().span.lo;
+    let lhs = p(s)?;
+    if s.peek().kind != TokenKind::Eq {
+        return Ok(lhs);
+    }
+
+    s.push_error(Error(ErrorKind::DidYouMeanEquality(s.peek().span)));
+    s.advance();
+    let rhs = p(s)?;
+    Ok(Box::new(Expr {
+        id: NodeId::default(),
+        span: s.span(lo),
+        kind: Box::new(ExprKind::BinOp(BinOp::Eq, lhs, rhs)),
+    }))
+}
+
+fn with_trailing_lambdas<T>(
+    s: &mut ParserContext,
+    allowed: bool,
+    mut p: impl FnMut(&mut ParserContext) -> Result<T>,
+) -> Result<T> {
+    let previous = s.set_trailing_lambdas(allowed);
+    let result = p(s);
+    s.set_trailing_lambdas(previous);
+    result
+}
+
 fn range_op(s: &mut ParserContext, start: Box<Expr>) -> Result<Box<ExprKind>> {
     let rhs = expr_op(s, OpContext::Precedence(RANGE_PRECEDENCE + 1))?;
     Ok(Box::new(if token(s, TokenKind::DotDot).is_ok() {
@@ -4,8 +4,18 @@
 #![allow(clippy::needless_raw_string_hashes)]
 
 use super::expr;
-use crate::tests::check;
+use crate::{scan::ParserContext, tests::check};
 use expect_test::expect;
+use qsc_ast::mut_visit::MutVisitor;
+use qsc_data_structures::{language_features::LanguageFeatures, span::Span};
+
+struct SpanEraser;
+
+impl MutVisitor for SpanEraser {
+    fn visit_span(&mut self, span: &mut Span) {
+        *span = Span::default();
+    }
+}
 
 #[test]
 fn lit_int() {
@@ -87,40 +97,20 @@ fn lit_int_too_big() {
 }
 
 #[test]
-fn lit_int_too_big_hexadecimal() {
+fn lit_int_too_big_hexadecimal_promotes_to_bigint() {
     check(
         expr,
         "0x8000000000000001",
-        &expect![[r#"
-            Error(
-                Lit(
-                    "integer",
-                    Span {
-                        lo: 0,
-                        hi: 18,
-                    },
-                ),
-            )
-        "#]],
+        &expect!["Expr _id_ [0-18]: Lit: BigInt(9223372036854775809)"],
     );
 }
 
 #[test]
-fn lit_int_too_big_binary() {
+fn lit_int_too_big_binary_promotes_to_bigint() {
     check(
         expr,
         "0b1000000000000000000000000000000000000000000000000000000000000001",
-        &expect![[r#"
-            Error(
-                Lit(
-                    "integer",
-                    Span {
-                        lo: 0,
-                        hi: 66,
-                    },
-                ),
-            )
-        "#]],
+        &expect!["Expr _id_ [0-66]: Lit: BigInt(9223372036854775809)"],
     );
 }
 
@@ -162,6 +152,43 @@ fn lit_int_binary() {
     check(expr, "0b10110", &expect!["Expr _id_ [0-7]: Lit: Int(22)"]);
 }
 
+#[test]
+fn lit_int_hexadecimal_upper() {
+    check(expr, "0xFF", &expect!["Expr _id_ [0-4]: Lit: Int(255)"]);
+}
+
+#[test]
+fn lit_int_binary_nibble() {
+    check(expr, "0b1010", &expect!["Expr _id_ [0-6]: Lit: Int(10)"]);
+}
+
+#[test]
+fn lit_int_underscore_millions() {
+    check(
+        expr,
+        "1_000_000",
+        &expect!["Expr _id_ [0-9]: Lit: Int(1000000)"],
+    );
+}
+
+#[test]
+fn lit_int_hexadecimal_underscore() {
+    check(
+        expr,
+        "0xFF_FF",
+        &expect!["Expr _id_ [0-7]: Lit: Int(65535)"],
+    );
+}
+
+#[test]
+fn lit_int_too_big_hexadecimal_many_digits() {
+    check(
+        expr,
+        "0x1_0000_0000_0000_0000",
+        &expect!["Expr _id_ [0-23]: Lit: BigInt(18446744073709551616)"],
+    );
+}
+
 #[test]
 fn lit_bigint() {
     check(expr, "123L", &expect!["Expr _id_ [0-4]: Lit: BigInt(123)"]);
@@ -563,6 +590,62 @@ fn if_then() {
     );
 }
 
+#[test]
+fn if_assign_cond_did_you_mean_equality() {
+    check(
+        expr,
+        "if x = 1 { e }",
+        &expect![[r#"
+            Expr _id_ [0-14]: If:
+                Expr _id_ [3-8]: BinOp (Eq):
+                    Expr _id_ [3-4]: Path: Path _id_ [3-4] (Ident _id_ [3-4] "x")
+                    Expr _id_ [7-8]: Lit: Int(1)
+                Block _id_ [9-14]:
+                    Stmt _id_ [11-12]: Expr: Expr _id_ [11-12]: Path: Path _id_ [11-12] (Ident _id_ [11-12] "e")
+
+            [
+                Error(
+                    DidYouMeanEquality(
+                        Span {
+                            lo: 5,
+                            hi: 6,
+                        },
+                    ),
+                ),
+            ]"#]],
+    );
+}
+
+#[test]
+fn elif_assign_cond_did_you_mean_equality() {
+    check(
+        expr,
+        "if c { x } elif y = 2 { z }",
+        &expect![[r#"
+            Expr _id_ [0-27]: If:
+                Expr _id_ [3-4]: Path: Path _id_ [3-4] (Ident _id_ [3-4] "c")
+                Block _id_ [5-10]:
+                    Stmt _id_ [7-8]: Expr: Expr _id_ [7-8]: Path: Path _id_ [7-8] (Ident _id_ [7-8] "x")
+                Expr _id_ [11-27]: If:
+                    Expr _id_ [16-21]: BinOp (Eq):
+                        Expr _id_ [16-17]: Path: Path _id_ [16-17] (Ident _id_ [16-17] "y")
+                        Expr _id_ [20-21]: Lit: Int(2)
+                    Block _id_ [22-27]:
+                        Stmt _id_ [24-25]: Expr: Expr _id_ [24-25]: Path: Path _id_ [24-25] (Ident _id_ [24-25] "z")
+
+            [
+                Error(
+                    DidYouMeanEquality(
+                        Span {
+                            lo: 18,
+                            hi: 19,
+                        },
+                    ),
+                ),
+            ]"#]],
+    );
+}
+
 #[test]
 fn if_else() {
     check(
@@ -896,6 +979,32 @@ fn while_expr() {
     );
 }
 
+#[test]
+fn while_assign_cond_did_you_mean_equality() {
+    check(
+        expr,
+        "while i = n { x }",
+        &expect![[r#"
+            Expr _id_ [0-17]: While:
+                Expr _id_ [6-11]: BinOp (Eq):
+                    Expr _id_ [6-7]: Path: Path _id_ [6-7] (Ident _id_ [6-7] "i")
+                    Expr _id_ [10-11]: Path: Path _id_ [10-11] (Ident _id_ [10-11] "n")
+                Block _id_ [12-17]:
+                    Stmt _id_ [14-15]: Expr: Expr _id_ [14-15]: Path: Path _id_ [14-15] (Ident _id_ [14-15] "x")
+
+            [
+                Error(
+                    DidYouMeanEquality(
+                        Span {
+                            lo: 8,
+                            hi: 9,
+                        },
+                    ),
+                ),
+            ]"#]],
+    );
+}
+
 #[test]
 fn within_apply() {
     check(
@@ -2030,6 +2139,117 @@ fn lambda_tuple_input() {
     );
 }
 
+#[test]
+fn trailing_lambda() {
+    check(
+        expr,
+        "ApplyToEach(qubits) { q => H(q) }",
+        &expect![[r#"
+            Expr _id_ [0-33]: Call:
+                Expr _id_ [0-11]: Path: Path _id_ [0-11] (Ident _id_ [0-11] "ApplyToEach")
+                Expr _id_ [11-33]: Tuple:
+                    Expr _id_ [12-18]: Path: Path _id_ [12-18] (Ident _id_ [12-18] "qubits")
+                    Expr _id_ [22-31]: Lambda (Operation):
+                        Pat _id_ [22-23]: Bind:
+                            Ident _id_ [22-23] "q"
+                        Expr _id_ [27-31]: Call:
+                            Expr _id_ [27-28]: Path: Path _id_ [27-28] (Ident _id_ [27-28] "H")
+                            Expr _id_ [28-31]: Paren: Expr _id_ [29-30]: Path: Path _id_ [29-30] (Ident _id_ [29-30] "q")"#]],
+    );
+}
+
+#[test]
+fn trailing_lambda_no_args() {
+    check(
+        expr,
+        "Apply() { () -> 1 }",
+        &expect![[r#"
+        Expr _id_ [0-19]: Call:
+            Expr _id_ [0-5]: Path: Path _id_ [0-5] (Ident _id_ [0-5] "Apply")
+            Expr _id_ [5-19]: Paren: Expr _id_ [10-17]: Lambda (Function):
+                Pat _id_ [10-12]: Unit
+                Expr _id_ [16-17]: Lit: Int(1)"#]],
+    );
+}
+
+#[test]
+fn trailing_lambda_matches_explicit_lambda() {
+    let erased = |input: &str| {
+        let mut s = ParserContext::new(input, LanguageFeatures::default());
+        let mut expr = expr(&mut s).expect("expression should parse");
+        assert!(s.into_errors().is_empty(), "expression should parse");
+        SpanEraser.visit_expr(&mut expr);
+        expr.to_string()
+    };
+    assert_eq!(
+        erased("ApplyToEach(qubits) { q => H(q) }"),
+        erased("ApplyToEach(qubits, q => H(q))")
+    );
+    assert_eq!(
+        erased("Mapped(xs) { (i, x) -> x + i }"),
+        erased("Mapped(xs, (i, x) -> x + i)")
+    );
+}
+
+#[test]
+fn trailing_lambda_invalid_body() {
+    check(
+        expr,
+        "ApplyToEach(qubits) { H(q) }",
+        &expect![[r#"
+        Error(
+            Convert(
+                "lambda",
+                "expression",
+                Span {
+                    lo: 22,
+                    hi: 26,
+                },
+            ),
+        )
+    "#]],
+    );
+}
+
+#[test]
+fn if_cond_call_not_trailing_lambda() {
+    check(
+        expr,
+        "if IsEven(n) { x => x }",
+        &expect![[r#"
+        Expr _id_ [0-23]: If:
+            Expr _id_ [3-12]: Call:
+                Expr _id_ [3-9]: Path: Path _id_ [3-9] (Ident _id_ [3-9] "IsEven")
+                Expr _id_ [9-12]: Paren: Expr _id_ [10-11]: Path: Path _id_ [10-11] (Ident _id_ [10-11] "n")
+            Block _id_ [13-23]:
+                Stmt _id_ [15-21]: Expr: Expr _id_ [15-21]: Lambda (Operation):
+                    Pat _id_ [15-16]: Bind:
+                        Ident _id_ [15-16] "x"
+                    Expr _id_ [20-21]: Path: Path _id_ [20-21] (Ident _id_ [20-21] "x")"#]],
+    );
+}
+
+#[test]
+fn while_cond_call_with_parenthesized_trailing_lambda() {
+    check(
+        expr,
+        "while (Any(xs) { x -> x > 0 }) {}",
+        &expect![[r#"
+        Expr _id_ [0-33]: While:
+            Expr _id_ [6-30]: Paren: Expr _id_ [7-29]: Call:
+                Expr _id_ [7-10]: Path: Path _id_ [7-10] (Ident _id_ [7-10] "Any")
+                Expr _id_ [10-29]: Tuple:
+                    Expr _id_ [11-13]: Path: Path _id_ [11-13] (Ident _id_ [11-13] "xs")
+                    Expr _id_ [17-27]: Lambda (Function):
+                        Pat _id_ [17-18]: Bind:
+                            Ident _id_ [17-18] "x"
+                        Expr _id_ [22-27]: BinOp (Gt):
+                            Expr _id_ [22-23]: Path: Path _id_ [22-23] (Ident _id_ [22-23] "x")
+                            Expr _id_ [26-27]: Lit: Int(0)
+            Block _id_ [31-33]: <empty>"#]],
+    );
+}
+
 #[test]
 fn lambda_invalid_input() {
     check(
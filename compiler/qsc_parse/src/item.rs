@@ -559,7 +559,8 @@ pub(super) fn check_input_parens(inputs: &Pat) -> Result<()> {
 
 /// Parses an import or export statement. Exports start with the `export` keyword, followed by a
 /// list of items.
-/// Imports are the same, but with the `import` keyword.
+/// Imports are the same, but with the `import` keyword. An import may be marked `import type`
+/// to only bring the types of the items into scope.
 ///
 /// ```qsharp
 /// export
@@ -582,18 +583,33 @@ fn parse_import_or_export(s: &mut ParserContext) -> Result<ImportOrExportDecl> {
         }
     };
     s.advance();
-    let (items, _) = seq(s, parse_import_or_export_item)?;
+    // `type` is not a keyword, so it is only a modifier when another name follows it
+    let mut is_type_only = false;
+    let mut first = None;
+    if !is_export && s.peek().kind == TokenKind::Ident && s.read() == "type" {
+        let name = *ident(s)?;
+        if s.peek().kind == TokenKind::Ident {
+            is_type_only = true;
+        } else {
+            first = Some(name);
+        }
+    }
+    let (items, _) = seq(s, |s| parse_import_or_export_item(s, first.take()))?;
     token(s, TokenKind::Semi)?;
-    Ok(ImportOrExportDecl::new(
-        s.span(lo),
-        items.into_boxed_slice(),
-        is_export,
-    ))
+    let decl = ImportOrExportDecl::new(s.span(lo), items.into_boxed_slice(), is_export);
+    Ok(if is_type_only { decl.type_only() } else { decl })
 }
 
-fn parse_import_or_export_item(s: &mut ParserContext) -> Result<ImportOrExportItem> {
+fn parse_import_or_export_item(
+    s: &mut ParserContext,
+    first: Option<Ident>,
+) -> Result<ImportOrExportItem> {
     let mut is_glob = false;
-    let mut parts = vec![*ident(s)?];
+    let first = match first {
+        Some(first) => first,
+        None => *ident(s)?,
+    };
+    let mut parts = vec![first];
     while token(s, TokenKind::Dot).is_ok() {
         if token(s, TokenKind::ClosedBinOp(ClosedBinOp::Star)).is_ok() {
             is_glob = true;
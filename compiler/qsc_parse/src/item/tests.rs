@@ -11,7 +11,8 @@ use crate::{
     tests::{check, check_vec, check_vec_v2_preview},
 };
 use expect_test::expect;
-use qsc_data_structures::span::Span;
+use qsc_ast::ast::{structural_hash, ItemKind};
+use qsc_data_structures::{language_features::LanguageFeatures, span::Span};
 
 fn parse_namespaces(s: &mut ParserContext) -> Result<Vec<qsc_ast::ast::Namespace>, crate::Error> {
     super::parse_namespaces(s)
@@ -1803,6 +1804,85 @@ fn parse_export_basic() {
     );
 }
 
+#[test]
+fn namespace_imports_yields_only_imported_items() {
+    let (namespaces, errors) = crate::namespaces(
+        "namespace Foo {
+            import Bar.Baz, Bar.Qux as Q;
+            operation Op() : Unit {}
+            export Op;
+            import Other.*;
+            export Op as Renamed;
+        }",
+        None,
+        LanguageFeatures::default(),
+    );
+    assert!(errors.is_empty(), "{errors:?}");
+    let imports = namespaces[0]
+        .imports()
+        .map(|item| {
+            let glob = if item.is_glob { ".*" } else { "" };
+            match &item.alias {
+                Some(alias) => format!("{}{glob} as {}", item.path.name.name, alias.name),
+                None => format!("{}{glob}", item.path.name.name),
+            }
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(imports, ["Baz", "Qux as Q", "Other.*"]);
+}
+
+#[test]
+fn strip_docs_clears_docs_and_keeps_structure() {
+    let (namespaces, errors) = crate::namespaces(
+        "/// Namespace doc.
+        namespace Foo {
+            /// Import doc.
+            import Bar.Baz;
+            /// Callable doc.
+            operation Op() : Unit {
+                /// Local doc.
+                function Local() : Unit {}
+            }
+            /// Export doc.
+            export Op;
+        }",
+        None,
+        LanguageFeatures::default(),
+    );
+    assert!(errors.is_empty(), "{errors:?}");
+    let mut package = qsc_ast::ast::Package {
+        id: qsc_ast::ast::NodeId::default(),
+        nodes: namespaces
+            .into_iter()
+            .map(qsc_ast::ast::TopLevelNode::Namespace)
+            .collect(),
+        entry: None,
+    };
+    assert_eq!(package.to_string().matches("doc:").count(), 5);
+
+    qsc_ast::ast::strip_docs(&mut package);
+    expect![[r#"
+        Package _id_:
+            Namespace _id_ [0-313] (Ident _id_ [37-40] "Foo"):
+                Item _id_ [55-98]:
+                    Import (ImportOrExportDecl [83-98]: [Path _id_ [90-97] (Ident _id_ [90-93] "Bar") (Ident _id_ [94-97] "Baz")])
+                Item _id_ [111-252]:
+                    Callable _id_ [141-252] (Operation):
+                        name: Ident _id_ [151-153] "Op"
+                        input: Pat _id_ [153-155]: Unit
+                        output: Type _id_ [158-162]: Path: Path _id_ [158-162] (Ident _id_ [158-162] "Unit")
+                        body: Block: Block _id_ [163-252]:
+                            Stmt _id_ [181-238]: Item: Item _id_ [181-238]:
+                                Callable _id_ [212-238] (Function):
+                                    name: Ident _id_ [221-226] "Local"
+                                    input: Pat _id_ [226-228]: Unit
+                                    output: Type _id_ [231-235]: Path: Path _id_ [231-235] (Ident _id_ [231-235] "Unit")
+                                    body: Block: Block _id_ [236-238]: <empty>
+                Item _id_ [265-303]:
+                    Export (ImportOrExportDecl [293-303]: [Path _id_ [300-302] (Ident _id_ [300-302] "Op")])"#]]
+    .assert_eq(&package.to_string());
+}
+
 #[test]
 fn parse_export_list() {
     check_vec(
@@ -1844,6 +1924,28 @@ fn parse_multiple_imports() {
     );
 }
 
+#[test]
+fn parse_type_only_import() {
+    check(
+        parse_import_or_export,
+        "import type Foo.MyType;",
+        &expect![[
+            r#"ImportOrExportDecl [0-23] (type only): [Path _id_ [12-22] (Ident _id_ [12-15] "Foo") (Ident _id_ [16-22] "MyType")]"#
+        ]],
+    );
+}
+
+#[test]
+fn parse_import_from_namespace_named_type() {
+    check(
+        parse_import_or_export,
+        "import type.Foo, type;",
+        &expect![[
+            r#"ImportOrExportDecl [0-22]: [Path _id_ [7-15] (Ident _id_ [7-11] "type") (Ident _id_ [12-15] "Foo"), Path _id_ [17-21] (Ident _id_ [17-21] "type")]"#
+        ]],
+    );
+}
+
 #[test]
 fn parse_import_with_alias() {
     check(
@@ -2054,3 +2156,90 @@ fn disallow_top_level_recursive_glob() {
         "#]],
     );
 }
+
+fn parse_attr_source(input: &str) -> qsc_ast::ast::Attr {
+    let mut s = ParserContext::new(input, LanguageFeatures::default());
+    *parse_attr(&mut s).expect("attribute should parse")
+}
+
+#[test]
+fn attr_string_arg() {
+    let attr = parse_attr_source(r#"@Deprecated("Foo.Bar")"#);
+    assert_eq!(attr.string_arg(), Some("Foo.Bar"));
+    assert_eq!(attr.int_arg(), None);
+}
+
+#[test]
+fn attr_int_arg() {
+    let attr = parse_attr_source("@Limit(3)");
+    assert_eq!(attr.int_arg(), Some(3));
+    assert_eq!(attr.string_arg(), None);
+
+    let attr = parse_attr_source("@Limit(-3)");
+    assert_eq!(attr.int_arg(), Some(-3));
+}
+
+#[test]
+fn attr_unit_arg() {
+    let attr = parse_attr_source("@EntryPoint()");
+    assert_eq!(attr.string_arg(), None);
+    assert_eq!(attr.int_arg(), None);
+}
+
+#[test]
+fn attr_multiple_args() {
+    let attr = parse_attr_source(r#"@Deprecated("Foo", 1)"#);
+    assert_eq!(attr.string_arg(), None);
+    assert_eq!(attr.int_arg(), None);
+}
+
+fn parse_callable(input: &str, name: &str) -> qsc_ast::ast::Item {
+    let mut s = ParserContext::new(input, LanguageFeatures::default());
+    let namespaces = parse_namespaces(&mut s).expect("namespaces should parse");
+    namespaces
+        .iter()
+        .flat_map(|namespace| namespace.items.iter())
+        .find(|item| {
+            matches!(&*item.kind, ItemKind::Callable(decl) if decl.name.name.as_ref() == name)
+        })
+        .map(|item| (**item).clone())
+        .expect("callable should be declared")
+}
+
+#[test]
+fn structural_hash_ignores_position() {
+    let first = parse_callable(
+        "namespace A { function F() : Int { let x = 1.5; x + 2 } }",
+        "F",
+    );
+    let second = parse_callable(
+        "namespace B {
+            function G() : Unit {}
+
+            /// Docs do not count.
+            function F() : Int {
+                let x = 1.5;
+                x + 2
+            }
+        }",
+        "F",
+    );
+    assert_eq!(structural_hash(&first), structural_hash(&second));
+}
+
+#[test]
+fn structural_hash_distinguishes_bodies() {
+    let hash = |body: &str| {
+        structural_hash(&parse_callable(
+            &format!("namespace A {{ function F() : Int {{ {body} }} }}"),
+            "F",
+        ))
+    };
+    let original = hash("let x = 1; x + 2");
+    assert_ne!(original, hash("let x = 1; x + 3"));
+    assert_ne!(original, hash("let x = 1; x - 2"));
+    assert_ne!(original, hash("let y = 1; y + 2"));
+    assert_ne!(original, hash("mutable x = 1; x + 2"));
+    assert_ne!(hash("0..1"), hash("0..1..2"));
+    assert_ne!(hash("x[0...]"), hash("x[...0]"));
+}
@@ -14,6 +14,7 @@ mod scan;
 mod stmt;
 #[cfg(test)]
 mod tests;
+pub mod trivia;
 mod ty;
 
 use crate::item::parse_doc;
@@ -86,6 +87,12 @@ enum ErrorKind {
     #[error("expected an item or closing brace, found {0}")]
     #[diagnostic(code("Qsc.Parse.ExpectedItem"))]
     ExpectedItem(TokenKind, #[label] Span),
+    #[error("expected `==` in condition, found `=`")]
+    #[diagnostic(code("Qsc.Parse.DidYouMeanEquality"))]
+    #[diagnostic(help(
+        "use `==` to compare values, `=` is only used in bindings and assignments"
+    ))]
+    DidYouMeanEquality(#[label] Span),
 }
 
 impl ErrorKind {
@@ -106,6 +113,7 @@ impl ErrorKind {
             Self::DotIdentAlias(span) => Self::DotIdentAlias(span + offset),
             Self::InvalidFileName(span, name) => Self::InvalidFileName(span + offset, name),
             Self::ExpectedItem(token, span) => Self::ExpectedItem(token, span + offset),
+            Self::DidYouMeanEquality(span) => Self::DidYouMeanEquality(span + offset),
         }
     }
 }
@@ -160,6 +168,20 @@ pub fn namespaces(
     }
 }
 
+/// Parses the namespaces of `input` like [`namespaces`], also returning its comments other than doc
+/// comments, which are not part of the AST, each attached to the nearest namespace, item or
+/// statement.
+#[must_use]
+pub fn namespaces_with_trivia(
+    input: &str,
+    source_name: Option<&str>,
+    language_features: LanguageFeatures,
+) -> (Vec<Namespace>, Vec<trivia::Comment>, Vec<Error>) {
+    let (namespaces, errors) = namespaces(input, source_name, language_features);
+    let comments = trivia::attach_comments(input, &namespaces);
+    (namespaces, comments, errors)
+}
+
 #[must_use]
 pub fn top_level_nodes(
     input: &str,
@@ -14,6 +14,7 @@ pub(super) struct NoBarrierError;
 pub(super) struct ParserContext<'a> {
     scanner: Scanner<'a>,
     language_features: LanguageFeatures,
+    trailing_lambdas: bool,
 }
 
 /// Scans over the token stream. Notably enforces LL(1) parser behavior via
@@ -35,6 +36,7 @@ impl<'a> ParserContext<'a> {
         Self {
             scanner: Scanner::new(input),
             language_features,
+            trailing_lambdas: true,
         }
     }
 
@@ -83,6 +85,17 @@ impl<'a> ParserContext<'a> {
     pub(crate) fn contains_language_feature(&self, feat: LanguageFeatures) -> bool {
         self.language_features.contains(feat)
     }
+
+    /// Returns true if a block following a call may be parsed as a trailing lambda argument.
+    pub(super) fn trailing_lambdas(&self) -> bool {
+        self.trailing_lambdas
+    }
+
+    /// Sets whether a block following a call may be parsed as a trailing lambda argument, and
+    /// returns the previous setting.
+    pub(super) fn set_trailing_lambdas(&mut self, allowed: bool) -> bool {
+        std::mem::replace(&mut self.trailing_lambdas, allowed)
+    }
 }
 
 impl<'a> Scanner<'a> {
@@ -0,0 +1,136 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+//! Comments other than doc comments are not part of the AST, so the parser discards them. Trivia
+//! capture recovers them from the source and attaches each one to the nearest namespace, item or
+//! statement, so that tools working from the AST can place them again.
+
+#[cfg(test)]
+mod tests;
+
+use crate::lex::raw::{CommentKind, Lexer, TokenKind};
+use qsc_ast::{
+    ast::{Item, Namespace, Stmt},
+    visit::{self, Visitor},
+};
+use qsc_data_structures::span::Span;
+
+/// A comment attached to an AST node.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Comment {
+    /// The span of the comment, including the leading `//`.
+    pub span: Span,
+    /// The span of the node the comment is attached to. Node IDs are not yet assigned when
+    /// parsing, so nodes are identified by their span.
+    pub node: Span,
+    /// Where the comment is relative to the node.
+    pub position: CommentPosition,
+}
+
+/// The position of a comment relative to the node it is attached to.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum CommentPosition {
+    /// The comment comes before the node, on its own line.
+    Leading,
+    /// The comment comes after the node, either on the same line as its end or at the end of the
+    /// enclosing block.
+    Trailing,
+    /// The comment is inside the node, which has no nested nodes to attach it to, as in an empty
+    /// block.
+    Dangling,
+}
+
+/// Finds the comments in `input` and attaches each one to a node of `namespaces`. A comment that
+/// follows a node on the same line trails that node. Otherwise, it leads the next node in the same
+/// enclosing node, or trails the previous one if there is no next node. Comments outside of any
+/// node, such as in a file without namespaces, are not attached.
+#[must_use]
+pub(super) fn attach_comments(input: &str, namespaces: &[Namespace]) -> Vec<Comment> {
+    let mut nodes = NodeSpans::default();
+    for namespace in namespaces {
+        nodes.visit_namespace(namespace);
+    }
+    let mut nodes = nodes.spans;
+    // Sort parents before children, so that the first of several nodes starting at the same
+    // offset is the outermost one.
+    nodes.sort_by_key(|span| (span.lo, std::cmp::Reverse(span.hi)));
+    nodes.dedup();
+
+    comment_spans(input)
+        .filter_map(|span| attach(input, &nodes, span))
+        .collect()
+}
+
+fn comment_spans(input: &str) -> impl Iterator<Item = Span> + '_ {
+    let mut tokens = Lexer::new(input).peekable();
+    std::iter::from_fn(move || loop {
+        let token = tokens.next()?;
+        if token.kind == TokenKind::Comment(CommentKind::Normal) {
+            let hi = tokens.peek().map_or_else(
+                || u32::try_from(input.len()).expect("input length should fit into u32"),
+                |next| next.offset,
+            );
+            return Some(Span {
+                lo: token.offset,
+                hi,
+            });
+        }
+    })
+}
+
+fn attach(input: &str, nodes: &[Span], comment: Span) -> Option<Comment> {
+    let enclosing = nodes
+        .iter()
+        .filter(|node| node.lo < comment.lo && comment.hi <= node.hi)
+        .min_by_key(|node| node.hi - node.lo)
+        .copied();
+    let siblings = nodes.iter().filter(|node| match enclosing {
+        Some(enclosing) => {
+            **node != enclosing && enclosing.lo <= node.lo && node.hi <= enclosing.hi
+        }
+        None => true,
+    });
+
+    let previous = siblings
+        .clone()
+        .filter(|node| node.hi <= comment.lo)
+        .min_by_key(|node| (comment.lo - node.hi, node.lo));
+    let next = siblings.clone().find(|node| node.lo >= comment.hi);
+
+    let attached = |node, position| Comment {
+        span: comment,
+        node,
+        position,
+    };
+    match (previous, next) {
+        (Some(previous), _) if !input[previous.hi as usize..comment.lo as usize].contains('\n') => {
+            Some(attached(*previous, CommentPosition::Trailing))
+        }
+        (_, Some(next)) => Some(attached(*next, CommentPosition::Leading)),
+        (Some(previous), None) => Some(attached(*previous, CommentPosition::Trailing)),
+        (None, None) => enclosing.map(|enclosing| attached(enclosing, CommentPosition::Dangling)),
+    }
+}
+
+/// Collects the spans of the namespaces, items and statements of the AST.
+#[derive(Default)]
+struct NodeSpans {
+    spans: Vec<Span>,
+}
+
+impl<'a> Visitor<'a> for NodeSpans {
+    fn visit_namespace(&mut self, namespace: &'a Namespace) {
+        self.spans.push(namespace.span);
+        visit::walk_namespace(self, namespace);
+    }
+
+    fn visit_item(&mut self, item: &'a Item) {
+        self.spans.push(item.span);
+        visit::walk_item(self, item);
+    }
+
+    fn visit_stmt(&mut self, stmt: &'a Stmt) {
+        self.spans.push(stmt.span);
+        visit::walk_stmt(self, stmt);
+    }
+}
@@ -0,0 +1,102 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+use crate::namespaces_with_trivia;
+use expect_test::{expect, Expect};
+use indoc::indoc;
+use qsc_data_structures::language_features::LanguageFeatures;
+
+fn check(input: &str, expect: &Expect) {
+    let (_, comments, errors) = namespaces_with_trivia(input, None, LanguageFeatures::default());
+    assert!(errors.is_empty(), "{errors:?}");
+    let actual = comments
+        .iter()
+        .map(|comment| {
+            format!(
+                "{:?} {:?} attached to {:?}",
+                comment.position, &input[comment.span], &input[comment.node],
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+    expect.assert_eq(&actual);
+}
+
+#[test]
+fn inline_comment_trails_statement() {
+    check(
+        indoc! {"
+            namespace A {
+                function F() : Int {
+                    let x = 1; // one
+                    x
+                }
+            }
+        "},
+        &expect![[r#"Trailing "// one" attached to "let x = 1;""#]],
+    );
+}
+
+#[test]
+fn own_line_comment_leads_next_node() {
+    check(
+        indoc! {"
+            namespace A {
+                // the function
+                function F() : Unit {
+                    // the call
+                    G();
+                }
+            }
+        "},
+        &expect![[r#"
+            Leading "// the function" attached to "function F() : Unit {\n        // the call\n        G();\n    }"
+            Leading "// the call" attached to "G();""#]],
+    );
+}
+
+#[test]
+fn comment_at_end_of_block_trails_last_statement() {
+    check(
+        indoc! {"
+            namespace A {
+                function F() : Unit {
+                    G();
+                    // done
+                }
+            }
+        "},
+        &expect![[r#"Trailing "// done" attached to "G();""#]],
+    );
+}
+
+#[test]
+fn comment_in_empty_block_is_dangling() {
+    check(
+        indoc! {"
+            namespace A {
+                function F() : Unit {
+                    // nothing to do
+                }
+            }
+        "},
+        &expect![[
+            r#"Dangling "// nothing to do" attached to "function F() : Unit {\n        // nothing to do\n    }""#
+        ]],
+    );
+}
+
+#[test]
+fn doc_comments_and_comments_in_strings_are_not_trivia() {
+    check(
+        indoc! {r#"
+            namespace A {
+                /// Docs.
+                function F() : String {
+                    "// not a comment"
+                }
+            }
+        "#},
+        &expect![[""]],
+    );
+}
@@ -1283,8 +1283,12 @@ impl<'a> PartialEvaluator<'a> {
         // There are a few special cases regarding intrinsic callables. Identify them and handle them properly.
         match callable_decl.name.name.as_ref() {
             // Qubit allocations and measurements have special handling.
-            "__quantum__rt__qubit_allocate" => Ok(self.allocate_qubit()),
-            "__quantum__rt__qubit_release" => Ok(self.release_qubit(args_value)),
+            "__quantum__rt__qubit_allocate" | "__quantum__rt__qubit_borrow" => {
+                Ok(self.allocate_qubit())
+            }
+            "__quantum__rt__qubit_release" | "__quantum__rt__qubit_return" => {
+                Ok(self.release_qubit(args_value))
+            }
             "__quantum__qis__m__body" => Ok(self.measure_qubit(builder::m_decl(), args_value)),
             "__quantum__qis__mresetz__body" => {
                 Ok(self.measure_qubit(builder::mresetz_decl(), args_value))
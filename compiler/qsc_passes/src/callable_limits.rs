@@ -8,7 +8,7 @@ use miette::Diagnostic;
 use qsc_data_structures::span::Span;
 use qsc_hir::{
     hir::{CallableDecl, CallableKind, Expr, ExprKind, Package, Stmt, StmtKind},
-    ty::{FunctorSetValue, Ty},
+    ty::FunctorSetValue,
     visit::{self, Visitor},
 };
 use thiserror::Error;
@@ -23,10 +23,6 @@ pub enum Error {
     #[diagnostic(code("Qsc.CallableLimits.Functor"))]
     Functor(#[label] Span),
 
-    #[error("functions cannot call operations")]
-    #[diagnostic(code("Qsc.CallableLimits.OpCall"))]
-    OpCall(#[label] Span),
-
     #[error("functions cannot allocate qubits")]
     #[diagnostic(code("Qsc.CallableLimits.QubitAlloc"))]
     QubitAlloc(#[label] Span),
@@ -71,11 +67,6 @@ impl Visitor<'_> for CallableLimits {
 
     fn visit_expr(&mut self, expr: &Expr) {
         match &expr.kind {
-            ExprKind::Call(callee, _) => {
-                if matches!(&callee.ty, Ty::Arrow(arrow) if arrow.kind == CallableKind::Operation) {
-                    self.errors.push(Error::OpCall(expr.span));
-                }
-            }
             ExprKind::Conjugate(..) => {
                 self.errors.push(Error::Conjugate(expr.span));
             }
@@ -73,30 +73,6 @@ fn funcs_cannot_have_functors() {
     );
 }
 
-#[test]
-fn funcs_cannot_call_ops() {
-    check(
-        indoc! {"
-            namespace Test {
-                operation A() : Unit {}
-                function B() : Unit {
-                    A();
-                }
-            }
-        "},
-        &expect![[r#"
-            [
-                OpCall(
-                    Span {
-                        lo: 79,
-                        hi: 82,
-                    },
-                ),
-            ]
-        "#]],
-    );
-}
-
 #[test]
 fn funcs_cannot_allocate_qubits() {
     check(
@@ -86,6 +86,30 @@ pub fn check_supported_capabilities(
     checker.check_all()
 }
 
+/// Returns the smallest set of target capabilities that supports every construct in the package,
+/// which is empty for a program that can run on a base profile target.
+#[must_use]
+pub fn required_capabilities(
+    package: &Package,
+    compute_properties: &PackageComputeProperties,
+) -> TargetCapabilityFlags {
+    let mut checker = Checker {
+        package,
+        compute_properties,
+        target_capabilities: TargetCapabilityFlags::empty(),
+        current_callable: None,
+        missing_features_map: FxHashMap::<Span, RuntimeFeatureFlags>::default(),
+    };
+
+    checker.visit_package(package);
+    checker
+        .missing_features_map
+        .values()
+        .fold(TargetCapabilityFlags::empty(), |capabilities, features| {
+            capabilities | features.target_capabilities()
+        })
+}
+
 struct Checker<'a> {
     package: &'a Package,
     compute_properties: &'a PackageComputeProperties,
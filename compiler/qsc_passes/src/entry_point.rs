@@ -10,10 +10,10 @@ use qsc_data_structures::span::Span;
 use qsc_hir::{
     assigner::Assigner,
     hir::{
-        Attr, CallableDecl, Expr, ExprKind, Item, ItemId, ItemKind, LocalItemId, Package, PatKind,
-        Res,
+        Attr, CallableDecl, Expr, ExprKind, Item, ItemId, ItemKind, LocalItemId, Package, Pat,
+        PatKind, Res,
     },
-    ty::Ty,
+    ty::{Prim, Ty},
     visit::Visitor,
 };
 use thiserror::Error;
@@ -33,6 +33,17 @@ pub enum Error {
     #[diagnostic(code("Qsc.EntryPoint.BodyMissing"))]
     BodyMissing(#[label("cannot have specialization implementation")] Span),
 
+    #[error("entry point parameter has type `{ty}`, which cannot be passed from the host")]
+    #[diagnostic(help(
+        "entry point parameters must have classical types, without qubits or callables"
+    ))]
+    #[diagnostic(code("Qsc.EntryPoint.NonClassicalEntryParam"))]
+    NonClassicalEntryParam {
+        ty: String,
+        #[label]
+        span: Span,
+    },
+
     #[error("entry point not found")]
     #[diagnostic(help("a single callable with the `@EntryPoint()` attribute must be present if no entry expression is provided and no callable named `Main` is present"))]
     #[diagnostic(code("Qsc.EntryPoint.NotFound"))]
@@ -45,17 +56,69 @@ pub(super) fn generate_entry_expr(
     package: &mut Package,
     assigner: &mut Assigner,
 ) -> Vec<super::Error> {
+    let mut errors = check_entry_param_types(package);
     if package.entry.is_some() {
-        return vec![];
+        return errors;
     }
     let callables = get_callables(package);
 
     match create_entry_from_callables(assigner, callables) {
-        Ok(expr) => {
-            package.entry = Some(expr);
-            vec![]
+        Ok(expr) => package.entry = Some(expr),
+        Err(errs) => errors.extend(errs),
+    }
+    errors
+}
+
+// Callables with the entry point attribute can be invoked from the host, even when an entry
+// expression is provided, so their parameters must have types the host can pass.
+fn check_entry_param_types(package: &Package) -> Vec<super::Error> {
+    fn check_pat(package: &Package, pat: &Pat, errors: &mut Vec<super::Error>) {
+        match &pat.kind {
+            PatKind::Tuple(pats) => {
+                for pat in pats {
+                    check_pat(package, pat, errors);
+                }
+            }
+            PatKind::Bind(_) | PatKind::Discard | PatKind::Err => {
+                if !is_classical(package, &pat.ty) {
+                    errors.push(PassErr::EntryPoint(Error::NonClassicalEntryParam {
+                        ty: pat.ty.display(),
+                        span: pat.span,
+                    }));
+                }
+            }
         }
-        Err(errs) => errs,
+    }
+
+    let mut errors = Vec::new();
+    for item in package.items.values() {
+        if let ItemKind::Callable(decl) = &item.kind {
+            if item.attrs.iter().any(|a| a == &Attr::EntryPoint) {
+                check_pat(package, &decl.input, &mut errors);
+            }
+        }
+    }
+    errors
+}
+
+// Types declared in other packages are assumed to be classical, since their definitions are not
+// available here.
+fn is_classical(package: &Package, ty: &Ty) -> bool {
+    match ty {
+        Ty::Prim(Prim::Qubit) | Ty::Arrow(_) => false,
+        Ty::Array(item) => is_classical(package, item),
+        Ty::Tuple(items) => items.iter().all(|item| is_classical(package, item)),
+        Ty::Udt(
+            _,
+            Res::Item(ItemId {
+                package: None,
+                item,
+            }),
+        ) => match package.items.get(*item).map(|item| &item.kind) {
+            Some(ItemKind::Ty(_, udt)) => is_classical(package, &udt.get_pure_ty()),
+            _ => true,
+        },
+        Ty::Infer(_) | Ty::Param(..) | Ty::Prim(_) | Ty::Udt(..) | Ty::Err => true,
     }
 }
 
@@ -173,3 +173,47 @@ fn test_entry_point_main_multiple() {
         "#]],
     );
 }
+
+#[test]
+fn test_entry_point_qubit_param_error() {
+    check(
+        indoc! {"
+            namespace Test {
+                @EntryPoint()
+                operation Main(q : Qubit) : Result { M(q) }
+                operation M(q : Qubit) : Result { body intrinsic; }
+            }"},
+        "{ use q = Qubit(); Test.Main(q) }",
+        &expect![[r#"
+            [
+                EntryPoint(
+                    NonClassicalEntryParam {
+                        ty: "Qubit",
+                        span: Span {
+                            lo: 88,
+                            hi: 97,
+                        },
+                    },
+                ),
+            ]
+        "#]],
+    );
+}
+
+#[test]
+fn test_entry_point_array_param_no_error() {
+    check(
+        indoc! {"
+            namespace Test {
+                @EntryPoint()
+                operation Main(xs : Int[]) : Int { xs[0] }
+            }"},
+        "Test.Main([1, 2])",
+        &expect![[r#"
+            Expr 11 [0-17] [Type Int]: Call:
+                Expr 12 [0-9] [Type (Int[] => Int)]: Var: Item 1
+                Expr 13 [10-16] [Type Int[]]: Array:
+                    Expr 14 [11-12] [Type Int]: Lit: Int(1)
+                    Expr 15 [14-15] [Type Int]: Lit: Int(2)"#]],
+    );
+}
@@ -0,0 +1,309 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+#[cfg(test)]
+mod tests;
+
+use qsc_hir::{
+    hir::{
+        BinOp, Block, CallableDecl, CallableKind, Expr, ExprKind, ItemId, ItemKind, Lit,
+        LocalItemId, Mutability, NodeId, Package, Pat, PatKind, Res, SpecBody, Stmt, StmtKind,
+        UnOp,
+    },
+    mut_visit::{walk_expr, walk_stmt, MutVisitor},
+};
+use rustc_hash::FxHashMap;
+
+/// The deepest chain of nested calls to constant functions that is folded. Calls nested more
+/// deeply are left in place to be evaluated at runtime.
+const MAX_FOLD_DEPTH: usize = 32;
+
+/// Replaces calls to functions declared in the package with the literal they return, when the
+/// body of the function and the arguments of the call fold entirely to constants.
+///
+/// Only immutable bindings, literals, arithmetic, comparisons, conditionals and calls to other
+/// such functions are folded. Anything that could fail at runtime, such as a division by zero, or
+/// that overflows is left in place so that it behaves the same when the program runs.
+pub fn inline_constant_functions(package: &mut Package) {
+    Folder::new(package).visit_package(package);
+}
+
+/// Replaces every expression in the package that folds entirely to a constant with the literal it
+/// evaluates to, including references to immutable bindings of constants and calls to constant
+/// functions, so that the work is done once instead of every time the expression is evaluated.
+///
+/// The same expressions as in [`inline_constant_functions`] are folded, and the result is computed
+/// the same way the evaluator computes it, so evaluation results are unchanged.
+pub fn fold_constants(package: &mut Package) {
+    ConstantFolder {
+        folder: Folder::new(package),
+        env: Env::default(),
+    }
+    .visit_package(package);
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Const {
+    Lit(Lit),
+    Tuple(Vec<Const>),
+}
+
+type Env = FxHashMap<NodeId, Const>;
+
+struct Folder {
+    functions: FxHashMap<LocalItemId, CallableDecl>,
+    /// The results of folding calls to each function, by argument.
+    results: FxHashMap<LocalItemId, Vec<(Const, Option<Const>)>>,
+    /// The functions being folded, innermost last.
+    stack: Vec<LocalItemId>,
+}
+
+impl Folder {
+    fn new(package: &Package) -> Self {
+        let functions = package
+            .items
+            .iter()
+            .filter_map(|(id, item)| match &item.kind {
+                ItemKind::Callable(decl)
+                    if decl.kind == CallableKind::Function && decl.generics.is_empty() =>
+                {
+                    Some((id, decl.clone()))
+                }
+                _ => None,
+            })
+            .collect();
+
+        Self {
+            functions,
+            results: FxHashMap::default(),
+            stack: Vec::new(),
+        }
+    }
+
+    fn call(&mut self, item: LocalItemId, arg: Const) -> Option<Const> {
+        if self.stack.len() >= MAX_FOLD_DEPTH || self.stack.contains(&item) {
+            return None;
+        }
+        if let Some((_, value)) = self
+            .results
+            .get(&item)
+            .and_then(|results| results.iter().find(|(cached, _)| *cached == arg))
+        {
+            return value.clone();
+        }
+        let decl = self.functions.get(&item)?;
+        let SpecBody::Impl(_, block) = &decl.body.body else {
+            return None;
+        };
+        let mut env = Env::default();
+        bind(&decl.input, arg.clone(), &mut env)?;
+
+        // Cloning the body avoids borrowing the declarations while folding nested calls.
+        let block = block.clone();
+        let is_outermost = self.stack.is_empty();
+        self.stack.push(item);
+        let value = self.fold_block(&block, env);
+        self.stack.pop();
+
+        // Whether a nested call folds can depend on the functions already being folded, so
+        // failures are cached only for outermost calls.
+        if value.is_some() || is_outermost {
+            self.results
+                .entry(item)
+                .or_default()
+                .push((arg, value.clone()));
+        }
+        value
+    }
+
+    fn fold_block(&mut self, block: &Block, mut env: Env) -> Option<Const> {
+        let (last, init) = block.stmts.split_last()?;
+        for stmt in init {
+            let StmtKind::Local(Mutability::Immutable, pat, value) = &stmt.kind else {
+                return None;
+            };
+            let value = self.fold_expr(value, &env)?;
+            bind(pat, value, &mut env)?;
+        }
+        match &last.kind {
+            StmtKind::Expr(expr) => self.fold_expr(expr, &env),
+            _ => None,
+        }
+    }
+
+    fn fold_expr(&mut self, expr: &Expr, env: &Env) -> Option<Const> {
+        match &expr.kind {
+            ExprKind::BinOp(op, lhs, rhs) => {
+                let Const::Lit(lhs) = self.fold_expr(lhs, env)? else {
+                    return None;
+                };
+                let Const::Lit(rhs) = self.fold_expr(rhs, env)? else {
+                    return None;
+                };
+                fold_bin_op(*op, &lhs, &rhs).map(Const::Lit)
+            }
+            ExprKind::Block(block) => self.fold_block(block, env.clone()),
+            ExprKind::Call(callee, arg) => {
+                let item = local_function(callee)?;
+                let arg = self.fold_expr(arg, env)?;
+                self.call(item, arg)
+            }
+            ExprKind::If(cond, body, otherwise) => {
+                let Const::Lit(Lit::Bool(cond)) = self.fold_expr(cond, env)? else {
+                    return None;
+                };
+                if cond {
+                    self.fold_expr(body, env)
+                } else {
+                    self.fold_expr(otherwise.as_ref()?, env)
+                }
+            }
+            ExprKind::Lit(lit) => Some(Const::Lit(lit.clone())),
+            ExprKind::Tuple(items) => items
+                .iter()
+                .map(|item| self.fold_expr(item, env))
+                .collect::<Option<_>>()
+                .map(Const::Tuple),
+            ExprKind::UnOp(op, operand) => {
+                let Const::Lit(operand) = self.fold_expr(operand, env)? else {
+                    return None;
+                };
+                fold_un_op(*op, &operand).map(Const::Lit)
+            }
+            ExprKind::Var(Res::Local(id), _) => env.get(id).cloned(),
+            _ => None,
+        }
+    }
+}
+
+/// Returns the function declared in the current package that the expression refers to, if any.
+fn local_function(callee: &Expr) -> Option<LocalItemId> {
+    match &callee.kind {
+        ExprKind::Var(
+            Res::Item(ItemId {
+                package: None,
+                item,
+            }),
+            generics,
+        ) if generics.is_empty() => Some(*item),
+        _ => None,
+    }
+}
+
+fn bind(pat: &Pat, value: Const, env: &mut Env) -> Option<()> {
+    match (&pat.kind, value) {
+        (PatKind::Bind(name), value) => {
+            env.insert(name.id, value);
+            Some(())
+        }
+        (PatKind::Discard, _) => Some(()),
+        (PatKind::Tuple(pats), Const::Tuple(values)) if pats.len() == values.len() => pats
+            .iter()
+            .zip(values)
+            .try_for_each(|(pat, value)| bind(pat, value, env)),
+        _ => None,
+    }
+}
+
+/// Folds a binary operator the same way the evaluator computes it, or returns `None` if the
+/// operation is not supported, would fail at runtime, or would overflow. Overflowing `Int`
+/// arithmetic is left to the evaluator, since it either wraps or fails depending on whether
+/// arithmetic is checked.
+#[allow(clippy::float_cmp)]
+fn fold_bin_op(op: BinOp, lhs: &Lit, rhs: &Lit) -> Option<Lit> {
+    let lit = match (lhs, rhs) {
+        (&Lit::Int(lhs), &Lit::Int(rhs)) => match op {
+            BinOp::Add => Lit::Int(lhs.checked_add(rhs)?),
+            BinOp::AndB => Lit::Int(lhs & rhs),
+            BinOp::Div => Lit::Int(lhs.checked_div(rhs)?),
+            BinOp::Eq => Lit::Bool(lhs == rhs),
+            BinOp::Exp => Lit::Int(lhs.checked_pow(u32::try_from(rhs).ok()?)?),
+            BinOp::Gt => Lit::Bool(lhs > rhs),
+            BinOp::Gte => Lit::Bool(lhs >= rhs),
+            BinOp::Lt => Lit::Bool(lhs < rhs),
+            BinOp::Lte => Lit::Bool(lhs <= rhs),
+            BinOp::Mod => Lit::Int(lhs.checked_rem(rhs)?),
+            BinOp::Mul => Lit::Int(lhs.checked_mul(rhs)?),
+            BinOp::Neq => Lit::Bool(lhs != rhs),
+            BinOp::OrB => Lit::Int(lhs | rhs),
+            BinOp::Sub => Lit::Int(lhs.checked_sub(rhs)?),
+            BinOp::XorB => Lit::Int(lhs ^ rhs),
+            _ => return None,
+        },
+        (&Lit::Double(lhs), &Lit::Double(rhs)) => match op {
+            BinOp::Add => Lit::Double(lhs + rhs),
+            BinOp::Div if rhs != 0.0 => Lit::Double(lhs / rhs),
+            BinOp::Eq => Lit::Bool(lhs == rhs),
+            BinOp::Exp => Lit::Double(lhs.powf(rhs)),
+            BinOp::Gt => Lit::Bool(lhs > rhs),
+            BinOp::Gte => Lit::Bool(lhs >= rhs),
+            BinOp::Lt => Lit::Bool(lhs < rhs),
+            BinOp::Lte => Lit::Bool(lhs <= rhs),
+            BinOp::Mod if rhs != 0.0 => Lit::Double(lhs % rhs),
+            BinOp::Mul => Lit::Double(lhs * rhs),
+            BinOp::Neq => Lit::Bool(lhs != rhs),
+            BinOp::Sub => Lit::Double(lhs - rhs),
+            _ => return None,
+        },
+        (&Lit::Bool(lhs), &Lit::Bool(rhs)) => match op {
+            BinOp::AndL => Lit::Bool(lhs && rhs),
+            BinOp::Eq => Lit::Bool(lhs == rhs),
+            BinOp::Neq => Lit::Bool(lhs != rhs),
+            BinOp::OrL => Lit::Bool(lhs || rhs),
+            _ => return None,
+        },
+        _ => return None,
+    };
+    Some(lit)
+}
+
+fn fold_un_op(op: UnOp, operand: &Lit) -> Option<Lit> {
+    match (op, operand) {
+        (UnOp::Neg, &Lit::Int(value)) => value.checked_neg().map(Lit::Int),
+        (UnOp::Neg, &Lit::Double(value)) => Some(Lit::Double(-value)),
+        (UnOp::NotB, &Lit::Int(value)) => Some(Lit::Int(!value)),
+        (UnOp::NotL, &Lit::Bool(value)) => Some(Lit::Bool(!value)),
+        (UnOp::Pos, Lit::Int(_) | Lit::Double(_)) => Some(operand.clone()),
+        _ => None,
+    }
+}
+
+impl MutVisitor for Folder {
+    fn visit_expr(&mut self, expr: &mut Expr) {
+        walk_expr(self, expr);
+        if let ExprKind::Call(callee, _) = &expr.kind {
+            if local_function(callee).is_some() {
+                if let Some(Const::Lit(lit)) = self.fold_expr(expr, &Env::default()) {
+                    expr.kind = ExprKind::Lit(lit);
+                }
+            }
+        }
+    }
+}
+
+struct ConstantFolder {
+    folder: Folder,
+    /// The immutable bindings whose values are known constants.
+    env: Env,
+}
+
+impl MutVisitor for ConstantFolder {
+    fn visit_stmt(&mut self, stmt: &mut Stmt) {
+        walk_stmt(self, stmt);
+        if let StmtKind::Local(Mutability::Immutable, pat, value) = &stmt.kind {
+            if let Some(value) = self.folder.fold_expr(value, &self.env) {
+                // A pattern that does not match the value leaves the bindings unknown.
+                let _ = bind(pat, value, &mut self.env);
+            }
+        }
+    }
+
+    fn visit_expr(&mut self, expr: &mut Expr) {
+        walk_expr(self, expr);
+        if !matches!(expr.kind, ExprKind::Lit(_)) {
+            if let Some(Const::Lit(lit)) = self.folder.fold_expr(expr, &self.env) {
+                expr.kind = ExprKind::Lit(lit);
+            }
+        }
+    }
+}
@@ -0,0 +1,300 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+use expect_test::{expect, Expect};
+use indoc::indoc;
+use qsc_data_structures::{language_features::LanguageFeatures, target::TargetCapabilityFlags};
+use qsc_frontend::compile::{self, compile, PackageStore, SourceMap};
+use qsc_hir::{
+    hir::{Lit, Package},
+    mut_visit::MutVisitor,
+    validate::Validator,
+    visit::Visitor,
+};
+
+use crate::inline_constant_functions::{fold_constants, inline_constant_functions, Const, Folder};
+
+fn check(file: &str, expect: &Expect) {
+    check_pass(file, inline_constant_functions, expect);
+}
+
+fn check_pass(file: &str, pass: fn(&mut Package), expect: &Expect) {
+    let store = PackageStore::new(compile::core());
+    let sources = SourceMap::new([("test".into(), file.into())], None);
+    let mut unit = compile(
+        &store,
+        &[],
+        sources,
+        TargetCapabilityFlags::all(),
+        LanguageFeatures::default(),
+    );
+    assert!(unit.errors.is_empty(), "{:?}", unit.errors);
+    pass(&mut unit.package);
+    Validator::default().visit_package(&unit.package);
+    expect.assert_eq(&unit.package.to_string());
+}
+
+#[test]
+fn call_to_constant_function_is_replaced_by_value() {
+    check(
+        indoc! {"
+        namespace Test {
+            function Scale(x : Int) : Int {
+                let factor = 2;
+                x * factor + 1
+            }
+            function Main() : Int {
+                Scale(20)
+            }
+        }
+        "},
+        &expect![[r#"
+            Package:
+                Item 0 [0-159] (Public):
+                    Namespace (Ident 25 [10-14] "Test"): Item 1, Item 2
+                Item 1 [21-105] (Public):
+                    Parent: 0
+                    Callable 0 [21-105] (function):
+                        name: Ident 1 [30-35] "Scale"
+                        input: Pat 2 [36-43] [Type Int]: Bind: Ident 3 [36-37] "x"
+                        output: Int
+                        functors: empty set
+                        body: SpecDecl 4 [21-105]: Impl:
+                            Block 5 [51-105] [Type Int]:
+                                Stmt 6 [61-76]: Local (Immutable):
+                                    Pat 7 [65-71] [Type Int]: Bind: Ident 8 [65-71] "factor"
+                                    Expr 9 [74-75] [Type Int]: Lit: Int(2)
+                                Stmt 10 [85-99]: Expr: Expr 11 [85-99] [Type Int]: BinOp (Add):
+                                    Expr 12 [85-95] [Type Int]: BinOp (Mul):
+                                        Expr 13 [85-86] [Type Int]: Var: Local 3
+                                        Expr 14 [89-95] [Type Int]: Var: Local 8
+                                    Expr 15 [98-99] [Type Int]: Lit: Int(1)
+                        adj: <none>
+                        ctl: <none>
+                        ctl-adj: <none>
+                Item 2 [110-157] (Public):
+                    Parent: 0
+                    Callable 16 [110-157] (function):
+                        name: Ident 17 [119-123] "Main"
+                        input: Pat 18 [123-125] [Type Unit]: Unit
+                        output: Int
+                        functors: empty set
+                        body: SpecDecl 19 [110-157]: Impl:
+                            Block 20 [132-157] [Type Int]:
+                                Stmt 21 [142-151]: Expr: Expr 22 [142-151] [Type Int]: Lit: Int(41)
+                        adj: <none>
+                        ctl: <none>
+                        ctl-adj: <none>"#]],
+    );
+}
+
+#[test]
+fn calls_that_cannot_be_folded_are_kept() {
+    check(
+        indoc! {"
+        namespace Test {
+            function Nothing() : Int { 0 }
+            function Divide(x : Int) : Int { x / Nothing() }
+            function Loop(x : Int) : Int { Loop(x) }
+            function Main(y : Int) : (Int, Int, Int) {
+                (Divide(1), Loop(2), Divide(y))
+            }
+        }
+        "},
+        &expect![[r#"
+            Package:
+                Item 0 [0-244] (Public):
+                    Namespace (Ident 46 [10-14] "Test"): Item 1, Item 2, Item 3, Item 4
+                Item 1 [21-51] (Public):
+                    Parent: 0
+                    Callable 0 [21-51] (function):
+                        name: Ident 1 [30-37] "Nothing"
+                        input: Pat 2 [37-39] [Type Unit]: Unit
+                        output: Int
+                        functors: empty set
+                        body: SpecDecl 3 [21-51]: Impl:
+                            Block 4 [46-51] [Type Int]:
+                                Stmt 5 [48-49]: Expr: Expr 6 [48-49] [Type Int]: Lit: Int(0)
+                        adj: <none>
+                        ctl: <none>
+                        ctl-adj: <none>
+                Item 2 [56-104] (Public):
+                    Parent: 0
+                    Callable 7 [56-104] (function):
+                        name: Ident 8 [65-71] "Divide"
+                        input: Pat 9 [72-79] [Type Int]: Bind: Ident 10 [72-73] "x"
+                        output: Int
+                        functors: empty set
+                        body: SpecDecl 11 [56-104]: Impl:
+                            Block 12 [87-104] [Type Int]:
+                                Stmt 13 [89-102]: Expr: Expr 14 [89-102] [Type Int]: BinOp (Div):
+                                    Expr 15 [89-90] [Type Int]: Var: Local 10
+                                    Expr 16 [93-102] [Type Int]: Lit: Int(0)
+                        adj: <none>
+                        ctl: <none>
+                        ctl-adj: <none>
+                Item 3 [109-149] (Public):
+                    Parent: 0
+                    Callable 19 [109-149] (function):
+                        name: Ident 20 [118-122] "Loop"
+                        input: Pat 21 [123-130] [Type Int]: Bind: Ident 22 [123-124] "x"
+                        output: Int
+                        functors: empty set
+                        body: SpecDecl 23 [109-149]: Impl:
+                            Block 24 [138-149] [Type Int]:
+                                Stmt 25 [140-147]: Expr: Expr 26 [140-147] [Type Int]: Call:
+                                    Expr 27 [140-144] [Type (Int -> Int)]: Var: Item 3
+                                    Expr 28 [145-146] [Type Int]: Var: Local 22
+                        adj: <none>
+                        ctl: <none>
+                        ctl-adj: <none>
+                Item 4 [154-242] (Public):
+                    Parent: 0
+                    Callable 29 [154-242] (function):
+                        name: Ident 30 [163-167] "Main"
+                        input: Pat 31 [168-175] [Type Int]: Bind: Ident 32 [168-169] "y"
+                        output: (Int, Int, Int)
+                        functors: empty set
+                        body: SpecDecl 33 [154-242]: Impl:
+                            Block 34 [195-242] [Type (Int, Int, Int)]:
+                                Stmt 35 [205-236]: Expr: Expr 36 [205-236] [Type (Int, Int, Int)]: Tuple:
+                                    Expr 37 [206-215] [Type Int]: Call:
+                                        Expr 38 [206-212] [Type (Int -> Int)]: Var: Item 2
+                                        Expr 39 [213-214] [Type Int]: Lit: Int(1)
+                                    Expr 40 [217-224] [Type Int]: Call:
+                                        Expr 41 [217-221] [Type (Int -> Int)]: Var: Item 3
+                                        Expr 42 [222-223] [Type Int]: Lit: Int(2)
+                                    Expr 43 [226-235] [Type Int]: Call:
+                                        Expr 44 [226-232] [Type (Int -> Int)]: Var: Item 2
+                                        Expr 45 [233-234] [Type Int]: Var: Local 32
+                        adj: <none>
+                        ctl: <none>
+                        ctl-adj: <none>"#]],
+    );
+}
+
+#[test]
+fn constant_sub_expressions_are_folded() {
+    check_pass(
+        indoc! {"
+        namespace Test {
+            function Main(x : Int) : Int {
+                let n = 2 * 3;
+                mutable m = n + 1;
+                x + n * 2 - m
+            }
+        }
+        "},
+        fold_constants,
+        &expect![[r#"
+            Package:
+                Item 0 [0-131] (Public):
+                    Namespace (Ident 26 [10-14] "Test"): Item 1
+                Item 1 [21-129] (Public):
+                    Parent: 0
+                    Callable 0 [21-129] (function):
+                        name: Ident 1 [30-34] "Main"
+                        input: Pat 2 [35-42] [Type Int]: Bind: Ident 3 [35-36] "x"
+                        output: Int
+                        functors: empty set
+                        body: SpecDecl 4 [21-129]: Impl:
+                            Block 5 [50-129] [Type Int]:
+                                Stmt 6 [60-74]: Local (Immutable):
+                                    Pat 7 [64-65] [Type Int]: Bind: Ident 8 [64-65] "n"
+                                    Expr 9 [68-73] [Type Int]: Lit: Int(6)
+                                Stmt 12 [83-101]: Local (Mutable):
+                                    Pat 13 [91-92] [Type Int]: Bind: Ident 14 [91-92] "m"
+                                    Expr 15 [95-100] [Type Int]: Lit: Int(7)
+                                Stmt 18 [110-123]: Expr: Expr 19 [110-123] [Type Int]: BinOp (Sub):
+                                    Expr 20 [110-119] [Type Int]: BinOp (Add):
+                                        Expr 21 [110-111] [Type Int]: Var: Local 3
+                                        Expr 22 [114-119] [Type Int]: Lit: Int(12)
+                                    Expr 25 [122-123] [Type Int]: Var: Local 14
+                        adj: <none>
+                        ctl: <none>
+                        ctl-adj: <none>"#]],
+    );
+}
+
+#[test]
+fn overflowing_and_dividing_by_zero_sub_expressions_are_kept() {
+    check_pass(
+        indoc! {"
+        namespace Test {
+            function Main() : (Int, Int, Int, Double) {
+                (9223372036854775807 + 1, -(-9223372036854775807 - 1), 1 / 0, 1.0 / 0.0)
+            }
+        }
+        "},
+        fold_constants,
+        &expect![[r#"
+            Package:
+                Item 0 [0-153] (Public):
+                    Namespace (Ident 21 [10-14] "Test"): Item 1
+                Item 1 [21-151] (Public):
+                    Parent: 0
+                    Callable 0 [21-151] (function):
+                        name: Ident 1 [30-34] "Main"
+                        input: Pat 2 [34-36] [Type Unit]: Unit
+                        output: (Int, Int, Int, Double)
+                        functors: empty set
+                        body: SpecDecl 3 [21-151]: Impl:
+                            Block 4 [63-151] [Type (Int, Int, Int, Double)]:
+                                Stmt 5 [73-145]: Expr: Expr 6 [73-145] [Type (Int, Int, Int, Double)]: Tuple:
+                                    Expr 7 [74-97] [Type Int]: BinOp (Add):
+                                        Expr 8 [74-93] [Type Int]: Lit: Int(9223372036854775807)
+                                        Expr 9 [96-97] [Type Int]: Lit: Int(1)
+                                    Expr 10 [99-126] [Type Int]: UnOp (Neg):
+                                        Expr 11 [101-125] [Type Int]: Lit: Int(-9223372036854775808)
+                                    Expr 15 [128-133] [Type Int]: BinOp (Div):
+                                        Expr 16 [128-129] [Type Int]: Lit: Int(1)
+                                        Expr 17 [132-133] [Type Int]: Lit: Int(0)
+                                    Expr 18 [135-144] [Type Double]: BinOp (Div):
+                                        Expr 19 [135-138] [Type Double]: Lit: Double(1)
+                                        Expr 20 [141-144] [Type Double]: Lit: Double(0)
+                        adj: <none>
+                        ctl: <none>
+                        ctl-adj: <none>"#]],
+    );
+}
+
+#[test]
+fn folded_calls_are_cached_per_callee_and_argument() {
+    let store = PackageStore::new(compile::core());
+    let sources = SourceMap::new(
+        [(
+            "test".into(),
+            indoc! {"
+            namespace Test {
+                function Double(x : Int) : Int {
+                    x * 2
+                }
+                function Main() : Int {
+                    Double(1) + Double(1) + Double(2)
+                }
+            }
+            "}
+            .into(),
+        )],
+        None,
+    );
+    let mut unit = compile(
+        &store,
+        &[],
+        sources,
+        TargetCapabilityFlags::all(),
+        LanguageFeatures::default(),
+    );
+    assert!(unit.errors.is_empty(), "{:?}", unit.errors);
+    let mut folder = Folder::new(&unit.package);
+    folder.visit_package(&mut unit.package);
+
+    let results: Vec<_> = folder.results.into_values().collect();
+    assert_eq!(
+        results,
+        [vec![
+            (Const::Lit(Lit::Int(1)), Some(Const::Lit(Lit::Int(2)))),
+            (Const::Lit(Lit::Int(2)), Some(Const::Lit(Lit::Int(4)))),
+        ]]
+    );
+}
@@ -8,15 +8,19 @@ mod common;
 mod conjugate_invert;
 mod entry_point;
 mod id_update;
+mod inline_constant_functions;
 mod invert_block;
 mod logic_sep;
 mod loop_unification;
 mod replace_qubit_allocation;
 mod spec_gen;
+mod unused_items;
 
 use callable_limits::CallableLimits;
+pub use capabilitiesck::required_capabilities;
 use capabilitiesck::{check_supported_capabilities, lower_store, run_rca_pass};
 use entry_point::generate_entry_expr;
+pub use inline_constant_functions::{fold_constants, inline_constant_functions};
 use loop_unification::LoopUni;
 use miette::Diagnostic;
 use qsc_data_structures::target::TargetCapabilityFlags;
@@ -48,6 +52,7 @@ pub enum Error {
     ConjInvert(conjugate_invert::Error),
     EntryPoint(entry_point::Error),
     SpecGen(spec_gen::Error),
+    UnusedItems(unused_items::Error),
 }
 
 #[derive(Clone, Copy, Debug, PartialEq)]
@@ -91,6 +96,27 @@ impl PassContext {
         assigner: &mut Assigner,
         core: &Table,
         package_type: PackageType,
+    ) -> Vec<Error> {
+        let errors = self.run_check_passes(package, assigner, core, package_type);
+
+        LoopUni { core, assigner }.visit_package(package);
+        Validator::default().visit_package(package);
+
+        ReplaceQubitAllocation::new(core, assigner).visit_package(package);
+        Validator::default().visit_package(package);
+
+        errors
+    }
+
+    /// Runs the passes of [`PassContext::run_default_passes`] that report errors, skipping the
+    /// ones that only transform the package in preparation for evaluation and code generation.
+    /// The package is left in a state suitable for diagnostics only.
+    pub fn run_check_passes(
+        &mut self,
+        package: &mut Package,
+        assigner: &mut Assigner,
+        core: &Table,
+        package_type: PackageType,
     ) -> Vec<Error> {
         let mut call_limits = CallableLimits::default();
         call_limits.visit_package(package);
@@ -113,12 +139,6 @@ impl PassContext {
             Vec::new()
         };
 
-        LoopUni { core, assigner }.visit_package(package);
-        Validator::default().visit_package(package);
-
-        ReplaceQubitAllocation::new(core, assigner).visit_package(package);
-        Validator::default().visit_package(package);
-
         callable_errors
             .into_iter()
             .map(Error::CallableLimits)
@@ -147,6 +167,32 @@ pub fn run_default_passes(
     PassContext::new().run_default_passes(&mut unit.package, &mut unit.assigner, core, package_type)
 }
 
+/// Run only the passes that produce diagnostics, without preparing the package for evaluation.
+pub fn run_check_passes(
+    core: &Table,
+    unit: &mut CompileUnit,
+    package_type: PackageType,
+) -> Vec<Error> {
+    PassContext::new().run_check_passes(&mut unit.package, &mut unit.assigner, core, package_type)
+}
+
+/// Reports the callables and types of the package that cannot be used outside of it and are never
+/// reached from its entry point or public items, as warnings. Entry points and exported items are
+/// exempt. This is not one of the default passes, since it needs the whole package: a fragment
+/// compiled into an interpreter may declare items that later fragments use.
+#[must_use]
+pub fn check_unused_private_items(unit: &CompileUnit) -> Vec<Error> {
+    let exported = unit
+        .exported_items()
+        .filter(|export| export.item.package.is_none())
+        .map(|export| export.item.item)
+        .collect();
+    unused_items::find_unused_private_items(&unit.package, &exported)
+        .into_iter()
+        .map(Error::UnusedItems)
+        .collect()
+}
+
 pub fn run_core_passes(core: &mut CompileUnit) -> Vec<Error> {
     let mut borrow_check = borrowck::Checker::default();
     borrow_check.visit_package(&core.package);
@@ -10,7 +10,8 @@ use qsc_hir::{
     assigner::Assigner,
     global::Table,
     hir::{
-        Block, Expr, ExprKind, Mutability, Pat, PatKind, QubitInit, QubitInitKind, Stmt, StmtKind,
+        Block, Expr, ExprKind, Mutability, Pat, PatKind, QubitInit, QubitInitKind, QubitSource,
+        Stmt, StmtKind,
     },
     mut_visit::{walk_expr, walk_stmt, MutVisitor},
     ty::{Prim, Ty},
@@ -24,6 +25,7 @@ use crate::QIR_RUNTIME_NAMESPACE;
 struct QubitIdent {
     id: IdentTemplate,
     is_array: bool,
+    is_borrowed: bool,
 }
 
 pub(crate) struct ReplaceQubitAllocation<'a> {
@@ -48,6 +50,7 @@ impl<'a> ReplaceQubitAllocation<'a> {
     fn generate_qubit_alloc_stmts(
         &mut self,
         stmt_span: Span,
+        source: QubitSource,
         pat: Pat,
         mut init: QubitInit,
     ) -> (Vec<QubitIdent>, Vec<Stmt>) {
@@ -60,6 +63,7 @@ impl<'a> ReplaceQubitAllocation<'a> {
             }
         }
 
+        let is_borrowed = source == QubitSource::Dirty;
         let mut new_stmts: Vec<Stmt> = vec![];
         let mut new_ids: Vec<QubitIdent> = vec![];
 
@@ -75,11 +79,15 @@ impl<'a> ReplaceQubitAllocation<'a> {
                 new_stmts.push(match opt {
                     Some(mut size) => {
                         self.visit_expr(&mut size);
-                        self.create_array_alloc_stmt(&id, size)
+                        self.create_array_alloc_stmt(&id, size, is_borrowed)
                     }
-                    None => self.create_alloc_stmt(&id),
+                    None => self.create_alloc_stmt(&id, is_borrowed),
+                });
+                new_ids.push(QubitIdent {
+                    id,
+                    is_array,
+                    is_borrowed,
                 });
-                new_ids.push(QubitIdent { id, is_array });
             } else {
                 panic!("Shape of identifier pattern doesn't match shape of initializer");
             }
@@ -90,9 +98,9 @@ impl<'a> ReplaceQubitAllocation<'a> {
                 .map(|(id, size)| match size {
                     Some(size) => {
                         self.visit_expr(size);
-                        self.create_array_alloc_stmt(id, size.clone())
+                        self.create_array_alloc_stmt(id, size.clone(), is_borrowed)
                     }
-                    None => self.create_alloc_stmt(id),
+                    None => self.create_alloc_stmt(id, is_borrowed),
                 })
                 .collect();
             new_ids = ids
@@ -100,6 +108,7 @@ impl<'a> ReplaceQubitAllocation<'a> {
                 .map(|(id, expr)| QubitIdent {
                     id,
                     is_array: expr.is_some(),
+                    is_borrowed,
                 })
                 .collect();
             new_stmts.push(Stmt {
@@ -115,11 +124,12 @@ impl<'a> ReplaceQubitAllocation<'a> {
     fn process_qubit_stmt(
         &mut self,
         stmt_span: Span,
+        source: QubitSource,
         pat: Pat,
         init: QubitInit,
         block: Option<Block>,
     ) -> Vec<Stmt> {
-        let (new_ids, new_stmts) = self.generate_qubit_alloc_stmts(stmt_span, pat, init);
+        let (new_ids, new_stmts) = self.generate_qubit_alloc_stmts(stmt_span, source, pat, init);
         if let Some(block) = block {
             vec![self.generate_block_stmt(stmt_span, new_ids, block, new_stmts)]
         } else {
@@ -213,9 +223,9 @@ impl<'a> ReplaceQubitAllocation<'a> {
 
     fn get_dealloc_stmt(&mut self, qubit: &QubitIdent) -> Stmt {
         if qubit.is_array {
-            self.create_array_dealloc_stmt(&qubit.id)
+            self.create_array_dealloc_stmt(&qubit.id, qubit.is_borrowed)
         } else {
-            self.create_dealloc_stmt(&qubit.id)
+            self.create_dealloc_stmt(&qubit.id, qubit.is_borrowed)
         }
     }
 
@@ -239,44 +249,55 @@ impl<'a> ReplaceQubitAllocation<'a> {
         stmts
     }
 
-    fn create_alloc_stmt(&mut self, ident: &IdentTemplate) -> Stmt {
+    fn create_alloc_stmt(&mut self, ident: &IdentTemplate, is_borrowed: bool) -> Stmt {
         let ns = self.get_qir_runtime_namespace();
-        let mut call_expr = create_gen_core_ref(
-            self.core,
-            ns,
-            "__quantum__rt__qubit_allocate",
-            Vec::new(),
-            ident.span,
-        );
+        let name = if is_borrowed {
+            "__quantum__rt__qubit_borrow"
+        } else {
+            "__quantum__rt__qubit_allocate"
+        };
+        let mut call_expr = create_gen_core_ref(self.core, ns, name, Vec::new(), ident.span);
         call_expr.id = self.assigner.next_node();
         create_general_alloc_stmt(self.assigner, ident, call_expr, None)
     }
 
-    fn create_array_alloc_stmt(&mut self, ident: &IdentTemplate, array_size: Expr) -> Stmt {
+    fn create_array_alloc_stmt(
+        &mut self,
+        ident: &IdentTemplate,
+        array_size: Expr,
+        is_borrowed: bool,
+    ) -> Stmt {
         let ns = self.get_qir_runtime_namespace();
-        let mut call_expr =
-            create_gen_core_ref(self.core, ns, "AllocateQubitArray", Vec::new(), ident.span);
+        let name = if is_borrowed {
+            "BorrowQubitArray"
+        } else {
+            "AllocateQubitArray"
+        };
+        let mut call_expr = create_gen_core_ref(self.core, ns, name, Vec::new(), ident.span);
         call_expr.id = self.assigner.next_node();
         create_general_alloc_stmt(self.assigner, ident, call_expr, Some(array_size))
     }
 
-    fn create_dealloc_stmt(&mut self, ident: &IdentTemplate) -> Stmt {
+    fn create_dealloc_stmt(&mut self, ident: &IdentTemplate, is_borrowed: bool) -> Stmt {
         let ns = self.get_qir_runtime_namespace();
-        let mut call_expr = create_gen_core_ref(
-            self.core,
-            ns,
-            "__quantum__rt__qubit_release",
-            Vec::new(),
-            ident.span,
-        );
+        let name = if is_borrowed {
+            "__quantum__rt__qubit_return"
+        } else {
+            "__quantum__rt__qubit_release"
+        };
+        let mut call_expr = create_gen_core_ref(self.core, ns, name, Vec::new(), ident.span);
         call_expr.id = self.assigner.next_node();
         create_general_dealloc_stmt(self.assigner, call_expr, ident)
     }
 
-    fn create_array_dealloc_stmt(&mut self, ident: &IdentTemplate) -> Stmt {
+    fn create_array_dealloc_stmt(&mut self, ident: &IdentTemplate, is_borrowed: bool) -> Stmt {
         let ns = self.get_qir_runtime_namespace();
-        let mut call_expr =
-            create_gen_core_ref(self.core, ns, "ReleaseQubitArray", Vec::new(), ident.span);
+        let name = if is_borrowed {
+            "ReturnQubitArray"
+        } else {
+            "ReleaseQubitArray"
+        };
+        let mut call_expr = create_gen_core_ref(self.core, ns, name, Vec::new(), ident.span);
         call_expr.id = self.assigner.next_node();
         create_general_dealloc_stmt(self.assigner, call_expr, ident)
     }
@@ -296,10 +317,14 @@ impl MutVisitor for ReplaceQubitAllocation<'_> {
         // walk block
         let old_stmts = take(&mut block.stmts);
         for mut stmt in old_stmts {
-            if let StmtKind::Qubit(_, pat, init, qubit_scope) = stmt.kind {
-                block
-                    .stmts
-                    .extend(self.process_qubit_stmt(stmt.span, pat, init, qubit_scope));
+            if let StmtKind::Qubit(source, pat, init, qubit_scope) = stmt.kind {
+                block.stmts.extend(self.process_qubit_stmt(
+                    stmt.span,
+                    source,
+                    pat,
+                    init,
+                    qubit_scope,
+                ));
             } else {
                 walk_stmt(self, &mut stmt);
                 block.stmts.push(stmt);
@@ -389,13 +414,15 @@ impl MutVisitor for ReplaceQubitAllocation<'_> {
         // This function is not called by visit_block above, so the only time it will be used is for
         // top-level statements. Given that, the qubits allocated will always be live for
         // the entirety of a global scope, so only qubit allocations need to be generated.
+        // Borrowed qubits are never returned either, so they are not checked for restoration.
         match stmt.kind.clone() {
-            StmtKind::Qubit(_, pat, qubit_init, None) => {
-                stmt.kind = create_qubit_global_alloc(self.assigner, self.core, pat, qubit_init);
+            StmtKind::Qubit(source, pat, qubit_init, None) => {
+                stmt.kind =
+                    create_qubit_global_alloc(self.assigner, self.core, source, pat, qubit_init);
             }
-            StmtKind::Qubit(_, pat, qubit_init, Some(block)) => {
+            StmtKind::Qubit(source, pat, qubit_init, Some(block)) => {
                 let (new_ids, new_stmts) =
-                    self.generate_qubit_alloc_stmts(stmt.span, pat, qubit_init);
+                    self.generate_qubit_alloc_stmts(stmt.span, source, pat, qubit_init);
                 *stmt = self.generate_block_stmt(stmt.span, new_ids, block, new_stmts);
             }
             kind => {
@@ -409,22 +436,28 @@ impl MutVisitor for ReplaceQubitAllocation<'_> {
 fn create_qubit_global_alloc(
     assigner: &mut Assigner,
     core: &Table,
+    source: QubitSource,
     pat: Pat,
     qubit_init: QubitInit,
 ) -> StmtKind {
-    fn qubit_alloc_expr(assigner: &mut Assigner, core: &Table, qubit_init: QubitInit) -> Expr {
+    fn qubit_alloc_expr(
+        assigner: &mut Assigner,
+        core: &Table,
+        is_borrowed: bool,
+        qubit_init: QubitInit,
+    ) -> Expr {
         let ns = core
             .find_namespace(QIR_RUNTIME_NAMESPACE.iter().copied())
             .expect("prelude namespaces should exist");
         match qubit_init.kind {
             QubitInitKind::Array(mut expr) => {
-                let mut call_expr = create_gen_core_ref(
-                    core,
-                    ns,
-                    "AllocateQubitArray",
-                    Vec::new(),
-                    qubit_init.span,
-                );
+                let name = if is_borrowed {
+                    "BorrowQubitArray"
+                } else {
+                    "AllocateQubitArray"
+                };
+                let mut call_expr =
+                    create_gen_core_ref(core, ns, name, Vec::new(), qubit_init.span);
                 call_expr.id = assigner.next_node();
                 create_qubit_alloc_call_expr(
                     assigner,
@@ -434,13 +467,13 @@ fn create_qubit_global_alloc(
                 )
             }
             QubitInitKind::Single => {
-                let mut call_expr = create_gen_core_ref(
-                    core,
-                    ns,
-                    "__quantum__rt__qubit_allocate",
-                    Vec::new(),
-                    qubit_init.span,
-                );
+                let name = if is_borrowed {
+                    "__quantum__rt__qubit_borrow"
+                } else {
+                    "__quantum__rt__qubit_allocate"
+                };
+                let mut call_expr =
+                    create_gen_core_ref(core, ns, name, Vec::new(), qubit_init.span);
                 call_expr.id = assigner.next_node();
                 create_qubit_alloc_call_expr(assigner, qubit_init.span, call_expr, None)
             }
@@ -450,7 +483,7 @@ fn create_qubit_global_alloc(
                 ty: qubit_init.ty,
                 kind: ExprKind::Tuple(
                     tup.into_iter()
-                        .map(|init| qubit_alloc_expr(assigner, core, init))
+                        .map(|init| qubit_alloc_expr(assigner, core, is_borrowed, init))
                         .collect(),
                 ),
             },
@@ -461,7 +494,7 @@ fn create_qubit_global_alloc(
     StmtKind::Local(
         Mutability::Immutable,
         pat,
-        qubit_alloc_expr(assigner, core, qubit_init),
+        qubit_alloc_expr(assigner, core, source == QubitSource::Dirty, qubit_init),
     )
 }
 
@@ -0,0 +1,162 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+#[cfg(test)]
+mod tests;
+
+use miette::Diagnostic;
+use qsc_data_structures::span::Span;
+use qsc_hir::{
+    hir::{
+        Attr, Expr, ExprKind, Item, ItemId, ItemKind, LocalItemId, Package, Pat, Res, Visibility,
+    },
+    ty::Ty,
+    visit::{self, Visitor},
+};
+use rustc_hash::{FxHashMap, FxHashSet};
+use thiserror::Error;
+
+#[derive(Clone, Debug, Diagnostic, Error)]
+pub enum Error {
+    #[error("`{name}` is never used")]
+    #[diagnostic(help(
+        "this item is not reachable from the entry point or from items usable outside of the package, so it can be removed"
+    ))]
+    #[diagnostic(severity(Warning))]
+    #[diagnostic(code("Qsc.UnusedItems.UnusedPrivateItem"))]
+    UnusedPrivateItem {
+        name: String,
+        #[label]
+        span: Span,
+    },
+}
+
+/// Reports the callables and types of the package that cannot be used outside of it and are not
+/// reachable from any item that can. These are the internal items and the items declared inside
+/// callables. The roots of reachability are the public items declared in namespaces, the entry
+/// points, the `exported` items, and the top-level statements and entry expression of the package.
+/// Reachability is transitive, so items that only use each other are reported together.
+pub(super) fn find_unused_private_items(
+    package: &Package,
+    exported: &FxHashSet<LocalItemId>,
+) -> Vec<Error> {
+    let mut used = UsedItems::default();
+    used.visit_package(package);
+
+    let is_private = |item: &Item| {
+        item.visibility == Visibility::Internal
+            || item
+                .parent
+                .and_then(|parent| package.items.get(parent))
+                .is_some_and(|parent| matches!(parent.kind, ItemKind::Callable(_)))
+    };
+    let mut reachable = FxHashSet::default();
+    let mut pending = package
+        .items
+        .values()
+        .filter(|item| {
+            !is_private(item)
+                || item.attrs.contains(&Attr::EntryPoint)
+                || exported.contains(&item.id)
+        })
+        .map(|item| item.id)
+        .chain(used.roots.iter().copied())
+        .collect::<Vec<_>>();
+    while let Some(item) = pending.pop() {
+        if reachable.insert(item) {
+            if let Some(items) = used.items.get(&item) {
+                pending.extend(items.iter().copied());
+            }
+        }
+    }
+
+    package
+        .items
+        .values()
+        .filter(|item| !reachable.contains(&item.id) && !used.closures.contains(&item.id))
+        .filter_map(|item| match &item.kind {
+            ItemKind::Callable(decl) => Some(Error::UnusedPrivateItem {
+                name: decl.name.name.to_string(),
+                span: decl.name.span,
+            }),
+            ItemKind::Ty(name, _) => Some(Error::UnusedPrivateItem {
+                name: name.name.to_string(),
+                span: name.span,
+            }),
+            ItemKind::Namespace(..) => None,
+        })
+        .collect()
+}
+
+/// Collects the local items referenced by each item of a package, whether by name, as the target
+/// of a closure, or as a type, along with the items referenced outside of any item.
+#[derive(Default)]
+struct UsedItems {
+    current: Option<LocalItemId>,
+    items: FxHashMap<LocalItemId, FxHashSet<LocalItemId>>,
+    roots: FxHashSet<LocalItemId>,
+    closures: FxHashSet<LocalItemId>,
+}
+
+impl UsedItems {
+    fn use_res(&mut self, res: &Res) {
+        if let Res::Item(ItemId {
+            package: None,
+            item,
+        }) = res
+        {
+            self.use_item(*item);
+        }
+    }
+
+    fn use_item(&mut self, item: LocalItemId) {
+        match self.current {
+            Some(current) => self.items.entry(current).or_default().insert(item),
+            None => self.roots.insert(item),
+        };
+    }
+
+    fn use_ty(&mut self, ty: &Ty) {
+        match ty {
+            Ty::Array(item) => self.use_ty(item),
+            Ty::Arrow(arrow) => {
+                self.use_ty(&arrow.input);
+                self.use_ty(&arrow.output);
+            }
+            Ty::Tuple(items) => items.iter().for_each(|item| self.use_ty(item)),
+            Ty::Udt(_, res) => self.use_res(res),
+            Ty::Infer(_) | Ty::Param(..) | Ty::Prim(_) | Ty::Err => {}
+        }
+    }
+}
+
+impl<'a> Visitor<'a> for UsedItems {
+    fn visit_item(&mut self, item: &'a Item) {
+        self.current = Some(item.id);
+        match &item.kind {
+            ItemKind::Callable(decl) => self.use_ty(&decl.output),
+            ItemKind::Ty(_, udt) => self.use_ty(&udt.get_pure_ty()),
+            ItemKind::Namespace(..) => {}
+        }
+        visit::walk_item(self, item);
+        self.current = None;
+    }
+
+    fn visit_expr(&mut self, expr: &'a Expr) {
+        match &expr.kind {
+            ExprKind::Var(res, _) | ExprKind::Struct(res, ..) => self.use_res(res),
+            ExprKind::Closure(_, item) => {
+                self.closures.insert(*item);
+                self.use_item(*item);
+            }
+            _ => {}
+        }
+        self.use_ty(&expr.ty);
+        visit::walk_expr(self, expr);
+    }
+
+    fn visit_pat(&mut self, pat: &'a Pat) {
+        self.use_ty(&pat.ty);
+        visit::walk_pat(self, pat);
+    }
+}
@@ -0,0 +1,131 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+#![allow(clippy::needless_raw_string_hashes)]
+
+use expect_test::{expect, Expect};
+use indoc::indoc;
+use qsc_data_structures::{language_features::LanguageFeatures, target::TargetCapabilityFlags};
+use qsc_frontend::compile::{self, compile, PackageStore, SourceMap};
+
+use crate::check_unused_private_items;
+
+fn check(file: &str, expect: &Expect) {
+    let store = PackageStore::new(compile::core());
+    let sources = SourceMap::new([("test".into(), file.into())], None);
+    let unit = compile(
+        &store,
+        &[],
+        sources,
+        TargetCapabilityFlags::all(),
+        LanguageFeatures::default(),
+    );
+    assert!(unit.errors.is_empty(), "{:?}", unit.errors);
+
+    let errors = check_unused_private_items(&unit);
+    expect.assert_debug_eq(&errors);
+}
+
+#[test]
+fn unused_internal_function() {
+    check(
+        indoc! {"
+            namespace Test {
+                internal function Helper() : Int { Helper() }
+                function Main() : Int { 0 }
+            }
+        "},
+        &expect![[r#"
+            [
+                UnusedItems(
+                    UnusedPrivateItem {
+                        name: "Helper",
+                        span: Span {
+                            lo: 39,
+                            hi: 45,
+                        },
+                    },
+                ),
+            ]
+        "#]],
+    );
+}
+
+#[test]
+fn used_internal_items() {
+    check(
+        indoc! {"
+            namespace Test {
+                internal newtype Pair = (First : Int, Second : Int);
+                internal function Helper(pair : Pair) : Int { pair::First }
+                @EntryPoint()
+                operation Main() : Int { Helper(Pair(1, 2)) }
+            }
+        "},
+        &expect![[r"
+            []
+        "]],
+    );
+}
+
+#[test]
+fn unused_mutually_recursive_internal_functions() {
+    check(
+        indoc! {"
+            namespace Test {
+                internal function Ping(n : Int) : Int { Pong(n) }
+                internal function Pong(n : Int) : Int { Ping(n) }
+                function Main() : Int { 0 }
+            }
+        "},
+        &expect![[r#"
+            [
+                UnusedItems(
+                    UnusedPrivateItem {
+                        name: "Ping",
+                        span: Span {
+                            lo: 39,
+                            hi: 43,
+                        },
+                    },
+                ),
+                UnusedItems(
+                    UnusedPrivateItem {
+                        name: "Pong",
+                        span: Span {
+                            lo: 93,
+                            hi: 97,
+                        },
+                    },
+                ),
+            ]
+        "#]],
+    );
+}
+
+#[test]
+fn unused_item_declared_in_callable() {
+    check(
+        indoc! {"
+            namespace Test {
+                function Main() : Int {
+                    function Helper() : Int { 1 }
+                    0
+                }
+            }
+        "},
+        &expect![[r#"
+            [
+                UnusedItems(
+                    UnusedPrivateItem {
+                        name: "Helper",
+                        span: Span {
+                            lo: 62,
+                            hi: 68,
+                        },
+                    },
+                ),
+            ]
+        "#]],
+    );
+}
@@ -17,7 +17,12 @@ use rustc_hash::FxHashSet;
 ///
 /// Relative ordering within each section is maintained. Note that the scope is limited to each
 /// block, so measurements are not necessarily deferred to the end of the entire program unless the
-/// program consists of a single block.
+/// program consists of a single block. In particular, a measurement whose result feeds a branch
+/// stays in the block that ends with that branch, along with the readout of its result, so control
+/// flow never depends on a measurement that has not happened yet.
+///
+/// `check_and_transform` only runs this pass for targets without any capabilities, whose
+/// programs cannot branch on measurement results, but the pass itself does not rely on that.
 pub fn defer_measurements(program: &mut Program) {
     let mut measure_call_ids = FxHashSet::default();
     let mut output_recording_ids = FxHashSet::default();
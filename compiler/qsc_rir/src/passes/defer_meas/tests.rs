@@ -76,6 +76,59 @@ fn branching_block_measurements_deferred_properly() {
     .assert_eq(&program.get_block(BlockId(0)).to_string());
 }
 
+#[test]
+fn terminal_measurements_are_unchanged() {
+    let mut program = Program::default();
+    add_terminal_measurement_block(&mut program);
+
+    defer_measurements(&mut program);
+    expect![[r#"
+        Block:
+            Call id(0), args( Qubit(0), )
+            Call id(0), args( Qubit(1), )
+            Call id(1), args( Qubit(0), Result(0), )
+            Call id(1), args( Qubit(1), Result(1), )
+            Call id(2), args( Result(0), Pointer, )
+            Call id(2), args( Result(1), Pointer, )
+            Return"#]]
+    .assert_eq(&program.get_block(BlockId(0)).to_string());
+}
+
+#[test]
+fn measurement_feeding_branch_stays_in_its_block() {
+    let mut program = Program::default();
+    add_measurement_feeding_branch_blocks(&mut program);
+
+    // Before
+    expect![[r#"
+        Block:
+            Call id(0), args( Qubit(0), Result(0), )
+            Variable(0, Boolean) = Call id(2), args( Result(0), )
+            Call id(1), args( Qubit(1), )
+            Branch Variable(0, Boolean), 1, 2"#]]
+    .assert_eq(&program.get_block(BlockId(0)).to_string());
+
+    // After
+    defer_measurements(&mut program);
+    expect![[r#"
+        Block:
+            Call id(1), args( Qubit(1), )
+            Call id(0), args( Qubit(0), Result(0), )
+            Variable(0, Boolean) = Call id(2), args( Result(0), )
+            Branch Variable(0, Boolean), 1, 2"#]]
+    .assert_eq(&program.get_block(BlockId(0)).to_string());
+    expect![[r#"
+        Block:
+            Call id(1), args( Qubit(0), )
+            Jump(2)"#]]
+    .assert_eq(&program.get_block(BlockId(1)).to_string());
+    expect![[r#"
+        Block:
+            Call id(0), args( Qubit(1), Result(1), )
+            Return"#]]
+    .assert_eq(&program.get_block(BlockId(2)).to_string());
+}
+
 fn add_simple_measurement_block(program: &mut Program) {
     program.callables.insert(CallableId(0), builder::m_decl());
     program
@@ -208,3 +261,130 @@ fn add_branching_measurement_block(program: &mut Program) {
         ]),
     );
 }
+
+fn add_terminal_measurement_block(program: &mut Program) {
+    program.callables.insert(CallableId(0), builder::x_decl());
+    program
+        .callables
+        .insert(CallableId(1), builder::mresetz_decl());
+    program
+        .callables
+        .insert(CallableId(2), builder::result_record_decl());
+    program.blocks.insert(
+        BlockId(0),
+        Block(vec![
+            Instruction::Call(
+                CallableId(0),
+                vec![Operand::Literal(Literal::Qubit(0))],
+                None,
+            ),
+            Instruction::Call(
+                CallableId(0),
+                vec![Operand::Literal(Literal::Qubit(1))],
+                None,
+            ),
+            Instruction::Call(
+                CallableId(1),
+                vec![
+                    Operand::Literal(Literal::Qubit(0)),
+                    Operand::Literal(Literal::Result(0)),
+                ],
+                None,
+            ),
+            Instruction::Call(
+                CallableId(1),
+                vec![
+                    Operand::Literal(Literal::Qubit(1)),
+                    Operand::Literal(Literal::Result(1)),
+                ],
+                None,
+            ),
+            Instruction::Call(
+                CallableId(2),
+                vec![
+                    Operand::Literal(Literal::Result(0)),
+                    Operand::Literal(Literal::Pointer),
+                ],
+                None,
+            ),
+            Instruction::Call(
+                CallableId(2),
+                vec![
+                    Operand::Literal(Literal::Result(1)),
+                    Operand::Literal(Literal::Pointer),
+                ],
+                None,
+            ),
+            Instruction::Return,
+        ]),
+    );
+}
+
+fn add_measurement_feeding_branch_blocks(program: &mut Program) {
+    program
+        .callables
+        .insert(CallableId(0), builder::mresetz_decl());
+    program.callables.insert(CallableId(1), builder::x_decl());
+    program
+        .callables
+        .insert(CallableId(2), builder::read_result_decl());
+    program.blocks.insert(
+        BlockId(0),
+        Block(vec![
+            Instruction::Call(
+                CallableId(0),
+                vec![
+                    Operand::Literal(Literal::Qubit(0)),
+                    Operand::Literal(Literal::Result(0)),
+                ],
+                None,
+            ),
+            Instruction::Call(
+                CallableId(2),
+                vec![Operand::Literal(Literal::Result(0))],
+                Some(Variable {
+                    variable_id: VariableId(0),
+                    ty: Ty::Boolean,
+                }),
+            ),
+            Instruction::Call(
+                CallableId(1),
+                vec![Operand::Literal(Literal::Qubit(1))],
+                None,
+            ),
+            Instruction::Branch(
+                Variable {
+                    variable_id: VariableId(0),
+                    ty: Ty::Boolean,
+                },
+                BlockId(1),
+                BlockId(2),
+            ),
+        ]),
+    );
+    program.blocks.insert(
+        BlockId(1),
+        Block(vec![
+            Instruction::Call(
+                CallableId(1),
+                vec![Operand::Literal(Literal::Qubit(0))],
+                None,
+            ),
+            Instruction::Jump(BlockId(2)),
+        ]),
+    );
+    program.blocks.insert(
+        BlockId(2),
+        Block(vec![
+            Instruction::Call(
+                CallableId(0),
+                vec![
+                    Operand::Literal(Literal::Qubit(1)),
+                    Operand::Literal(Literal::Result(1)),
+                ],
+                None,
+            ),
+            Instruction::Return,
+        ]),
+    );
+}
@@ -22,14 +22,13 @@ pub(crate) fn get_code_actions(
     position_encoding: Encoding,
 ) -> Vec<CodeAction> {
     // Compute quick_fixes and other code_actions, and then merge them together
-    let span = compilation.source_range_to_package_span(source_name, range, position_encoding);
-    quick_fixes(compilation, source_name, span, position_encoding)
+    quick_fixes(compilation, source_name, range, position_encoding)
 }
 
 fn quick_fixes(
     compilation: &Compilation,
     source_name: &str,
-    span: Span,
+    range: Range,
     encoding: Encoding,
 ) -> Vec<CodeAction> {
     let mut code_actions = Vec::new();
@@ -38,7 +37,7 @@ fn quick_fixes(
     let diagnostics = compilation
         .errors
         .iter()
-        .filter(|error| is_error_relevant(error, span));
+        .filter(|error| is_error_relevant(error, source_name, range, encoding));
 
     // An example of what quickfixes could look like if they were generated here.
     // The other option I considered was generating the quickfixes when the errors
@@ -87,8 +86,19 @@ fn quick_fixes(
                     kind: Some(CodeActionKind::QuickFix),
                     is_preferred: None,
                 }),
-                LintKind::Ast(AstLint::DivisionByZero)
-                | LintKind::Hir(HirLint::NeedlessOperation) => (),
+                LintKind::Ast(
+                    AstLint::DivisionByZero
+                    | AstLint::EmptyBlock
+                    | AstLint::LargeQubitAllocation
+                    | AstLint::UnnecessaryMutable
+                    | AstLint::UnusedQubit,
+                )
+                | LintKind::Hir(
+                    HirLint::DoubleAdjoint
+                    | HirLint::EmptyControls
+                    | HirLint::MissingControlledFunctor
+                    | HirLint::NeedlessOperation,
+                ) => {}
             }
         }
     }
@@ -96,33 +106,29 @@ fn quick_fixes(
     code_actions
 }
 
-/// Returns true if the error has a `Range` and it overlaps
+/// Returns true if the error has a `Range` in the given source and it overlaps
 /// with the code action's range.
-fn is_error_relevant(error: &WithSource<ErrorKind>, span: Span) -> bool {
-    let Some(error_span) = resolve_span(error) else {
+fn is_error_relevant(
+    error: &WithSource<ErrorKind>,
+    source_name: &str,
+    range: Range,
+    encoding: Encoding,
+) -> bool {
+    let Some((error_source_name, error_range)) = resolve_source_range(error, encoding) else {
         return false;
     };
-    span.intersection(&error_span).is_some()
-}
-
-/// Extracts the uri and `Span` from an error.
-fn resolve_span(e: &WithSource<ErrorKind>) -> Option<Span> {
-    e.labels()
-        .into_iter()
-        .flatten()
-        .map(|labeled_span| {
-            let start = u32::try_from(labeled_span.offset()).expect("offset should fit in u32");
-            let len = u32::try_from(labeled_span.len()).expect("length should fit in u32");
-            qsc::Span {
-                lo: start,
-                hi: start + len,
-            }
-        })
-        .next()
+    // Two ranges overlap exactly when one of them contains the start of the other.
+    error_source_name == source_name
+        && (range.contains(error_range.start) || error_range.contains(range.start))
 }
 
 /// Extracts the `Range` from an error.
 fn resolve_range(e: &WithSource<ErrorKind>, encoding: Encoding) -> Option<Range> {
+    resolve_source_range(e, encoding).map(|(_, range)| range)
+}
+
+/// Extracts the source name and `Range` from an error.
+fn resolve_source_range(e: &WithSource<ErrorKind>, encoding: Encoding) -> Option<(&str, Range)> {
     e.labels()
         .into_iter()
         .flatten()
@@ -130,14 +136,15 @@ fn resolve_range(e: &WithSource<ErrorKind>, encoding: Encoding) -> Option<Range>
             let (source, span) = e.resolve_span(labeled_span.inner());
             let start = u32::try_from(span.offset()).expect("offset should fit in u32");
             let len = u32::try_from(span.len()).expect("length should fit in u32");
-            qsc::line_column::Range::from_span(
+            let range = Range::from_span(
                 encoding,
                 &source.contents,
-                &qsc::Span {
+                &Span {
                     lo: start,
                     hi: start + len,
                 },
-            )
+            );
+            (&*source.name, range)
         })
         .next()
 }
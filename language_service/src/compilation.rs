@@ -9,7 +9,7 @@ use qsc::{
     error::WithSource,
     hir::{self, PackageId},
     incremental::Compiler,
-    line_column::{Encoding, Position, Range},
+    line_column::{Encoding, Position},
     resolve,
     target::Profile,
     CompileUnit, LanguageFeatures, PackageStore, PackageType, PassContext, SourceMap, Span,
@@ -191,25 +191,6 @@ impl Compilation {
         source.offset + offset
     }
 
-    pub(crate) fn source_range_to_package_span(
-        &self,
-        source_name: &str,
-        source_range: Range,
-        position_encoding: Encoding,
-    ) -> Span {
-        let lo = self.source_position_to_package_offset(
-            source_name,
-            source_range.start,
-            position_encoding,
-        );
-        let hi = self.source_position_to_package_offset(
-            source_name,
-            source_range.end,
-            position_encoding,
-        );
-        Span { lo, hi }
-    }
-
     /// Gets the span of the whole source file.
     pub(crate) fn package_span_of_source(&self, source_name: &str) -> Span {
         let unit = self.user_unit();
@@ -271,7 +252,7 @@ fn run_fir_passes(
     package_id: PackageId,
     unit: &CompileUnit,
 ) {
-    if !errors.is_empty() {
+    if compile::has_errors(errors) {
         // can't run passes on a package with errors
         return;
     }
@@ -293,7 +274,7 @@ fn run_fir_passes(
 }
 
 /// Compute new lints and append them to the errors Vec.
-/// Lints are only computed if the erros vector is empty. For performance
+/// Lints are only computed if the errors vector has no errors, only warnings. For performance
 /// reasons we don't want to waste time running lints every few keystrokes,
 /// if the user is in the middle of typing a statement, for example.
 fn run_linter_passes(
@@ -301,7 +282,7 @@ fn run_linter_passes(
     errors: &mut Vec<WithSource<compile::ErrorKind>>,
     unit: &CompileUnit,
 ) {
-    if errors.is_empty() {
+    if !compile::has_errors(errors) {
         let lints = qsc::linter::run_lints(unit, Some(config));
         let lints = lints
             .into_iter()
@@ -20,6 +20,10 @@ fn check(source_with_markers: &str, expect: &Expect) {
     let actual = get_hover(&compilation, "<source>", cursor_position, Encoding::Utf8)
         .expect("Expected a hover.");
     assert_eq!(&actual.span, &target_spans[0]);
+    assert!(
+        actual.span.contains(cursor_position),
+        "hover span should contain the cursor"
+    );
     expect.assert_eq(&actual.contents);
 }
 
@@ -1465,12 +1465,14 @@ async fn loading_lints_config_from_manifest() {
                         DivisionByZero,
                     ),
                     level: Error,
+                    threshold: None,
                 },
                 LintConfig {
                     kind: Ast(
                         NeedlessParens,
                     ),
                     level: Error,
+                    threshold: None,
                 },
             ]"#]],
     )
@@ -1609,6 +1611,7 @@ async fn lints_prefer_workspace_over_defaults() {
         lints_config: Some(vec![LintConfig {
             kind: LintKind::Ast(AstLint::DivisionByZero),
             level: LintLevel::Warn,
+            threshold: None,
         }]),
         ..WorkspaceConfigurationUpdate::default()
     });
@@ -1668,6 +1671,7 @@ async fn lints_prefer_manifest_over_workspace() {
         lints_config: Some(vec![LintConfig {
             kind: LintKind::Ast(AstLint::DivisionByZero),
             level: LintLevel::Warn,
+            threshold: None,
         }]),
         ..WorkspaceConfigurationUpdate::default()
     });
@@ -198,7 +198,8 @@ fn compile_fake_stdlib() -> (PackageStore, PackageId) {
         Profile::Unrestricted.into(),
         LanguageFeatures::default(),
     );
-    assert!(std_errors.is_empty());
+    // The fake `Hidden` operation is never used, which is only a warning.
+    assert!(!compile::has_errors(&std_errors), "{std_errors:?}");
     let std_package_id = package_store.insert(std_compile_unit);
     (package_store, std_package_id)
 }
@@ -352,7 +352,7 @@ serializable_type! {
     r#"export interface IWorkspaceConfiguration {
         targetProfile?: TargetProfile;
         packageType?: "exe" | "lib";
-        languageFeatures?: LanguageFeatures[];
+        languageFeatures?: "v2-preview-syntax"[];
         lints?: { lint: string; level: string }[];
     }"#,
     IWorkspaceConfiguration
@@ -589,7 +589,7 @@ serializable_type! {
     },
     r#"export interface INotebookMetadata {
         targetProfile?: "base" | "adaptive_ri" | "unrestricted";
-        languageFeatures?: "v2-preview-syntax"[];
+        languageFeatures?: LanguageFeatures[];
         manifest?: string;
     }"#,
     INotebookMetadata
@@ -94,7 +94,7 @@ pub(crate) fn _get_qir(
     language_features: LanguageFeatures,
     capabilities: TargetCapabilityFlags,
 ) -> Result<String, String> {
-    qsc::codegen::get_qir(sources, language_features, capabilities)
+    qsc::codegen::get_qir(sources, language_features, capabilities, true)
         .map_err(interpret_errors_into_qsharp_errors_json)
 }
 
@@ -51,6 +51,7 @@ fn test_compile() {
         SourceMap::new([("test.qs".into(), code.into())], None),
         LanguageFeatures::default(),
         TargetCapabilityFlags::empty(),
+        true,
     );
     assert!(result.is_ok());
 }